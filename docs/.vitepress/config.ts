@@ -40,17 +40,29 @@ export default defineConfig({
         items: [
           { text: 'Aliases', link: '/dev-tools/aliases' },
           { text: 'Comparison to asdf', link: '/dev-tools/comparison-to-asdf' },
+          { text: 'Lockfile', link: '/dev-tools/lockfile' },
           { text: 'Shims', link: '/dev-tools/shims' },
           {
             text: 'Backends',
             link: '/dev-tools/backends/',
             items: [
               { text: 'asdf', link: '/dev-tools/backends/asdf' },
+              { text: 'cabal', link: '/dev-tools/backends/cabal' },
               { text: 'cargo', link: '/dev-tools/backends/cargo' },
+              { text: 'deno', link: '/dev-tools/backends/deno' },
+              { text: 'gitea', link: '/dev-tools/backends/gitea' },
+              { text: 'gitlab', link: '/dev-tools/backends/gitlab' },
               { text: 'go', link: '/dev-tools/backends/go' },
+              { text: 'http', link: '/dev-tools/backends/http' },
+              { text: 'krew', link: '/dev-tools/backends/krew' },
               { text: 'npm', link: '/dev-tools/backends/npm' },
+              { text: 'oci', link: '/dev-tools/backends/oci' },
               { text: 'pipx', link: '/dev-tools/backends/pipx' },
+              { text: 'pixi', link: '/dev-tools/backends/pixi' },
+              { text: 'rscript', link: '/dev-tools/backends/rscript' },
+              { text: 'system', link: '/dev-tools/backends/system' },
               { text: 'ubi', link: '/dev-tools/backends/ubi' },
+              { text: 'wasm', link: '/dev-tools/backends/wasm' },
             ]
           }
         ],
@@ -0,0 +1,79 @@
+use std::path::PathBuf;
+
+use base64::prelude::*;
+use eyre::{bail, eyre, Result};
+
+use crate::config::Settings;
+use crate::file::display_path;
+use crate::{env, file};
+
+/// encrypts a value with the `age` CLI for `recipients`, or for the identity file if there are
+/// none, returns the ciphertext as base64 so it fits in a toml string
+pub fn encrypt(value: &str, recipients: &[String]) -> Result<String> {
+    let mut args = vec!["--encrypt".to_string()];
+    if recipients.is_empty() {
+        args.push("--identity".into());
+        args.push(identity_file()?.to_string_lossy().to_string());
+    }
+    for recipient in recipients {
+        args.push("--recipient".into());
+        args.push(recipient.clone());
+    }
+    let ciphertext = run(args, value.as_bytes().to_vec())?;
+    Ok(BASE64_STANDARD.encode(ciphertext))
+}
+
+/// decrypts a value from `encrypt` with the identity file
+pub fn decrypt(value: &str) -> Result<String> {
+    let ciphertext = BASE64_STANDARD
+        .decode(value.trim())
+        .map_err(|e| eyre!("invalid age ciphertext: {e}"))?;
+    let identity = identity_file()?;
+    let args = vec![
+        "--decrypt".to_string(),
+        "--identity".into(),
+        identity.to_string_lossy().to_string(),
+    ];
+    Ok(String::from_utf8(run(args, ciphertext)?)?)
+}
+
+/// `age_identity_file`, otherwise mise's or sops' default identity file
+fn identity_file() -> Result<PathBuf> {
+    if let Some(path) = &Settings::get().age_identity_file {
+        return Ok(file::replace_path(path));
+    }
+    let defaults = [
+        env::XDG_CONFIG_HOME.join("mise/age.txt"),
+        env::XDG_CONFIG_HOME.join("sops/age/keys.txt"),
+    ];
+    defaults
+        .iter()
+        .find(|p| p.exists())
+        .cloned()
+        .ok_or_else(|| {
+            eyre!(
+                "no age identity found, create one with `age-keygen -o {}` or set age_identity_file",
+                display_path(&defaults[0])
+            )
+        })
+}
+
+/// the plaintext is only passed through stdin and stdout so it is never written to disk
+fn run(args: Vec<String>, input: Vec<u8>) -> Result<Vec<u8>> {
+    let age = file::which_non_pristine("age").ok_or_else(|| {
+        eyre!("age is required for encrypted env vars, install it with `mise use -g age`")
+    })?;
+    let output = duct::cmd(age, args)
+        .stdin_bytes(input)
+        .stdout_capture()
+        .stderr_capture()
+        .unchecked()
+        .run()?;
+    if !output.status.success() {
+        bail!(
+            "age failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(output.stdout)
+}
@@ -0,0 +1,490 @@
+use std::fs::File;
+use std::io::{BufReader, Cursor, Read, Seek, SeekFrom};
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Component, Path, PathBuf};
+
+use bzip2::read::BzDecoder;
+use eyre::{bail, ensure, eyre, Result, WrapErr};
+use flate2::read::{GzDecoder, ZlibDecoder};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use tar::Archive;
+use xz2::read::XzDecoder;
+
+use crate::file::{self, display_path};
+use crate::store;
+
+/// Archive formats that tools are released in, detected from the filename.
+///
+/// Everything is extracted in-process except dmg which needs `hdiutil`, that is only a
+/// problem in theory since dmg files are only released for macOS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    TarGz,
+    TarXz,
+    TarBz2,
+    TarZst,
+    Tar,
+    Zip,
+    SevenZip,
+    Dmg,
+    Pkg,
+}
+
+impl ArchiveFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_string_lossy().to_lowercase();
+        let format = match () {
+            _ if name.ends_with(".tar.gz") || name.ends_with(".tgz") => Self::TarGz,
+            _ if name.ends_with(".tar.xz") || name.ends_with(".txz") => Self::TarXz,
+            _ if name.ends_with(".tar.bz2") || name.ends_with(".tbz2") => Self::TarBz2,
+            _ if name.ends_with(".tar.zst") || name.ends_with(".tzst") => Self::TarZst,
+            _ if name.ends_with(".tar") => Self::Tar,
+            _ if name.ends_with(".zip") => Self::Zip,
+            _ if name.ends_with(".7z") => Self::SevenZip,
+            _ if name.ends_with(".dmg") => Self::Dmg,
+            _ if name.ends_with(".pkg") || name.ends_with(".xar") => Self::Pkg,
+            _ => return None,
+        };
+        Some(format)
+    }
+}
+
+pub fn is_archive(path: &Path) -> bool {
+    ArchiveFormat::from_path(path).is_some()
+}
+
+/// extracts an archive into `dest` based on its file extension, with the `content_store` setting
+/// the files are linked from the tree previously extracted from the same archive
+pub fn extract(archive: &Path, dest: &Path) -> Result<()> {
+    let Some(format) = ArchiveFormat::from_path(archive) else {
+        bail!("unsupported archive format: {}", display_path(archive));
+    };
+    if store::is_enabled() {
+        return store::extract(archive, dest, |tree| extract_format(format, archive, tree));
+    }
+    extract_format(format, archive, dest)
+}
+
+fn extract_format(format: ArchiveFormat, archive: &Path, dest: &Path) -> Result<()> {
+    debug!(
+        "extracting {format:?} {} to {}",
+        archive.display(),
+        dest.display()
+    );
+    file::create_dir_all(dest)?;
+    let f = || File::open(archive).wrap_err_with(|| display_path(archive));
+    match format {
+        ArchiveFormat::TarGz => return file::untar(archive, dest),
+        ArchiveFormat::Zip => return file::unzip(archive, dest),
+        ArchiveFormat::TarXz => unpack_tar(XzDecoder::new(f()?), dest),
+        ArchiveFormat::TarBz2 => unpack_tar(BzDecoder::new(f()?), dest),
+        ArchiveFormat::TarZst => unpack_tar(zstd::stream::read::Decoder::new(f()?)?, dest),
+        ArchiveFormat::Tar => unpack_tar(f()?, dest),
+        ArchiveFormat::SevenZip => {
+            sevenz_rust::decompress_file(archive, dest).map_err(|e| eyre!("{e}"))
+        }
+        ArchiveFormat::Dmg => extract_dmg(archive, dest),
+        ArchiveFormat::Pkg => extract_pkg(archive, dest),
+    }
+    .wrap_err_with(|| {
+        format!(
+            "failed to extract {} to {}",
+            display_path(archive),
+            display_path(dest)
+        )
+    })
+}
+
+fn unpack_tar(r: impl Read, dest: &Path) -> Result<()> {
+    Archive::new(r).unpack(dest)?;
+    Ok(())
+}
+
+fn extract_dmg(archive: &Path, dest: &Path) -> Result<()> {
+    ensure!(
+        cfg!(target_os = "macos"),
+        "dmg files can only be extracted on macOS"
+    );
+    let mount = tempfile::tempdir()?;
+    cmd!(
+        "hdiutil",
+        "attach",
+        "-nobrowse",
+        "-readonly",
+        "-noautoopen",
+        "-mountpoint",
+        mount.path(),
+        archive
+    )
+    .stdout_null()
+    .run()?;
+    let result = file::copy_dir_all(mount.path(), dest);
+    cmd!("hdiutil", "detach", "-quiet", mount.path()).run()?;
+    result
+}
+
+/// macOS installer packages are xar archives with the files of each component in a `Payload`,
+/// the payloads are extracted into `dest`, not the xar itself
+fn extract_pkg(archive: &Path, dest: &Path) -> Result<()> {
+    let xar = tempfile::tempdir()?;
+    extract_xar(archive, xar.path())?;
+    let payloads = file::recursive_ls(xar.path())?
+        .into_iter()
+        .filter(|p| p.file_name().is_some_and(|n| n == "Payload"))
+        .collect::<Vec<_>>();
+    if payloads.is_empty() {
+        // a plain xar archive, not an installer package
+        return file::copy_dir_all(xar.path(), dest);
+    }
+    for payload in payloads {
+        trace!("extracting payload {}", payload.display());
+        extract_cpio(&decode_payload(&payload)?, dest)?;
+    }
+    Ok(())
+}
+
+/// payloads are cpio archives compressed with gzip, or with xz in "pbzx" chunks
+fn decode_payload(path: &Path) -> Result<Vec<u8>> {
+    let mut raw = vec![];
+    File::open(path)?.read_to_end(&mut raw)?;
+    let mut out = vec![];
+    match raw.as_slice() {
+        [0x1f, 0x8b, ..] => {
+            GzDecoder::new(raw.as_slice()).read_to_end(&mut out)?;
+        }
+        [b'B', b'Z', b'h', ..] => {
+            BzDecoder::new(raw.as_slice()).read_to_end(&mut out)?;
+        }
+        [b'p', b'b', b'z', b'x', ..] => {
+            let mut r = Cursor::new(&raw[4..]);
+            let mut flags = read_u64(&mut r)?;
+            while flags & 0x0100_0000 != 0 {
+                flags = read_u64(&mut r)?;
+                let len = read_u64(&mut r)?;
+                let remaining = r.get_ref().len() as u64 - r.position();
+                if len > remaining {
+                    bail!("truncated pbzx payload in {}", display_path(path));
+                }
+                let mut chunk = vec![0; len as usize];
+                r.read_exact(&mut chunk)?;
+                if chunk.starts_with(&[0xfd, b'7', b'z', b'X', b'Z', 0]) {
+                    XzDecoder::new(chunk.as_slice()).read_to_end(&mut out)?;
+                } else {
+                    out.extend(chunk);
+                }
+            }
+        }
+        _ => out = raw,
+    }
+    Ok(out)
+}
+
+fn read_u64(r: &mut impl Read) -> Result<u64> {
+    let mut buf = [0; 8];
+    r.read_exact(&mut buf)?;
+    Ok(u64::from_be_bytes(buf))
+}
+
+#[derive(Debug, Default)]
+struct XarFile {
+    name: String,
+    kind: String,
+    offset: u64,
+    length: u64,
+    encoding: String,
+}
+
+/// https://en.wikipedia.org/wiki/Xar_(archiver) a header, a zlib compressed XML table of
+/// contents, then the heap with the (usually also compressed) contents of the files
+fn extract_xar(archive: &Path, dest: &Path) -> Result<()> {
+    let mut f = BufReader::new(File::open(archive)?);
+    let mut header = [0; 28];
+    f.read_exact(&mut header)?;
+    ensure!(&header[..4] == b"xar!", "not a xar archive");
+    let header_size = u16::from_be_bytes([header[4], header[5]]) as u64;
+    let toc_len = u64::from_be_bytes(header[8..16].try_into()?);
+    f.seek(SeekFrom::Start(header_size))?;
+    let mut toc = String::new();
+    ZlibDecoder::new((&mut f).take(toc_len)).read_to_string(&mut toc)?;
+    let heap = header_size + toc_len;
+
+    for (path, entry) in parse_xar_toc(&toc)? {
+        let path = dest.join(safe_path(&path)?);
+        match entry.kind.as_str() {
+            "directory" => file::create_dir_all(&path)?,
+            "file" => {
+                file::create_dir_all(path.parent().unwrap())?;
+                f.seek(SeekFrom::Start(heap + entry.offset))?;
+                let mut data = (&mut f).take(entry.length);
+                let mut out = file::create(&path)?;
+                match entry.encoding.as_str() {
+                    "application/x-gzip" => std::io::copy(&mut ZlibDecoder::new(data), &mut out)?,
+                    "application/x-bzip2" => std::io::copy(&mut BzDecoder::new(data), &mut out)?,
+                    "application/x-xz" | "application/x-lzma" => {
+                        std::io::copy(&mut XzDecoder::new(data), &mut out)?
+                    }
+                    _ => std::io::copy(&mut data, &mut out)?,
+                };
+            }
+            kind => debug!("skipping {kind} {}", path.display()),
+        }
+    }
+    Ok(())
+}
+
+/// the paths and metadata of the `<file>` elements which are nested for directories
+fn parse_xar_toc(toc: &str) -> Result<Vec<(PathBuf, XarFile)>> {
+    let mut reader = Reader::from_str(toc);
+    let mut tags: Vec<String> = vec![];
+    let mut stack: Vec<XarFile> = vec![];
+    let mut files = vec![];
+    loop {
+        let event = reader.read_event()?;
+        match &event {
+            Event::Start(e) | Event::Empty(e)
+                if e.name().as_ref() == b"encoding" && tags.last().is_some_and(|t| t == "data") =>
+            {
+                if let (Some(entry), Some(style)) =
+                    (stack.last_mut(), e.try_get_attribute("style")?)
+                {
+                    entry.encoding = style.unescape_value()?.to_string();
+                }
+                if matches!(event, Event::Start(_)) {
+                    tags.push("encoding".into());
+                }
+            }
+            Event::Start(e) => {
+                let tag = String::from_utf8_lossy(e.name().as_ref()).to_string();
+                if tag == "file" {
+                    stack.push(XarFile::default());
+                }
+                tags.push(tag);
+            }
+            Event::Text(t) => {
+                let (Some(tag), Some(entry)) = (tags.last(), stack.last_mut()) else {
+                    continue;
+                };
+                let parent = tags.iter().rev().nth(1).map(|s| s.as_str());
+                let text = t.unescape()?.to_string();
+                match (parent, tag.as_str()) {
+                    (Some("file"), "name") => entry.name = text,
+                    (Some("file"), "type") => entry.kind = text,
+                    (Some("data"), "offset") => entry.offset = text.trim().parse()?,
+                    (Some("data"), "length") => entry.length = text.trim().parse()?,
+                    _ => {}
+                }
+            }
+            Event::End(e) => {
+                tags.pop();
+                if e.name().as_ref() == b"file" {
+                    let entry = stack.pop().unwrap();
+                    let path = stack
+                        .iter()
+                        .map(|f| f.name.as_str())
+                        .chain([entry.name.as_str()])
+                        .collect::<PathBuf>();
+                    files.push((path, entry));
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    // directories end after their children so they come first after sorting by path
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(files)
+}
+
+/// extracts "odc" (used by macOS) and "newc" cpio archives
+fn extract_cpio(data: &[u8], dest: &Path) -> Result<()> {
+    let mut pos = 0;
+    loop {
+        let field = |start: usize, len: usize, radix: u32| -> Result<u64> {
+            let s = data
+                .get(pos + start..pos + start + len)
+                .ok_or_else(|| eyre!("truncated cpio archive"))?;
+            Ok(u64::from_str_radix(std::str::from_utf8(s)?, radix)?)
+        };
+        let magic = data.get(pos..pos + 6).unwrap_or_default();
+        let (mode, namesize, filesize, header_len, align) = match magic {
+            b"070707" => (field(18, 6, 8)?, field(59, 6, 8)?, field(65, 11, 8)?, 76, 1),
+            b"070701" | b"070702" => (
+                field(14, 8, 16)?,
+                field(94, 8, 16)?,
+                field(54, 8, 16)?,
+                110,
+                4,
+            ),
+            _ => bail!("invalid cpio header at offset {pos}"),
+        };
+        let name_start = pos + header_len;
+        let name_end = name_start + namesize as usize;
+        let name = data
+            .get(name_start..name_end - 1)
+            .ok_or_else(|| eyre!("truncated cpio archive"))?;
+        let name = String::from_utf8_lossy(name).to_string();
+        let data_start = name_end.next_multiple_of(align);
+        let data_end = data_start + filesize as usize;
+        let contents = data
+            .get(data_start..data_end)
+            .ok_or_else(|| eyre!("truncated cpio archive"))?;
+        pos = data_end.next_multiple_of(align);
+        if name == "TRAILER!!!" {
+            return Ok(());
+        }
+        let path = safe_path(Path::new(&name))?;
+        if path.as_os_str().is_empty() {
+            continue;
+        }
+        ensure_no_symlink_parents(dest, &path)?;
+        let path = dest.join(path);
+        match mode & 0o170000 {
+            0o040000 => file::create_dir_all(&path)?,
+            0o120000 => {
+                let target = String::from_utf8_lossy(contents).to_string();
+                file::create_dir_all(path.parent().unwrap())?;
+                file::remove_all(&path)?;
+                file::make_symlink(Path::new(&target), &path)?;
+            }
+            0o100000 => {
+                file::create_dir_all(path.parent().unwrap())?;
+                if path.is_symlink() {
+                    // replace the link instead of writing to its target
+                    file::remove_file(&path)?;
+                }
+                file::write(&path, contents)?;
+                #[cfg(unix)]
+                std::fs::set_permissions(
+                    &path,
+                    std::fs::Permissions::from_mode(mode as u32 & 0o7777),
+                )?;
+            }
+            _ => debug!("skipping special file {}", path.display()),
+        }
+    }
+}
+
+/// refuses to extract through a symlink an earlier entry created, it could point outside `dest`
+fn ensure_no_symlink_parents(dest: &Path, path: &Path) -> Result<()> {
+    let mut dir = dest.to_path_buf();
+    for c in path.parent().into_iter().flat_map(|p| p.components()) {
+        dir.push(c);
+        if dir.is_symlink() {
+            bail!("refusing to extract {} through a symlink", path.display());
+        }
+    }
+    Ok(())
+}
+
+/// strips leading "./" and "/" and refuses paths that would escape the destination
+fn safe_path(path: &Path) -> Result<PathBuf> {
+    let mut out = PathBuf::new();
+    for c in path.components() {
+        match c {
+            Component::Normal(c) => out.push(c),
+            Component::CurDir | Component::RootDir | Component::Prefix(_) => {}
+            Component::ParentDir => bail!("refusing to extract {}", path.display()),
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_from_path() {
+        let format = |p: &str| ArchiveFormat::from_path(Path::new(p));
+        assert_eq!(format("tool-1.0.0.tar.zst"), Some(ArchiveFormat::TarZst));
+        assert_eq!(format("tool-1.0.0.TGZ"), Some(ArchiveFormat::TarGz));
+        assert_eq!(format("tool-1.0.0.7z"), Some(ArchiveFormat::SevenZip));
+        assert_eq!(format("Tool-1.0.0.pkg"), Some(ArchiveFormat::Pkg));
+        assert_eq!(format("tool-linux-amd64"), None);
+    }
+
+    #[test]
+    fn test_extract_cpio() {
+        fn odc(name: &str, mode: u32, contents: &str) -> String {
+            format!(
+                "070707000000000000{mode:06o}000000000000000001000000{:011o}{:06o}{:011o}{name}\0{contents}",
+                0,
+                name.len() + 1,
+                contents.len()
+            )
+        }
+        let archive = [
+            odc(".", 0o040755, ""),
+            odc("./bin", 0o040755, ""),
+            odc("./bin/tool", 0o100755, "#!/bin/sh\n"),
+            odc("TRAILER!!!", 0, ""),
+        ]
+        .concat();
+        let dest = tempfile::tempdir().unwrap();
+        extract_cpio(archive.as_bytes(), dest.path()).unwrap();
+        let tool = dest.path().join("bin/tool");
+        assert_eq!(file::read_to_string(&tool).unwrap(), "#!/bin/sh\n");
+        #[cfg(unix)]
+        assert!(file::is_executable(&tool));
+
+        let archive = odc("../escape", 0o100644, "");
+        assert!(extract_cpio(archive.as_bytes(), dest.path()).is_err());
+
+        let outside = tempfile::tempdir().unwrap();
+        let archive = [
+            odc("link", 0o120777, &outside.path().to_string_lossy()),
+            odc("link/escape", 0o100644, "pwned"),
+            odc("TRAILER!!!", 0, ""),
+        ]
+        .concat();
+        assert!(extract_cpio(archive.as_bytes(), dest.path()).is_err());
+        assert!(!outside.path().join("escape").exists());
+    }
+
+    #[test]
+    fn test_decode_payload_truncated_pbzx() {
+        let dir = tempfile::tempdir().unwrap();
+        let payload = dir.path().join("Payload");
+        let mut raw = b"pbzx".to_vec();
+        raw.extend(0x0100_0000u64.to_be_bytes());
+        raw.extend(0u64.to_be_bytes());
+        raw.extend(u64::MAX.to_be_bytes());
+        raw.extend(b"short");
+        file::write(&payload, &raw).unwrap();
+        let err = decode_payload(&payload).unwrap_err();
+        assert!(err.to_string().contains("truncated pbzx payload"));
+    }
+
+    #[test]
+    fn test_parse_xar_toc() {
+        let toc = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xar><toc>
+  <file id="1">
+    <name>tool.pkg</name>
+    <type>directory</type>
+    <file id="2">
+      <data>
+        <length>20</length>
+        <offset>10</offset>
+        <size>30</size>
+        <encoding style="application/x-gzip"/>
+      </data>
+      <ea><name>com.apple.quarantine</name><offset>0</offset><length>5</length></ea>
+      <name>Payload</name>
+      <type>file</type>
+    </file>
+  </file>
+</toc></xar>"#;
+        let files = parse_xar_toc(toc).unwrap();
+        assert_eq!(files.len(), 2);
+        let (path, payload) = &files[1];
+        assert_eq!(path, &PathBuf::from("tool.pkg/Payload"));
+        assert_eq!(payload.kind, "file");
+        assert_eq!((payload.offset, payload.length), (10, 20));
+        assert_eq!(payload.encoding, "application/x-gzip");
+    }
+}
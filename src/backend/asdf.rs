@@ -123,6 +123,7 @@ impl Asdf {
         if let Some(ref_) = &repo_ref {
             pr.set_message(format!("checking out {ref_}"));
             git.update(Some(ref_.to_string()))?;
+            self.pin(&git, ref_)?;
         }
         self.exec_hook(pr, "post-plugin-add")?;
 
@@ -134,6 +135,19 @@ impl Asdf {
         Ok(())
     }
 
+    /// records the commit if the plugin was checked out at a sha so later updates
+    /// and `mise doctor` can tell when it changes, other refs remove the pin
+    fn pin(&self, git: &Git, gitref: &str) -> Result<()> {
+        if !crate::git::is_sha(gitref) {
+            return git.set_pinned_sha(None);
+        }
+        let sha = git.current_sha()?;
+        if !sha.starts_with(gitref) {
+            bail!("plugin {} checked out {sha} instead of {gitref}", self.name);
+        }
+        git.set_pinned_sha(Some(&sha))
+    }
+
     fn fetch_versions(&self) -> Result<Option<Vec<String>>> {
         if !*env::MISE_USE_VERSIONS_HOST {
             return Ok(None);
@@ -322,7 +336,7 @@ impl Asdf {
             sm.prepend_path(p);
         }
         let script = sm.get_script_path(&ExecEnv);
-        let ed = EnvDiff::from_bash_script(&script, &sm.env)?;
+        let ed = EnvDiff::from_bash_script(&script, &sm.script_env())?;
         let env = ed
             .to_patches()
             .into_iter()
@@ -331,6 +345,10 @@ impl Asdf {
                 EnvDiffOperation::Change(key, value) => Some((key, value)),
                 _ => None,
             })
+            .map(|(key, value)| {
+                let value = sm.untranslate(&key, value);
+                (key, value)
+            })
             .collect();
         Ok(env)
     }
@@ -338,6 +356,9 @@ impl Asdf {
     fn script_man_for_tv(&self, tv: &ToolVersion) -> Result<ScriptManager> {
         let config = Config::get();
         let mut sm = self.script_man.clone();
+        if let Some(translate_paths) = tv.request.options().get("translate_paths") {
+            sm = sm.with_translate_paths(translate_paths == "true");
+        }
         for (key, value) in &tv.request.options() {
             let k = format!("RTX_TOOL_OPTS__{}", key.to_uppercase());
             sm = sm.with_env(k, value.clone());
@@ -555,8 +576,22 @@ impl Backend for Asdf {
             );
             return Ok(());
         }
+        if let (Some(pinned), None) = (git.pinned_sha(), &gitref) {
+            // pinned plugins are only updated when a new ref is given explicitly
+            pr.finish_with_message(format!(
+                "pinned to {}, not updating",
+                style(pinned.chars().take(7).collect::<String>())
+                    .bright()
+                    .yellow()
+                    .for_stderr(),
+            ));
+            return Ok(());
+        }
         pr.set_message("updating git repo".into());
-        let (pre, post) = git.update(gitref)?;
+        let (pre, post) = git.update(gitref.clone())?;
+        if let Some(gitref) = &gitref {
+            self.pin(&git, gitref)?;
+        }
         let sha = git.current_sha_short()?;
         let repo_url = self.get_remote_url().unwrap_or_default();
         self.exec_hook_post_plugin_update(pr, pre, post)?;
@@ -0,0 +1,111 @@
+use std::fmt::Debug;
+
+use itertools::Itertools;
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT};
+use versions::Versioning;
+
+use crate::backend::{Backend, BackendType};
+use crate::cache::CacheManager;
+use crate::cli::args::BackendArg;
+use crate::cmd::CmdLineRunner;
+use crate::config::{Config, Settings};
+use crate::http::HTTP_FETCH;
+use crate::install_context::InstallContext;
+use crate::toolset::ToolRequest;
+
+/// Installs Haskell executables from Hackage with `cabal install`
+///
+/// cabal:hlint
+/// cabal:ormolu
+#[derive(Debug)]
+pub struct CabalBackend {
+    fa: BackendArg,
+    remote_version_cache: CacheManager<Vec<String>>,
+}
+
+impl Backend for CabalBackend {
+    fn get_type(&self) -> BackendType {
+        BackendType::Cabal
+    }
+
+    fn fa(&self) -> &BackendArg {
+        &self.fa
+    }
+
+    fn get_dependencies(&self, _tvr: &ToolRequest) -> eyre::Result<Vec<BackendArg>> {
+        Ok(vec!["cabal".into(), "ghc".into()])
+    }
+
+    fn _list_remote_versions(&self) -> eyre::Result<Vec<String>> {
+        self.remote_version_cache
+            .get_or_try_init(|| {
+                let url = format!(
+                    "https://hackage.haskell.org/package/{}/preferred",
+                    self.name()
+                );
+                let mut headers = HeaderMap::new();
+                headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
+                let preferred: HackagePreferred = HTTP_FETCH.json_with_headers(url, &headers)?;
+                Ok(preferred
+                    .normal_version
+                    .into_iter()
+                    .sorted_by_cached_key(|v| Versioning::new(v))
+                    .collect())
+            })
+            .cloned()
+    }
+
+    fn install_version_impl(&self, ctx: &InstallContext) -> eyre::Result<()> {
+        let config = Config::try_get()?;
+        let settings = Settings::get();
+        settings.ensure_experimental("cabal backend")?;
+        let opts = ctx.tv.request.options();
+
+        CmdLineRunner::new("cabal")
+            .arg("update")
+            .with_pr(ctx.pr.as_ref())
+            .envs(ctx.ts.env_with_path(&config)?)
+            .prepend_path(ctx.ts.list_paths())?
+            .execute()?;
+
+        let mut cmd = CmdLineRunner::new("cabal")
+            .arg("install")
+            .arg(format!("{}-{}", self.name(), ctx.tv.version))
+            .arg("--installdir")
+            .arg(ctx.tv.install_path().join("bin"))
+            .arg("--install-method=copy")
+            .arg("--overwrite-policy=always");
+        // e.g.: flags = "+embed_data_files -trypandoc"
+        if let Some(flags) = opts.get("flags") {
+            cmd = cmd.arg(format!("--flags={flags}"));
+        }
+        // e.g.: ghc = "9.6.5" to build with a specific compiler on PATH as ghc-9.6.5
+        if let Some(ghc) = opts.get("ghc") {
+            cmd = cmd.arg(format!("--with-compiler=ghc-{ghc}"));
+        }
+        cmd.with_pr(ctx.pr.as_ref())
+            .envs(ctx.ts.env_with_path(&config)?)
+            .prepend_path(ctx.ts.list_paths())?
+            .execute()?;
+
+        Ok(())
+    }
+}
+
+impl CabalBackend {
+    pub fn new(name: String) -> Self {
+        let fa = BackendArg::new(BackendType::Cabal, &name);
+        Self {
+            remote_version_cache: CacheManager::new(
+                fa.cache_path.join("remote_versions-$KEY.msgpack.z"),
+            ),
+            fa,
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct HackagePreferred {
+    #[serde(rename = "normal-version", default)]
+    normal_version: Vec<String>,
+}
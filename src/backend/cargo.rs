@@ -1,18 +1,26 @@
 use std::fmt::Debug;
+use std::path::{Path, PathBuf};
 
+use eyre::eyre;
+use itertools::Itertools;
+use reqwest::header::{HeaderMap, AUTHORIZATION};
 use serde_json::Deserializer;
 use url::Url;
+use versions::Versioning;
 
 use crate::backend::{Backend, BackendType};
+use crate::build_time::built_info;
 use crate::cache::CacheManager;
 use crate::cli::args::BackendArg;
 use crate::cmd::CmdLineRunner;
 use crate::config::{Config, Settings};
 use crate::env::GITHUB_TOKEN;
-use crate::file;
-use crate::http::HTTP_FETCH;
+use crate::http::{HTTP, HTTP_FETCH};
 use crate::install_context::InstallContext;
-use crate::toolset::ToolRequest;
+use crate::toolset::{ToolRequest, ToolVersionOptions};
+use crate::{dirs, env, file, mirrors};
+
+const QUICKINSTALL_URL: &str = "https://github.com/cargo-bins/cargo-quickinstall/releases/download";
 
 #[derive(Debug)]
 pub struct CargoBackend {
@@ -34,18 +42,24 @@ impl Backend for CargoBackend {
     }
 
     fn _list_remote_versions(&self) -> eyre::Result<Vec<String>> {
+        if let Some(name) = self.registry()? {
+            // versions from other registries are cached separately from crates.io
+            let cache = CacheManager::new(
+                self.fa
+                    .cache_path
+                    .join(format!("remote_versions-{name}-$KEY.msgpack.z")),
+            );
+            let registry = CargoRegistry::find(&name)?;
+            return cache
+                .get_or_try_init(|| registry.list_versions(self.name()))
+                .cloned();
+        }
         self.remote_version_cache
             .get_or_try_init(|| {
-                let raw = HTTP_FETCH.get_text(get_crate_url(self.name())?)?;
-                let stream = Deserializer::from_str(&raw).into_iter::<CrateVersion>();
-                let mut versions = vec![];
-                for v in stream {
-                    let v = v?;
-                    if !v.yanked {
-                        versions.push(v.vers);
-                    }
+                if self.is_git() {
+                    return list_git_tags(self.name());
                 }
-                Ok(versions)
+                CargoRegistry::crates_io().list_versions(self.name())
             })
             .cloned()
     }
@@ -54,17 +68,65 @@ impl Backend for CargoBackend {
         let config = Config::try_get()?;
         let settings = Settings::get();
         settings.ensure_experimental("cargo backend")?;
-        let cmd = if self.is_binstall_enabled() {
+        let opts = ctx.tv.request.options();
+        let target = opts.get("target");
+        if self.is_git() {
+            if let Some(target) = target {
+                self.add_rustup_target(ctx, target)?;
+            }
+            let (flag, gitref) = git_ref_arg(&ctx.tv.version);
+            CmdLineRunner::new("cargo")
+                .arg("install")
+                .arg("--git")
+                .arg(self.name())
+                .arg(flag)
+                .arg(gitref)
+                .args(cargo_install_args(&opts))
+                .args(target.iter().flat_map(|t| ["--target", t.as_str()]))
+                .arg("--root")
+                .arg(ctx.tv.install_path())
+                .with_pr(ctx.pr.as_ref())
+                .envs(ctx.ts.env_with_path(&config)?)
+                .prepend_path(ctx.ts.list_paths())?
+                .execute()?;
+            return Ok(());
+        }
+        // prebuilt binaries are built with the default features, `locked` doesn't change them
+        let prebuilt = opts.get("binstall").map(|s| s.as_str()) != Some("false")
+            && settings.cargo_binstall
+            && cargo_features_args(&opts).is_empty();
+        let binstall = prebuilt && file::which_non_pristine("cargo-binstall").is_some();
+        // quickinstall only has crates from crates.io, its binaries aren't verified like
+        // cargo-binstall does so it has to be enabled
+        if prebuilt && !binstall && settings.cargo_quickinstall && registry_args(&opts).is_empty() {
+            match self.quickinstall(ctx) {
+                Ok(()) => return Ok(()),
+                Err(err) => debug!("no prebuilt {} from quickinstall: {err:#}", self.name()),
+            }
+        }
+        let cmd = if binstall {
             let mut runner = CmdLineRunner::new("cargo-binstall").arg("-y");
             if let Some(token) = &*GITHUB_TOKEN {
                 runner = runner.env("GITHUB_TOKEN", token)
             }
+            if let Some(target) = target {
+                runner = runner.arg("--targets").arg(target);
+            }
             runner
         } else {
-            CmdLineRunner::new("cargo").arg("install")
+            if let Some(target) = target {
+                self.add_rustup_target(ctx, target)?;
+            }
+            let runner = CmdLineRunner::new("cargo").arg("install");
+            match target {
+                Some(target) => runner.arg("--target").arg(target),
+                None => runner,
+            }
         };
 
         cmd.arg(&format!("{}@{}", self.name(), ctx.tv.version))
+            .args(cargo_install_args(&opts))
+            .args(registry_args(&opts))
             .arg("--root")
             .arg(ctx.tv.install_path())
             .with_pr(ctx.pr.as_ref())
@@ -87,21 +149,224 @@ impl CargoBackend {
         }
     }
 
-    fn is_binstall_enabled(&self) -> bool {
-        let settings = Settings::get();
-        settings.cargo_binstall && file::which_non_pristine("cargo-binstall").is_some()
+    /// cross-compiling needs the standard library for the target, this fetches it if rust is
+    /// managed by rustup
+    fn add_rustup_target(&self, ctx: &InstallContext, target: &str) -> eyre::Result<()> {
+        let config = Config::try_get()?;
+        let env = ctx.ts.env_with_path(&config)?;
+        let path = env.get("PATH").cloned().unwrap_or_default();
+        if !std::env::split_paths(&path).any(|p| file::is_executable(&p.join("rustup"))) {
+            debug!("rustup not found, assuming the {target} target is installed");
+            return Ok(());
+        }
+        ctx.pr.set_message(format!("rustup target add {target}"));
+        CmdLineRunner::new("rustup")
+            .arg("target")
+            .arg("add")
+            .arg(target)
+            .with_pr(ctx.pr.as_ref())
+            .envs(env)
+            .prepend_path(ctx.ts.list_paths())?
+            .execute()
+    }
+
+    /// the `registry` option of this tool in the config, needed to list versions from it
+    fn registry(&self) -> eyre::Result<Option<String>> {
+        let config = Config::get();
+        let registry = config
+            .get_tool_request_set()?
+            .iter()
+            .filter(|(fa, _, _)| **fa == self.fa)
+            .flat_map(|(_, trs, _)| trs)
+            .find_map(|tr| tr.options().get("registry").cloned());
+        Ok(registry)
+    }
+
+    /// e.g.: cargo:https://github.com/org/tool
+    fn is_git(&self) -> bool {
+        self.name().starts_with("https://") || self.name().starts_with("http://")
+    }
+
+    /// downloads a prebuilt binary from cargo-quickinstall, which is what cargo-binstall falls
+    /// back to when a crate doesn't publish its own binaries
+    fn quickinstall(&self, ctx: &InstallContext) -> eyre::Result<()> {
+        let name = format!("{}-{}", self.name(), ctx.tv.version);
+        let opts = ctx.tv.request.options();
+        let target = opts
+            .get("target")
+            .map(|t| t.as_str())
+            .unwrap_or(built_info::TARGET);
+        let filename = format!("{name}-{target}.tar.gz");
+        let url = format!("{QUICKINSTALL_URL}/{name}/{filename}");
+        let tarball = ctx.tv.download_path().join(&filename);
+        ctx.pr.set_message(format!("downloading {filename}"));
+        HTTP.download_file(&url, &tarball, Some(ctx.pr.as_ref()))?;
+        ctx.pr.set_message(format!("extracting {filename}"));
+        let bin_dir = ctx.tv.install_path().join("bin");
+        file::untar(&tarball, &bin_dir)?;
+        for entry in file::ls(&bin_dir)? {
+            file::make_executable(&entry)?;
+        }
+        Ok(())
     }
 }
 
-fn get_crate_url(n: &str) -> eyre::Result<Url> {
-    let n = n.to_lowercase();
-    let url = match n.len() {
-        1 => format!("https://index.crates.io/1/{n}"),
-        2 => format!("https://index.crates.io/2/{n}"),
-        3 => format!("https://index.crates.io/3/{}/{n}", &n[..1]),
-        _ => format!("https://index.crates.io/{}/{}/{n}", &n[..2], &n[2..4]),
-    };
-    Ok(url.parse()?)
+/// a registry with a sparse index, e.g.: crates.io or one from `[registries]` in .cargo/config.toml
+struct CargoRegistry {
+    index: String,
+    token: Option<String>,
+}
+
+impl CargoRegistry {
+    /// crates.io or `mirrors.crates`
+    fn crates_io() -> Self {
+        let index = mirrors::crates_index().map(|i| {
+            i.trim_start_matches("sparse+")
+                .trim_end_matches('/')
+                .to_string()
+        });
+        Self {
+            index: index.unwrap_or_else(|| "https://index.crates.io".to_string()),
+            token: None,
+        }
+    }
+
+    /// finds a registry the same way cargo does, from CARGO_REGISTRIES_<NAME>_INDEX and
+    /// CARGO_REGISTRIES_<NAME>_TOKEN or from the cargo config and credentials files
+    fn find(name: &str) -> eyre::Result<Self> {
+        let env_name = name.to_uppercase().replace('-', "_");
+        let index = env::var(format!("CARGO_REGISTRIES_{env_name}_INDEX"))
+            .ok()
+            .or_else(|| {
+                cargo_config_files()
+                    .into_iter()
+                    .find_map(|f| registry_config_value(&f, name, "index"))
+            })
+            .ok_or_else(|| {
+                eyre!(
+                    "cargo registry {name} not found, add it to [registries] in .cargo/config.toml"
+                )
+            })?;
+        let index = index
+            .strip_prefix("sparse+")
+            .ok_or_else(|| eyre!("cargo registry {name} must use a sparse index"))?
+            .trim_end_matches('/')
+            .to_string();
+        let token = env::var(format!("CARGO_REGISTRIES_{env_name}_TOKEN"))
+            .ok()
+            .or_else(|| {
+                registry_config_value(&cargo_home().join("credentials.toml"), name, "token")
+            });
+        Ok(Self { index, token })
+    }
+
+    fn list_versions(&self, name: &str) -> eyre::Result<Vec<String>> {
+        let mut headers = HeaderMap::new();
+        if let Some(token) = &self.token {
+            headers.insert(AUTHORIZATION, token.parse()?);
+        }
+        let raw = HTTP_FETCH.get_text_with_headers(self.crate_url(name)?, &headers)?;
+        let stream = Deserializer::from_str(&raw).into_iter::<CrateVersion>();
+        let mut versions = vec![];
+        for v in stream {
+            let v = v?;
+            if !v.yanked {
+                versions.push(v.vers);
+            }
+        }
+        Ok(versions)
+    }
+
+    fn crate_url(&self, n: &str) -> eyre::Result<Url> {
+        let n = n.to_lowercase();
+        let index = &self.index;
+        let url = match n.len() {
+            1 => format!("{index}/1/{n}"),
+            2 => format!("{index}/2/{n}"),
+            3 => format!("{index}/3/{}/{n}", &n[..1]),
+            _ => format!("{index}/{}/{}/{n}", &n[..2], &n[2..4]),
+        };
+        Ok(url.parse()?)
+    }
+}
+
+fn cargo_home() -> PathBuf {
+    env::var_path("CARGO_HOME").unwrap_or_else(|| dirs::HOME.join(".cargo"))
+}
+
+/// .cargo/config.toml files from the current directory up, then the one in CARGO_HOME
+fn cargo_config_files() -> Vec<PathBuf> {
+    let cwd = dirs::CWD.clone().unwrap_or_default();
+    cwd.ancestors()
+        .map(|d| d.join(".cargo"))
+        .chain(std::iter::once(cargo_home()))
+        .flat_map(|d| [d.join("config.toml"), d.join("config")])
+        .filter(|f| f.is_file())
+        .collect()
+}
+
+fn registry_config_value(path: &Path, registry: &str, key: &str) -> Option<String> {
+    let config: toml::Table = file::read_to_string(path).ok()?.parse().ok()?;
+    config
+        .get("registries")?
+        .get(registry)?
+        .get(key)?
+        .as_str()
+        .map(|s| s.to_string())
+}
+
+/// tags of a git repository are used as its versions
+fn list_git_tags(url: &str) -> eyre::Result<Vec<String>> {
+    let output = cmd!("git", "ls-remote", "--tags", "--refs", url).read()?;
+    let tags = output
+        .lines()
+        .filter_map(|l| l.split_once("refs/tags/"))
+        .map(|(_, tag)| tag.to_string())
+        .unique()
+        .sorted_by_cached_key(|t| Versioning::new(t.trim_start_matches('v')))
+        .collect();
+    Ok(tags)
+}
+
+/// flags for `cargo install` from the `features` and `default_features` tool options
+fn cargo_features_args(opts: &ToolVersionOptions) -> Vec<String> {
+    let mut args = vec![];
+    if let Some(features) = opts.get("features").filter(|f| !f.is_empty()) {
+        args.push("--features".to_string());
+        args.push(features.clone());
+    }
+    if opts.get("default_features").is_some_and(|v| v == "false") {
+        args.push("--no-default-features".to_string());
+    }
+    args
+}
+
+/// flags for `cargo install` from the `features`, `default_features`, and `locked` tool options
+fn cargo_install_args(opts: &ToolVersionOptions) -> Vec<String> {
+    let mut args = cargo_features_args(opts);
+    if opts.get("locked").is_some_and(|v| v == "true") {
+        args.push("--locked".to_string());
+    }
+    args
+}
+
+fn registry_args(opts: &ToolVersionOptions) -> Vec<String> {
+    match (opts.get("registry"), mirrors::crates_index()) {
+        (Some(registry), _) => vec!["--registry".to_string(), registry.clone()],
+        (None, Some(index)) => vec!["--index".to_string(), index],
+        (None, None) => vec![],
+    }
+}
+
+/// maps a version like `branch:main` or `rev:abc123` to the `cargo install` flag for it,
+/// anything else is treated as a tag
+fn git_ref_arg(version: &str) -> (&'static str, &str) {
+    match version.split_once(':') {
+        Some(("branch", b)) => ("--branch", b),
+        Some(("rev", r)) => ("--rev", r),
+        Some(("tag", t)) => ("--tag", t),
+        _ => ("--tag", version),
+    }
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -110,3 +375,79 @@ struct CrateVersion {
     vers: String,
     yanked: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::toolset::ToolVersionOptions;
+    use crate::{dirs, file};
+
+    use super::{
+        cargo_features_args, cargo_install_args, git_ref_arg, registry_config_value, CargoRegistry,
+    };
+
+    #[test]
+    fn test_git_ref_arg() {
+        assert_eq!(git_ref_arg("branch:main"), ("--branch", "main"));
+        assert_eq!(git_ref_arg("rev:abc123"), ("--rev", "abc123"));
+        assert_eq!(git_ref_arg("tag:v1.0.0"), ("--tag", "v1.0.0"));
+        assert_eq!(git_ref_arg("v1.0.0"), ("--tag", "v1.0.0"));
+    }
+
+    #[test]
+    fn test_cargo_install_args() {
+        let opts = ToolVersionOptions::from([
+            ("features".to_string(), "postgres,rustls".to_string()),
+            ("default_features".to_string(), "false".to_string()),
+            ("locked".to_string(), "true".to_string()),
+        ]);
+        assert_eq!(
+            cargo_install_args(&opts),
+            vec![
+                "--features",
+                "postgres,rustls",
+                "--no-default-features",
+                "--locked"
+            ]
+        );
+        assert!(cargo_install_args(&ToolVersionOptions::new()).is_empty());
+        let opts = ToolVersionOptions::from([("locked".to_string(), "true".to_string())]);
+        assert!(cargo_features_args(&opts).is_empty());
+    }
+
+    #[test]
+    fn test_crate_url() {
+        let registry = CargoRegistry {
+            index: "https://kellnr.example.com/api/v1/cratesio".to_string(),
+            token: None,
+        };
+        assert_eq!(
+            registry.crate_url("sqlx-cli").unwrap().as_str(),
+            "https://kellnr.example.com/api/v1/cratesio/sq/lx/sqlx-cli"
+        );
+        assert_eq!(
+            CargoRegistry::crates_io()
+                .crate_url("ubi")
+                .unwrap()
+                .as_str(),
+            "https://index.crates.io/3/u/ubi"
+        );
+    }
+
+    #[test]
+    fn test_registry_config_value() {
+        let path = dirs::HOME.join("cargo-config-test.toml");
+        file::write(
+            &path,
+            "[registries.kellnr]\nindex = \"sparse+https://kellnr.example.com/api/v1/crates/\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            registry_config_value(&path, "kellnr", "index").unwrap(),
+            "sparse+https://kellnr.example.com/api/v1/crates/"
+        );
+        assert_eq!(registry_config_value(&path, "other", "index"), None);
+        file::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,208 @@
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::str::FromStr;
+
+use eyre::eyre;
+use itertools::Itertools;
+use serde_json::Value;
+use versions::Versioning;
+
+use crate::backend::{Backend, BackendType};
+use crate::cache::CacheManager;
+use crate::cli::args::BackendArg;
+use crate::cmd::CmdLineRunner;
+use crate::config::{Config, Settings};
+use crate::http::HTTP_FETCH;
+use crate::install_context::InstallContext;
+use crate::toolset::ToolRequest;
+
+/// Installs CLIs with `deno install` into a per-version root
+///
+/// deno:jsr:@std/http
+/// deno:npm:cowsay
+/// deno:https://deno.land/std/http/file_server.ts
+#[derive(Debug)]
+pub struct DenoBackend {
+    fa: BackendArg,
+    remote_version_cache: CacheManager<Vec<String>>,
+    latest_version_cache: CacheManager<Option<String>>,
+}
+
+impl Backend for DenoBackend {
+    fn get_type(&self) -> BackendType {
+        BackendType::Deno
+    }
+
+    fn fa(&self) -> &BackendArg {
+        &self.fa
+    }
+
+    fn get_dependencies(&self, _tvr: &ToolRequest) -> eyre::Result<Vec<BackendArg>> {
+        Ok(vec!["deno".into()])
+    }
+
+    fn _list_remote_versions(&self) -> eyre::Result<Vec<String>> {
+        self.remote_version_cache
+            .get_or_try_init(|| match self.name().parse()? {
+                DenoRequest::Jsr(package) => {
+                    let meta: JsrMeta = HTTP_FETCH.json(jsr_meta_url(&package))?;
+                    let versions = meta
+                        .versions
+                        .into_iter()
+                        .filter(|(_, v)| !v.yanked)
+                        .map(|(v, _)| v)
+                        .sorted_by_cached_key(|v| Versioning::new(v))
+                        .collect();
+                    Ok(versions)
+                }
+                DenoRequest::Npm(package) => {
+                    let url = format!("https://registry.npmjs.org/{package}");
+                    let data: Value = HTTP_FETCH.json(url)?;
+                    let versions = data["versions"]
+                        .as_object()
+                        .map(|v| v.keys().cloned().collect_vec())
+                        .unwrap_or_default()
+                        .into_iter()
+                        .sorted_by_cached_key(|v| Versioning::new(v))
+                        .collect();
+                    Ok(versions)
+                }
+                DenoRequest::Url(_) => Ok(vec!["latest".to_string()]),
+            })
+            .cloned()
+    }
+
+    fn latest_stable_version(&self) -> eyre::Result<Option<String>> {
+        self.latest_version_cache
+            .get_or_try_init(|| match self.name().parse()? {
+                DenoRequest::Jsr(package) => {
+                    let meta: JsrMeta = HTTP_FETCH.json(jsr_meta_url(&package))?;
+                    Ok(meta.latest)
+                }
+                _ => self.latest_version(Some("latest".into())),
+            })
+            .cloned()
+    }
+
+    fn install_version_impl(&self, ctx: &InstallContext) -> eyre::Result<()> {
+        let config = Config::try_get()?;
+        let settings = Settings::get();
+        settings.ensure_experimental("deno backend")?;
+        let request: DenoRequest = self.name().parse()?;
+        let opts = ctx.tv.request.options();
+
+        let mut cmd = CmdLineRunner::new("deno")
+            .arg("install")
+            .arg("--global")
+            .arg("--force")
+            .arg("--root")
+            .arg(ctx.tv.install_path());
+        if let Some(name) = opts.get("name") {
+            cmd = cmd.arg("--name").arg(name);
+        }
+        // e.g.: permissions = "--allow-net --allow-read"
+        if let Some(permissions) = opts.get("permissions") {
+            cmd = cmd.args(shell_words::split(permissions)?);
+        }
+        cmd.arg(request.deno_request(&ctx.tv.version))
+            .with_pr(ctx.pr.as_ref())
+            .env("DENO_INSTALL_ROOT", ctx.tv.install_path())
+            .envs(ctx.ts.env_with_path(&config)?)
+            .prepend_path(ctx.ts.list_paths())?
+            .execute()?;
+
+        Ok(())
+    }
+}
+
+impl DenoBackend {
+    pub fn new(name: String) -> Self {
+        let fa = BackendArg::new(BackendType::Deno, &name);
+        Self {
+            remote_version_cache: CacheManager::new(
+                fa.cache_path.join("remote_versions-$KEY.msgpack.z"),
+            ),
+            latest_version_cache: CacheManager::new(
+                fa.cache_path.join("latest_version-$KEY.msgpack.z"),
+            ),
+            fa,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum DenoRequest {
+    /// jsr:@std/http
+    Jsr(String),
+    /// npm:cowsay
+    Npm(String),
+    /// https://deno.land/std/http/file_server.ts
+    Url(String),
+}
+
+impl DenoRequest {
+    fn deno_request(&self, v: &str) -> String {
+        match (self, v) {
+            (DenoRequest::Url(url), _) => url.to_string(),
+            (DenoRequest::Jsr(package), "latest") => format!("jsr:{package}"),
+            (DenoRequest::Npm(package), "latest") => format!("npm:{package}"),
+            (DenoRequest::Jsr(package), v) => format!("jsr:{package}@{v}"),
+            (DenoRequest::Npm(package), v) => format!("npm:{package}@{v}"),
+        }
+    }
+}
+
+impl FromStr for DenoRequest {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some(("jsr", package)) => Ok(DenoRequest::Jsr(package.to_string())),
+            Some(("npm", package)) => Ok(DenoRequest::Npm(package.to_string())),
+            Some(("http" | "https" | "file", _)) => Ok(DenoRequest::Url(s.to_string())),
+            _ => Err(eyre!(
+                "invalid deno package: {s}, expected jsr:@scope/name, npm:name, or a url"
+            )),
+        }
+    }
+}
+
+fn jsr_meta_url(package: &str) -> String {
+    format!("https://jsr.io/{package}/meta.json")
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct JsrMeta {
+    latest: Option<String>,
+    versions: BTreeMap<String, JsrVersion>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct JsrVersion {
+    #[serde(default)]
+    yanked: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::DenoRequest;
+
+    #[test]
+    fn test_deno_request() {
+        let t = |name: &str, version, expected| {
+            let request: DenoRequest = name.parse().unwrap();
+            assert_eq!(request.deno_request(version), expected);
+        };
+        t("jsr:@std/http", "latest", "jsr:@std/http");
+        t("jsr:@std/http", "1.0.0", "jsr:@std/http@1.0.0");
+        t("npm:cowsay", "1.6.0", "npm:cowsay@1.6.0");
+        t(
+            "https://deno.land/std/http/file_server.ts",
+            "latest",
+            "https://deno.land/std/http/file_server.ts",
+        );
+        assert!("cowsay".parse::<DenoRequest>().is_err());
+    }
+}
@@ -0,0 +1,132 @@
+use std::fmt::Debug;
+
+use eyre::eyre;
+use itertools::Itertools;
+
+use crate::backend::release_assets::{install_asset, pick_asset};
+use crate::backend::{Backend, BackendType};
+use crate::cache::CacheManager;
+use crate::cli::args::BackendArg;
+use crate::cli::version::NATIVE_ARCH;
+use crate::config::Settings;
+use crate::env::MISE_GITEA_HOST;
+use crate::gitea;
+use crate::http::HTTP;
+use crate::install_context::InstallContext;
+
+/// Installs release assets from codeberg.org or a Gitea/Forgejo instance set with MISE_GITEA_HOST,
+/// other instances are prefixed to the repository
+///
+/// gitea:owner/repo
+/// gitea:git.example.com/owner/repo
+#[derive(Debug)]
+pub struct GiteaBackend {
+    fa: BackendArg,
+    remote_version_cache: CacheManager<Vec<String>>,
+}
+
+impl Backend for GiteaBackend {
+    fn get_type(&self) -> BackendType {
+        BackendType::Gitea
+    }
+
+    fn fa(&self) -> &BackendArg {
+        &self.fa
+    }
+
+    fn _list_remote_versions(&self) -> eyre::Result<Vec<String>> {
+        let (host, repo) = self.host_and_repo();
+        self.remote_version_cache
+            .get_or_try_init(|| {
+                Ok(gitea::list_releases(host, repo)?
+                    .into_iter()
+                    .filter(|r| !r.prerelease)
+                    .map(|r| r.tag_name)
+                    .rev()
+                    .collect())
+            })
+            .cloned()
+    }
+
+    fn install_version_impl(&self, ctx: &InstallContext) -> eyre::Result<()> {
+        let settings = Settings::get();
+        settings.ensure_experimental("gitea backend")?;
+        let opts = ctx.tv.request.options();
+        let (host, repo) = self.host_and_repo();
+        let release = gitea::get_release(host, repo, &ctx.tv.version)?;
+        let names = release.assets.iter().map(|a| a.name.as_str()).collect_vec();
+        let name = match opts.get("asset") {
+            Some(asset) => asset.as_str(),
+            None => pick_asset(&names).ok_or_else(|| {
+                eyre!(
+                    "no asset found for {}-{} in {}@{}, set the asset tool option to one of: {}",
+                    std::env::consts::OS,
+                    *NATIVE_ARCH,
+                    self.name(),
+                    ctx.tv.version,
+                    names.join(", ")
+                )
+            })?,
+        };
+        let asset = release
+            .assets
+            .iter()
+            .find(|a| a.name == name)
+            .ok_or_else(|| eyre!("asset {name} not found in {}", self.name()))?;
+
+        let tarball = ctx.tv.download_path().join(&asset.name);
+        ctx.pr.set_message(format!("downloading {}", asset.name));
+        HTTP.download_file(&asset.browser_download_url, &tarball, Some(ctx.pr.as_ref()))?;
+
+        let bin_name = opts.get("bin").cloned().unwrap_or_else(|| self.bin_name());
+        install_asset(ctx, &tarball, &bin_name)
+    }
+}
+
+impl GiteaBackend {
+    pub fn new(name: String) -> Self {
+        let fa = BackendArg::new(BackendType::Gitea, &name);
+        Self {
+            remote_version_cache: CacheManager::new(
+                fa.cache_path.join("remote_versions-$KEY.msgpack.z"),
+            ),
+            fa,
+        }
+    }
+
+    fn host_and_repo(&self) -> (&str, &str) {
+        host_and_repo(self.name())
+    }
+
+    fn bin_name(&self) -> String {
+        let name = self.name().trim_end_matches('/');
+        name.rsplit('/').next().unwrap_or(name).to_string()
+    }
+}
+
+/// repositories on gitea are always owner/repo so a third segment in front is the host
+fn host_and_repo(name: &str) -> (&str, &str) {
+    match name.split_once('/') {
+        Some((host, repo)) if repo.contains('/') => (host, repo),
+        _ => (&MISE_GITEA_HOST, name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_host_and_repo() {
+        assert_eq!(
+            host_and_repo("git.example.com/owner/repo"),
+            ("git.example.com", "owner/repo")
+        );
+        assert_eq!(
+            host_and_repo("owner/repo"),
+            (MISE_GITEA_HOST.as_str(), "owner/repo")
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use std::fmt::Debug;
+
+use eyre::eyre;
+use itertools::Itertools;
+
+use crate::backend::release_assets::{install_asset, pick_asset};
+use crate::backend::{Backend, BackendType};
+use crate::cache::CacheManager;
+use crate::cli::args::BackendArg;
+use crate::cli::version::NATIVE_ARCH;
+use crate::config::Settings;
+use crate::env::MISE_GITLAB_HOST;
+use crate::gitlab;
+use crate::http::HTTP;
+use crate::install_context::InstallContext;
+
+/// Installs release assets from gitlab.com or a self-hosted instance set with MISE_GITLAB_HOST
+///
+/// gitlab:group/project
+/// gitlab:group/subgroup/project
+#[derive(Debug)]
+pub struct GitlabBackend {
+    fa: BackendArg,
+    remote_version_cache: CacheManager<Vec<String>>,
+}
+
+impl Backend for GitlabBackend {
+    fn get_type(&self) -> BackendType {
+        BackendType::Gitlab
+    }
+
+    fn fa(&self) -> &BackendArg {
+        &self.fa
+    }
+
+    fn _list_remote_versions(&self) -> eyre::Result<Vec<String>> {
+        self.remote_version_cache
+            .get_or_try_init(|| {
+                Ok(gitlab::list_releases(&MISE_GITLAB_HOST, self.name())?
+                    .into_iter()
+                    .filter(|r| !r.upcoming_release)
+                    .map(|r| r.tag_name)
+                    .rev()
+                    .collect())
+            })
+            .cloned()
+    }
+
+    fn install_version_impl(&self, ctx: &InstallContext) -> eyre::Result<()> {
+        let settings = Settings::get();
+        settings.ensure_experimental("gitlab backend")?;
+        let opts = ctx.tv.request.options();
+        let release = gitlab::get_release(&MISE_GITLAB_HOST, self.name(), &ctx.tv.version)?;
+        let names = release
+            .assets
+            .links
+            .iter()
+            .map(|l| l.name.as_str())
+            .collect_vec();
+        let name = match opts.get("asset") {
+            Some(asset) => asset.as_str(),
+            None => pick_asset(&names).ok_or_else(|| {
+                eyre!(
+                    "no asset found for {}-{} in {}@{}, set the asset tool option to one of: {}",
+                    std::env::consts::OS,
+                    *NATIVE_ARCH,
+                    self.name(),
+                    ctx.tv.version,
+                    names.join(", ")
+                )
+            })?,
+        };
+        let link = release
+            .assets
+            .links
+            .iter()
+            .find(|l| l.name == name)
+            .ok_or_else(|| eyre!("asset {name} not found in {}", self.name()))?;
+
+        let tarball = ctx.tv.download_path().join(&link.name);
+        ctx.pr.set_message(format!("downloading {}", link.name));
+        HTTP.download_file(link.download_url(), &tarball, Some(ctx.pr.as_ref()))?;
+
+        let bin_name = opts.get("bin").cloned().unwrap_or_else(|| self.bin_name());
+        install_asset(ctx, &tarball, &bin_name)
+    }
+}
+
+impl GitlabBackend {
+    pub fn new(name: String) -> Self {
+        let fa = BackendArg::new(BackendType::Gitlab, &name);
+        Self {
+            remote_version_cache: CacheManager::new(
+                fa.cache_path.join("remote_versions-$KEY.msgpack.z"),
+            ),
+            fa,
+        }
+    }
+
+    fn bin_name(&self) -> String {
+        let name = self.name().trim_end_matches('/');
+        name.rsplit('/').next().unwrap_or(name).to_string()
+    }
+}
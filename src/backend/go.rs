@@ -5,8 +5,9 @@ use crate::cache::CacheManager;
 use crate::cli::args::BackendArg;
 use crate::cmd::CmdLineRunner;
 use crate::config::Settings;
+use crate::env;
 use crate::install_context::InstallContext;
-use crate::toolset::ToolRequest;
+use crate::toolset::{ToolRequest, ToolVersionOptions};
 
 #[derive(Debug)]
 pub struct GoBackend {
@@ -30,7 +31,7 @@ impl Backend for GoBackend {
     fn _list_remote_versions(&self) -> eyre::Result<Vec<String>> {
         self.remote_version_cache
             .get_or_try_init(|| {
-                let mut mod_path = Some(self.name());
+                let mut mod_path = Some(split_module(self.name()).0);
                 let env = self.dependency_env()?;
 
                 while let Some(cur_mod_path) = mod_path {
@@ -70,13 +71,21 @@ impl Backend for GoBackend {
             ctx.tv.version.clone()
         };
 
-        CmdLineRunner::new("go")
+        let opts = ctx.tv.request.options();
+        let mut cmd = CmdLineRunner::new("go")
             .arg("install")
-            .arg(&format!("{}@{}", self.name(), version))
+            .args(go_install_args(&opts, &ctx.tv.version))
+            .arg(format!("{}@{}", package(self.name()), version))
             .with_pr(ctx.pr.as_ref())
             .envs(self.dependency_env()?)
-            .env("GOBIN", ctx.tv.install_path().join("bin"))
-            .execute()?;
+            .env("GOBIN", ctx.tv.install_path().join("bin"));
+        if let Some(goflags) = opts.get("goflags") {
+            cmd = cmd.env("GOFLAGS", goflags);
+        } else if settings.offline && env::var_os("GOFLAGS").is_none() {
+            // only for `go install` since it would change how go builds modules everywhere else
+            cmd = cmd.env("GOFLAGS", "-mod=mod");
+        }
+        cmd.execute()?;
 
         Ok(())
     }
@@ -94,6 +103,39 @@ impl GoBackend {
     }
 }
 
+/// splits `github.com/org/mono//cmd/tool` into the module and the path of the command inside it
+fn split_module(name: &str) -> (&str, Option<&str>) {
+    match name.split_once("//") {
+        Some((module, sub)) => (module, Some(sub.trim_matches('/'))),
+        None => (name, None),
+    }
+}
+
+/// the package to build, versions are resolved from the module but `go install` builds the
+/// nested package
+fn package(name: &str) -> String {
+    match split_module(name) {
+        (module, Some(sub)) if !sub.is_empty() => format!("{module}/{sub}"),
+        (module, _) => module.to_string(),
+    }
+}
+
+/// flags for `go install` from the `tags` and `ldflags` tool options,
+/// `{version}` in ldflags is replaced with the version being installed
+fn go_install_args(opts: &ToolVersionOptions, version: &str) -> Vec<String> {
+    let mut args = vec![];
+    if let Some(tags) = opts.get("tags").filter(|t| !t.is_empty()) {
+        args.push(format!("-tags={tags}"));
+    }
+    if let Some(ldflags) = opts.get("ldflags").filter(|l| !l.is_empty()) {
+        args.push(format!(
+            "-ldflags={}",
+            ldflags.replace("{version}", version)
+        ));
+    }
+    args
+}
+
 fn trim_after_last_slash(s: &str) -> Option<&str> {
     match s.rsplit_once('/') {
         Some((new_path, _)) => Some(new_path),
@@ -106,3 +148,45 @@ fn trim_after_last_slash(s: &str) -> Option<&str> {
 pub struct GoModInfo {
     versions: Vec<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_package() {
+        assert_eq!(
+            split_module("github.com/org/mono//cmd/tool"),
+            ("github.com/org/mono", Some("cmd/tool"))
+        );
+        assert_eq!(
+            package("github.com/org/mono//cmd/tool"),
+            "github.com/org/mono/cmd/tool"
+        );
+        assert_eq!(
+            package("github.com/DarthSim/hivemind"),
+            "github.com/DarthSim/hivemind"
+        );
+    }
+
+    #[test]
+    fn test_go_install_args() {
+        let opts = ToolVersionOptions::from([
+            ("tags".to_string(), "extended,netgo".to_string()),
+            (
+                "ldflags".to_string(),
+                "-s -w -X main.version={version}".to_string(),
+            ),
+        ]);
+        assert_eq!(
+            go_install_args(&opts, "1.2.3"),
+            vec![
+                "-tags=extended,netgo",
+                "-ldflags=-s -w -X main.version=1.2.3"
+            ]
+        );
+        assert!(go_install_args(&Default::default(), "1.2.3").is_empty());
+    }
+}
@@ -0,0 +1,204 @@
+use std::fmt::Debug;
+use std::path::PathBuf;
+
+use eyre::bail;
+
+use crate::backend::{Backend, BackendType};
+use crate::cli::args::BackendArg;
+use crate::cli::version::{ARCH, OS};
+use crate::cmd::CmdLineRunner;
+use crate::config::Settings;
+use crate::file::display_path;
+use crate::hash::ensure_checksum_sha256;
+use crate::http::HTTP;
+use crate::install_context::InstallContext;
+use crate::toolset::{ToolRequest, ToolVersion, ToolVersionOptions};
+use crate::{archive, file, hash, signature};
+
+/// Downloads archives from a url built from tool options
+///
+/// [tools]
+/// "http:mytool" = { version = "1.2.3", url = "https://example.com/{version}/mytool-{os}-{arch}.tar.gz" }
+#[derive(Debug)]
+pub struct HttpBackend {
+    fa: BackendArg,
+}
+
+impl Backend for HttpBackend {
+    fn get_type(&self) -> BackendType {
+        BackendType::Http
+    }
+
+    fn fa(&self) -> &BackendArg {
+        &self.fa
+    }
+
+    /// there is no way to list versions from an arbitrary url so the requested version is used as-is
+    fn _list_remote_versions(&self) -> eyre::Result<Vec<String>> {
+        Ok(vec![])
+    }
+
+    fn install_version_impl(&self, ctx: &InstallContext) -> eyre::Result<()> {
+        let settings = Settings::get();
+        settings.ensure_experimental("http backend")?;
+        let opts = ctx.tv.request.options();
+        let url = match opts.get("url") {
+            Some(url) => render(url, &ctx.tv.version, &opts),
+            None => bail!(
+                "{} requires a url tool option, e.g.: url = \"https://example.com/{{version}}/tool-{{os}}-{{arch}}.tar.gz\"",
+                self.fa
+            ),
+        };
+        let filename = url
+            .split(['?', '#'])
+            .next()
+            .and_then(|u| u.rsplit('/').next())
+            .filter(|f| !f.is_empty())
+            .unwrap_or(self.name())
+            .to_string();
+        let tarball = ctx.tv.download_path().join(&filename);
+
+        ctx.pr.set_message(format!("downloading {filename}"));
+        HTTP.download_file(&url, &tarball, Some(ctx.pr.as_ref()))?;
+
+        match checksum(&opts) {
+            Some(checksum) => {
+                ctx.pr.set_message(format!("verifying {filename}"));
+                let checksum = checksum.trim_start_matches("sha256:");
+                ensure_checksum_sha256(&tarball, checksum, Some(ctx.pr.as_ref()))?;
+            }
+            None => {
+                let actual = hash::file_hash_sha256(&tarball)?;
+                warn!(
+                    "no checksum for {filename}, add checksum = \"sha256:{actual}\" to verify it"
+                );
+            }
+        }
+
+        if signature::is_required(&opts) {
+            let signature_urls = match opts.get("signature_url") {
+                Some(signature_url) => vec![render(signature_url, &ctx.tv.version, &opts)],
+                None => signature::Method::from_options(&opts)?
+                    .map(|m| m.signature_names(&url))
+                    .unwrap_or_default(),
+            };
+            signature::verify(&ctx.tv, &tarball, &signature_urls, ctx.pr.as_ref())?;
+        }
+
+        ctx.pr.set_message(format!("extracting {filename}"));
+        let install_path = ctx.tv.install_path();
+        if archive::is_archive(&tarball) {
+            archive::extract(&tarball, &install_path)?;
+        } else if filename.contains(".tar.") {
+            CmdLineRunner::new("tar")
+                .arg("-xf")
+                .arg(&tarball)
+                .arg("-C")
+                .arg(&install_path)
+                .with_pr(ctx.pr.as_ref())
+                .execute()?;
+        } else {
+            let bin_name = opts.get("bin").cloned().unwrap_or_else(|| self.bin_name());
+            let bin = install_path.join("bin").join(bin_name);
+            file::create_dir_all(bin.parent().unwrap())?;
+            file::copy(&tarball, &bin)?;
+            file::make_executable(&bin)?;
+        }
+
+        let bin_paths = self.list_bin_paths(&ctx.tv)?;
+        if !bin_paths.iter().any(|p| p.exists()) {
+            warn!(
+                "{} does not exist, set the bin_path tool option to the directory containing the binaries",
+                bin_paths.iter().map(display_path).collect::<Vec<_>>().join(", ")
+            );
+        }
+
+        Ok(())
+    }
+
+    fn list_bin_paths(&self, tv: &ToolVersion) -> eyre::Result<Vec<PathBuf>> {
+        match tv.request {
+            ToolRequest::System(_) => Ok(vec![]),
+            _ => {
+                let opts = tv.request.options();
+                let bin_path = match opts.get("bin_path") {
+                    Some(p) => render(p, &tv.version, &opts),
+                    None => "bin".to_string(),
+                };
+                Ok(vec![tv.install_short_path().join(bin_path)])
+            }
+        }
+    }
+}
+
+impl HttpBackend {
+    pub fn new(name: String) -> Self {
+        Self {
+            fa: BackendArg::new(BackendType::Http, &name),
+        }
+    }
+
+    fn bin_name(&self) -> String {
+        let name = self.name().trim_end_matches('/');
+        name.rsplit('/').next().unwrap_or(name).to_string()
+    }
+}
+
+/// replaces {version}, {os}, and {arch} in a url or path, os and arch can be remapped with
+/// options like `os_macos = "darwin"` or `arch_x64 = "amd64"`
+fn render(template: &str, version: &str, opts: &ToolVersionOptions) -> String {
+    let os = opts
+        .get(&format!("os_{}", *OS))
+        .cloned()
+        .unwrap_or_else(|| OS.to_string());
+    let arch = opts
+        .get(&format!("arch_{}", *ARCH))
+        .cloned()
+        .unwrap_or_else(|| ARCH.to_string());
+    template
+        .replace("{version}", version)
+        .replace("{os}", &os)
+        .replace("{arch}", &arch)
+}
+
+/// `checksum_<os>_<arch>` takes precedence over `checksum` so each platform can be verified
+fn checksum(opts: &ToolVersionOptions) -> Option<&String> {
+    opts.get(&format!("checksum_{}_{}", *OS, *ARCH))
+        .or_else(|| opts.get("checksum"))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::cli::version::{ARCH, OS};
+    use crate::toolset::ToolVersionOptions;
+
+    use super::{checksum, render};
+
+    #[test]
+    fn test_render() {
+        let mut opts = ToolVersionOptions::new();
+        assert_eq!(
+            render(
+                "https://example.com/{version}/tool-{os}-{arch}.tar.gz",
+                "1.2.3",
+                &opts
+            ),
+            format!("https://example.com/1.2.3/tool-{}-{}.tar.gz", *OS, *ARCH)
+        );
+        opts.insert(format!("os_{}", *OS), "myos".into());
+        opts.insert(format!("arch_{}", *ARCH), "myarch".into());
+        assert_eq!(render("{os}/{arch}", "1.2.3", &opts), "myos/myarch");
+    }
+
+    #[test]
+    fn test_checksum() {
+        let mut opts = ToolVersionOptions::new();
+        assert_eq!(checksum(&opts), None);
+        opts.insert("checksum".into(), "sha256:abc".into());
+        assert_eq!(checksum(&opts).unwrap(), "sha256:abc");
+        opts.insert(format!("checksum_{}_{}", *OS, *ARCH), "sha256:def".into());
+        assert_eq!(checksum(&opts).unwrap(), "sha256:def");
+    }
+}
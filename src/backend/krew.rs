@@ -0,0 +1,300 @@
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::path::Path;
+
+use eyre::{bail, eyre};
+use globwalk::GlobWalkerBuilder;
+use itertools::Itertools;
+use serde_derive::Deserialize;
+use versions::Versioning;
+
+use crate::backend::{Backend, BackendType};
+use crate::cache::CacheManager;
+use crate::cli::args::BackendArg;
+use crate::config::Settings;
+use crate::git::Git;
+use crate::hash::ensure_checksum_sha256;
+use crate::http::HTTP;
+use crate::install_context::InstallContext;
+// krew uses the same GOOS/GOARCH names as oci platforms
+use crate::oci::{arch, os};
+use crate::{archive, dirs, file};
+
+const KREW_INDEX: &str = "https://github.com/kubernetes-sigs/krew-index.git";
+
+/// Installs kubectl plugins from the krew index as kubectl-<name>
+///
+/// krew:ctx
+/// krew:neat
+#[derive(Debug)]
+pub struct KrewBackend {
+    fa: BackendArg,
+    remote_version_cache: CacheManager<Vec<String>>,
+}
+
+impl Backend for KrewBackend {
+    fn get_type(&self) -> BackendType {
+        BackendType::Krew
+    }
+
+    fn fa(&self) -> &BackendArg {
+        &self.fa
+    }
+
+    /// every version that has been in the index, found from the history of its manifest
+    fn _list_remote_versions(&self) -> eyre::Result<Vec<String>> {
+        self.remote_version_cache
+            .get_or_try_init(|| {
+                let git = index(true)?;
+                let versions = self
+                    .manifests(&git)?
+                    .map(|m| m.spec.version)
+                    .unique()
+                    .sorted_by_cached_key(|v| Versioning::new(v.trim_start_matches('v')))
+                    .collect();
+                Ok(versions)
+            })
+            .cloned()
+    }
+
+    fn install_version_impl(&self, ctx: &InstallContext) -> eyre::Result<()> {
+        let settings = Settings::get();
+        settings.ensure_experimental("krew backend")?;
+        let git = index(false)?;
+        let version = &ctx.tv.version;
+        let manifest = self
+            .manifests(&git)?
+            .find(|m| m.spec.version.trim_start_matches('v') == version.trim_start_matches('v'))
+            .ok_or_else(|| eyre!("{} {version} not found in the krew index", self.name()))?;
+        let platform = manifest
+            .spec
+            .platforms
+            .into_iter()
+            .find(|p| p.selector.matches(os(), arch()))
+            .ok_or_else(|| {
+                eyre!(
+                    "{} {version} is not available for {}/{}",
+                    self.name(),
+                    os(),
+                    arch()
+                )
+            })?;
+
+        let filename = platform
+            .uri
+            .rsplit('/')
+            .next()
+            .unwrap_or(self.name())
+            .to_string();
+        let tarball = ctx.tv.download_path().join(&filename);
+        ctx.pr.set_message(format!("downloading {filename}"));
+        HTTP.download_file(&platform.uri, &tarball, Some(ctx.pr.as_ref()))?;
+        ctx.pr.set_message(format!("verifying {filename}"));
+        ensure_checksum_sha256(&tarball, &platform.sha256, Some(ctx.pr.as_ref()))?;
+
+        ctx.pr.set_message(format!("extracting {filename}"));
+        let extracted = ctx.tv.download_path().join("extracted");
+        file::remove_all(&extracted)?;
+        if archive::is_archive(&tarball) {
+            archive::extract(&tarball, &extracted)?;
+        } else {
+            file::untar(&tarball, &extracted)?;
+        }
+
+        let plugin_dir = ctx.tv.install_path().join("plugin");
+        if platform.files.is_empty() {
+            file::copy_dir_all(&extracted, &plugin_dir)?;
+        }
+        for op in &platform.files {
+            copy_files(&extracted, &op.from, &plugin_dir.join(&op.to))?;
+        }
+
+        let bin = plugin_dir.join(&platform.bin);
+        if !bin.exists() {
+            bail!("{} does not contain {}", filename, platform.bin);
+        }
+        file::make_executable(&bin)?;
+        let bin_dir = ctx.tv.install_path().join("bin");
+        file::create_dir_all(&bin_dir)?;
+        let link_name = format!("kubectl-{}", self.name().replace('-', "_"));
+        if cfg!(windows) {
+            file::copy(&bin, bin_dir.join(format!("{link_name}.exe")))?;
+        } else {
+            file::make_symlink(&bin, &bin_dir.join(link_name))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl KrewBackend {
+    pub fn new(name: String) -> Self {
+        let fa = BackendArg::new(BackendType::Krew, &name);
+        Self {
+            remote_version_cache: CacheManager::new(
+                fa.cache_path.join("remote_versions-$KEY.msgpack.z"),
+            ),
+            fa,
+        }
+    }
+
+    /// manifests of this plugin from newest to oldest, ones that fail to parse are skipped
+    fn manifests<'a>(
+        &'a self,
+        git: &'a Git,
+    ) -> eyre::Result<impl Iterator<Item = KrewPlugin> + 'a> {
+        let path = format!("plugins/{}.yaml", self.name());
+        let history = git.file_history(&path)?;
+        if history.is_empty() {
+            bail!("{} not found in the krew index", self.name());
+        }
+        Ok(history.into_iter().filter_map(move |rev| {
+            let yaml = git.show_file(&rev, &path).ok()?;
+            serde_yaml::from_str(&yaml)
+                .inspect_err(|err| debug!("failed to parse {path} at {rev}: {err}"))
+                .ok()
+        }))
+    }
+}
+
+/// clones the krew index into the cache, it is fetched again when listing versions
+fn index(update: bool) -> eyre::Result<Git> {
+    let git = Git::new(dirs::CACHE.join("krew-index"));
+    if !git.exists() {
+        git.clone(KREW_INDEX)?;
+    } else if update {
+        git.update(None)?;
+    }
+    Ok(git)
+}
+
+/// copies files matching a glob like krew does, matches are placed in `to` by their name
+fn copy_files(src: &Path, from: &str, to: &Path) -> eyre::Result<()> {
+    let walker = GlobWalkerBuilder::from_patterns(src, &[from])
+        .follow_links(true)
+        .build()?;
+    for entry in walker.filter_map(Result::ok) {
+        let dest = to.join(entry.file_name());
+        if entry.file_type().is_dir() {
+            file::copy_dir_all(entry.path(), &dest)?;
+        } else {
+            file::create_dir_all(to)?;
+            file::copy(entry.path(), &dest)?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Deserialize)]
+struct KrewPlugin {
+    spec: KrewSpec,
+}
+
+#[derive(Debug, Deserialize)]
+struct KrewSpec {
+    version: String,
+    #[serde(default)]
+    platforms: Vec<KrewPlatform>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KrewPlatform {
+    #[serde(default)]
+    selector: KrewSelector,
+    uri: String,
+    sha256: String,
+    bin: String,
+    #[serde(default)]
+    files: Vec<KrewFileOperation>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KrewFileOperation {
+    from: String,
+    to: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KrewSelector {
+    #[serde(default)]
+    match_labels: BTreeMap<String, String>,
+    #[serde(default)]
+    match_expressions: Vec<KrewSelectorExpression>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KrewSelectorExpression {
+    key: String,
+    operator: String,
+    #[serde(default)]
+    values: Vec<String>,
+}
+
+impl KrewSelector {
+    fn matches(&self, os: &str, arch: &str) -> bool {
+        let labels = BTreeMap::from([("os", os), ("arch", arch)]);
+        let label = |k: &str| labels.get(k).copied();
+        self.match_labels
+            .iter()
+            .all(|(k, v)| label(k) == Some(v.as_str()))
+            && self.match_expressions.iter().all(|e| {
+                let value = label(&e.key);
+                match e.operator.as_str() {
+                    "In" => value.is_some_and(|v| e.values.iter().any(|e| e == v)),
+                    "NotIn" => !value.is_some_and(|v| e.values.iter().any(|e| e == v)),
+                    "Exists" => value.is_some(),
+                    "DoesNotExist" => value.is_none(),
+                    _ => false,
+                }
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::KrewPlugin;
+
+    #[test]
+    fn test_manifest() {
+        let manifest: KrewPlugin = serde_yaml::from_str(
+            r#"
+apiVersion: krew.googlecontainertools.github.com/v1alpha2
+kind: Plugin
+metadata:
+  name: ctx
+spec:
+  version: v0.9.5
+  platforms:
+  - selector:
+      matchLabels:
+        os: linux
+        arch: amd64
+    uri: https://example.com/kubectx_v0.9.5_linux_x86_64.tar.gz
+    sha256: abc
+    bin: kubectx
+    files:
+    - from: kubectx
+      to: .
+  - selector:
+      matchExpressions:
+      - key: os
+        operator: In
+        values: [darwin, linux]
+    uri: https://example.com/kubectx.tar.gz
+    sha256: def
+    bin: kubectx
+"#,
+        )
+        .unwrap();
+        assert_eq!(manifest.spec.version, "v0.9.5");
+        let platforms = &manifest.spec.platforms;
+        assert!(platforms[0].selector.matches("linux", "amd64"));
+        assert!(!platforms[0].selector.matches("linux", "arm64"));
+        assert!(platforms[1].selector.matches("darwin", "arm64"));
+        assert!(!platforms[1].selector.matches("windows", "amd64"));
+        assert_eq!(platforms[0].files[0].to, ".");
+    }
+}
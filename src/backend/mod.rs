@@ -16,30 +16,57 @@ use strum::IntoEnumIterator;
 use versions::Versioning;
 
 use crate::backend::asdf::Asdf;
+use crate::backend::cabal::CabalBackend;
 use crate::backend::cargo::CargoBackend;
+use crate::backend::deno::DenoBackend;
+use crate::backend::gitea::GiteaBackend;
+use crate::backend::gitlab::GitlabBackend;
+use crate::backend::http::HttpBackend;
+use crate::backend::krew::KrewBackend;
+use crate::backend::oci::OciBackend;
+use crate::backend::pixi::PixiBackend;
+use crate::backend::rscript::RscriptBackend;
 use crate::cli::args::BackendArg;
-use crate::config::{Config, Settings};
+use crate::config::{lockfile, Config, Settings};
 use crate::file::{display_path, remove_all, remove_all_with_warning};
 use crate::install_context::InstallContext;
 use crate::lock_file::LockFile;
 use crate::plugins::core::CORE_PLUGINS;
-use crate::plugins::{PluginType, VERSION_REGEX};
+use crate::plugins::PluginType;
 use crate::runtime_symlinks::is_runtime_symlink;
-use crate::toolset::{ToolRequest, ToolVersion, Toolset};
+use crate::toolset::{
+    PrereleasePolicy, ToolRequest, ToolVersion, Toolset, ToolsetBuilder, VersionRange,
+};
 use crate::ui::multi_progress_report::MultiProgressReport;
 use crate::ui::progress_report::SingleReport;
-use crate::{dirs, file};
+use crate::{binary_cache, dirs, file, mirrors, store, trash};
 
 use self::backend_meta::BackendMeta;
 
 pub mod asdf;
 pub mod backend_meta;
+pub mod cabal;
 pub mod cargo;
+pub mod deno;
+pub mod gitea;
+pub mod gitlab;
 pub mod go;
+pub mod http;
+pub mod krew;
 pub mod npm;
+pub mod oci;
+pub mod patches;
 pub mod pipx;
+pub mod pixi;
+pub mod postinstall;
+pub mod preuninstall;
+pub mod receipt;
+pub mod release_assets;
+pub mod rscript;
 pub mod spm;
+pub mod system;
 pub mod ubi;
+pub mod wasm;
 
 pub type ABackend = Arc<dyn Backend>;
 pub type BackendMap = BTreeMap<BackendArg, ABackend>;
@@ -61,13 +88,24 @@ pub type BackendList = Vec<ABackend>;
 #[strum(serialize_all = "snake_case")]
 pub enum BackendType {
     Asdf,
+    Cabal,
     Cargo,
     Core,
+    Deno,
+    Gitea,
+    Gitlab,
     Go,
+    Http,
+    Krew,
     Npm,
+    Oci,
     Pipx,
+    Pixi,
+    Rscript,
     Spm,
+    System,
     Ubi,
+    Wasm,
 }
 
 impl Display for BackendType {
@@ -76,6 +114,16 @@ impl Display for BackendType {
     }
 }
 
+/// a remote version with the release metadata the backend knows about, shown by `mise outdated`
+#[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
+pub struct VersionInfo {
+    pub version: String,
+    /// when the version was published, e.g.: "2024-05-01T12:00:00Z"
+    pub created_at: Option<String>,
+    /// the changelog or release notes of the version
+    pub release_url: Option<String>,
+}
+
 static FORGES: Mutex<Option<BackendMap>> = Mutex::new(None);
 
 fn load_backends() -> BackendMap {
@@ -104,13 +152,24 @@ fn list_installed_backends() -> eyre::Result<BackendList> {
             let fa: BackendArg = id.as_str().into();
             match fa.backend_type {
                 BackendType::Asdf => Arc::new(Asdf::new(fa.name)) as ABackend,
+                BackendType::Cabal => Arc::new(CabalBackend::new(fa.name)) as ABackend,
                 BackendType::Cargo => Arc::new(CargoBackend::new(fa.name)) as ABackend,
                 BackendType::Core => Arc::new(Asdf::new(fa.name)) as ABackend,
+                BackendType::Deno => Arc::new(DenoBackend::new(fa.name)) as ABackend,
+                BackendType::Gitea => Arc::new(GiteaBackend::new(fa.name)) as ABackend,
+                BackendType::Gitlab => Arc::new(GitlabBackend::new(fa.name)) as ABackend,
                 BackendType::Npm => Arc::new(npm::NPMBackend::new(fa.name)) as ABackend,
+                BackendType::Oci => Arc::new(OciBackend::new(fa.name)) as ABackend,
                 BackendType::Go => Arc::new(go::GoBackend::new(fa.name)) as ABackend,
+                BackendType::Http => Arc::new(HttpBackend::new(fa.name)) as ABackend,
+                BackendType::Krew => Arc::new(KrewBackend::new(fa.name)) as ABackend,
                 BackendType::Pipx => Arc::new(pipx::PIPXBackend::new(fa.name)) as ABackend,
+                BackendType::Pixi => Arc::new(PixiBackend::new(fa.name)) as ABackend,
+                BackendType::Rscript => Arc::new(RscriptBackend::new(fa.name)) as ABackend,
                 BackendType::Spm => Arc::new(spm::SPMBackend::new(fa.name)) as ABackend,
+                BackendType::System => Arc::new(system::SystemBackend::new(fa.name)) as ABackend,
                 BackendType::Ubi => Arc::new(ubi::UbiBackend::new(fa.name)) as ABackend,
+                BackendType::Wasm => Arc::new(wasm::WasmBackend::new(fa.name)) as ABackend,
             }
         })
         .filter(|f| f.fa().backend_type != BackendType::Asdf)
@@ -136,13 +195,24 @@ pub fn get(fa: &BackendArg) -> ABackend {
             .entry(fa.clone())
             .or_insert_with(|| match fa.backend_type {
                 BackendType::Asdf => Arc::new(Asdf::new(name)),
+                BackendType::Cabal => Arc::new(CabalBackend::new(name)),
                 BackendType::Cargo => Arc::new(CargoBackend::new(name)),
                 BackendType::Core => Arc::new(Asdf::new(name)),
+                BackendType::Deno => Arc::new(DenoBackend::new(name)),
+                BackendType::Gitea => Arc::new(GiteaBackend::new(name)),
+                BackendType::Gitlab => Arc::new(GitlabBackend::new(name)),
                 BackendType::Npm => Arc::new(npm::NPMBackend::new(name)),
+                BackendType::Oci => Arc::new(OciBackend::new(name)),
                 BackendType::Go => Arc::new(go::GoBackend::new(name)),
+                BackendType::Http => Arc::new(HttpBackend::new(name)),
+                BackendType::Krew => Arc::new(KrewBackend::new(name)),
                 BackendType::Pipx => Arc::new(pipx::PIPXBackend::new(name)),
+                BackendType::Pixi => Arc::new(PixiBackend::new(name)),
+                BackendType::Rscript => Arc::new(RscriptBackend::new(name)),
                 BackendType::Spm => Arc::new(spm::SPMBackend::new(name)),
+                BackendType::System => Arc::new(system::SystemBackend::new(name)),
                 BackendType::Ubi => Arc::new(ubi::UbiBackend::new(name)),
+                BackendType::Wasm => Arc::new(wasm::WasmBackend::new(name)),
             })
             .clone()
     }
@@ -194,6 +264,24 @@ pub trait Backend: Debug + Send + Sync {
         self._list_remote_versions()
     }
     fn _list_remote_versions(&self) -> eyre::Result<Vec<String>>;
+    /// like `_list_remote_versions` but with publish dates and release notes, backends which
+    /// can get these cheaply (e.g.: from the same API call) should override this
+    fn _list_remote_versions_with_info(&self) -> eyre::Result<Vec<VersionInfo>> {
+        Ok(self
+            .list_remote_versions()?
+            .into_iter()
+            .map(|version| VersionInfo {
+                version,
+                ..Default::default()
+            })
+            .collect())
+    }
+    fn release_info(&self, version: &str) -> eyre::Result<Option<VersionInfo>> {
+        Ok(self
+            ._list_remote_versions_with_info()?
+            .into_iter()
+            .find(|v| v.version == version))
+    }
     fn latest_stable_version(&self) -> eyre::Result<Option<String>> {
         self.latest_version(Some("latest".into()))
     }
@@ -346,6 +434,7 @@ pub trait Backend: Debug + Send + Sync {
     #[requires(ctx.tv.backend.backend_type == self.get_type())]
     fn install_version(&self, ctx: InstallContext) -> eyre::Result<()> {
         ensure!(self.is_installed(), "{} is not installed", self.id());
+        ctx.tv.request.ensure_supported()?;
         let config = Config::get();
         let settings = Settings::try_get()?;
         if self.is_version_installed(&ctx.tv) {
@@ -353,20 +442,48 @@ pub trait Backend: Debug + Send + Sync {
                 self.uninstall_version(&ctx.tv, ctx.pr.as_ref(), false)?;
                 ctx.pr.set_message("installing".into());
             } else {
-                return Ok(());
+                // re-runs a postinstall script that changed since it last ran
+                return self.run_postinstall(&ctx);
             }
         }
         let _lock = self.get_lock(&ctx.tv.install_path(), ctx.force)?;
         self.create_install_dirs(&ctx.tv)?;
 
-        if let Err(e) = self.install_version_impl(&ctx) {
+        let cacheable = binary_cache::is_cacheable(self.get_type());
+        let mut cached = false;
+        let result = match cacheable {
+            true => binary_cache::restore(&ctx.tv, ctx.pr.as_ref()),
+            false => Ok(false),
+        }
+        .and_then(|restored| {
+            cached = restored;
+            // cached installs were already patched before they were uploaded
+            match restored {
+                true => Ok(()),
+                false => self
+                    .install_version_impl(&ctx)
+                    .and_then(|_| patches::apply(&ctx))
+                    .and_then(|_| lockfile::record_install(&ctx.tv)),
+            }
+        });
+        if let Err(e) = result {
             self.cleanup_install_dirs_on_error(&settings, &ctx.tv);
             return Err(e);
         }
 
         BackendMeta::write(&ctx.tv.backend)?;
+        if let Err(err) = receipt::write(&ctx.tv) {
+            warn!("failed to write install receipt for {}: {err:#}", ctx.tv);
+        }
+        if cacheable && !cached {
+            if let Err(err) = binary_cache::upload(&ctx.tv, ctx.pr.as_ref()) {
+                warn!("failed to upload {} to the binary cache: {err:#}", ctx.tv);
+            }
+        }
 
-        self.cleanup_install_dirs(&settings, &ctx.tv);
+        if !ctx.keep_download {
+            self.cleanup_install_dirs(&settings, &ctx.tv);
+        }
         // attempt to touch all the .tool-version files to trigger updates in hook-env
         let mut touch_dirs = vec![dirs::DATA.to_path_buf()];
         touch_dirs.extend(config.config_files.keys().cloned());
@@ -379,10 +496,23 @@ pub trait Backend: Debug + Send + Sync {
         if let Err(err) = file::remove_file(self.incomplete_file_path(&ctx.tv)) {
             debug!("error removing incomplete file: {:?}", err);
         }
+        // the tool is installed even if its postinstall script fails, it's retried on the next install
+        self.run_postinstall(&ctx)?;
         ctx.pr.finish_with_message("installed".to_string());
 
         Ok(())
     }
+    fn run_postinstall(&self, ctx: &InstallContext) -> eyre::Result<()> {
+        if let Some(script) =
+            postinstall::pending(&ctx.tv.install_path(), &ctx.tv.request.options())
+        {
+            store::detach(&ctx.tv.install_path())?;
+            let config = Config::try_get()?;
+            let tool_env = self.exec_env(&config, ctx.ts, &ctx.tv)?;
+            postinstall::run(ctx, &script, self.list_bin_paths(&ctx.tv)?, tool_env)?;
+        }
+        Ok(())
+    }
     fn install_version_impl(&self, ctx: &InstallContext) -> eyre::Result<()>;
     fn uninstall_version(
         &self,
@@ -393,6 +523,7 @@ pub trait Backend: Debug + Send + Sync {
         pr.set_message("uninstall".into());
 
         if !dryrun {
+            self.run_preuninstall(pr, tv)?;
             self.uninstall_version_impl(pr, tv)?;
         }
         let rmdir = |dir: &Path| {
@@ -403,13 +534,27 @@ pub trait Backend: Debug + Send + Sync {
             if dryrun {
                 return Ok(());
             }
-            remove_all_with_warning(dir)
+            trash::remove_all(dir)
         };
         rmdir(&tv.install_path())?;
         rmdir(&tv.download_path())?;
         rmdir(&tv.cache_path())?;
         Ok(())
     }
+    fn run_preuninstall(&self, pr: &dyn SingleReport, tv: &ToolVersion) -> eyre::Result<()> {
+        if !tv.install_path().exists() {
+            return Ok(());
+        }
+        if let Some(script) = preuninstall::script(tv)? {
+            let config = Config::try_get()?;
+            let ts = ToolsetBuilder::new().build(&config)?;
+            let tool_env = self.exec_env(&config, &ts, tv)?;
+            preuninstall::run(pr, tv, &script, &ts, self.list_bin_paths(tv)?, tool_env)?;
+        }
+        Ok(())
+    }
+    /// removes state the backend keeps outside of the install, download, and cache directories
+    /// of the version, those are removed after this runs
     fn uninstall_version_impl(
         &self,
         _pr: &dyn SingleReport,
@@ -458,8 +603,15 @@ pub trait Backend: Debug + Send + Sync {
         Ok(lock)
     }
     fn create_install_dirs(&self, tv: &ToolVersion) -> eyre::Result<()> {
-        let _ = remove_all_with_warning(tv.install_path());
-        let _ = remove_all_with_warning(tv.download_path());
+        if cfg!(windows) {
+            trash::remove_all(tv.install_path())?;
+        } else {
+            let _ = remove_all_with_warning(tv.install_path());
+        }
+        // offline installs can only use downloads that were kept from a previous install
+        if !Settings::get().offline {
+            let _ = remove_all_with_warning(tv.download_path());
+        }
         let _ = remove_all_with_warning(tv.cache_path());
         let _ = file::remove_file(tv.install_path()); // removes if it is a symlink
         file::create_dir_all(tv.install_path())?;
@@ -494,11 +646,18 @@ pub trait Backend: Debug + Send + Sync {
             .filter_by_tool(&dependencies)
             .into();
         ts.resolve()?;
-        ts.full_env()
+        let mut env = mirrors::env();
+        env.extend(ts.full_env()?);
+        Ok(env)
     }
 }
 
 fn fuzzy_match_filter(versions: Vec<String>, query: &str) -> eyre::Result<Vec<String>> {
+    let prerelease = PrereleasePolicy::global()?;
+    if let Some(range) = VersionRange::parse(query) {
+        let versions = versions.into_iter().filter(|v| prerelease.allows(v));
+        return Ok(range?.filter(versions.collect()));
+    }
     let mut query = query;
     if query == "latest" {
         query = "v?[0-9].*";
@@ -510,7 +669,7 @@ fn fuzzy_match_filter(versions: Vec<String>, query: &str) -> eyre::Result<Vec<St
             if query == v {
                 return true;
             }
-            if VERSION_REGEX.is_match(v) {
+            if !prerelease.allows(v) {
                 return false;
             }
             query_regex.is_match(v)
@@ -1,14 +1,17 @@
+use std::collections::BTreeMap;
 use std::fmt::Debug;
+use std::path::PathBuf;
 
 use serde_json::Value;
 
-use crate::backend::{Backend, BackendType};
+use crate::backend::{Backend, BackendType, VersionInfo};
 use crate::cache::CacheManager;
 use crate::cli::args::BackendArg;
 use crate::cmd::CmdLineRunner;
 use crate::config::{Config, Settings};
 use crate::install_context::InstallContext;
 use crate::toolset::ToolRequest;
+use crate::{dirs, file, mirrors};
 
 #[derive(Debug)]
 pub struct NPMBackend {
@@ -33,17 +36,43 @@ impl Backend for NPMBackend {
     fn _list_remote_versions(&self) -> eyre::Result<Vec<String>> {
         self.remote_version_cache
             .get_or_try_init(|| {
-                let raw = cmd!("npm", "view", self.name(), "versions", "--json").read()?;
+                let raw = self
+                    .npm_view(&["versions", "--json"])
+                    .full_env(self.dependency_env()?)
+                    .read()?;
                 let versions: Vec<String> = serde_json::from_str(&raw)?;
                 Ok(versions)
             })
             .cloned()
     }
 
+    fn _list_remote_versions_with_info(&self) -> eyre::Result<Vec<VersionInfo>> {
+        self.ensure_dependencies_installed()?;
+        let raw = self
+            .npm_view(&["time", "--json"])
+            .full_env(self.dependency_env()?)
+            .read()?;
+        // also has "created" and "modified" keys for the package itself
+        let mut times: BTreeMap<String, String> = serde_json::from_str(&raw)?;
+        Ok(self
+            ._list_remote_versions()?
+            .into_iter()
+            .map(|version| VersionInfo {
+                created_at: times.remove(&version),
+                release_url: Some(format!(
+                    "https://www.npmjs.com/package/{}/v/{version}",
+                    self.name()
+                )),
+                version,
+            })
+            .collect())
+    }
+
     fn latest_stable_version(&self) -> eyre::Result<Option<String>> {
         self.latest_version_cache
             .get_or_try_init(|| {
-                let raw = cmd!("npm", "view", self.name(), "dist-tags", "--json")
+                let raw = self
+                    .npm_view(&["dist-tags", "--json"])
                     .full_env(self.dependency_env()?)
                     .read()?;
                 let dist_tags: Value = serde_json::from_str(&raw)?;
@@ -61,13 +90,20 @@ impl Backend for NPMBackend {
         let settings = Settings::get();
         settings.ensure_experimental("npm backend")?;
 
-        CmdLineRunner::new("npm")
+        let mut cmd = CmdLineRunner::new("npm")
             .arg("install")
             .arg("-g")
             .arg(&format!("{}@{}", self.name(), ctx.tv.version))
             .arg("--prefix")
-            .arg(ctx.tv.install_path())
-            .with_pr(ctx.pr.as_ref())
+            .arg(ctx.tv.install_path());
+        if let Some(npmrc) = project_npmrc() {
+            cmd = cmd
+                .arg("--globalconfig")
+                .arg(&npmrc)
+                .current_dir(npmrc.parent().unwrap());
+        }
+        cmd.with_pr(ctx.pr.as_ref())
+            .envs(mirrors::env())
             .envs(ctx.ts.env_with_path(&config)?)
             .prepend_path(ctx.ts.list_paths())?
             .execute()?;
@@ -77,6 +113,19 @@ impl Backend for NPMBackend {
 }
 
 impl NPMBackend {
+    fn npm_view(&self, args: &[&str]) -> duct::Expression {
+        let mut cmd_args = vec!["view".to_string(), self.name().to_string()];
+        cmd_args.extend(args.iter().map(|a| a.to_string()));
+        match project_npmrc() {
+            Some(npmrc) => {
+                cmd_args.push("--globalconfig".into());
+                cmd_args.push(npmrc.to_string_lossy().to_string());
+                duct::cmd("npm", cmd_args).dir(npmrc.parent().unwrap())
+            }
+            None => duct::cmd("npm", cmd_args),
+        }
+    }
+
     pub fn new(name: String) -> Self {
         let fa = BackendArg::new(BackendType::Npm, &name);
         Self {
@@ -90,3 +139,12 @@ impl NPMBackend {
         }
     }
 }
+
+/// the .npmrc closest to the current directory. npm only reads the project .npmrc for local
+/// commands from the project root and never for global installs so it is passed as the
+/// globalconfig to make scoped registries and auth tokens work everywhere.
+/// ~/.npmrc is skipped since npm always reads it as the userconfig.
+fn project_npmrc() -> Option<PathBuf> {
+    let cwd = dirs::CWD.as_ref()?;
+    file::find_up(cwd, &[".npmrc"]).filter(|p| *p != dirs::HOME.join(".npmrc"))
+}
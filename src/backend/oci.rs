@@ -0,0 +1,194 @@
+use std::fmt::Debug;
+use std::path::{Component, Path, PathBuf};
+
+use eyre::bail;
+use itertools::Itertools;
+use versions::Versioning;
+
+use crate::backend::{Backend, BackendType};
+use crate::cache::CacheManager;
+use crate::cli::args::BackendArg;
+use crate::config::Settings;
+use crate::install_context::InstallContext;
+use crate::oci::{Descriptor, OciClient};
+use crate::toolset::{ToolRequest, ToolVersion};
+use crate::{archive, file, oci};
+
+/// Installs tools published to OCI registries, either as artifacts (`oras push`) or as
+/// container images
+///
+/// oci:ghcr.io/org/tool
+#[derive(Debug)]
+pub struct OciBackend {
+    fa: BackendArg,
+    remote_version_cache: CacheManager<Vec<String>>,
+}
+
+impl Backend for OciBackend {
+    fn get_type(&self) -> BackendType {
+        BackendType::Oci
+    }
+
+    fn fa(&self) -> &BackendArg {
+        &self.fa
+    }
+
+    fn _list_remote_versions(&self) -> eyre::Result<Vec<String>> {
+        self.remote_version_cache
+            .get_or_try_init(|| {
+                let client = OciClient::new(self.name().parse()?)?;
+                Ok(client
+                    .list_tags()?
+                    .into_iter()
+                    .filter(|t| t != "latest" && !t.starts_with("sha256-"))
+                    .sorted_by_cached_key(|t| Versioning::new(t))
+                    .collect())
+            })
+            .cloned()
+    }
+
+    fn install_version_impl(&self, ctx: &InstallContext) -> eyre::Result<()> {
+        let settings = Settings::get();
+        settings.ensure_experimental("oci backend")?;
+        let opts = ctx.tv.request.options();
+        let client = OciClient::new(self.name().parse()?)?;
+        ctx.pr.set_message("fetching manifest".into());
+        let layers = client.layers(&ctx.tv.version)?;
+        let install_path = ctx.tv.install_path();
+        let download_path = ctx.tv.download_path();
+        file::remove_all(&install_path)?;
+        file::create_dir_all(&install_path)?;
+
+        if layers.iter().any(|l| l.title().is_some()) {
+            // artifact pushed with `oras push`, each layer is a file
+            let layer = self.find_artifact_layer(&layers, opts.get("layer"))?;
+            let title = layer.title().unwrap();
+            let tarball = download_path.join(layer_file_name(title)?);
+            ctx.pr.set_message(format!("downloading {title}"));
+            client.download_blob(layer, &tarball, Some(ctx.pr.as_ref()))?;
+            ctx.pr.set_message(format!("extracting {title}"));
+            if archive::is_archive(&tarball) {
+                archive::extract(&tarball, &install_path)?;
+            } else {
+                let bin = install_path
+                    .join("bin")
+                    .join(self.bin_name(opts.get("bin")));
+                file::create_dir_all(bin.parent().unwrap())?;
+                file::copy(&tarball, &bin)?;
+                file::make_executable(&bin)?;
+            }
+        } else {
+            // container image, extract the filesystem and expose `bin_path`
+            for layer in &layers {
+                let media_type = layer.media_type.as_deref().unwrap_or_default();
+                if !media_type.ends_with("gzip") {
+                    bail!("unsupported layer type in {}: {media_type}", self.name());
+                }
+                let tarball = download_path.join(layer.digest.replace(':', "-"));
+                ctx.pr.set_message(format!("downloading {}", layer.digest));
+                client.download_blob(layer, &tarball, Some(ctx.pr.as_ref()))?;
+                ctx.pr.set_message(format!("extracting {}", layer.digest));
+                file::untar(&tarball, &install_path)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn list_bin_paths(&self, tv: &ToolVersion) -> eyre::Result<Vec<PathBuf>> {
+        match tv.request {
+            ToolRequest::System(_) => Ok(vec![]),
+            _ => {
+                let opts = tv.request.options();
+                let bin_path = opts
+                    .get("bin_path")
+                    .map(|p| p.trim_start_matches('/'))
+                    .unwrap_or("bin");
+                Ok(vec![tv.install_short_path().join(bin_path)])
+            }
+        }
+    }
+}
+
+impl OciBackend {
+    pub fn new(name: String) -> Self {
+        let fa = BackendArg::new(BackendType::Oci, &name);
+        Self {
+            remote_version_cache: CacheManager::new(
+                fa.cache_path.join("remote_versions-$KEY.msgpack.z"),
+            ),
+            fa,
+        }
+    }
+
+    fn bin_name(&self, bin: Option<&String>) -> String {
+        bin.cloned().unwrap_or_else(|| {
+            let name = self.name().trim_end_matches('/');
+            name.rsplit('/').next().unwrap_or(name).to_string()
+        })
+    }
+
+    /// picks the layer named with the `layer` option or the single layer that matches this
+    /// platform
+    fn find_artifact_layer<'a>(
+        &self,
+        layers: &'a [Descriptor],
+        layer: Option<&String>,
+    ) -> eyre::Result<&'a Descriptor> {
+        let titles = || layers.iter().filter_map(|l| l.title()).join(", ");
+        if let Some(layer) = layer {
+            return match layers.iter().find(|l| l.title() == Some(layer.as_str())) {
+                Some(l) => Ok(l),
+                None => bail!("layer {layer} not found in {}: {}", self.name(), titles()),
+            };
+        }
+        let layers = layers.iter().filter(|l| l.title().is_some()).collect_vec();
+        if let [layer] = layers.as_slice() {
+            return Ok(layer);
+        }
+        let matching = layers
+            .into_iter()
+            .filter(|l| {
+                let title = l.title().unwrap().to_lowercase();
+                title.contains(oci::os()) && title.contains(oci::arch())
+            })
+            .collect_vec();
+        match matching.as_slice() {
+            [layer] => Ok(layer),
+            _ => bail!(
+                "unable to determine which layer to install from {}, set the layer tool option to one of: {}",
+                self.name(),
+                titles()
+            ),
+        }
+    }
+}
+
+/// the file name to download an artifact layer to, the title annotation comes from the registry
+/// so it has to be a single file name that stays inside of the download directory
+fn layer_file_name(title: &str) -> eyre::Result<&Path> {
+    let mut components = Path::new(title).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(name)), None) => Ok(Path::new(name)),
+        _ => bail!("refusing to download an oci layer titled {title}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::layer_file_name;
+
+    #[test]
+    fn test_layer_file_name() {
+        assert_eq!(
+            layer_file_name("tool-linux-amd64.tar.gz").unwrap(),
+            Path::new("tool-linux-amd64.tar.gz")
+        );
+        assert!(layer_file_name("../../../.bashrc").is_err());
+        assert!(layer_file_name("/etc/passwd").is_err());
+        assert!(layer_file_name("bin/tool").is_err());
+        assert!(layer_file_name("..").is_err());
+        assert!(layer_file_name("").is_err());
+    }
+}
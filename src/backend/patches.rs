@@ -0,0 +1,127 @@
+use std::path::{Path, PathBuf};
+
+use itertools::Itertools;
+
+use crate::cmd::CmdLineRunner;
+use crate::config::{lockfile, Config};
+use crate::file::display_path;
+use crate::hash::file_hash_sha256;
+use crate::install_context::InstallContext;
+use crate::toolset::ToolVersionOptions;
+use crate::{file, hash, store};
+
+/// sha256 of each patch applied to an install, written next to the installed files so the
+/// patched state of a tool can be verified later
+pub const PATCHES_FILE: &str = ".mise-patches.sha256";
+
+/// applies the `patches` and `post_extract` tool options to the installed tree
+///
+/// [tools]
+/// "ubi:foo/bar" = { version = "1", patches = ["patches/fix-shebang.patch"], post_extract = "rm -rf docs" }
+pub fn apply(ctx: &InstallContext) -> eyre::Result<()> {
+    let opts = ctx.tv.request.options();
+    let patches = list(&opts);
+    let post_extract = opts.get("post_extract");
+    if patches.is_empty() && post_extract.is_none() {
+        return lockfile::record_patches(&ctx.tv, Default::default());
+    }
+    let install_path = ctx.tv.install_path();
+    store::detach(&install_path)?;
+    let mut hashes = vec![];
+    for patch in &patches {
+        ctx.pr
+            .set_message(format!("applying {}", display_path(patch)));
+        CmdLineRunner::new("patch")
+            .arg("-p1")
+            .arg("--forward")
+            .arg("-d")
+            .arg(&install_path)
+            .arg("-i")
+            .arg(patch)
+            .with_pr(ctx.pr.as_ref())
+            .execute()?;
+        hashes.push((file_hash_sha256(patch)?, patch.clone()));
+    }
+    if let Some(script) = post_extract {
+        ctx.pr.set_message("running post_extract".into());
+        let config = Config::try_get()?;
+        CmdLineRunner::new("sh")
+            .arg("-c")
+            .arg(script)
+            .current_dir(&install_path)
+            .with_pr(ctx.pr.as_ref())
+            .envs(ctx.ts.env_with_path(&config)?)
+            .env("MISE_TOOL_INSTALL_PATH", &install_path)
+            .env("MISE_TOOL_VERSION", &ctx.tv.version)
+            .execute()?;
+        hashes.push((
+            hash::hash_sha256_to_str(script),
+            PathBuf::from("post_extract"),
+        ));
+    }
+    file::write(install_path.join(PATCHES_FILE), render(&hashes))?;
+    let locked = hashes
+        .iter()
+        .map(|(hash, path)| (name(path), format!("sha256:{hash}")))
+        .collect();
+    lockfile::record_patches(&ctx.tv, locked)
+}
+
+/// patches are a comma-separated list of files, relative paths were already resolved from the
+/// config file's project root
+fn list(opts: &ToolVersionOptions) -> Vec<PathBuf> {
+    opts.get("patches")
+        .map(|p| {
+            p.split(',')
+                .map(|p| p.trim())
+                .filter(|p| !p.is_empty())
+                .map(PathBuf::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn render(hashes: &[(String, PathBuf)]) -> String {
+    hashes
+        .iter()
+        .map(|(hash, path)| format!("{hash}  {}", name(path)))
+        .join("\n")
+        + "\n"
+}
+
+fn name(path: &Path) -> String {
+    path.file_name()
+        .map(|f| f.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::toolset::ToolVersionOptions;
+
+    use super::{list, render};
+
+    #[test]
+    fn test_list() {
+        let mut opts = ToolVersionOptions::new();
+        assert!(list(&opts).is_empty());
+        opts.insert("patches".into(), "/a/one.patch, /a/two.patch,".into());
+        assert_eq!(
+            list(&opts),
+            vec![PathBuf::from("/a/one.patch"), PathBuf::from("/a/two.patch")]
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let hashes = vec![
+            ("abc".to_string(), PathBuf::from("/a/one.patch")),
+            ("def".to_string(), PathBuf::from("post_extract")),
+        ];
+        assert_eq!(render(&hashes), "abc  one.patch\ndef  post_extract\n");
+    }
+}
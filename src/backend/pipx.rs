@@ -1,18 +1,23 @@
 use indexmap::IndexMap;
 use itertools::Itertools;
 use std::fmt::Debug;
+use std::path::PathBuf;
 use std::str::FromStr;
 use versions::Versioning;
 
+use crate::backend;
 use crate::backend::{Backend, BackendType};
 use crate::cache::CacheManager;
 use crate::cli::args::BackendArg;
 use crate::cmd::CmdLineRunner;
 use crate::config::{Config, Settings};
-use crate::github;
+use crate::forge::Forge;
 use crate::http::HTTP_FETCH;
 use crate::install_context::InstallContext;
-use crate::toolset::ToolRequest;
+use crate::mirrors;
+use crate::toolset::{ToolRequest, ToolVersion, ToolVersionOptions, ToolsetBuilder};
+use crate::ui::multi_progress_report::MultiProgressReport;
+use crate::ui::progress_report::SingleReport;
 
 #[derive(Debug)]
 pub struct PIPXBackend {
@@ -30,8 +35,17 @@ impl Backend for PIPXBackend {
         &self.fa
     }
 
-    fn get_dependencies(&self, _tvr: &ToolRequest) -> eyre::Result<Vec<BackendArg>> {
-        Ok(vec!["pipx".into()])
+    fn get_dependencies(&self, tvr: &ToolRequest) -> eyre::Result<Vec<BackendArg>> {
+        let opts = tvr.options();
+        let mut deps = if uvx(&opts) {
+            vec!["uv".into()]
+        } else {
+            vec!["pipx".into()]
+        };
+        if opts.contains_key("python") {
+            deps.push("python".into());
+        }
+        Ok(deps)
     }
 
     /*
@@ -42,7 +56,7 @@ impl Backend for PIPXBackend {
         self.remote_version_cache
             .get_or_try_init(|| match self.name().parse()? {
                 PipxRequest::Pypi(package) => {
-                    let url = format!("https://pypi.org/pypi/{}/json", package);
+                    let url = mirrors::pypi_json_url(&package);
                     let data: PypiPackage = HTTP_FETCH.json(url)?;
                     let versions = data
                         .releases
@@ -52,12 +66,13 @@ impl Backend for PIPXBackend {
                         .collect();
                     Ok(versions)
                 }
-                PipxRequest::Git(url) if url.starts_with("https://github.com/") => {
-                    let repo = url.strip_prefix("https://github.com/").unwrap();
-                    let data = github::list_releases(repo)?;
-                    Ok(data.into_iter().map(|r| r.tag_name).collect())
-                }
-                PipxRequest::Git { .. } => Ok(vec!["latest".to_string()]),
+                PipxRequest::Git(url) => match Forge::parse_url(&url) {
+                    Some((forge, project)) => {
+                        let data = forge.list_releases(&project)?;
+                        Ok(data.into_iter().map(|r| r.tag_name).collect())
+                    }
+                    None => Ok(vec!["latest".to_string()]),
+                },
             })
             .cloned()
     }
@@ -66,7 +81,7 @@ impl Backend for PIPXBackend {
         self.latest_version_cache
             .get_or_try_init(|| match self.name().parse()? {
                 PipxRequest::Pypi(package) => {
-                    let url = format!("https://pypi.org/pypi/{}/json", package);
+                    let url = mirrors::pypi_json_url(&package);
                     let pkg: PypiPackage = HTTP_FETCH.json(url)?;
                     Ok(Some(pkg.info.version))
                 }
@@ -79,17 +94,34 @@ impl Backend for PIPXBackend {
         let config = Config::try_get()?;
         let settings = Settings::get();
         settings.ensure_experimental("pipx backend")?;
+        let mut extras = extras(self.name());
+        if let Some(opt) = ctx.tv.request.options().get("extras") {
+            extras.extend(opt.split(',').map(|e| e.trim().to_string()));
+        }
         let pipx_request = self
             .name()
             .parse::<PipxRequest>()?
-            .pipx_request(&ctx.tv.version);
-
-        CmdLineRunner::new("pipx")
-            .arg("install")
-            .arg(pipx_request)
-            .with_pr(ctx.pr.as_ref())
-            .env("PIPX_HOME", ctx.tv.install_path())
-            .env("PIPX_BIN_DIR", ctx.tv.install_path().join("bin"))
+            .pipx_request(&ctx.tv.version, &extras);
+
+        let mut cmd = if uvx(&ctx.tv.request.options()) {
+            CmdLineRunner::new("uv")
+                .arg("tool")
+                .arg("install")
+                .arg(pipx_request)
+                .env("UV_TOOL_DIR", ctx.tv.install_path())
+                .env("UV_TOOL_BIN_DIR", ctx.tv.install_path().join("bin"))
+        } else {
+            CmdLineRunner::new("pipx")
+                .arg("install")
+                .arg(pipx_request)
+                .env("PIPX_HOME", ctx.tv.install_path())
+                .env("PIPX_BIN_DIR", ctx.tv.install_path().join("bin"))
+        };
+        if let Some(python) = ctx.tv.request.options().get("python") {
+            cmd = cmd.arg("--python").arg(self.python(ctx, python)?);
+        }
+        cmd.with_pr(ctx.pr.as_ref())
+            .envs(mirrors::env())
             .envs(ctx.ts.env_with_path(&config)?)
             .prepend_path(ctx.ts.list_paths())?
             // Prepend install path so pipx doesn't issue a warning about missing path
@@ -98,9 +130,53 @@ impl Backend for PIPXBackend {
 
         Ok(())
     }
+
+    /// pipx links man pages into PIPX_MAN_DIR outside of the install dir, `pipx uninstall-all`
+    /// removes them with the venvs in PIPX_HOME
+    fn uninstall_version_impl(&self, pr: &dyn SingleReport, tv: &ToolVersion) -> eyre::Result<()> {
+        if uvx(&tv.request.options()) || !tv.install_path().exists() {
+            return Ok(());
+        }
+        let config = Config::try_get()?;
+        let ts = ToolsetBuilder::new().build(&config)?;
+        let result = CmdLineRunner::new("pipx")
+            .arg("uninstall-all")
+            .env("PIPX_HOME", tv.install_path())
+            .env("PIPX_BIN_DIR", tv.install_path().join("bin"))
+            .with_pr(pr)
+            .envs(ts.env_with_path(&config)?)
+            .prepend_path(ts.list_paths())?
+            .execute();
+        if let Err(err) = result {
+            debug!("pipx uninstall-all failed for {tv}: {err:#}");
+        }
+        Ok(())
+    }
 }
 
 impl PIPXBackend {
+    /// the interpreter for the `python` tool option, e.g.: `python = "3.11"`, which is installed
+    /// with mise if it is missing so tools can be pinned to an older python than the project uses
+    fn python(&self, ctx: &InstallContext, version: &str) -> eyre::Result<PathBuf> {
+        let python = backend::get(&"python".into());
+        let tv = ToolRequest::new(python.fa().clone(), version)?.resolve(python.as_ref(), false)?;
+        if !python.is_version_installed(&tv) {
+            python.install_version(InstallContext {
+                ts: ctx.ts,
+                tv: tv.clone(),
+                pr: MultiProgressReport::get().add(&tv.style()),
+                force: false,
+                keep_download: false,
+            })?;
+        }
+        for bin in ["python3", "python", "python.exe"] {
+            if let Some(path) = python.which(&tv, bin)? {
+                return Ok(path);
+            }
+        }
+        Err(eyre::eyre!("no python executable found in {tv}"))
+    }
+
     pub fn new(name: String) -> Self {
         let fa = BackendArg::new(BackendType::Pipx, &name);
         Self {
@@ -115,6 +191,15 @@ impl PIPXBackend {
     }
 }
 
+/// installs with `uv tool install` instead of pipx, the `uvx` tool option overrides the
+/// `pipx_uvx` setting
+fn uvx(opts: &ToolVersionOptions) -> bool {
+    match opts.get("uvx").map(|s| s.as_str()) {
+        Some(uvx) => uvx == "true",
+        None => Settings::get().pipx_uvx,
+    }
+}
+
 enum PipxRequest {
     /// git+https://github.com/psf/black.git@24.2.0
     /// psf/black@24.2.0
@@ -123,17 +208,33 @@ enum PipxRequest {
     Pypi(String),
 }
 
+/// ansible[azure,aws] -> ["azure", "aws"]
+fn extras(name: &str) -> Vec<String> {
+    match regex!(r"\[(.*)\]$").captures(name) {
+        Some(caps) => caps[1]
+            .split(',')
+            .map(|e| e.trim().to_string())
+            .filter(|e| !e.is_empty())
+            .collect(),
+        None => vec![],
+    }
+}
+
 impl PipxRequest {
-    fn pipx_request(&self, v: &str) -> String {
+    fn pipx_request(&self, v: &str, extras: &[String]) -> String {
+        let extras = match extras.is_empty() {
+            true => String::new(),
+            false => format!("[{}]", extras.iter().unique().join(",")),
+        };
         if v == "latest" {
             match self {
                 PipxRequest::Git(url) => format!("git+{url}.git"),
-                PipxRequest::Pypi(package) => package.to_string(),
+                PipxRequest::Pypi(package) => format!("{package}{extras}"),
             }
         } else {
             match self {
                 PipxRequest::Git(url) => format!("git+{}.git@{}", url, v),
-                PipxRequest::Pypi(package) => format!("{}=={}", package, v),
+                PipxRequest::Pypi(package) => format!("{package}{extras}=={v}"),
             }
         }
     }
@@ -148,7 +249,9 @@ impl FromStr for PipxRequest {
         } else if s.contains('/') {
             Ok(PipxRequest::Git(format!("https://github.com/{s}")))
         } else {
-            Ok(PipxRequest::Pypi(s.to_string()))
+            // extras are passed to pipx_request() separately
+            let package = s.split_once('[').map(|(p, _)| p).unwrap_or(s);
+            Ok(PipxRequest::Pypi(package.to_string()))
         }
     }
 }
@@ -166,3 +269,22 @@ struct PypiInfo {
 
 #[derive(serde::Deserialize)]
 struct PypiRelease {}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_pipx_request_extras() {
+        let name = "ansible[azure,aws]";
+        let req: PipxRequest = name.parse().unwrap();
+        assert_eq!(req.pipx_request("latest", &[]), "ansible");
+        assert_eq!(
+            req.pipx_request("9.0.0", &extras(name)),
+            "ansible[azure,aws]==9.0.0"
+        );
+        assert_eq!(extras("black"), Vec::<String>::new());
+    }
+}
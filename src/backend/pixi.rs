@@ -0,0 +1,129 @@
+use std::fmt::Debug;
+use std::path::{Path, PathBuf};
+
+use eyre::{bail, eyre};
+
+use crate::backend::{Backend, BackendType};
+use crate::cli::args::BackendArg;
+use crate::cmd::CmdLineRunner;
+use crate::config::{Config, Settings};
+use crate::file::display_path;
+use crate::hash::file_hash_sha256;
+use crate::install_context::InstallContext;
+use crate::toolset::{ToolRequest, ToolVersion};
+use crate::{dirs, file};
+
+const ENV_FILE: &str = "pixi-env";
+
+/// Exposes the binaries of a pixi environment from the nearest pixi.lock
+/// the version is the hash of the lockfile so changing it installs a new version
+///
+/// pixi:default
+/// pixi:lint
+#[derive(Debug)]
+pub struct PixiBackend {
+    fa: BackendArg,
+}
+
+impl Backend for PixiBackend {
+    fn get_type(&self) -> BackendType {
+        BackendType::Pixi
+    }
+
+    fn fa(&self) -> &BackendArg {
+        &self.fa
+    }
+
+    fn get_dependencies(&self, _tvr: &ToolRequest) -> eyre::Result<Vec<BackendArg>> {
+        Ok(vec!["pixi".into()])
+    }
+
+    fn _list_remote_versions(&self) -> eyre::Result<Vec<String>> {
+        match find_lockfile() {
+            Some(lockfile) => Ok(vec![lockfile_version(&lockfile)?]),
+            None => Ok(vec![]),
+        }
+    }
+
+    fn install_version_impl(&self, ctx: &InstallContext) -> eyre::Result<()> {
+        let config = Config::try_get()?;
+        let settings = Settings::get();
+        settings.ensure_experimental("pixi backend")?;
+        let lockfile = find_lockfile().ok_or_else(|| {
+            eyre!("no pixi.lock found, run pixi lock in the project before installing")
+        })?;
+        let version = lockfile_version(&lockfile)?;
+        if version != ctx.tv.version {
+            bail!(
+                "{} has changed, it is now version {version} instead of {}",
+                display_path(&lockfile),
+                ctx.tv.version
+            );
+        }
+        let project = lockfile.parent().unwrap();
+        let manifest = ["pixi.toml", "pyproject.toml"]
+            .iter()
+            .map(|f| project.join(f))
+            .find(|f| f.exists())
+            .ok_or_else(|| eyre!("no pixi.toml found in {}", display_path(project)))?;
+
+        CmdLineRunner::new("pixi")
+            .arg("install")
+            .arg("--manifest-path")
+            .arg(&manifest)
+            .arg("--environment")
+            .arg(self.name())
+            .arg("--frozen")
+            .with_pr(ctx.pr.as_ref())
+            .envs(ctx.ts.env_with_path(&config)?)
+            .prepend_path(ctx.ts.list_paths())?
+            .execute()?;
+
+        // conda environments contain absolute paths to their prefix so they can't be copied,
+        // the location of the environment is stored instead
+        let env = project.join(".pixi").join("envs").join(self.name());
+        if !env.exists() {
+            bail!("pixi did not create {}", display_path(&env));
+        }
+        file::write(
+            ctx.tv.install_path().join(ENV_FILE),
+            env.to_string_lossy().as_bytes(),
+        )?;
+        Ok(())
+    }
+
+    fn list_bin_paths(&self, tv: &ToolVersion) -> eyre::Result<Vec<PathBuf>> {
+        let env_file = tv.install_path().join(ENV_FILE);
+        if !env_file.exists() {
+            return Ok(vec![]);
+        }
+        let env = PathBuf::from(file::read_to_string(env_file)?.trim());
+        if cfg!(windows) {
+            Ok(vec![
+                env.clone(),
+                env.join("Library").join("bin"),
+                env.join("Scripts"),
+            ])
+        } else {
+            Ok(vec![env.join("bin")])
+        }
+    }
+}
+
+impl PixiBackend {
+    pub fn new(name: String) -> Self {
+        Self {
+            fa: BackendArg::new(BackendType::Pixi, &name),
+        }
+    }
+}
+
+fn find_lockfile() -> Option<PathBuf> {
+    let cwd = dirs::CWD.as_ref()?;
+    file::find_up(cwd, &["pixi.lock"])
+}
+
+fn lockfile_version(lockfile: &Path) -> eyre::Result<String> {
+    let hash = file_hash_sha256(lockfile)?;
+    Ok(hash[..12].to_string())
+}
@@ -0,0 +1,54 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::cmd::CmdLineRunner;
+use crate::config::Config;
+use crate::install_context::InstallContext;
+use crate::toolset::ToolVersionOptions;
+use crate::{file, hash};
+
+/// sha256 of the `postinstall` script that last ran successfully for an install
+pub const POSTINSTALL_FILE: &str = ".mise-postinstall.sha256";
+
+/// the `postinstall` tool option if it hasn't run for this install yet, or if it has changed since
+/// it last ran
+///
+/// [tools]
+/// node = { version = "22", postinstall = "corepack enable" }
+pub fn pending(install_path: &Path, opts: &ToolVersionOptions) -> Option<String> {
+    let script = opts.get("postinstall")?.clone();
+    let marker = install_path.join(POSTINSTALL_FILE);
+    match file::read_to_string(marker) {
+        Ok(hash) if hash.trim() == hash::hash_sha256_to_str(&script) => None,
+        _ => Some(script),
+    }
+}
+
+/// runs `script` with the bin paths and exec env of the freshly installed tool ahead of the rest
+/// of the toolset
+pub fn run(
+    ctx: &InstallContext,
+    script: &str,
+    bin_paths: Vec<PathBuf>,
+    tool_env: BTreeMap<String, String>,
+) -> eyre::Result<()> {
+    ctx.pr.set_message("running postinstall".into());
+    let config = Config::try_get()?;
+    let install_path = ctx.tv.install_path();
+    CmdLineRunner::new("sh")
+        .arg("-c")
+        .arg(script)
+        .current_dir(&install_path)
+        .with_pr(ctx.pr.as_ref())
+        .envs(ctx.ts.env_with_path(&config)?)
+        .envs(tool_env)
+        .prepend_path(bin_paths)?
+        .env("MISE_TOOL_INSTALL_PATH", &install_path)
+        .env("MISE_TOOL_VERSION", &ctx.tv.version)
+        .execute()?;
+    file::write(
+        install_path.join(POSTINSTALL_FILE),
+        hash::hash_sha256_to_str(script),
+    )?;
+    Ok(())
+}
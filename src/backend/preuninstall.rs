@@ -0,0 +1,52 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::cmd::CmdLineRunner;
+use crate::config::Config;
+use crate::toolset::{ToolVersion, Toolset};
+use crate::ui::progress_report::SingleReport;
+
+/// the `preuninstall` tool option of a version, or of any request for the same tool in the config
+/// since versions removed by `mise prune` aren't requested anymore
+///
+/// [tools]
+/// java = { version = "21", preuninstall = "jenv remove $MISE_TOOL_VERSION" }
+pub fn script(tv: &ToolVersion) -> eyre::Result<Option<String>> {
+    if let Some(script) = tv.request.options().get("preuninstall") {
+        return Ok(Some(script.clone()));
+    }
+    let config = Config::try_get()?;
+    let trs = config.get_tool_request_set()?;
+    Ok(trs
+        .tools
+        .get(&tv.backend)
+        .into_iter()
+        .flatten()
+        .find_map(|tr| tr.options().get("preuninstall").cloned()))
+}
+
+/// runs `script` inside of the install directory before it is removed, a failing script aborts
+/// the uninstall so the state it was meant to clean up isn't left behind
+pub fn run(
+    pr: &dyn SingleReport,
+    tv: &ToolVersion,
+    script: &str,
+    ts: &Toolset,
+    bin_paths: Vec<PathBuf>,
+    tool_env: BTreeMap<String, String>,
+) -> eyre::Result<()> {
+    pr.set_message("running preuninstall".into());
+    let config = Config::try_get()?;
+    let install_path = tv.install_path();
+    CmdLineRunner::new("sh")
+        .arg("-c")
+        .arg(script)
+        .current_dir(&install_path)
+        .with_pr(pr)
+        .envs(ts.env_with_path(&config)?)
+        .envs(tool_env)
+        .prepend_path(bin_paths)?
+        .env("MISE_TOOL_INSTALL_PATH", &install_path)
+        .env("MISE_TOOL_VERSION", &tv.version)
+        .execute()
+}
@@ -0,0 +1,103 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+use itertools::Itertools;
+use rayon::prelude::*;
+use walkdir::WalkDir;
+
+use crate::file;
+use crate::hash::file_hash_sha256;
+use crate::toolset::ToolVersion;
+
+/// sha256 of every file in an install, written after installing so `mise verify` can detect
+/// files that were modified or removed later
+pub const RECEIPT_FILE: &str = ".mise-receipt.sha256";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Problem {
+    Missing(String),
+    Modified(String),
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Problem::Missing(path) => write!(f, "missing: {path}"),
+            Problem::Modified(path) => write!(f, "modified: {path}"),
+        }
+    }
+}
+
+pub fn write(tv: &ToolVersion) -> eyre::Result<()> {
+    let install_path = tv.install_path();
+    let receipt = hash_files(&install_path)?
+        .into_iter()
+        .map(|(path, hash)| format!("{hash}  {path}"))
+        .join("\n");
+    file::write(install_path.join(RECEIPT_FILE), receipt + "\n")
+}
+
+/// returns None if the install has no receipt, e.g.: it was installed by an older version of mise
+pub fn verify(tv: &ToolVersion) -> eyre::Result<Option<Vec<Problem>>> {
+    let install_path = tv.install_path();
+    let receipt = install_path.join(RECEIPT_FILE);
+    if !receipt.exists() {
+        return Ok(None);
+    }
+    let expected = file::read_to_string(&receipt)?;
+    let actual = hash_files(&install_path)?;
+    let problems = expected
+        .lines()
+        .filter_map(|l| l.split_once("  "))
+        .map(|(hash, path)| (path.to_string(), hash))
+        .sorted()
+        .filter_map(|(path, hash)| match actual.get(&path) {
+            None => Some(Problem::Missing(path)),
+            Some(h) if *h != hash => Some(Problem::Modified(path)),
+            Some(_) => None,
+        })
+        .collect();
+    Ok(Some(problems))
+}
+
+/// hashes regular files by their path relative to the install, symlinks are not followed
+fn hash_files(dir: &Path) -> eyre::Result<BTreeMap<String, String>> {
+    let files: Vec<PathBuf> = WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.into_path())
+        .filter(|p| p.file_name().is_some_and(|f| f != RECEIPT_FILE))
+        .collect();
+    files
+        .into_par_iter()
+        .map(|p| {
+            let rel = p.strip_prefix(dir)?.to_string_lossy().replace('\\', "/");
+            Ok((rel, file_hash_sha256(&p)?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::dirs;
+    use crate::file;
+
+    use super::hash_files;
+
+    #[test]
+    fn test_hash_files() {
+        let dir = dirs::HOME.join("receipt-test");
+        file::create_dir_all(dir.join("bin")).unwrap();
+        file::write(dir.join("bin").join("tool"), "foo").unwrap();
+        let hashes = hash_files(&dir).unwrap();
+        assert_eq!(
+            hashes.get("bin/tool").unwrap(),
+            "2c26b46b68ffc68ff99b453c1d30413413422d706483bfa0f98a5e886266e7ae"
+        );
+        file::remove_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,303 @@
+use std::path::{Path, PathBuf};
+
+use eyre::{bail, ensure, Result};
+use itertools::Itertools;
+use walkdir::WalkDir;
+
+use crate::archive;
+use crate::cli::version::NATIVE_ARCH;
+use crate::cmd::CmdLineRunner;
+use crate::config::Settings;
+use crate::file;
+use crate::install_context::InstallContext;
+
+const OS_KEYWORDS: &[(&str, &[&str])] = &[
+    ("linux", &["linux"]),
+    ("macos", &["darwin", "macos", "osx", "apple", "mac"]),
+    ("windows", &["windows", "win64", "win32", "pc-windows"]),
+    ("freebsd", &["freebsd"]),
+];
+
+const ARCH_KEYWORDS: &[(&str, &[&str])] = &[
+    ("x86_64", &["x86_64", "x86-64", "amd64", "x64", "64bit"]),
+    ("aarch64", &["aarch64", "arm64"]),
+    ("x86", &["i386", "i686", "x86", "386", "32bit"]),
+    ("arm", &["armv7", "armhf", "arm"]),
+];
+
+const IGNORED_EXTENSIONS: &[&str] = &[
+    ".sha256",
+    ".sha512",
+    ".sha256sum",
+    ".md5",
+    ".asc",
+    ".sig",
+    ".pem",
+    ".sbom",
+    ".json",
+    ".txt",
+    ".deb",
+    ".rpm",
+    ".apk",
+    ".msi",
+    ".pkg",
+    ".dmg",
+];
+
+/// picks the release asset for the current platform from a list of filenames
+pub fn pick_asset<'a>(names: &[&'a str]) -> Option<&'a str> {
+    let (os, arch) = (std::env::consts::OS, *NATIVE_ARCH);
+    if let Some(name) = pick_asset_for(names, os, arch) {
+        return Some(name);
+    }
+    let emulated = emulated_arch(os, arch)?;
+    let name = pick_asset_for(names, os, emulated)?;
+    warn!("no {os}-{arch} asset found, using {name} which runs under emulation");
+    Some(name)
+}
+
+/// the architecture that runs under emulation if a release has no build for this one
+fn emulated_arch(os: &str, arch: &str) -> Option<&'static str> {
+    match (os, arch) {
+        ("windows", "aarch64") if Settings::get().windows_x64_emulation => Some("x86_64"),
+        _ => None,
+    }
+}
+
+fn pick_asset_for<'a>(names: &[&'a str], os: &str, arch: &str) -> Option<&'a str> {
+    let os_keywords = keywords(OS_KEYWORDS, os);
+    let arch_keywords = keywords(ARCH_KEYWORDS, arch);
+    let other_arch_keywords = ARCH_KEYWORDS
+        .iter()
+        .filter(|(k, _)| *k != arch)
+        .flat_map(|(_, v)| v.iter().copied())
+        .filter(|k| !arch_keywords.contains(k))
+        .collect_vec();
+    names
+        .iter()
+        .filter(|n| {
+            let n = n.to_lowercase();
+            !IGNORED_EXTENSIONS.iter().any(|ext| n.ends_with(ext))
+        })
+        .filter(|n| {
+            let n = n.to_lowercase();
+            // "win" on its own is common, e.g.: tool-win-arm64.zip, but "darwin" contains it
+            os_keywords.iter().any(|k| n.contains(k))
+                || (os == "windows" && regex!(r"(^|[^a-z])win([^a-z]|$)").is_match(&n))
+        })
+        .filter_map(|n| {
+            let lower = n.to_lowercase();
+            let mut score = 0;
+            if arch_keywords.iter().any(|k| lower.contains(k)) {
+                score += 10;
+            } else if other_arch_keywords.iter().any(|k| lower.contains(k)) {
+                return None;
+            }
+            if os == "linux" && lower.contains("musl") {
+                // statically linked binaries work on any distro
+                score += 1;
+            }
+            if is_archive(&lower) {
+                score += 1;
+            }
+            Some((score, *n))
+        })
+        .max_by_key(|(score, n)| (*score, std::cmp::Reverse(n.len())))
+        .map(|(_, n)| n)
+}
+
+fn keywords(table: &[(&str, &'static [&'static str])], key: &str) -> &'static [&'static str] {
+    table
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, v)| *v)
+        .unwrap_or_default()
+}
+
+/// extracts an archive (or copies a bare binary) into the install path and makes sure
+/// the binaries are available in `bin/`
+pub fn install_asset(ctx: &InstallContext, asset: &Path, bin_name: &str) -> Result<()> {
+    let install_path = ctx.tv.install_path();
+    let filename = asset.file_name().unwrap().to_string_lossy().to_lowercase();
+    if !is_archive(&filename) {
+        return install_bare_binary(ctx, asset, bin_name);
+    }
+    extract(ctx, asset)?;
+    let bin_dir = install_path.join("bin");
+    if bin_dir.exists() {
+        return Ok(());
+    }
+    let bins = find_executables(&install_path)?;
+    if bins.is_empty() {
+        bail!("no executables found in {filename}");
+    }
+    file::create_dir_all(&bin_dir)?;
+    for bin in bins {
+        let link = bin_dir.join(bin.file_name().unwrap());
+        if cfg!(windows) {
+            file::copy(&bin, &link)?;
+        } else {
+            file::make_symlink(&bin, &link)?;
+        }
+    }
+    Ok(())
+}
+
+/// like `install_asset()` but only the named executables are linked into `bin/`,
+/// they can be anywhere in the archive
+pub fn install_asset_bins(ctx: &InstallContext, asset: &Path, bins: &[String]) -> Result<()> {
+    let install_path = ctx.tv.install_path();
+    let filename = asset.file_name().unwrap().to_string_lossy().to_lowercase();
+    if !is_archive(&filename) {
+        ensure!(
+            bins.len() == 1,
+            "{filename} is a single executable, it can't be installed as {}",
+            bins.join(", ")
+        );
+        return install_bare_binary(ctx, asset, &bins[0]);
+    }
+    extract(ctx, asset)?;
+    let bin_dir = install_path.join("bin");
+    file::create_dir_all(&bin_dir)?;
+    for bin in bins {
+        let exe = format!("{bin}.exe");
+        let Some(path) = WalkDir::new(&install_path)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .map(|e| e.into_path())
+            .find(|p| {
+                p.file_name()
+                    .is_some_and(|n| n == bin.as_str() || n == exe.as_str())
+            })
+        else {
+            bail!("{bin} not found in {filename}");
+        };
+        if path.parent() == Some(bin_dir.as_path()) {
+            continue;
+        }
+        let link = bin_dir.join(path.file_name().unwrap());
+        if cfg!(windows) {
+            file::copy(&path, &link)?;
+        } else {
+            file::make_executable(&path)?;
+            file::make_symlink(&path, &link)?;
+        }
+    }
+    Ok(())
+}
+
+fn extract(ctx: &InstallContext, asset: &Path) -> Result<()> {
+    let install_path = ctx.tv.install_path();
+    let filename = asset.file_name().unwrap().to_string_lossy().to_lowercase();
+    ctx.pr.set_message(format!("extracting {filename}"));
+    if archive::is_archive(asset) {
+        archive::extract(asset, &install_path)?;
+    } else {
+        // other compressions of tarballs, e.g.: .tar.lz
+        CmdLineRunner::new("tar")
+            .arg("-xf")
+            .arg(asset)
+            .arg("-C")
+            .arg(&install_path)
+            .with_pr(ctx.pr.as_ref())
+            .execute()?;
+    }
+    Ok(())
+}
+
+fn install_bare_binary(ctx: &InstallContext, asset: &Path, bin_name: &str) -> Result<()> {
+    let ext = if cfg!(windows) { ".exe" } else { "" };
+    let bin = ctx
+        .tv
+        .install_path()
+        .join("bin")
+        .join(format!("{bin_name}{ext}"));
+    file::create_dir_all(bin.parent().unwrap())?;
+    file::copy(asset, &bin)?;
+    file::make_executable(&bin)?;
+    Ok(())
+}
+
+/// finds executables in the top 2 levels of an extracted archive
+fn find_executables(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut bins = vec![];
+    for path in file::ls(dir)? {
+        if path.is_dir() {
+            bins.extend(file::ls(&path)?.into_iter().filter(|p| is_executable(p)));
+        } else if is_executable(&path) {
+            bins.push(path);
+        }
+    }
+    Ok(bins)
+}
+
+fn is_executable(path: &Path) -> bool {
+    if cfg!(windows) {
+        path.extension().is_some_and(|e| e == "exe")
+    } else {
+        path.is_file() && file::is_executable(path)
+    }
+}
+
+fn is_archive(name: &str) -> bool {
+    archive::is_archive(Path::new(name)) || name.contains(".tar")
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::pick_asset_for;
+
+    #[test]
+    fn test_pick_asset() {
+        let names = [
+            "tool-1.0.0-linux-amd64.tar.gz",
+            "tool-1.0.0-linux-amd64.tar.gz.sha256",
+            "tool-1.0.0-linux-arm64.tar.gz",
+            "tool-1.0.0-darwin-arm64.tar.gz",
+            "tool-1.0.0-darwin-amd64.tar.gz",
+            "tool-1.0.0-windows-amd64.zip",
+            "checksums.txt",
+        ];
+        let t = |os, arch| pick_asset_for(&names, os, arch);
+        assert_eq!(t("linux", "x86_64"), Some("tool-1.0.0-linux-amd64.tar.gz"));
+        assert_eq!(t("linux", "aarch64"), Some("tool-1.0.0-linux-arm64.tar.gz"));
+        assert_eq!(
+            t("macos", "aarch64"),
+            Some("tool-1.0.0-darwin-arm64.tar.gz")
+        );
+        assert_eq!(t("windows", "x86_64"), Some("tool-1.0.0-windows-amd64.zip"));
+        assert_eq!(t("freebsd", "x86_64"), None);
+    }
+
+    #[test]
+    fn test_pick_asset_musl() {
+        let names = [
+            "tool-x86_64-unknown-linux-gnu.tar.gz",
+            "tool-x86_64-unknown-linux-musl.tar.gz",
+        ];
+        assert_eq!(
+            pick_asset_for(&names, "linux", "x86_64"),
+            Some("tool-x86_64-unknown-linux-musl.tar.gz")
+        );
+    }
+
+    #[test]
+    fn test_pick_asset_windows_arm64() {
+        let names = [
+            "tool-darwin-arm64.tar.gz",
+            "tool-win-x64.zip",
+            "tool-win-arm64.zip",
+        ];
+        let t = |names: &[&'static str]| pick_asset_for(names, "windows", "aarch64");
+        assert_eq!(t(&names), Some("tool-win-arm64.zip"));
+        // x64 builds are only used as a fallback by pick_asset
+        assert_eq!(t(&names[..2]), None);
+        assert_eq!(
+            pick_asset_for(&names[..2], "windows", "x86_64"),
+            Some("tool-win-x64.zip")
+        );
+    }
+}
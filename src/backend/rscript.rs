@@ -0,0 +1,233 @@
+use std::fmt::Debug;
+use std::path::Path;
+
+use eyre::bail;
+use itertools::Itertools;
+use versions::Versioning;
+
+use crate::backend::{Backend, BackendType};
+use crate::cache::CacheManager;
+use crate::cli::args::BackendArg;
+use crate::cmd::CmdLineRunner;
+use crate::config::Settings;
+use crate::file;
+use crate::http::{HTTP, HTTP_FETCH};
+use crate::install_context::InstallContext;
+use crate::toolset::ToolRequest;
+
+const CRAN: &str = "https://cloud.r-project.org";
+
+/// Installs CRAN packages into an isolated library and creates wrappers for their entry points
+///
+/// [tools]
+/// "rscript:lintr" = { version = "3.1.2", entrypoints = ["lint=lintr::lint_dir"] }
+#[derive(Debug)]
+pub struct RscriptBackend {
+    fa: BackendArg,
+    remote_version_cache: CacheManager<Vec<String>>,
+}
+
+impl Backend for RscriptBackend {
+    fn get_type(&self) -> BackendType {
+        BackendType::Rscript
+    }
+
+    fn fa(&self) -> &BackendArg {
+        &self.fa
+    }
+
+    fn get_dependencies(&self, _tvr: &ToolRequest) -> eyre::Result<Vec<BackendArg>> {
+        Ok(vec!["r".into()])
+    }
+
+    /// archived versions come from the CRAN archive listing, the current version from its DESCRIPTION
+    fn _list_remote_versions(&self) -> eyre::Result<Vec<String>> {
+        self.remote_version_cache
+            .get_or_try_init(|| {
+                let name = self.name();
+                let archive = HTTP_FETCH
+                    .get_text(format!("{CRAN}/src/contrib/Archive/{name}/"))
+                    .unwrap_or_default();
+                let description =
+                    HTTP_FETCH.get_text(format!("{CRAN}/web/packages/{name}/DESCRIPTION"))?;
+                Ok(archive_versions(name, &archive)
+                    .into_iter()
+                    .chain(description_version(&description))
+                    .unique()
+                    .sorted_by_cached_key(|v| Versioning::new(v))
+                    .collect())
+            })
+            .cloned()
+    }
+
+    fn install_version_impl(&self, ctx: &InstallContext) -> eyre::Result<()> {
+        let settings = Settings::get();
+        settings.ensure_experimental("rscript backend")?;
+        let opts = ctx.tv.request.options();
+        let repo = opts.get("repo").map(|r| r.as_str()).unwrap_or(CRAN);
+        let name = self.name();
+        let version = &ctx.tv.version;
+
+        let filename = format!("{name}_{version}.tar.gz");
+        let tarball = ctx.tv.download_path().join(&filename);
+        ctx.pr.set_message(format!("downloading {filename}"));
+        let current = format!("{repo}/src/contrib/{filename}");
+        if HTTP
+            .download_file(&current, &tarball, Some(ctx.pr.as_ref()))
+            .is_err()
+        {
+            let archived = format!("{repo}/src/contrib/Archive/{name}/{filename}");
+            HTTP.download_file(&archived, &tarball, Some(ctx.pr.as_ref()))?;
+        }
+
+        let lib = ctx.tv.install_path().join("lib");
+        file::create_dir_all(&lib)?;
+        CmdLineRunner::new("Rscript")
+            .arg("-e")
+            .arg(INSTALL_SCRIPT)
+            .with_pr(ctx.pr.as_ref())
+            .envs(self.dependency_env()?)
+            .env("R_LIBS", &lib)
+            .env("MISE_R_REPO", repo)
+            .env("MISE_R_PACKAGE", name)
+            .env("MISE_R_TARBALL", &tarball)
+            .execute()?;
+
+        let bin = ctx.tv.install_path().join("bin");
+        file::create_dir_all(&bin)?;
+        let exec_dir = lib.join(name).join("exec");
+        for script in file::ls(&exec_dir).unwrap_or_default() {
+            let name = script.file_name().unwrap().to_string_lossy().to_string();
+            let args = vec!["Rscript".to_string(), script.to_string_lossy().to_string()];
+            write_wrapper(&bin, &name, &lib, &args)?;
+        }
+        if let Some(entrypoints) = opts.get("entrypoints") {
+            for (bin_name, func) in parse_entrypoints(entrypoints)? {
+                let expr = format!("do.call({func}, as.list(commandArgs(trailingOnly = TRUE)))");
+                let args = vec!["Rscript".to_string(), "-e".to_string(), expr];
+                write_wrapper(&bin, &bin_name, &lib, &args)?;
+            }
+        }
+        if file::ls(&bin)?.is_empty() {
+            warn!(
+                "{name} has no exec/ scripts, set the entrypoints tool option to create executables, e.g.: entrypoints = [\"{name}={name}::main\"]"
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl RscriptBackend {
+    pub fn new(name: String) -> Self {
+        let fa = BackendArg::new(BackendType::Rscript, &name);
+        Self {
+            remote_version_cache: CacheManager::new(
+                fa.cache_path.join("remote_versions-$KEY.msgpack.z"),
+            ),
+            fa,
+        }
+    }
+}
+
+/// installs the dependencies listed in the package's DESCRIPTION from the repo, then the
+/// package itself from the downloaded tarball so the requested version is used
+const INSTALL_SCRIPT: &str = r#"
+lib <- Sys.getenv("R_LIBS")
+tarball <- Sys.getenv("MISE_R_TARBALL")
+dir <- tempfile()
+untar(tarball, exdir = dir)
+desc <- read.dcf(file.path(dir, Sys.getenv("MISE_R_PACKAGE"), "DESCRIPTION"))
+fields <- intersect(c("Depends", "Imports", "LinkingTo"), colnames(desc))
+deps <- unlist(strsplit(desc[, fields], ","))
+deps <- trimws(sub("\\(.*\\)", "", deps))
+base <- rownames(installed.packages(priority = "base"))
+deps <- setdiff(deps[nzchar(deps)], c("R", base, rownames(installed.packages(lib.loc = lib))))
+if (length(deps) > 0) install.packages(deps, lib = lib, repos = Sys.getenv("MISE_R_REPO"))
+install.packages(tarball, lib = lib, repos = NULL, type = "source")
+"#;
+
+fn write_wrapper(bin: &Path, name: &str, lib: &Path, args: &[String]) -> eyre::Result<()> {
+    if cfg!(windows) {
+        let args = args.iter().map(|a| format!("\"{a}\"")).join(" ");
+        let wrapper = format!(
+            "@echo off\r\nset \"R_LIBS={}\"\r\n{args} %*\r\n",
+            lib.display()
+        );
+        file::write(bin.join(format!("{name}.cmd")), wrapper)?;
+    } else {
+        let wrapper = format!(
+            "#!/bin/sh\nR_LIBS={} exec {} \"$@\"\n",
+            shell_words::quote(&lib.to_string_lossy()),
+            shell_words::join(args)
+        );
+        let path = bin.join(name);
+        file::write(&path, wrapper)?;
+        file::make_executable(&path)?;
+    }
+    Ok(())
+}
+
+/// entrypoints are "bin=pkg::function" pairs
+fn parse_entrypoints(entrypoints: &str) -> eyre::Result<Vec<(String, String)>> {
+    entrypoints
+        .split(',')
+        .map(|e| e.trim())
+        .filter(|e| !e.is_empty())
+        .map(|e| match e.split_once('=') {
+            Some((bin, func)) => Ok((bin.trim().to_string(), func.trim().to_string())),
+            None => bail!("invalid entrypoint {e}, expected bin=pkg::function"),
+        })
+        .collect()
+}
+
+fn archive_versions(name: &str, listing: &str) -> Vec<String> {
+    let re = regex::Regex::new(&format!(
+        r#"href="{}_([^"/]+)\.tar\.gz""#,
+        regex::escape(name)
+    ))
+    .unwrap();
+    re.captures_iter(listing)
+        .map(|c| c[1].to_string())
+        .collect()
+}
+
+fn description_version(description: &str) -> Option<String> {
+    description
+        .lines()
+        .find_map(|l| l.strip_prefix("Version:"))
+        .map(|v| v.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::{archive_versions, description_version, parse_entrypoints};
+
+    #[test]
+    fn test_archive_versions() {
+        let listing = r#"<a href="lintr_3.0.0.tar.gz">lintr_3.0.0.tar.gz</a>
+<a href="lintr_3.1.0.tar.gz">lintr_3.1.0.tar.gz</a>
+<a href="lintrx_1.0.tar.gz">lintrx_1.0.tar.gz</a>"#;
+        assert_eq!(archive_versions("lintr", listing), vec!["3.0.0", "3.1.0"]);
+    }
+
+    #[test]
+    fn test_description_version() {
+        let description = "Package: lintr\nTitle: A 'Linter' for R Code\nVersion: 3.1.2\n";
+        assert_eq!(description_version(description).unwrap(), "3.1.2");
+    }
+
+    #[test]
+    fn test_parse_entrypoints() {
+        assert_eq!(
+            parse_entrypoints("lint=lintr::lint_dir, style = styler::style_dir").unwrap(),
+            vec![
+                ("lint".to_string(), "lintr::lint_dir".to_string()),
+                ("style".to_string(), "styler::style_dir".to_string())
+            ]
+        );
+        assert!(parse_entrypoints("lintr").is_err());
+    }
+}
@@ -14,8 +14,9 @@ use crate::cache::CacheManager;
 use crate::cli::args::BackendArg;
 use crate::cmd::CmdLineRunner;
 use crate::config::Settings;
+use crate::file;
+use crate::forge::Forge;
 use crate::install_context::InstallContext;
-use crate::{file, github};
 
 #[derive(Debug)]
 pub struct SPMBackend {
@@ -45,7 +46,9 @@ impl Backend for SPMBackend {
         let repo = SwiftPackageRepo::new(self.name())?;
         self.remote_version_cache
             .get_or_try_init(|| {
-                Ok(github::list_releases(repo.shorthand.as_str())?
+                Ok(repo
+                    .forge
+                    .list_releases(&repo.shorthand)?
                     .into_iter()
                     .map(|r| r.tag_name)
                     .rev()
@@ -67,20 +70,11 @@ impl Backend for SPMBackend {
         };
         let repo_dir = self.clone_package_repo(&repo, &revision)?;
 
-        let executables = self.get_executable_names(&repo_dir)?;
-        if executables.is_empty() {
-            return Err(eyre::eyre!("No executables found in the package"));
-        }
-        for executable in executables {
-            let bin_path = self.build_executable(&executable, &repo_dir, ctx)?;
-            let install_bin_path = ctx.tv.install_path().join("bin");
-            self.copy_build_artifacts(&executable, &bin_path, &install_bin_path)?;
-        }
-
+        // the clone and its .build directory are removed even if the build fails
+        let result = self.build_package(ctx, &repo_dir);
         debug!("Cleaning up temporary files");
         file::remove_all(&repo_dir)?;
-
-        Ok(())
+        result
     }
 }
 
@@ -117,6 +111,19 @@ impl SPMBackend {
         Ok(tmp_repo_dir)
     }
 
+    fn build_package(&self, ctx: &InstallContext, repo_dir: &PathBuf) -> eyre::Result<()> {
+        let executables = self.get_executable_names(repo_dir)?;
+        if executables.is_empty() {
+            return Err(eyre::eyre!("No executables found in the package"));
+        }
+        for executable in executables {
+            let bin_path = self.build_executable(&executable, repo_dir, ctx)?;
+            let install_bin_path = ctx.tv.install_path().join("bin");
+            self.copy_build_artifacts(&executable, &bin_path, &install_bin_path)?;
+        }
+        Ok(())
+    }
+
     fn get_executable_names(&self, repo_dir: &PathBuf) -> Result<Vec<String>, eyre::Error> {
         let package_json = cmd!(
             "swift",
@@ -208,31 +215,28 @@ impl SPMBackend {
 struct SwiftPackageRepo {
     /// https://github.com/owner/repo.git
     url: Url,
-    /// owner/repo_name
+    /// owner/repo_name, or group/subgroup/project on GitLab
     shorthand: String,
+    forge: Forge,
 }
 
 impl SwiftPackageRepo {
     fn new(name: &str) -> Result<Self, eyre::Error> {
         let shorthand_regex = regex!(r"^[a-zA-Z0-9_-]+/[a-zA-Z0-9_-]+$");
-        let shorthand_in_url_regex =
-            regex!(r"https://github.com/([a-zA-Z0-9_-]+/[a-zA-Z0-9_-]+)\.git");
-
-        let shorthand = if let Some(Some(m)) =
-            shorthand_in_url_regex.captures(name).map(|c| c.get(1))
-        {
-            m.as_str()
-        } else if shorthand_regex.is_match(name) {
-            name
+
+        let (forge, shorthand) = if shorthand_regex.is_match(name) {
+            (Forge::Github, name.to_string())
+        } else if let Some(repo) = Forge::parse_url(name) {
+            repo
         } else {
-            Err(eyre::eyre!("Invalid Swift package repository: {}. The repository should either be a GitHub repository slug, owner/name, or the complete URL, https://github.com/owner/name.", name))?
+            Err(eyre::eyre!("Invalid Swift package repository: {}. The repository should either be a GitHub repository slug, owner/name, or the complete URL of a GitHub or GitLab repository, https://github.com/owner/name.git.", name))?
         };
-        let url_str = format!("https://github.com/{}.git", shorthand);
-        let url = Url::parse(&url_str)?;
+        let url = Url::parse(&forge.repo_url(&shorthand))?;
 
         Ok(Self {
             url,
-            shorthand: shorthand.to_string(),
+            shorthand,
+            forge,
         })
     }
 
@@ -243,7 +247,7 @@ impl SwiftPackageRepo {
 
 #[cfg(test)]
 mod tests {
-    use pretty_assertions::assert_str_eq;
+    use pretty_assertions::{assert_eq, assert_str_eq};
     use test_log::test;
 
     use super::*;
@@ -269,6 +273,18 @@ mod tests {
         );
         assert_str_eq!(package_repo.shorthand, "nicklockwood/SwiftFormat");
     }
+
+    #[test]
+    fn test_spm_repo_init_by_gitlab_url() {
+        let package_name = "https://gitlab.com/group/tools/swift-tool.git";
+        let package_repo = SwiftPackageRepo::new(package_name).unwrap();
+        assert_str_eq!(
+            package_repo.url.as_str(),
+            "https://gitlab.com/group/tools/swift-tool.git"
+        );
+        assert_str_eq!(package_repo.shorthand, "group/tools/swift-tool");
+        assert_eq!(package_repo.forge, Forge::Gitlab("gitlab.com".to_string()));
+    }
 }
 
 /// https://developer.apple.com/documentation/packagedescription
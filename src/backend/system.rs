@@ -0,0 +1,112 @@
+use std::fmt::Debug;
+use std::path::{Path, PathBuf};
+
+use eyre::{bail, eyre};
+use versions::Versioning;
+
+use crate::backend::{Backend, BackendType};
+use crate::cli::args::BackendArg;
+use crate::file;
+use crate::file::display_path;
+use crate::install_context::InstallContext;
+use crate::toolset::{ToolVersion, ToolVersionOptions};
+
+/// A tool provided by the system that mise checks but does not install
+///
+/// [tools]
+/// system-go = { backend = "system", bin = "go", min_version = "1.22", version_arg = "version" }
+#[derive(Debug)]
+pub struct SystemBackend {
+    fa: BackendArg,
+}
+
+impl Backend for SystemBackend {
+    fn get_type(&self) -> BackendType {
+        BackendType::System
+    }
+
+    fn fa(&self) -> &BackendArg {
+        &self.fa
+    }
+
+    fn _list_remote_versions(&self) -> eyre::Result<Vec<String>> {
+        Ok(vec![])
+    }
+
+    fn is_version_installed(&self, _tv: &ToolVersion) -> bool {
+        true
+    }
+
+    fn install_version_impl(&self, ctx: &InstallContext) -> eyre::Result<()> {
+        self.check(&ctx.tv)?;
+        Ok(())
+    }
+
+    /// the bin is already on PATH
+    fn list_bin_paths(&self, _tv: &ToolVersion) -> eyre::Result<Vec<PathBuf>> {
+        Ok(vec![])
+    }
+
+    fn which(&self, tv: &ToolVersion, bin_name: &str) -> eyre::Result<Option<PathBuf>> {
+        let bin = self.bin(&tv.request.options());
+        if bin != bin_name {
+            return Ok(None);
+        }
+        Ok(file::which(bin))
+    }
+}
+
+impl SystemBackend {
+    pub fn new(name: String) -> Self {
+        Self {
+            fa: BackendArg::new(BackendType::System, &name),
+        }
+    }
+
+    /// finds the bin on PATH and checks that it is at least `min_version`,
+    /// returns the path and the version that was found
+    pub fn check(&self, tv: &ToolVersion) -> eyre::Result<(PathBuf, Option<String>)> {
+        let opts = tv.request.options();
+        let bin = self.bin(&opts);
+        let Some(path) = file::which(&bin) else {
+            bail!("{}: {bin} was not found on PATH", self.id());
+        };
+        let Some(min_version) = opts.get("min_version") else {
+            let version = system_version(&path, &opts).ok();
+            return Ok((path, version));
+        };
+        let version = system_version(&path, &opts)?;
+        if Versioning::new(&version) < Versioning::new(min_version) {
+            bail!(
+                "{}: {bin} {version} at {} is older than min_version {min_version}",
+                self.id(),
+                display_path(&path)
+            );
+        }
+        Ok((path, Some(version)))
+    }
+
+    fn bin(&self, opts: &ToolVersionOptions) -> String {
+        opts.get("bin")
+            .cloned()
+            .unwrap_or_else(|| self.name().to_string())
+    }
+}
+
+/// runs `<bin> --version` (or `version_arg`) and finds the first version number in the output
+fn system_version(path: &Path, opts: &ToolVersionOptions) -> eyre::Result<String> {
+    let arg = opts
+        .get("version_arg")
+        .map(|s| s.as_str())
+        .unwrap_or("--version");
+    let output = cmd!(path, arg).stderr_to_stdout().read()?;
+    regex!(r"\d+(\.\d+)+")
+        .find(&output)
+        .map(|m| m.as_str().to_string())
+        .ok_or_else(|| {
+            eyre!(
+                "no version found in the output of {} {arg}",
+                display_path(path)
+            )
+        })
+}
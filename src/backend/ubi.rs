@@ -1,19 +1,28 @@
 use std::fmt::Debug;
+use std::path::{Path, PathBuf};
 
-use crate::backend::{Backend, BackendType};
+use eyre::{bail, eyre};
+use itertools::Itertools;
+use regex::Regex;
+
+use crate::backend::release_assets::{install_asset, install_asset_bins, pick_asset};
+use crate::backend::{Backend, BackendType, VersionInfo};
 use crate::cache::CacheManager;
 use crate::cli::args::BackendArg;
+use crate::cli::version::NATIVE_ARCH;
 use crate::cmd::CmdLineRunner;
 use crate::config::{Config, Settings};
 use crate::env::GITHUB_TOKEN;
-use crate::github;
+use crate::github::{GithubAsset, GithubRelease};
+use crate::http::HTTP;
 use crate::install_context::InstallContext;
-use crate::toolset::ToolRequest;
+use crate::toolset::{ToolRequest, ToolVersionOptions};
+use crate::{file, github, hash, signature};
 
 #[derive(Debug)]
 pub struct UbiBackend {
     fa: BackendArg,
-    remote_version_cache: CacheManager<Vec<String>>,
+    remote_version_cache: CacheManager<Vec<VersionInfo>>,
 }
 
 // Uses ubi for installations https://github.com/houseabsolute/ubi
@@ -33,14 +42,29 @@ impl Backend for UbiBackend {
 
     // TODO: v0.0.3 is stripped of 'v' such that it reports incorrectly in tool :-/
     fn _list_remote_versions(&self) -> eyre::Result<Vec<String>> {
+        Ok(self
+            ._list_remote_versions_with_info()?
+            .into_iter()
+            .map(|v| v.version)
+            .collect())
+    }
+
+    fn _list_remote_versions_with_info(&self) -> eyre::Result<Vec<VersionInfo>> {
         if name_is_url(self.name()) {
-            Ok(vec!["latest".to_string()])
+            Ok(vec![VersionInfo {
+                version: "latest".to_string(),
+                ..Default::default()
+            }])
         } else {
             self.remote_version_cache
                 .get_or_try_init(|| {
                     Ok(github::list_releases(self.name())?
                         .into_iter()
-                        .map(|r| r.tag_name)
+                        .map(|r| VersionInfo {
+                            version: r.tag_name,
+                            created_at: Some(r.published_at),
+                            release_url: Some(r.html_url),
+                        })
                         .rev()
                         .collect())
                 })
@@ -53,27 +77,54 @@ impl Backend for UbiBackend {
         let settings = Settings::get();
         let version = &ctx.tv.version;
         settings.ensure_experimental("ubi backend")?;
+        let bins = bins(&ctx.tv.request.options());
+        if name_is_url(self.name()) && signature::is_required(&ctx.tv.request.options()) {
+            bail!(
+                "{} can't verify signatures of assets installed from a url",
+                self.id()
+            );
+        }
+        if !name_is_url(self.name()) {
+            if let Some(asset) = self.release_asset(ctx)? {
+                return match &bins {
+                    Some(bins) => install_asset_bins(ctx, &asset, bins),
+                    None => install_asset(ctx, &asset, &self.bin_name()),
+                };
+            }
+        }
         // Workaround because of not knowing how to pull out the value correctly without quoting
         let path_with_bin = ctx.tv.install_path().join("bin");
 
-        let mut cmd = CmdLineRunner::new("ubi")
-            .arg("--in")
-            .arg(path_with_bin)
-            .arg("--project")
-            .arg(self.name())
-            .with_pr(ctx.pr.as_ref())
-            .envs(ctx.ts.env_with_path(&config)?)
-            .prepend_path(ctx.ts.list_paths())?;
-
-        if let Some(token) = &*GITHUB_TOKEN {
-            cmd = cmd.env("GITHUB_TOKEN", token);
-        }
+        // ubi installs a single executable so it is run once for each of `bins`
+        let exes = match bins {
+            Some(bins) => bins.into_iter().map(Some).collect_vec(),
+            None => vec![None],
+        };
+        for exe in exes {
+            let mut cmd = CmdLineRunner::new("ubi")
+                .arg("--in")
+                .arg(&path_with_bin)
+                .arg("--project")
+                .arg(self.name())
+                .with_pr(ctx.pr.as_ref())
+                .envs(ctx.ts.env_with_path(&config)?)
+                .prepend_path(ctx.ts.list_paths())?;
 
-        if version != "latest" {
-            cmd = cmd.arg("--tag").arg(version);
-        }
+            if let Some(token) = &*GITHUB_TOKEN {
+                cmd = cmd.env("GITHUB_TOKEN", token);
+            }
+
+            if version != "latest" {
+                cmd = cmd.arg("--tag").arg(version);
+            }
 
-        cmd.execute()
+            if let Some(exe) = exe {
+                cmd = cmd.arg("--exe").arg(exe);
+            }
+
+            cmd.execute()?;
+        }
+        Ok(())
     }
 }
 
@@ -82,13 +133,224 @@ impl UbiBackend {
         let fa = BackendArg::new(BackendType::Ubi, &name);
         Self {
             remote_version_cache: CacheManager::new(
-                fa.cache_path.join("remote_versions-$KEY.msgpack.z"),
+                fa.cache_path.join("remote_versions_info-$KEY.msgpack.z"),
             ),
             fa,
         }
     }
+
+    fn bin_name(&self) -> String {
+        let name = self.name().trim_end_matches('/');
+        name.rsplit('/').next().unwrap_or(name).to_string()
+    }
+
+    /// downloads the release asset itself instead of letting ubi pick and download it if the
+    /// `asset_pattern` option is set or the release publishes checksums to verify it with before
+    /// it is extracted. Returns None to let ubi install it
+    fn release_asset(&self, ctx: &InstallContext) -> eyre::Result<Option<PathBuf>> {
+        let opts = ctx.tv.request.options();
+        let pattern = opts.get("asset_pattern");
+        let verify_signature = signature::is_required(&opts);
+        let release = match github::get_release(self.name(), &ctx.tv.version) {
+            Ok(release) => release,
+            Err(err) if pattern.is_none() && !verify_signature => {
+                debug!("{}: failed to fetch release: {err:#}", self.id());
+                return Ok(None);
+            }
+            Err(err) => return Err(err),
+        };
+        let names = release.assets.iter().map(|a| a.name.as_str()).collect_vec();
+        let name = match pattern {
+            Some(pattern) => self.match_asset(&names, pattern, &ctx.tv.version)?,
+            None => match pick_asset(&names) {
+                Some(name) => name,
+                None => return Ok(None),
+            },
+        };
+        let download_path = ctx.tv.download_path();
+        let checksum = self.checksum(&release, name, &download_path)?;
+        // when mise runs under emulation ubi would also detect the emulated arch
+        let emulated = *NATIVE_ARCH != std::env::consts::ARCH;
+        if checksum.is_none()
+            && pattern.is_none()
+            && !opts.contains_key("bins")
+            && !emulated
+            && !verify_signature
+        {
+            return Ok(None);
+        }
+
+        let asset = release.assets.iter().find(|a| a.name == name).unwrap();
+        let path = download_path.join(name);
+        ctx.pr.set_message(format!("downloading {name}"));
+        HTTP.download_file(&asset.browser_download_url, &path, Some(ctx.pr.as_ref()))?;
+        if let Some(checksum) = checksum {
+            ctx.pr.set_message(format!("checksum {name}"));
+            if let Err(err) = hash::ensure_checksum_sha256(&path, &checksum, Some(ctx.pr.as_ref()))
+            {
+                match Settings::get().ubi_checksum_mismatch.as_str() {
+                    "warn" => warn!("{}: {err}", self.id()),
+                    _ => return Err(err),
+                }
+            }
+        }
+        if verify_signature {
+            // signatures published as release assets next to the asset, e.g.: tool.tar.gz.minisig
+            let signature_urls = signature::Method::from_options(&opts)?
+                .map(|m| m.signature_names(name))
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|n| release.assets.iter().find(|a| a.name == n))
+                .map(|a| a.browser_download_url.clone())
+                .collect_vec();
+            signature::verify(&ctx.tv, &path, &signature_urls, ctx.pr.as_ref())?;
+        }
+        Ok(Some(path))
+    }
+
+    /// the asset matching the `asset_pattern` regex, if several match the one for this platform
+    fn match_asset<'a>(
+        &self,
+        names: &[&'a str],
+        pattern: &str,
+        version: &str,
+    ) -> eyre::Result<&'a str> {
+        let re = Regex::new(pattern)?;
+        let matching = names
+            .iter()
+            .filter(|n| re.is_match(n))
+            .copied()
+            .collect_vec();
+        match matching.as_slice() {
+            [] => bail!(
+                "no asset in {}@{version} matches asset_pattern {pattern}, assets: {}",
+                self.name(),
+                names.join(", ")
+            ),
+            [name] => Ok(name),
+            _ => pick_asset(&matching).ok_or_else(|| {
+                eyre!(
+                    "multiple assets in {}@{version} match asset_pattern {pattern}: {}",
+                    self.name(),
+                    matching.join(", ")
+                )
+            }),
+        }
+    }
+
+    /// the sha256 of the asset if the release publishes checksums
+    fn checksum(
+        &self,
+        release: &GithubRelease,
+        name: &str,
+        download_path: &Path,
+    ) -> eyre::Result<Option<String>> {
+        let Some(checksums) = checksum_asset(release, name) else {
+            debug!("{}: no checksums published for {name}", self.id());
+            return Ok(None);
+        };
+        let checksums_path = download_path.join(&checksums.name);
+        HTTP.download_file(&checksums.browser_download_url, &checksums_path, None)?;
+        let checksum = find_checksum(&file::read_to_string(&checksums_path)?, name);
+        if checksum.is_none() {
+            debug!("{}: {name} is not in {}", self.id(), checksums.name);
+        }
+        Ok(checksum)
+    }
+}
+
+/// executables to install from the `bins` option, e.g.: bins = ["etcd", "etcdctl"]
+fn bins(opts: &ToolVersionOptions) -> Option<Vec<String>> {
+    let bins = opts
+        .get("bins")?
+        .split(',')
+        .map(|b| b.trim().to_string())
+        .filter(|b| !b.is_empty())
+        .collect_vec();
+    (!bins.is_empty()).then_some(bins)
+}
+
+/// `<asset>.sha256` or a file with the checksums of every asset like `checksums.txt` or `SHA256SUMS`
+fn checksum_asset<'a>(release: &'a GithubRelease, name: &str) -> Option<&'a GithubAsset> {
+    let per_asset = [format!("{name}.sha256"), format!("{name}.sha256sum")];
+    release
+        .assets
+        .iter()
+        .find(|a| per_asset.contains(&a.name))
+        .or_else(|| {
+            release.assets.iter().find(|a| {
+                regex!(r"(?i)^(.*[-_.])?(sha256sums?|checksums?)(\.txt)?$").is_match(&a.name)
+            })
+        })
+}
+
+/// finds the sha256 of a file in the `<hash>  <file>` format of sha256sum,
+/// a line with only a hash is used for `<asset>.sha256` files
+fn find_checksum(text: &str, name: &str) -> Option<String> {
+    text.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        let hash = parts.next()?;
+        let matches = match parts.next() {
+            Some(file) => file.trim_start_matches('*').trim_start_matches("./") == name,
+            None => hash.len() == 64,
+        };
+        matches.then(|| hash.to_lowercase())
+    })
 }
 
 fn name_is_url(n: &str) -> bool {
     n.starts_with("http")
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::{bins, find_checksum, UbiBackend};
+
+    #[test]
+    fn test_find_checksum() {
+        let sums = "\
+aaaa000000000000000000000000000000000000000000000000000000000000  tool-linux-amd64.tar.gz
+BBBB000000000000000000000000000000000000000000000000000000000000 *tool-darwin-arm64.tar.gz
+";
+        assert_eq!(
+            find_checksum(sums, "tool-linux-amd64.tar.gz").unwrap(),
+            "aaaa000000000000000000000000000000000000000000000000000000000000"
+        );
+        assert_eq!(
+            find_checksum(sums, "tool-darwin-arm64.tar.gz").unwrap(),
+            "bbbb000000000000000000000000000000000000000000000000000000000000"
+        );
+        assert_eq!(find_checksum(sums, "tool-windows-amd64.zip"), None);
+        let single = "cccc000000000000000000000000000000000000000000000000000000000000\n";
+        assert_eq!(
+            find_checksum(single, "tool.zip").unwrap(),
+            "cccc000000000000000000000000000000000000000000000000000000000000"
+        );
+    }
+
+    #[test]
+    fn test_match_asset() {
+        let ubi = UbiBackend::new("owner/tool".into());
+        let names = [
+            "tool-x86_64-unknown-linux-gnu.tar.gz",
+            "tool-x86_64-unknown-linux-musl.tar.gz",
+            "tool-x86_64-pc-windows-msvc.zip",
+        ];
+        let t = |pattern| ubi.match_asset(&names, pattern, "1.0.0");
+        assert_eq!(
+            t("linux-gnu").unwrap(),
+            "tool-x86_64-unknown-linux-gnu.tar.gz"
+        );
+        assert_eq!(t(r"msvc\.zip$").unwrap(), "tool-x86_64-pc-windows-msvc.zip");
+        assert!(t("aarch64").is_err());
+    }
+
+    #[test]
+    fn test_bins() {
+        let opts = [("bins".to_string(), "etcd, etcdctl".to_string())].into();
+        assert_eq!(bins(&opts).unwrap(), vec!["etcd", "etcdctl"]);
+        assert_eq!(bins(&Default::default()), None);
+    }
+}
@@ -0,0 +1,254 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+#[cfg(feature = "wasm")]
+use extism::{Manifest, Plugin, Wasm};
+use eyre::{bail, eyre, Result};
+use serde::de::DeserializeOwned;
+use serde_derive::Deserialize;
+use serde_json::json;
+
+use crate::backend::release_assets::{install_asset, install_asset_bins};
+use crate::backend::{Backend, BackendType};
+use crate::cache::CacheManager;
+use crate::cli::args::BackendArg;
+use crate::cli::version::{ARCH, OS};
+use crate::config::{Config, Settings};
+#[cfg(feature = "wasm")]
+use crate::file;
+use crate::hash::ensure_checksum_sha256;
+#[cfg(feature = "wasm")]
+use crate::hash::hash_to_str;
+use crate::http::HTTP;
+use crate::install_context::InstallContext;
+use crate::toolset::{ToolRequest, ToolVersion, Toolset};
+
+/// the version of the host API, plugins export `mise_api_version` returning it
+#[cfg(feature = "wasm")]
+const API_VERSION: &str = "1";
+
+/// Backends written as WebAssembly plugins, e.g.: with the extism PDK in Rust, Go, or JS
+///
+/// [plugins]
+/// my-tool = "https://example.com/my-tool.wasm"
+///
+/// [tools]
+/// "wasm:my-tool" = "1"
+///
+/// Plugins run sandboxed without filesystem or process access. They only describe what to
+/// install, mise downloads, verifies, and extracts it. Loading plugins needs the `wasm` cargo
+/// feature since it pulls in wasmtime.
+#[derive(Debug)]
+pub struct WasmBackend {
+    fa: BackendArg,
+    remote_version_cache: CacheManager<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListVersions {
+    versions: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Download {
+    url: String,
+    sha256: Option<String>,
+    #[serde(default)]
+    bins: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BinPaths {
+    bin_paths: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExecEnv {
+    env: BTreeMap<String, String>,
+}
+
+impl Backend for WasmBackend {
+    fn get_type(&self) -> BackendType {
+        BackendType::Wasm
+    }
+
+    fn fa(&self) -> &BackendArg {
+        &self.fa
+    }
+
+    fn _list_remote_versions(&self) -> Result<Vec<String>> {
+        self.remote_version_cache
+            .get_or_try_init(|| {
+                let out: ListVersions = self.call("list_versions", json!({}))?;
+                Ok(out.versions)
+            })
+            .cloned()
+    }
+
+    fn install_version_impl(&self, ctx: &InstallContext) -> Result<()> {
+        Settings::get().ensure_experimental("wasm backend")?;
+        let download: Download = self.call(
+            "download",
+            json!({"version": ctx.tv.version, "os": *OS, "arch": *ARCH}),
+        )?;
+        let filename = download
+            .url
+            .split(['?', '#'])
+            .next()
+            .and_then(|u| u.rsplit('/').next())
+            .filter(|f| !f.is_empty())
+            .unwrap_or(self.name())
+            .to_string();
+        let path = ctx.tv.download_path().join(&filename);
+        ctx.pr.set_message(format!("downloading {filename}"));
+        HTTP.download_file(&download.url, &path, Some(ctx.pr.as_ref()))?;
+        if let Some(sha256) = &download.sha256 {
+            ctx.pr.set_message(format!("checksum {filename}"));
+            ensure_checksum_sha256(&path, sha256, Some(ctx.pr.as_ref()))?;
+        }
+        match download.bins.as_slice() {
+            [] => install_asset(ctx, &path, self.name()),
+            bins => install_asset_bins(ctx, &path, bins),
+        }
+    }
+
+    /// cached since loading the plugin is too slow to do every time the prompt is displayed
+    fn list_bin_paths(&self, tv: &ToolVersion) -> Result<Vec<PathBuf>> {
+        if matches!(tv.request, ToolRequest::System(_)) {
+            return Ok(vec![]);
+        }
+        let bin_paths =
+            CacheManager::<Vec<String>>::new(tv.cache_path().join("bin_paths-$KEY.msgpack.z"))
+                .with_fresh_file(tv.install_path())
+                .get_or_try_init(|| {
+                    let out: Option<BinPaths> =
+                        self.call_optional("bin_paths", json!({"version": tv.version}))?;
+                    Ok(out.map_or_else(|| vec!["bin".to_string()], |o| o.bin_paths))
+                })?
+                .iter()
+                .map(|p| tv.install_short_path().join(p))
+                .collect();
+        Ok(bin_paths)
+    }
+
+    fn exec_env(
+        &self,
+        _config: &Config,
+        _ts: &Toolset,
+        tv: &ToolVersion,
+    ) -> Result<BTreeMap<String, String>> {
+        CacheManager::<BTreeMap<String, String>>::new(
+            tv.cache_path().join("exec_env-$KEY.msgpack.z"),
+        )
+        .with_fresh_file(tv.install_path())
+        .get_or_try_init(|| {
+            let input = json!({"version": tv.version, "install_path": tv.install_path()});
+            let out: Option<ExecEnv> = self.call_optional("exec_env", input)?;
+            Ok(out.map(|o| o.env).unwrap_or_default())
+        })
+        .cloned()
+    }
+}
+
+impl WasmBackend {
+    pub fn new(name: String) -> Self {
+        let fa = BackendArg::new(BackendType::Wasm, &name);
+        Self {
+            remote_version_cache: CacheManager::new(
+                fa.cache_path.join("remote_versions-$KEY.msgpack.z"),
+            ),
+            fa,
+        }
+    }
+
+    fn call<T: DeserializeOwned>(&self, func: &str, input: serde_json::Value) -> Result<T> {
+        self.call_optional(func, input)?
+            .ok_or_else(|| eyre!("{} does not export {func}", self.id()))
+    }
+}
+
+#[cfg(feature = "wasm")]
+impl WasmBackend {
+    /// the .wasm file from the `[plugins]` entry for the tool, urls are downloaded once
+    fn wasm_path(&self) -> Result<PathBuf> {
+        let config = Config::get();
+        let url = config
+            .get_repo_url(&self.name().to_string())
+            .filter(|u| u.ends_with(".wasm"))
+            .ok_or_else(|| {
+                eyre!(
+                    "no wasm plugin for {}, add it to [plugins], e.g.: {} = \"https://example.com/{}.wasm\"",
+                    self.id(),
+                    self.name(),
+                    self.name()
+                )
+            })?;
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Ok(file::replace_path(&url));
+        }
+        let path = self
+            .fa
+            .cache_path
+            .join(format!("plugin-{}.wasm", hash_to_str(&url)));
+        if !path.exists() {
+            HTTP.download_file(&url, &path, None)?;
+        }
+        Ok(path)
+    }
+
+    fn plugin(&self) -> Result<Plugin> {
+        let manifest = Manifest::new([Wasm::file(self.wasm_path()?)])
+            // plugins fetch their version lists themselves but have no filesystem access
+            .with_allowed_host("*");
+        let mut plugin = Plugin::new(&manifest, [], true)
+            .map_err(|e| eyre!("failed to load wasm plugin {}: {e:#}", self.id()))?;
+        let version = plugin
+            .call::<&str, String>("mise_api_version", "")
+            .map_err(|e| {
+                eyre!(
+                    "{} does not implement the mise plugin api: {e:#}",
+                    self.id()
+                )
+            })?;
+        if version.trim() != API_VERSION {
+            bail!(
+                "{} uses mise plugin api version {version}, this version of mise supports {API_VERSION}",
+                self.id()
+            );
+        }
+        Ok(plugin)
+    }
+
+    /// calls an export of the plugin with JSON input and output, None if it isn't exported
+    fn call_optional<T: DeserializeOwned>(
+        &self,
+        func: &str,
+        input: serde_json::Value,
+    ) -> Result<Option<T>> {
+        let mut plugin = self.plugin()?;
+        if !plugin.function_exists(func) {
+            return Ok(None);
+        }
+        trace!("{}: calling {func} with {input}", self.id());
+        let out = plugin
+            .call::<&str, String>(func, &input.to_string())
+            .map_err(|e| eyre!("{}: {func} failed: {e:#}", self.id()))?;
+        serde_json::from_str(&out)
+            .map(Some)
+            .map_err(|e| eyre!("{}: invalid output from {func}: {e}, got {out}", self.id()))
+    }
+}
+
+#[cfg(not(feature = "wasm"))]
+impl WasmBackend {
+    fn call_optional<T: DeserializeOwned>(
+        &self,
+        _func: &str,
+        _input: serde_json::Value,
+    ) -> Result<Option<T>> {
+        bail!(
+            "{} can't be loaded, this build of mise doesn't have the wasm feature",
+            self.id()
+        )
+    }
+}
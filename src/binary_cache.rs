@@ -0,0 +1,218 @@
+use std::fs::File;
+use std::path::Path;
+
+use eyre::{bail, Result};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+
+use crate::backend::BackendType;
+use crate::cli::version::{ARCH, OS};
+use crate::cmd::CmdLineRunner;
+use crate::config::lockfile;
+use crate::config::lockfile::Artifact;
+use crate::config::Settings;
+use crate::file::display_path;
+use crate::hash::{file_hash_sha256, hash_sha256_to_str};
+use crate::http::HTTP;
+use crate::toolset::{ToolVersion, ToolVersionOptions};
+use crate::ui::progress_report::SingleReport;
+use crate::{env, file, http};
+
+/// Remote cache of tools that are built from source, set with the `binary_cache` setting. Each
+/// install is a tarball of its install path:
+///
+/// <binary_cache>/<tool>/<version>/<os>-<arch>.tar.gz
+/// s3://my-bucket/mise/cargo-eza/0.18.0/linux-x64.tar.gz
+///
+/// The sha256 of restored tarballs is checked against and recorded in mise.lock like other
+/// downloads so a tarball that changed in the cache is never installed.
+pub fn is_cacheable(backend_type: BackendType) -> bool {
+    matches!(
+        backend_type,
+        BackendType::Cargo | BackendType::Go | BackendType::Spm
+    )
+}
+
+/// tools with options like `features` build differently so a hash of the options is in the key,
+/// it has to be the same for every mise build and platform that shares the cache
+fn key(tool: &str, version: &str, opts: &ToolVersionOptions) -> String {
+    let platform = match opts.is_empty() {
+        true => format!("{}-{}", *OS, *ARCH),
+        false => {
+            // the options are sorted by key
+            let opts = opts
+                .iter()
+                .map(|(k, v)| format!("{k}={v}\n"))
+                .collect::<String>();
+            format!("{}-{}-{}", *OS, *ARCH, &hash_sha256_to_str(&opts)[..16])
+        }
+    };
+    format!("{tool}/{version}/{platform}.tar.gz")
+}
+
+fn url(base: &str, tv: &ToolVersion) -> String {
+    let tool = tv.backend.installs_path.file_name().unwrap();
+    let key = key(&tool.to_string_lossy(), &tv.version, &tv.request.options());
+    format!("{}/{key}", base.trim_end_matches('/'))
+}
+
+/// extracts the install of `tv` from the cache into its install path, returns false if it isn't
+/// in the cache or can't be downloaded. Fails if the tarball doesn't match mise.lock.
+pub fn restore(tv: &ToolVersion, pr: &dyn SingleReport) -> Result<bool> {
+    let settings = Settings::get();
+    let Some(base) = &settings.binary_cache else {
+        return Ok(false);
+    };
+    if settings.offline {
+        return Ok(false);
+    }
+    let url = url(base, tv);
+    let tarball = tv.download_path().join("binary-cache.tar.gz");
+    pr.set_message(format!("downloading {url}"));
+    let downloaded = if url.starts_with("s3://") {
+        file::create_dir_all(tarball.parent().unwrap())?;
+        // the aws cli doesn't have a separate exit code for missing objects
+        aws(Some(pr))?.arg("cp").arg(&url).arg(&tarball).execute()
+    } else {
+        HTTP.download_file_with_headers(
+            &url,
+            &tarball,
+            Some(pr),
+            &headers(env::MISE_BINARY_CACHE_TOKEN.as_ref()),
+        )
+    };
+    match downloaded {
+        Ok(()) => {}
+        Err(err) if http::error_code(&err) == Some(404) => {
+            debug!("{tv} is not in the binary cache");
+            return Ok(false);
+        }
+        Err(err) => {
+            warn!("failed to download {tv} from the binary cache: {err:#}");
+            return Ok(false);
+        }
+    }
+    pr.set_message(format!("verifying {}", display_path(&tarball)));
+    let artifact = Artifact {
+        url: url.clone(),
+        checksum: format!("sha256:{}", file_hash_sha256(&tarball)?),
+    };
+    if let Err(err) = lockfile::record_artifact(tv, artifact) {
+        file::remove_file(&tarball)?;
+        return Err(err);
+    }
+    pr.set_message(format!("extracting {}", display_path(&tarball)));
+    let install_path = tv.install_path();
+    if let Err(err) = file::untar(&tarball, &install_path) {
+        file::remove_all(&install_path)?;
+        file::create_dir_all(&install_path)?;
+        return Err(err);
+    }
+    file::remove_file(&tarball)?;
+    debug!("{tv}: installed from {url}");
+    Ok(true)
+}
+
+/// uploads the install of `tv` to the cache if `binary_cache_upload` is enabled
+pub fn upload(tv: &ToolVersion, pr: &dyn SingleReport) -> Result<()> {
+    let settings = Settings::get();
+    let Some(base) = &settings.binary_cache else {
+        return Ok(());
+    };
+    if !settings.binary_cache_upload || settings.offline {
+        return Ok(());
+    }
+    let url = url(base, tv);
+    let tmp = tempfile::tempdir()?;
+    let tarball = tmp.path().join("install.tar.gz");
+    pr.set_message(format!("uploading to {url}"));
+    tar_gz(&tv.install_path(), &tarball)?;
+    if url.starts_with("s3://") {
+        aws(Some(pr))?.arg("cp").arg(&tarball).arg(&url).execute()?;
+    } else {
+        HTTP.upload_file(
+            &url,
+            &tarball,
+            &headers(env::MISE_BINARY_CACHE_TOKEN.as_ref()),
+        )?;
+    }
+    debug!("{tv}: uploaded to {url}");
+    Ok(())
+}
+
+fn tar_gz(dir: &Path, archive: &Path) -> Result<()> {
+    debug!(
+        "tar -czf {} -C {} .",
+        display_path(archive),
+        display_path(dir)
+    );
+    let gz = GzEncoder::new(File::create(archive)?, Compression::default());
+    let mut tar = tar::Builder::new(gz);
+    tar.follow_symlinks(false);
+    tar.append_dir_all(".", dir)?;
+    tar.into_inner()?.finish()?;
+    Ok(())
+}
+
+/// `Authorization: Bearer <token>` for http caches
+pub fn headers(token: Option<&String>) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if let Some(token) = token {
+        if let Ok(value) = HeaderValue::from_str(&format!("Bearer {token}")) {
+            headers.insert(AUTHORIZATION, value);
+        }
+    }
+    headers
+}
+
+/// `aws s3` for s3:// caches
+pub fn aws(pr: Option<&dyn SingleReport>) -> Result<CmdLineRunner<'_>> {
+    if file::which("aws").is_none() {
+        bail!("the aws cli is required for s3:// caches");
+    }
+    let cmd = CmdLineRunner::new("aws").arg("s3");
+    Ok(match pr {
+        Some(pr) => cmd.with_pr(pr),
+        None => cmd,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_key() {
+        let platform = format!("{}-{}", *OS, *ARCH);
+        assert_eq!(
+            key("cargo-eza", "0.18.0", &Default::default()),
+            format!("cargo-eza/0.18.0/{platform}.tar.gz")
+        );
+        let opts = ToolVersionOptions::from([("features".to_string(), "git".to_string())]);
+        let key = key("cargo-eza", "0.18.0", &opts);
+        assert_eq!(
+            key,
+            format!("cargo-eza/0.18.0/{platform}-a66e1ba9aeb4ce35.tar.gz")
+        );
+    }
+
+    #[test]
+    fn test_tar_gz() {
+        let dir = tempfile::tempdir().unwrap();
+        let install = dir.path().join("install");
+        file::create_dir_all(install.join("bin")).unwrap();
+        file::write(install.join("bin/tool"), "#!/bin/sh\n").unwrap();
+        let archive = dir.path().join("install.tar.gz");
+        tar_gz(&install, &archive).unwrap();
+        let restored = dir.path().join("restored");
+        file::create_dir_all(&restored).unwrap();
+        file::untar(&archive, &restored).unwrap();
+        assert_eq!(
+            file::read_to_string(restored.join("bin/tool")).unwrap(),
+            "#!/bin/sh\n"
+        );
+    }
+}
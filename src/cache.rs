@@ -13,11 +13,17 @@ use serde::de::DeserializeOwned;
 use serde::Serialize;
 
 use crate::build_time::built_info;
+use crate::config::Settings;
 use crate::file;
 use crate::file::{display_path, modified_duration};
 use crate::hash::hash_to_str;
+use crate::lock_file::LockFile;
 use crate::rand::random_string;
 
+/// bump when the format of cache files changes, files with another version are refetched
+const SCHEMA_VERSION: u8 = 1;
+const MAGIC: &[u8; 4] = b"MISE";
+
 #[derive(Debug, Clone)]
 pub struct CacheManager<T>
 where
@@ -64,17 +70,23 @@ where
         F: FnOnce() -> Result<T>,
     {
         let val = self.cache.get_or_try_init(|| {
-            let path = &self.cache_file_path;
-            if !self.no_cache && self.is_fresh() {
-                match self.parse() {
-                    Ok(val) => return Ok::<_, color_eyre::Report>(val),
-                    Err(err) => {
-                        warn!("failed to parse cache file: {} {:#}", path.display(), err);
-                    }
+            if let Some(val) = self.read_fresh() {
+                return Ok::<_, color_eyre::Report>(val);
+            }
+            // only one process fetches at a time, the others wait and then read what it wrote
+            let _lock = match LockFile::new(&self.cache_file_path).lock() {
+                Ok(lock) => Some(lock),
+                Err(err) => {
+                    debug!("failed to lock cache file: {err:#}");
+                    None
                 }
+            };
+            if let Some(val) = self.read_fresh() {
+                return Ok(val);
             }
             let val = (fetch)()?;
             if let Err(err) = self.write(&val) {
+                let path = &self.cache_file_path;
                 warn!("failed to write cache file: {} {:#}", path.display(), err);
             }
             Ok(val)
@@ -82,15 +94,37 @@ where
         Ok(val)
     }
 
-    fn parse(&self) -> Result<T> {
+    pub fn read_fresh(&self) -> Option<T> {
+        if self.no_cache || !self.is_fresh() {
+            return None;
+        }
+        match self.parse() {
+            Ok(val) => val,
+            Err(err) => {
+                let path = &self.cache_file_path;
+                warn!("failed to parse cache file: {} {:#}", path.display(), err);
+                None
+            }
+        }
+    }
+
+    /// returns None if the file was written by a version of mise with a different format
+    fn parse(&self) -> Result<Option<T>> {
         let path = &self.cache_file_path;
         trace!("reading {}", display_path(path));
-        let mut zlib = ZlibDecoder::new(File::open(path)?);
+        let mut f = File::open(path)?;
+        let mut header = [0; 9];
+        if f.read_exact(&mut header).is_err() || header != Self::header() {
+            debug!("ignoring outdated cache file: {}", display_path(path));
+            return Ok(None);
+        }
+        let mut zlib = ZlibDecoder::new(f);
         let mut bytes = Vec::new();
         zlib.read_to_end(&mut bytes)?;
-        Ok(rmp_serde::from_slice(&bytes)?)
+        Ok(Some(rmp_serde::from_slice(&bytes)?))
     }
 
+    /// files are written to a temporary file and renamed so readers never see a partial file
     pub fn write(&self, val: &T) -> Result<()> {
         trace!("writing {}", display_path(&self.cache_file_path));
         if let Some(parent) = self.cache_file_path.parent() {
@@ -99,11 +133,29 @@ where
         let partial_path = self
             .cache_file_path
             .with_extension(format!("part-{}", random_string(8)));
-        let mut zlib = ZlibEncoder::new(File::create(&partial_path)?, Compression::fast());
-        zlib.write_all(&rmp_serde::to_vec_named(&val)?[..])?;
-        file::rename(&partial_path, &self.cache_file_path)?;
+        let result = (|| -> Result<()> {
+            let mut f = File::create(&partial_path)?;
+            f.write_all(&Self::header())?;
+            let mut zlib = ZlibEncoder::new(f, Compression::fast());
+            zlib.write_all(&rmp_serde::to_vec_named(&val)?[..])?;
+            zlib.finish()?.sync_all()?;
+            file::rename(&partial_path, &self.cache_file_path)
+        })();
+        if result.is_err() {
+            let _ = file::remove_file(&partial_path);
+        }
+        result
+    }
 
-        Ok(())
+    /// magic, schema version, and a hash of the cached type so a cache written for a different
+    /// type is never deserialized
+    fn header() -> [u8; 9] {
+        let mut header = [0; 9];
+        header[..4].copy_from_slice(MAGIC);
+        header[4] = SCHEMA_VERSION;
+        let type_hash = format!("{:0>4}", hash_to_str(&std::any::type_name::<T>()));
+        header[5..].copy_from_slice(&type_hash.as_bytes()[..4]);
+        header
     }
 
     #[cfg(test)]
@@ -120,6 +172,10 @@ where
         if !self.cache_file_path.exists() {
             return false;
         }
+        if Settings::get().offline {
+            // anything cached is better than nothing
+            return true;
+        }
         if let Some(fresh_duration) = self.freshest_duration() {
             if let Ok(metadata) = self.cache_file_path.metadata() {
                 if let Ok(modified) = metadata.modified() {
@@ -172,4 +228,19 @@ mod tests {
         let val = cache.get_or_try_init(|| Ok(2)).unwrap();
         assert_eq!(val, &1);
     }
+
+    #[test]
+    fn test_cache_header() {
+        let path = crate::dirs::CACHE.join("test_cache_header.msgpack.z");
+        let cache = CacheManager::new(&path);
+        cache.write(&vec!["1.0.0".to_string()]).unwrap();
+        assert_eq!(cache.parse().unwrap(), Some(vec!["1.0.0".to_string()]));
+
+        // a different type or an old file without a header is ignored
+        let other: CacheManager<u32> = CacheManager::new(&path);
+        assert_eq!(other.parse().unwrap(), None);
+        file::write(&path, "garbage").unwrap();
+        assert_eq!(cache.parse().unwrap(), None);
+        cache.clear().unwrap();
+    }
 }
@@ -6,7 +6,7 @@ use crate::config::Config;
 
 /// Add/update an alias for a plugin
 ///
-/// This modifies the contents of ~/.config/mise/config.toml
+/// This modifies the contents of ~/.config/mise/config.toml unless --local is used
 #[derive(Debug, clap::Args)]
 #[clap(visible_aliases = ["add", "create"], after_long_help = AFTER_LONG_HELP, verbatim_doc_comment)]
 pub struct AliasSet {
@@ -16,13 +16,23 @@ pub struct AliasSet {
     pub alias: String,
     /// The value to set the alias to
     pub value: String,
+    /// Modify the closest .mise.toml instead of the global config
+    ///
+    /// Project aliases override global ones
+    #[clap(short, long)]
+    pub local: bool,
 }
 
 impl AliasSet {
     pub fn run(self) -> Result<()> {
-        let mut global_config = Config::get().global_config()?;
-        global_config.set_alias(&self.plugin, &self.alias, &self.value)?;
-        global_config.save()
+        let config = Config::get();
+        let mut cf = if self.local {
+            config.local_config()?
+        } else {
+            config.global_config()?
+        };
+        cf.set_alias(&self.plugin, &self.alias, &self.value)?;
+        cf.save()
     }
 }
 
@@ -30,6 +40,9 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
     r#"<bold><underline>Examples:</underline></bold>
 
     $ <bold>mise alias set node lts-hydrogen 18.0.0</bold>
+
+    # set an alias for the current project only
+    $ <bold>mise alias set --local node lts 22</bold>
 "#
 );
 
@@ -6,7 +6,7 @@ use crate::config::Config;
 
 /// Clears an alias for a plugin
 ///
-/// This modifies the contents of ~/.config/mise/config.toml
+/// This modifies the contents of ~/.config/mise/config.toml unless --local is used
 #[derive(Debug, clap::Args)]
 #[clap(visible_aliases = ["rm", "remove", "delete", "del"], after_long_help = AFTER_LONG_HELP, verbatim_doc_comment)]
 pub struct AliasUnset {
@@ -14,13 +14,23 @@ pub struct AliasUnset {
     pub plugin: BackendArg,
     /// The alias to remove
     pub alias: String,
+    /// Modify the closest .mise.toml instead of the global config
+    ///
+    /// Project aliases override global ones
+    #[clap(short, long)]
+    pub local: bool,
 }
 
 impl AliasUnset {
     pub fn run(self) -> Result<()> {
-        let mut global_config = Config::get().global_config()?;
-        global_config.remove_alias(&self.plugin, &self.alias)?;
-        global_config.save()
+        let config = Config::get();
+        let mut cf = if self.local {
+            config.local_config()?
+        } else {
+            config.global_config()?
+        };
+        cf.remove_alias(&self.plugin, &self.alias)?;
+        cf.save()
     }
 }
 
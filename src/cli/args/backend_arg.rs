@@ -8,7 +8,8 @@ use once_cell::sync::Lazy;
 
 use crate::backend::{unalias_backend, BackendType};
 use crate::dirs;
-use crate::registry::REGISTRY;
+use crate::registry::{REGISTRY, TOOL_ALIASES};
+use crate::toolset::ToolVersionOptions;
 
 #[derive(Clone, PartialOrd, Ord)]
 pub struct BackendArg {
@@ -26,6 +27,8 @@ pub struct BackendArg {
     pub installs_path: PathBuf,
     /// ~/.local/share/mise/downloads/<THIS>
     pub downloads_path: PathBuf,
+    /// default tool options of a tool alias from `[alias]`, options of a request take precedence
+    pub opts: ToolVersionOptions,
 }
 
 impl<A: AsRef<str>> From<A> for BackendArg {
@@ -58,6 +61,7 @@ impl BackendArg {
             cache_path: dirs::CACHE.join(&pathname),
             installs_path: dirs::INSTALLS.join(&pathname),
             downloads_path: dirs::DOWNLOADS.join(&pathname),
+            opts: Default::default(),
         }
     }
 }
@@ -89,15 +93,19 @@ impl Hash for BackendArg {
 }
 
 static FORGE_MAP: Lazy<HashMap<&'static str, BackendArg>> = Lazy::new(|| {
-    REGISTRY
-        .iter()
-        .map(|(short, full)| {
-            let (backend_type, name) = full.split_once(':').unwrap();
-            let backend_type = backend_type.parse().unwrap();
-            let fa = BackendArg::new(backend_type, name);
-            (*short, fa)
-        })
-        .collect()
+    let registry = REGISTRY.iter().map(|(short, full)| {
+        let (backend_type, name) = full.split_once(':').unwrap();
+        let backend_type = backend_type.parse().unwrap();
+        let fa = BackendArg::new(backend_type, name);
+        (short.as_str(), fa)
+    });
+    let aliases = TOOL_ALIASES.iter().map(|(short, alias)| {
+        let (backend_type, name) = alias.backend.split_once(':').unwrap();
+        let mut fa = BackendArg::new(backend_type.parse().unwrap(), name);
+        fa.opts.clone_from(&alias.options);
+        (short.as_str(), fa)
+    });
+    registry.chain(aliases).collect()
 });
 
 #[cfg(test)]
@@ -0,0 +1,13 @@
+use clap::{Arg, ArgAction};
+
+pub struct OfflineArg;
+
+impl OfflineArg {
+    pub fn arg() -> Arg {
+        Arg::new("offline")
+            .long("offline")
+            .help("Forbid all network access, only use installed versions and caches")
+            .action(ArgAction::SetTrue)
+            .global(true)
+    }
+}
@@ -0,0 +1,14 @@
+use clap::{Arg, ArgAction};
+
+pub struct ProgressEventsArg;
+
+impl ProgressEventsArg {
+    pub fn arg() -> Arg {
+        Arg::new("progress-events")
+            .long("progress-events")
+            .help("Write progress events as JSON lines to fd:<N>, unix:<socket>, or a file")
+            .global(true)
+            .action(ArgAction::Set)
+            .value_name("TARGET")
+    }
+}
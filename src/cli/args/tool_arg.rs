@@ -64,6 +64,7 @@ impl FromStr for ToolVersionType {
                 sub: p.split_once('-').unwrap().1.to_string(),
                 orig_version: v.to_string(),
             },
+            Some(("branch" | "rev" | "tag", _)) => Self::Version(s.to_string()),
             Some((p, _)) => bail!("invalid prefix: {}", style::ered(p)),
             None if s == "system" => Self::System,
             None => Self::Version(s.to_string()),
@@ -147,8 +148,8 @@ fn parse_input(s: &str) -> (&str, Option<&str>) {
         .map(|(f, v)| (f, Some(v)))
         .unwrap_or((s, None));
 
-    // special case for packages with npm scopes like "npm:@antfu/ni"
-    if backend == "npm:" {
+    // special case for packages with scopes like "npm:@antfu/ni" or "deno:jsr:@std/http"
+    if backend.ends_with(':') {
         if let Some(v) = version {
             return if let Some(i) = v.find('@') {
                 (&s[..backend.len() + i + 1], Some(&v[i + 1..]))
@@ -222,5 +223,17 @@ mod tests {
         t("npm:", "npm:", None);
         t("npm:prettier", "npm:prettier", None);
         t("npm:prettier@1.0.0", "npm:prettier", Some("1.0.0"));
+        t("deno:jsr:@std/http", "deno:jsr:@std/http", None);
+        t(
+            "deno:jsr:@std/http@1.0.0",
+            "deno:jsr:@std/http",
+            Some("1.0.0"),
+        );
+        t("deno:npm:cowsay@1.6.0", "deno:npm:cowsay", Some("1.6.0"));
+        t(
+            "cargo:https://github.com/org/tool@branch:main",
+            "cargo:https://github.com/org/tool",
+            Some("branch:main"),
+        );
     }
 }
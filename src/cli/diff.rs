@@ -0,0 +1,248 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+use clap::ValueHint;
+use eyre::{bail, Result};
+use serde_derive::{Deserialize, Serialize};
+use tabled::{Table, Tabled};
+
+use crate::cli::version::{ARCH, OS, VERSION};
+use crate::config::Config;
+use crate::file::display_path;
+use crate::toolset::ToolsetBuilder;
+use crate::ui::table;
+use crate::{dirs, file};
+
+/// Compare the tools and env with a snapshot from another machine
+///
+/// Export a snapshot on a machine where things work with `mise diff --export`,
+/// then run `mise diff <SNAPSHOT>` on the machine where they don't to see which
+/// tool versions, tool options, and env vars are different.
+///
+/// The snapshot contains the values of env vars set by mise, don't share it if
+/// those contain secrets. Paths inside of the home directory are relative to `~`
+/// so they match across machines.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct Diff {
+    /// Snapshot to compare with
+    #[clap(value_hint = ValueHint::FilePath, required_unless_present = "export")]
+    snapshot: Option<PathBuf>,
+
+    /// Write a snapshot of this machine instead of comparing
+    #[clap(long, conflicts_with = "snapshot")]
+    export: bool,
+
+    /// Write the snapshot to this file instead of stdout
+    #[clap(long, short, value_hint = ValueHint::FilePath, requires = "export")]
+    output: Option<PathBuf>,
+
+    /// Output the differences in JSON format
+    #[clap(long, short = 'J')]
+    json: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Snapshot {
+    mise_version: String,
+    os: String,
+    arch: String,
+    tools: BTreeMap<String, SnapshotTool>,
+    env: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SnapshotTool {
+    version: String,
+    requested: String,
+    #[serde(default)]
+    options: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Tabled)]
+#[tabled(rename_all = "PascalCase")]
+struct Difference {
+    kind: &'static str,
+    name: String,
+    snapshot: String,
+    current: String,
+}
+
+impl Diff {
+    pub fn run(self) -> Result<()> {
+        let current = current()?;
+        if self.export {
+            let out = serde_json::to_string_pretty(&current)?;
+            match &self.output {
+                Some(output) => {
+                    file::write(output, out)?;
+                    info!("snapshot written to {}", display_path(output));
+                }
+                None => miseprintln!("{out}"),
+            }
+            return Ok(());
+        }
+        let path = self.snapshot.as_ref().unwrap();
+        let snapshot: Snapshot = match serde_json::from_str(&file::read_to_string(path)?) {
+            Ok(snapshot) => snapshot,
+            Err(err) => bail!(
+                "{} is not a snapshot from `mise diff --export`: {err}",
+                display_path(path)
+            ),
+        };
+        let diffs = diff(&snapshot, &current);
+        if self.json {
+            miseprintln!("{}", serde_json::to_string_pretty(&diffs)?);
+        } else if diffs.is_empty() {
+            info!("no differences from {}", display_path(path));
+        } else {
+            let mut table = Table::new(diffs);
+            table::default_style(&mut table, false);
+            miseprintln!("{table}");
+        }
+        Ok(())
+    }
+}
+
+fn current() -> Result<Snapshot> {
+    let config = Config::try_get()?;
+    let ts = ToolsetBuilder::new().build(&config)?;
+    let tools = ts
+        .list_current_versions()
+        .into_iter()
+        .map(|(p, tv)| {
+            let tool = SnapshotTool {
+                requested: tv.request.version(),
+                options: tv.request.options(),
+                version: tv.version,
+            };
+            (p.id().to_string(), tool)
+        })
+        .collect();
+    let env = ts
+        .env(&config)?
+        .into_iter()
+        .map(|(k, v)| (k, home_relative(&v)))
+        .collect();
+    Ok(Snapshot {
+        mise_version: VERSION.to_string(),
+        os: OS.to_string(),
+        arch: ARCH.to_string(),
+        tools,
+        env,
+    })
+}
+
+fn home_relative(value: &str) -> String {
+    let home = dirs::HOME.to_string_lossy();
+    match value.strip_prefix(home.as_ref()) {
+        Some(rest) if rest.is_empty() || rest.starts_with(['/', '\\']) => format!("~{rest}"),
+        _ => value.to_string(),
+    }
+}
+
+fn diff(snapshot: &Snapshot, current: &Snapshot) -> Vec<Difference> {
+    let mut diffs = vec![];
+    let mut push = |kind, name: String, a: Option<&String>, b: Option<&String>| {
+        if a != b {
+            let missing = || "(none)".to_string();
+            diffs.push(Difference {
+                kind,
+                name,
+                snapshot: a.cloned().unwrap_or_else(missing),
+                current: b.cloned().unwrap_or_else(missing),
+            });
+        }
+    };
+    push(
+        "mise",
+        "version".into(),
+        Some(&snapshot.mise_version),
+        Some(&current.mise_version),
+    );
+    let platform = |s: &Snapshot| format!("{}-{}", s.os, s.arch);
+    push(
+        "mise",
+        "platform".into(),
+        Some(&platform(snapshot)),
+        Some(&platform(current)),
+    );
+    for tool in keys(&snapshot.tools, &current.tools) {
+        let (a, b) = (snapshot.tools.get(tool), current.tools.get(tool));
+        push(
+            "tool",
+            tool.clone(),
+            a.map(|t| &t.version),
+            b.map(|t| &t.version),
+        );
+        let no_options = BTreeMap::new();
+        let a_opts = a.map_or(&no_options, |t| &t.options);
+        let b_opts = b.map_or(&no_options, |t| &t.options);
+        for opt in keys(a_opts, b_opts) {
+            push(
+                "option",
+                format!("{tool} {opt}"),
+                a_opts.get(opt),
+                b_opts.get(opt),
+            );
+        }
+    }
+    for key in keys(&snapshot.env, &current.env) {
+        push(
+            "env",
+            key.clone(),
+            snapshot.env.get(key),
+            current.env.get(key),
+        );
+    }
+    diffs
+}
+
+fn keys<'a, V>(a: &'a BTreeMap<String, V>, b: &'a BTreeMap<String, V>) -> BTreeSet<&'a String> {
+    a.keys().chain(b.keys()).collect()
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    # on the machine where it works
+    $ <bold>mise diff --export -o snapshot.json</bold>
+
+    # on the machine where it doesn't
+    $ <bold>mise diff snapshot.json</bold>
+    Kind    Name         Snapshot  Current
+    tool    node         20.11.0   20.12.0
+    option  python venv  .venv     (none)
+    env     NODE_ENV     (none)    production
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        let tool = |version: &str| SnapshotTool {
+            version: version.into(),
+            requested: "20".into(),
+            options: Default::default(),
+        };
+        let a = Snapshot {
+            tools: [("node".to_string(), tool("20.11.0"))].into(),
+            env: [("FOO".to_string(), "1".to_string())].into(),
+            ..Default::default()
+        };
+        let b = Snapshot {
+            tools: [("node".to_string(), tool("20.12.0"))].into(),
+            ..Default::default()
+        };
+        let diffs = diff(&a, &b)
+            .into_iter()
+            .map(|d| format!("{} {} {} {}", d.kind, d.name, d.snapshot, d.current))
+            .collect::<Vec<_>>();
+        assert_eq!(diffs, vec!["tool node 20.11.0 20.12.0", "env FOO 1 (none)"]);
+    }
+}
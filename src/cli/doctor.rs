@@ -1,3 +1,4 @@
+use std::collections::BTreeSet;
 use std::fmt::{Display, Write};
 use std::process::exit;
 
@@ -7,6 +8,7 @@ use indoc::formatdoc;
 use itertools::Itertools;
 use rayon::prelude::*;
 
+use crate::backend::system::SystemBackend;
 use crate::backend::BackendType;
 use crate::build_time::built_info;
 use crate::cli::version;
@@ -17,9 +19,9 @@ use crate::git::Git;
 use crate::plugins::core::CORE_PLUGINS;
 use crate::plugins::PluginType;
 use crate::shell::ShellType;
-use crate::toolset::{Toolset, ToolsetBuilder};
+use crate::toolset::{Probe, Toolset, ToolsetBuilder};
 use crate::ui::style;
-use crate::{backend, cmd, dirs, duration, env, file, shims};
+use crate::{backend, cmd, dirs, duration, env, file, gitea, github, gitlab, shims};
 
 /// Check mise installation for possible problems
 #[derive(Debug, clap::Args)]
@@ -127,6 +129,9 @@ impl Doctor {
             Ok(ts) => {
                 self.analyze_shims(&ts);
                 self.analyze_toolset(&ts)?;
+                self.analyze_backends(&ts)?;
+                self.analyze_system_tools(&ts)?;
+                self.analyze_probes(&ts)?;
             }
             Err(err) => self.errors.push(format!("failed to load toolset: {}", err)),
         }
@@ -159,6 +164,159 @@ impl Doctor {
         Ok(())
     }
 
+    /// checks that the commands and APIs the configured backends rely on are usable
+    /// before an install fails partway through
+    fn analyze_backends(&mut self, ts: &Toolset) -> eyre::Result<()> {
+        let backend_types: BTreeSet<BackendType> = ts
+            .list_current_versions()
+            .into_iter()
+            .map(|(b, _)| b.get_type())
+            .collect();
+        let mut probes = vec![];
+        for bt in &backend_types {
+            for bin in required_bins(*bt) {
+                let path = ts
+                    .which(bin)
+                    .and_then(|(b, tv)| b.which(&tv, bin).ok().flatten())
+                    .or_else(|| file::which_non_pristine(bin));
+                match path {
+                    Some(path) => probes.push(format!("{bt}: {bin} ({})", display_path(path))),
+                    None => {
+                        probes.push(format!("{bt}: {bin} {}", style::nred("(not found)")));
+                        self.errors.push(format!(
+                            "{bin} is required by the {bt} backend but was not found on PATH"
+                        ));
+                    }
+                }
+            }
+        }
+
+        if backend_types
+            .iter()
+            .any(|bt| matches!(bt, BackendType::Spm | BackendType::Ubi))
+        {
+            match github::rate_limit() {
+                Ok(rl) => {
+                    probes.push(format!(
+                        "github: {}/{} requests remaining",
+                        rl.remaining, rl.limit
+                    ));
+                    let token = style::nyellow("GITHUB_API_TOKEN");
+                    let reset = chrono::DateTime::from_timestamp(rl.reset, 0)
+                        .map(|t| t.with_timezone(&chrono::Local).format("%H:%M").to_string())
+                        .unwrap_or_default();
+                    if rl.remaining == 0 {
+                        self.errors.push(format!(
+                            "GitHub API rate limit exceeded until {reset}, set {token} to raise the limit"
+                        ));
+                    } else if rl.remaining < rl.limit / 10 {
+                        self.warnings.push(format!(
+                            "GitHub API rate limit is almost exhausted ({}/{} remaining until {reset}), set {token} to raise the limit",
+                            rl.remaining, rl.limit
+                        ));
+                    }
+                }
+                Err(err) => {
+                    probes.push(format!("github: {}", style::nred("(unreachable)")));
+                    self.errors.push(format!(
+                        "failed to reach the GitHub API, check that GITHUB_API_TOKEN is valid if set: {err}"
+                    ));
+                }
+            }
+        }
+
+        if backend_types.contains(&BackendType::Gitlab) {
+            let host = &*env::MISE_GITLAB_HOST;
+            if env::GITLAB_TOKEN.is_none() {
+                probes.push(format!("gitlab: {host} (no token, public projects only)"));
+            } else {
+                match gitlab::current_user() {
+                    Ok(user) => probes.push(format!(
+                        "gitlab: {host} (authenticated as {})",
+                        user.username
+                    )),
+                    Err(err) => {
+                        probes.push(format!("gitlab: {host} {}", style::nred("(unauthorized)")));
+                        self.errors
+                            .push(format!("GITLAB_TOKEN is not valid for {host}: {err}"));
+                    }
+                }
+            }
+        }
+
+        if backend_types.contains(&BackendType::Gitea) {
+            let host = &*env::MISE_GITEA_HOST;
+            if env::GITEA_TOKEN.is_none() {
+                probes.push(format!(
+                    "gitea: {host} (no token, public repositories only)"
+                ));
+            } else {
+                match gitea::current_user() {
+                    Ok(user) => {
+                        probes.push(format!("gitea: {host} (authenticated as {})", user.login))
+                    }
+                    Err(err) => {
+                        probes.push(format!("gitea: {host} {}", style::nred("(unauthorized)")));
+                        self.errors
+                            .push(format!("GITEA_TOKEN is not valid for {host}: {err}"));
+                    }
+                }
+            }
+        }
+
+        if !probes.is_empty() {
+            section("backend_probes", probes.join("\n"))?;
+        }
+        Ok(())
+    }
+
+    /// checks that tools with `backend = "system"` are on PATH and new enough
+    fn analyze_system_tools(&mut self, ts: &Toolset) -> eyre::Result<()> {
+        let mut tools = vec![];
+        for (b, tv) in ts.list_current_versions() {
+            if b.get_type() != BackendType::System {
+                continue;
+            }
+            match SystemBackend::new(b.name().to_string()).check(&tv) {
+                Ok((path, version)) => tools.push(format!(
+                    "{}: {} {}",
+                    b.id(),
+                    display_path(path),
+                    version.unwrap_or_default()
+                )),
+                Err(err) => {
+                    tools.push(format!("{}: {}", b.id(), style::nred("(invalid)")));
+                    self.errors.push(format!("{err}"));
+                }
+            }
+        }
+        if !tools.is_empty() {
+            section("system_tools", tools.join("\n"))?;
+        }
+        Ok(())
+    }
+
+    /// runs the `probe` tool options to check that the active versions are the pinned ones
+    fn analyze_probes(&mut self, ts: &Toolset) -> eyre::Result<()> {
+        let mut probes = vec![];
+        for (_, tv) in ts.list_current_versions() {
+            let Some(probe) = Probe::for_tv(&tv) else {
+                continue;
+            };
+            match probe.check() {
+                Ok(output) => probes.push(format!("{tv}: {output}")),
+                Err(err) => {
+                    probes.push(format!("{tv}: {}", style::nred("(failed)")));
+                    self.errors.push(format!("{tv}: {err}"));
+                }
+            }
+        }
+        if !probes.is_empty() {
+            section("probes", probes.join("\n"))?;
+        }
+        Ok(())
+    }
+
     fn analyze_shims(&mut self, toolset: &Toolset) {
         let start_ms = std::time::Instant::now();
         let mise_bin = file::which("mise").unwrap_or(env::MISE_BIN.clone());
@@ -194,10 +352,91 @@ impl Doctor {
                 self.warnings
                     .push(format!("plugin {} overrides a core plugin", &plugin.id()));
             }
+            if plugin_type == PluginType::Asdf && plugin.is_installed() {
+                let bin = dirs::PLUGINS.join(plugin.id()).join("bin");
+                for script in ["list-all", "install"] {
+                    let path = bin.join(script);
+                    if !path.exists() {
+                        self.errors
+                            .push(format!("plugin {} is missing bin/{script}", plugin.id()));
+                    } else if !file::is_executable(&path) {
+                        self.errors.push(format!(
+                            "plugin {} bin/{script} is not executable, run {}",
+                            plugin.id(),
+                            style::nyellow(format!("chmod +x {}", display_path(&path)))
+                        ));
+                    }
+                }
+            }
+        }
+
+        let unreachable = backend::list()
+            .into_par_iter()
+            .filter(|p| p.get_plugin_type() == PluginType::Asdf && p.is_installed())
+            .filter_map(|p| {
+                let url = Git::new(dirs::PLUGINS.join(p.id())).get_remote_url()?;
+                let reachable = cmd!("git", "ls-remote", "--exit-code", &url, "HEAD")
+                    .stdout_capture()
+                    .stderr_capture()
+                    .run()
+                    .is_ok();
+                (!reachable).then(|| format!("plugin {} remote {url} is not reachable", p.id()))
+            })
+            .collect::<Vec<_>>();
+        self.warnings.extend(unreachable);
+
+        let pins = backend::list()
+            .into_par_iter()
+            .filter(|p| p.get_plugin_type() == PluginType::Asdf && p.is_installed())
+            .filter_map(|p| {
+                let git = Git::new(dirs::PLUGINS.join(p.id()));
+                let pinned = git.pinned_sha()?;
+                let current = git.current_sha().unwrap_or_default();
+                let remote = git.remote_sha("HEAD").ok().flatten();
+                Some((p.id().to_string(), pinned, current, remote))
+            })
+            .collect::<Vec<_>>();
+        for (id, pinned, current, remote) in pins {
+            if current != pinned {
+                self.errors.push(format!(
+                    "plugin {id} is pinned to {pinned} but {current} is checked out, run {}",
+                    style::nyellow(format!("mise plugins update {id}#{pinned}"))
+                ));
+            }
+            if let Some(remote) = remote.filter(|r| *r != pinned) {
+                self.warnings.push(format!(
+                    "plugin {id} is pinned to {pinned} but the remote has moved to {remote}"
+                ));
+            }
         }
     }
 }
 
+/// commands a backend shells out to when installing or listing versions
+fn required_bins(backend_type: BackendType) -> &'static [&'static str] {
+    match backend_type {
+        BackendType::Asdf => &["git"],
+        BackendType::Cabal => &["cabal", "ghc"],
+        BackendType::Cargo => &["cargo"],
+        BackendType::Deno => &["deno"],
+        BackendType::Go => &["go"],
+        BackendType::Krew => &["git"],
+        BackendType::Npm => &["npm"],
+        BackendType::Pipx => &["pipx"],
+        BackendType::Pixi => &["pixi"],
+        BackendType::Rscript => &["Rscript"],
+        BackendType::Spm => &["swift"],
+        BackendType::Ubi => &["ubi"],
+        BackendType::Core
+        | BackendType::Gitea
+        | BackendType::Gitlab
+        | BackendType::Http
+        | BackendType::Oci
+        | BackendType::System
+        | BackendType::Wasm => &[],
+    }
+}
+
 fn shims_on_path() -> bool {
     env::PATH.contains(&dirs::SHIMS.to_path_buf())
 }
@@ -327,6 +566,12 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
 
     $ <bold>mise doctor</bold>
     [WARN] plugin node is not installed
+
+    $ <bold>mise doctor</bold>
+    backend_probes:
+      cargo: cargo (~/.cargo/bin/cargo)
+      ubi: ubi (~/.local/share/mise/installs/cargo-ubi/0.1.0/bin/ubi)
+      github: 4987/5000 requests remaining
 "#
 );
 
@@ -8,12 +8,17 @@ use eyre::{bail, Result};
 #[cfg(any(test, windows))]
 use eyre::{eyre, Result};
 
+use serde_derive::{Deserialize, Serialize};
+
+use crate::cache::CacheManager;
 use crate::cli::args::ToolArg;
 #[cfg(any(test, windows))]
 use crate::cmd;
-use crate::config::Config;
-use crate::env;
-use crate::toolset::{InstallOptions, ToolsetBuilder};
+use crate::config::{load_config_paths, Config, Settings, DEFAULT_CONFIG_FILENAMES};
+use crate::hash::hash_to_str;
+use crate::hook_env::HookEnvWatches;
+use crate::toolset::{InstallOptions, Toolset, ToolsetBuilder};
+use crate::{dirs, env, hermetic, hook_env, sandbox};
 
 /// Execute a command with tool(s) set
 ///
@@ -49,10 +54,27 @@ pub struct Exec {
     /// Sets --jobs=1
     #[clap(long, overrides_with = "jobs")]
     pub raw: bool,
+
+    /// Only put tools managed by mise and commands in `hermetic_allow` on PATH
+    #[clap(long)]
+    pub hermetic: bool,
 }
 
 impl Exec {
     pub fn run(self) -> Result<()> {
+        let settings = Settings::get();
+        let hermetic = self.hermetic || settings.hermetic;
+        let (program, args) = parse_command(&env::SHELL, &self.command, &self.c);
+        let cache = (settings.exec_cache && !hermetic).then(|| self.cache(&program));
+        let cached = cache
+            .as_ref()
+            .and_then(|c| c.read_fresh())
+            .filter(|c| hook_env::are_watches_fresh(&c.watches));
+        if let Some(cached) = cached {
+            trace!("using env from exec cache");
+            return self.exec(program, args, cached.env);
+        }
+
         let config = Config::try_get()?;
         let mut ts = ToolsetBuilder::new()
             .with_args(&self.tool)
@@ -62,15 +84,46 @@ impl Exec {
             force: false,
             jobs: self.jobs,
             raw: self.raw,
+            keep_download: false,
             latest_versions: false,
         };
-        ts.install_arg_versions(&config, &opts)?;
+        ts.auto_install_arg_versions(&config, settings.auto_install.exec, &opts)?;
         ts.notify_if_versions_missing();
 
-        let (program, args) = parse_command(&env::SHELL, &self.command, &self.c);
-        let env = ts.env_with_path(&config)?;
+        let mut env = ts.env_with_path(&config)?;
+        if hermetic {
+            hermetic::apply(&config, &ts, &mut env)?;
+            hermetic::ensure_available(&program.to_string_lossy(), &env)?;
+        }
+
+        let (wrapped_program, wrapped_args) = sandbox::wrap(&ts, program.clone(), args.clone())?;
+        let sandboxed = wrapped_program != program || wrapped_args != args;
+        if let Some(cache) = cache.filter(|_| !sandboxed) {
+            if let Err(err) = write_cache(&cache, &config, &ts, &env) {
+                debug!("failed to write exec cache: {err:#}");
+            }
+        }
+
+        self.exec(wrapped_program, wrapped_args, env)
+    }
 
-        self.exec(program, args, env)
+    /// the env only depends on the directory, config files, tool args, program (for sandboxing),
+    /// and the env mise was called with
+    fn cache(&self, program: &OsString) -> CacheManager<ExecCache> {
+        let tools = self.tool.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+        let pristine_env = env::PRISTINE_ENV.iter().collect::<BTreeMap<_, _>>();
+        let key = hash_to_str(&(
+            &*dirs::CWD,
+            tools,
+            program,
+            load_config_paths(&DEFAULT_CONFIG_FILENAMES),
+            pristine_env,
+        ));
+        CacheManager::new(
+            dirs::CACHE
+                .join("exec")
+                .join(format!("{key}-$KEY.msgpack.z")),
+        )
     }
 
     #[cfg(not(any(test, windows)))]
@@ -111,6 +164,38 @@ impl Exec {
     }
 }
 
+/// env from a previous `mise exec`, reused until a config file, env file, or installed version of
+/// one of its tools changes
+#[derive(Debug, Serialize, Deserialize)]
+struct ExecCache {
+    watches: HookEnvWatches,
+    env: BTreeMap<String, String>,
+}
+
+fn write_cache(
+    cache: &CacheManager<ExecCache>,
+    config: &Config,
+    ts: &Toolset,
+    env: &BTreeMap<String, String>,
+) -> Result<()> {
+    // decrypted values would be written to disk in plaintext
+    if !ts.list_missing_versions().is_empty() || config.env_results()?.decrypted {
+        return Ok(());
+    }
+    let installs = ts
+        .list_current_versions()
+        .into_iter()
+        .map(|(p, _)| p.fa().installs_path.clone());
+    let watches = hook_env::build_watches(
+        config.watch_files()?.into_iter().chain(installs),
+        Default::default(),
+    )?;
+    cache.write(&ExecCache {
+        watches,
+        env: env.clone(),
+    })
+}
+
 fn parse_command(
     shell: &str,
     command: &Option<Vec<OsString>>,
@@ -0,0 +1,142 @@
+use std::path::PathBuf;
+
+use clap::ValueHint;
+use eyre::{bail, Result};
+use itertools::Itertools;
+
+use crate::cli::args::ToolArg;
+use crate::config::Config;
+use crate::errors::Error::VersionNotInstalled;
+use crate::file::display_path;
+use crate::hash::file_hash_sha256;
+use crate::toolset::ToolsetBuilder;
+use crate::ui::style;
+use crate::{backend, file};
+
+/// Copy the downloaded files of an installed tool into a directory
+///
+/// Downloads are removed after installing unless `mise install --keep-download`
+/// was used or the `always_keep_download` setting is enabled.
+/// A SHASUMS256.txt file is written alongside the files which can be used to
+/// populate air-gapped mirrors or compliance archives.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct ExportArtifacts {
+    /// Tool to export the downloads of
+    /// e.g.: node@20
+    #[clap(value_name = "TOOL@VERSION", verbatim_doc_comment)]
+    tool: ToolArg,
+
+    /// Directory to copy the files into
+    #[clap(long, short, value_hint = ValueHint::DirPath)]
+    dest: PathBuf,
+}
+
+impl ExportArtifacts {
+    pub fn run(self) -> Result<()> {
+        let config = Config::try_get()?;
+        let tool = match self.tool.tvr {
+            Some(_) => self.tool.clone(),
+            None => {
+                let ts = ToolsetBuilder::new()
+                    .with_args(std::slice::from_ref(&self.tool))
+                    .build(&config)?;
+                let v = ts
+                    .versions
+                    .get(&self.tool.backend)
+                    .and_then(|v| v.requests.first())
+                    .map(|r| r.version());
+                self.tool.with_version(&v.unwrap_or(String::from("latest")))
+            }
+        };
+        let backend = backend::get(&tool.backend);
+        let tv = match tool
+            .tvr
+            .as_ref()
+            .map(|tvr| tvr.resolve(backend.as_ref(), false))
+        {
+            Some(Ok(tv)) if backend.is_version_installed(&tv) => tv,
+            _ => Err(VersionNotInstalled(
+                tool.backend.to_string(),
+                tool.tvr.map(|tvr| tvr.version()).unwrap_or_default(),
+            ))?,
+        };
+        let download_path = tv.download_path();
+        let files = file::recursive_ls(&download_path)?;
+        if files.is_empty() {
+            bail!(
+                "no downloads were kept for {tv}, reinstall with {}",
+                style::eyellow(format!("mise install --force --keep-download {tv}"))
+            );
+        }
+
+        let mut shasums = vec![];
+        for src in files.into_iter().sorted() {
+            let rel = src.strip_prefix(&download_path)?;
+            let dest = self.dest.join(rel);
+            file::create_dir_all(dest.parent().unwrap())?;
+            file::copy(&src, &dest)?;
+            let rel = rel.to_string_lossy().replace('\\', "/");
+            shasums.push(format!("{}  {rel}", file_hash_sha256(&dest)?));
+        }
+        let shasums_path = self.dest.join("SHASUMS256.txt");
+        file::write(&shasums_path, shasums.join("\n") + "\n")?;
+        info!(
+            "exported {} file(s) from {tv} to {}",
+            shasums.len(),
+            display_path(&self.dest)
+        );
+        Ok(())
+    }
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise install --keep-download node@20</bold>
+    $ <bold>mise export-artifacts node@20 --dest ./mirror/node/20</bold>
+    $ <bold>cat ./mirror/node/20/SHASUMS256.txt</bold>
+    3f3f6b9a...  node-v20.13.1-linux-x64.tar.gz
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::dirs;
+    use crate::file;
+    use crate::test::reset;
+
+    #[test]
+    fn test_export_artifacts() {
+        reset();
+        assert_cli!("install", "tiny@3.1.0");
+        let download = dirs::DOWNLOADS.join("tiny").join("3.1.0");
+        file::create_dir_all(&download).unwrap();
+        file::write(download.join("tiny.tar.gz"), "tiny").unwrap();
+        let dest = dirs::HOME.join("artifacts");
+        assert_cli!(
+            "export-artifacts",
+            "tiny@3.1.0",
+            "--dest",
+            dest.to_string_lossy().to_string()
+        );
+        assert_eq!(
+            file::read_to_string(dest.join("tiny.tar.gz")).unwrap(),
+            "tiny"
+        );
+        assert_eq!(
+            file::read_to_string(dest.join("SHASUMS256.txt")).unwrap(),
+            "8950abfda7b727630760dd35bcf5c3daa7631aff223a90f7728c0d2521dde10c  tiny.tar.gz\n"
+        );
+        file::remove_all(dest).unwrap();
+    }
+
+    #[test]
+    fn test_export_artifacts_not_installed() {
+        reset();
+        let err = assert_cli_err!("export-artifacts", "tiny@1.0.0", "--dest", "artifacts");
+        assert_eq!(err.to_string(), "tiny@1.0.0 not installed");
+    }
+}
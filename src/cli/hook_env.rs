@@ -1,3 +1,4 @@
+use std::collections::{BTreeMap, BTreeSet};
 use std::env::{join_paths, split_paths};
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
@@ -8,11 +9,11 @@ use itertools::Itertools;
 
 use crate::config::{Config, Settings};
 use crate::direnv::DirenvDiff;
-use crate::env::{TERM_WIDTH, __MISE_DIFF};
+use crate::env::{__MISE_DIFF, __MISE_WATCH, TERM_WIDTH};
 use crate::env_diff::{EnvDiff, EnvDiffOperation};
 use crate::shell::{get_shell, ShellType};
 use crate::toolset::{Toolset, ToolsetBuilder};
-use crate::{env, hook_env};
+use crate::{env, hook_env, hooks};
 
 /// [internal] called by activate hook to update env vars directory change
 #[derive(Debug, clap::Args)]
@@ -56,7 +57,23 @@ impl HookEnv {
         let settings = Settings::try_get()?;
         patches.extend(self.build_path_operations(&settings, &paths, &__MISE_DIFF.path)?);
         patches.push(self.build_diff_operation(&diff)?);
-        patches.push(self.build_watch_operation(&watch_files)?);
+        let tools = ts
+            .list_current_installed_versions()
+            .into_iter()
+            .map(|(p, tv)| (p.id().to_string(), tv.version))
+            .collect();
+        // the first run in a shell has nothing to compare against
+        if let Some(watches) = &*__MISE_WATCH {
+            hooks::run_version_change_hooks(&config, &ts, &watches.tools, &tools);
+        }
+        // unlike version changes, a shell started inside of a project enters it
+        let dir_hook_files = hooks::dir_hook_files(&config);
+        let (previous_dir, previous) = match &*__MISE_WATCH {
+            Some(watches) => (watches.dir.as_deref(), watches.dir_hook_files.clone()),
+            None => (None, BTreeSet::new()),
+        };
+        hooks::run_dir_hooks(&config, &ts, previous_dir, &previous, &dir_hook_files);
+        patches.push(self.build_watch_operation(&watch_files, tools, dir_hook_files)?);
 
         let output = hook_env::build_env_commands(&*shell, &patches);
         miseprint!("{output}")?;
@@ -163,8 +180,11 @@ impl HookEnv {
     fn build_watch_operation(
         &self,
         watch_files: impl IntoIterator<Item = impl AsRef<Path>>,
+        tools: BTreeMap<String, String>,
+        dir_hook_files: BTreeSet<PathBuf>,
     ) -> Result<EnvDiffOperation> {
-        let watches = hook_env::build_watches(watch_files)?;
+        let mut watches = hook_env::build_watches(watch_files, tools)?;
+        watches.dir_hook_files = dir_hook_files;
         Ok(EnvDiffOperation::Add(
             "__MISE_WATCH".into(),
             hook_env::serialize_watches(&watches)?,
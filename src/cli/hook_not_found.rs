@@ -25,7 +25,10 @@ impl HookNotFound {
         let settings = Settings::try_get()?;
         if settings.not_found_auto_install {
             let mut ts = ToolsetBuilder::new().build(&config)?;
-            if ts.install_missing_bin(&self.bin)?.is_some() {
+            if ts
+                .install_missing_bin(&self.bin, settings.auto_install.shim)?
+                .is_some()
+            {
                 return Ok(());
             }
         }
@@ -1,9 +1,10 @@
-use eyre::Result;
+use eyre::{bail, Result};
 use itertools::Itertools;
 use std::collections::HashSet;
 
+use crate::backend::postinstall;
 use crate::cli::args::{BackendArg, ToolArg};
-use crate::config::Config;
+use crate::config::{lockfile, Config, Settings};
 use crate::toolset::{InstallOptions, ToolRequest, ToolVersion, ToolVersionOptions, Toolset};
 use crate::ui::multi_progress_report::MultiProgressReport;
 
@@ -38,6 +39,18 @@ pub struct Install {
     #[clap(long, overrides_with = "jobs")]
     raw: bool,
 
+    /// Keep the downloaded files after installing
+    /// These can be copied out with `mise export-artifacts`
+    /// Set `always_keep_download` to always keep them
+    #[clap(long, verbatim_doc_comment)]
+    keep_download: bool,
+
+    /// Only install the versions in mise.lock
+    /// Fails instead of resolving versions of tools which are not in it
+    /// Requires the `lockfile` setting
+    #[clap(long, verbatim_doc_comment)]
+    locked: bool,
+
     /// Show installation output
     ///
     /// This argument will print plugin output such as download, configuration, and compilation output.
@@ -48,10 +61,14 @@ pub struct Install {
 impl Install {
     pub fn run(self) -> Result<()> {
         let config = Config::try_get()?;
+        if self.locked && !Settings::get().lockfile {
+            bail!("--locked requires the lockfile setting, enable it with `mise settings set lockfile true`");
+        }
         match &self.tool {
             Some(runtime) => self.install_runtimes(&config, runtime)?,
             None => self.install_missing_runtimes(&config)?,
         };
+        lockfile::update(&config)?;
 
         Ok(())
     }
@@ -73,6 +90,7 @@ impl Install {
             force: self.force,
             jobs: self.jobs,
             raw: self.raw,
+            keep_download: self.keep_download,
             latest_versions: true,
         }
     }
@@ -119,7 +137,13 @@ impl Install {
 
     fn install_missing_runtimes(&self, config: &Config) -> eyre::Result<Vec<ToolVersion>> {
         let trs = config.get_tool_request_set()?;
-        let versions = trs.missing_tools().into_iter().cloned().collect_vec();
+        let versions = trs
+            .tools
+            .values()
+            .flatten()
+            .filter(|tr| !tr.is_installed() || postinstall_pending(tr))
+            .cloned()
+            .collect_vec();
         if versions.is_empty() {
             info!("all runtimes are installed");
             return Ok(vec![]);
@@ -130,6 +154,12 @@ impl Install {
     }
 }
 
+/// installed tools whose postinstall script failed or changed since it last ran
+fn postinstall_pending(tr: &ToolRequest) -> bool {
+    tr.install_path()
+        .is_some_and(|p| postinstall::pending(&p, &tr.options()).is_some())
+}
+
 static AFTER_LONG_HELP: &str = color_print::cstr!(
     r#"<bold><underline>Examples:</underline></bold>
 
@@ -137,6 +167,7 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
     $ <bold>mise install node@20</bold>      # install fuzzy node version
     $ <bold>mise install node</bold>         # install version specified in .tool-versions or .mise.toml
     $ <bold>mise install</bold>              # installs everything specified in .tool-versions or .mise.toml
+    $ <bold>mise install --locked</bold>     # installs the versions in mise.lock
 "#
 );
 
@@ -14,6 +14,7 @@ use versions::Versioning;
 use crate::backend::Backend;
 use crate::cli::args::BackendArg;
 use crate::config::Config;
+use crate::registry::{self, ToolMetadata};
 use crate::toolset::{ToolSource, ToolVersion, Toolset};
 use crate::ui::table;
 use crate::{backend, config};
@@ -250,6 +251,8 @@ struct JSONToolVersion {
     source: Option<IndexMap<String, String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     symlinked_to: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metadata: Option<ToolMetadata>,
     installed: bool,
     active: bool,
 }
@@ -292,6 +295,7 @@ impl From<RuntimeRow> for JSONToolVersion {
         let vs: VersionStatus = (p.as_ref(), &tv, &source).into();
         JSONToolVersion {
             symlinked_to: p.symlink_path(&tv),
+            metadata: registry::metadata(p.fa(), &tv.request.options()),
             install_path: tv.install_path(),
             version: tv.version,
             requested_version: source.as_ref().map(|_| tv.request.version()),
@@ -382,6 +386,10 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
           "source": {
             "type": ".mise.toml",
             "path": "/Users/jdx/.mise.toml"
+          },
+          "metadata": {
+            "license": "MIT",
+            "source": "https://github.com/nodejs/node"
           }
         }
       ],
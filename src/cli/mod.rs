@@ -16,10 +16,12 @@ mod completion;
 mod config;
 mod current;
 mod deactivate;
+mod diff;
 mod direnv;
 mod doctor;
 mod env;
 pub mod exec;
+mod export_artifacts;
 mod external;
 mod generate;
 mod global;
@@ -32,20 +34,26 @@ mod link;
 mod local;
 mod ls;
 mod ls_remote;
+mod new;
 mod outdated;
 mod plugins;
 mod prune;
+mod ps;
 mod registry;
 #[cfg(debug_assertions)]
 mod render_help;
 #[cfg(feature = "clap_mangen")]
 mod render_mangen;
+mod report;
 mod reshim;
 mod run;
+mod sbom;
+mod schedule;
 mod self_update;
 mod set;
 mod settings;
 mod shell;
+mod stop;
 mod sync;
 mod tasks;
 mod trust;
@@ -54,7 +62,9 @@ mod unset;
 mod upgrade;
 mod usage;
 mod r#use;
+mod verify;
 pub mod version;
+mod wait;
 mod watch;
 mod r#where;
 mod r#which;
@@ -73,10 +83,12 @@ pub enum Commands {
     Config(config::Config),
     Current(current::Current),
     Deactivate(deactivate::Deactivate),
+    Diff(diff::Diff),
     Direnv(direnv::Direnv),
     Doctor(doctor::Doctor),
     Env(env::Env),
     Exec(exec::Exec),
+    ExportArtifacts(export_artifacts::ExportArtifacts),
     Generate(generate::Generate),
     Global(global::Global),
     HookEnv(hook_env::HookEnv),
@@ -88,16 +100,22 @@ pub enum Commands {
     Local(local::Local),
     Ls(ls::Ls),
     LsRemote(ls_remote::LsRemote),
+    New(new::New),
     Outdated(outdated::Outdated),
     Plugins(plugins::Plugins),
     Prune(prune::Prune),
+    Ps(ps::Ps),
     Registry(registry::Registry),
+    Report(report::Report),
     Reshim(reshim::Reshim),
     Run(run::Run),
+    Sbom(sbom::Sbom),
+    Schedule(schedule::Schedule),
     SelfUpdate(self_update::SelfUpdate),
     Set(set::Set),
     Settings(settings::Settings),
     Shell(shell::Shell),
+    Stop(stop::Stop),
     Sync(sync::Sync),
     Tasks(tasks::Tasks),
     Trust(trust::Trust),
@@ -106,7 +124,9 @@ pub enum Commands {
     Upgrade(upgrade::Upgrade),
     Usage(usage::Usage),
     Use(r#use::Use),
+    Verify(verify::Verify),
     Version(version::Version),
+    Wait(wait::Wait),
     Watch(watch::Watch),
     Where(r#where::Where),
     Which(which::Which),
@@ -131,10 +151,12 @@ impl Commands {
             Self::Config(cmd) => cmd.run(),
             Self::Current(cmd) => cmd.run(),
             Self::Deactivate(cmd) => cmd.run(),
+            Self::Diff(cmd) => cmd.run(),
             Self::Direnv(cmd) => cmd.run(),
             Self::Doctor(cmd) => cmd.run(),
             Self::Env(cmd) => cmd.run(),
             Self::Exec(cmd) => cmd.run(),
+            Self::ExportArtifacts(cmd) => cmd.run(),
             Self::Generate(cmd) => cmd.run(),
             Self::Global(cmd) => cmd.run(),
             Self::HookEnv(cmd) => cmd.run(),
@@ -146,16 +168,22 @@ impl Commands {
             Self::Local(cmd) => cmd.run(),
             Self::Ls(cmd) => cmd.run(),
             Self::LsRemote(cmd) => cmd.run(),
+            Self::New(cmd) => cmd.run(),
             Self::Outdated(cmd) => cmd.run(),
             Self::Plugins(cmd) => cmd.run(),
             Self::Prune(cmd) => cmd.run(),
+            Self::Ps(cmd) => cmd.run(),
             Self::Registry(cmd) => cmd.run(),
+            Self::Report(cmd) => cmd.run(),
             Self::Reshim(cmd) => cmd.run(),
             Self::Run(cmd) => cmd.run(),
+            Self::Sbom(cmd) => cmd.run(),
+            Self::Schedule(cmd) => cmd.run(),
             Self::SelfUpdate(cmd) => cmd.run(),
             Self::Set(cmd) => cmd.run(),
             Self::Settings(cmd) => cmd.run(),
             Self::Shell(cmd) => cmd.run(),
+            Self::Stop(cmd) => cmd.run(),
             Self::Sync(cmd) => cmd.run(),
             Self::Tasks(cmd) => cmd.run(),
             Self::Trust(cmd) => cmd.run(),
@@ -164,7 +192,9 @@ impl Commands {
             Self::Upgrade(cmd) => cmd.run(),
             Self::Usage(cmd) => cmd.run(),
             Self::Use(cmd) => cmd.run(),
+            Self::Verify(cmd) => cmd.run(),
             Self::Version(cmd) => cmd.run(),
+            Self::Wait(cmd) => cmd.run(),
             Self::Watch(cmd) => cmd.run(),
             Self::Where(cmd) => cmd.run(),
             Self::Which(cmd) => cmd.run(),
@@ -192,6 +222,8 @@ impl Cli {
                 .arg(args::CdArg::arg())
                 .arg(args::DebugArg::arg())
                 .arg(args::LogLevelArg::arg())
+                .arg(args::OfflineArg::arg())
+                .arg(args::ProgressEventsArg::arg())
                 .arg(args::QuietArg::arg())
                 .arg(args::TraceArg::arg())
                 .arg(args::VerboseArg::arg())
@@ -212,6 +244,9 @@ impl Cli {
                     .get_matches_from(args)
             });
         Settings::add_cli_matches(&matches);
+        if Settings::get().offline {
+            crate::env::set_offline_vars();
+        }
         logger::init();
         migrate::run();
         debug!("ARGS: {}", &args.join(" "));
@@ -0,0 +1,242 @@
+use std::path::{Path, PathBuf};
+
+use clap::ValueHint;
+use eyre::{bail, Result};
+use itertools::Itertools;
+use path_absolutize::Absolutize;
+
+use crate::cli::args::EnvVarArg;
+use crate::config::Settings;
+use crate::file::display_path;
+use crate::git::Git;
+use crate::hash::hash_to_str;
+use crate::tera::{get_tera, BASE_CONTEXT};
+use crate::ui::style;
+use crate::{dirs, file};
+
+/// [experimental] Create a new project from a template
+///
+/// Templates are directories that contain a mise.toml (tools, tasks, env, hooks)
+/// along with any other files the project should start with. Files ending in
+/// `.tera` are rendered as tera templates and written without the extension.
+///
+/// TEMPLATE can be a local directory, a git url, or the name of a directory inside
+/// one of the repositories listed in the `template_sources` setting.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct New {
+    /// Template to scaffold the project from
+    #[clap()]
+    template: String,
+
+    /// Directory to create the project in
+    /// [default: ./<TEMPLATE>]
+    #[clap(value_hint = ValueHint::DirPath, verbatim_doc_comment)]
+    dir: Option<PathBuf>,
+
+    /// Overwrite files that already exist in the target directory
+    #[clap(long, short)]
+    force: bool,
+
+    /// Variables to pass to `.tera` templates
+    /// e.g.: --var description="my new service"
+    #[clap(long = "var", value_name = "KEY=VALUE", verbatim_doc_comment)]
+    vars: Vec<EnvVarArg>,
+}
+
+impl New {
+    pub fn run(self) -> Result<()> {
+        let settings = Settings::try_get()?;
+        settings.ensure_experimental("mise new")?;
+        let template = self.find_template(&settings)?;
+        let dir = match &self.dir {
+            Some(dir) => dir.absolutize()?.to_path_buf(),
+            None => dirs::CWD
+                .clone()
+                .unwrap_or_default()
+                .join(template_name(&self.template)),
+        };
+        debug!("new: {} -> {}", display_path(&template), display_path(&dir));
+        let files = template_files(&template)?;
+        if !self.force {
+            let existing = files
+                .iter()
+                .map(|f| dir.join(output_path(f)))
+                .filter(|f| f.exists())
+                .collect_vec();
+            if !existing.is_empty() {
+                bail!(
+                    "{} already exists, use {} to overwrite",
+                    existing.iter().map(display_path).join(", "),
+                    style::eyellow("--force")
+                );
+            }
+        }
+
+        let project_name = dir
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mut ctx = BASE_CONTEXT.clone();
+        ctx.insert("project_name", &project_name);
+        ctx.insert("project_root", &dir);
+        ctx.insert("template", &self.template);
+        for var in &self.vars {
+            ctx.insert(&var.key, &var.value.clone().unwrap_or_default());
+        }
+        let mut tera = get_tera(Some(&template));
+        for rel in files {
+            let src = template.join(&rel);
+            let dest = dir.join(output_path(&rel));
+            file::create_dir_all(dest.parent().unwrap())?;
+            if is_tera(&rel) {
+                let body = file::read_to_string(&src)?;
+                file::write(&dest, tera.render_str(&body, &ctx)?)?;
+                if file::is_executable(&src) {
+                    file::make_executable(&dest)?;
+                }
+            } else {
+                file::copy(&src, &dest)?;
+            }
+        }
+
+        miseprintln!("Created {} from {}", display_path(&dir), self.template);
+        info!(
+            "run {} and {} to get started",
+            style::eyellow("mise trust"),
+            style::eyellow("mise install")
+        );
+        Ok(())
+    }
+
+    fn find_template(&self, settings: &Settings) -> Result<PathBuf> {
+        let local = PathBuf::from(&self.template);
+        if local.is_dir() {
+            return Ok(local.absolutize()?.to_path_buf());
+        }
+        if is_git_url(&self.template) {
+            return fetch_repo(&self.template);
+        }
+        for source in &settings.template_sources {
+            let repo = fetch_repo(source)?;
+            for dir in [
+                repo.join(&self.template),
+                repo.join("templates").join(&self.template),
+            ] {
+                if dir.is_dir() {
+                    return Ok(dir);
+                }
+            }
+        }
+        if settings.template_sources.is_empty() {
+            bail!(
+                "template not found: {}\nadd a template repository with {}",
+                self.template,
+                style::eyellow("mise settings set template_sources <GIT_URL>")
+            );
+        }
+        bail!(
+            "template not found: {} (searched {})",
+            self.template,
+            settings.template_sources.iter().join(", ")
+        )
+    }
+}
+
+/// clones a template repository into the cache or updates it if it was already cloned
+fn fetch_repo(url: &str) -> Result<PathBuf> {
+    let dir = dirs::CACHE.join("templates").join(hash_to_str(&url));
+    let git = Git::new(dir.clone());
+    if git.is_repo() {
+        if let Err(err) = git.update(None) {
+            warn!("failed to update template repository {url}: {err:#}");
+        }
+    } else {
+        git.clone(url)?;
+    }
+    Ok(dir)
+}
+
+fn template_files(template: &Path) -> Result<Vec<PathBuf>> {
+    Ok(file::recursive_ls(template)?
+        .into_iter()
+        .filter_map(|p| p.strip_prefix(template).ok().map(|p| p.to_path_buf()))
+        .filter(|p| !p.starts_with(".git"))
+        .sorted()
+        .collect())
+}
+
+fn is_git_url(s: &str) -> bool {
+    s.starts_with("https://") || s.starts_with("git@") || s.ends_with(".git")
+}
+
+fn is_tera(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "tera")
+}
+
+fn output_path(path: &Path) -> PathBuf {
+    match is_tera(path) {
+        true => path.with_extension(""),
+        false => path.to_path_buf(),
+    }
+}
+
+fn template_name(template: &str) -> String {
+    template
+        .trim_end_matches('/')
+        .trim_end_matches(".git")
+        .rsplit(['/', ':'])
+        .next()
+        .unwrap_or(template)
+        .to_string()
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise settings set template_sources https://github.com/acme/mise-templates</bold>
+    $ <bold>mise new node-service ./billing</bold>
+    Created ~/src/billing from node-service
+
+    $ <bold>mise new https://github.com/acme/rust-cli-template.git --var description="a cli"</bold>
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{is_git_url, output_path, template_name};
+
+    #[test]
+    fn test_template_name() {
+        assert_eq!(template_name("node-service"), "node-service");
+        assert_eq!(template_name("./templates/rust/"), "rust");
+        assert_eq!(
+            template_name("https://github.com/acme/rust-cli-template.git"),
+            "rust-cli-template"
+        );
+        assert_eq!(template_name("git@github.com:acme/cli.git"), "cli");
+    }
+
+    #[test]
+    fn test_is_git_url() {
+        assert!(is_git_url("https://github.com/acme/templates"));
+        assert!(is_git_url("git@github.com:acme/templates.git"));
+        assert!(!is_git_url("node-service"));
+    }
+
+    #[test]
+    fn test_output_path() {
+        assert_eq!(
+            output_path(Path::new("mise.toml.tera")),
+            Path::new("mise.toml")
+        );
+        assert_eq!(
+            output_path(Path::new("src/main.rs")),
+            Path::new("src/main.rs")
+        );
+    }
+}
@@ -4,12 +4,15 @@ use std::sync::Arc;
 use console::{pad_str, style, Alignment};
 use eyre::Result;
 
-use crate::backend::Backend;
+use crate::backend::{Backend, VersionInfo};
 use crate::cli::args::ToolArg;
 use crate::config::Config;
 use crate::toolset::{ToolVersion, ToolsetBuilder};
 
 /// Shows outdated tool versions
+///
+/// For backends that know it, e.g.: ubi and npm, this also shows when the
+/// latest version was released and where to find its release notes.
 #[derive(Debug, clap::Args)]
 #[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
 pub struct Outdated {
@@ -68,6 +71,29 @@ impl Outdated {
             .iter()
             .map(|(_, _, c)| c.clone())
             .collect::<Vec<_>>();
+        let infos = outdated
+            .iter()
+            .map(|(t, _, c)| release_info(t.as_ref(), c))
+            .collect::<Vec<_>>();
+        let released_dates = infos
+            .iter()
+            .map(|i| {
+                let date = i.as_ref().and_then(|i| i.created_at.as_deref());
+                // only the date of timestamps like "2024-05-01T12:00:00Z"
+                date.unwrap_or_default()
+                    .chars()
+                    .take(10)
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>();
+        let notes = infos
+            .iter()
+            .map(|i| {
+                let url = i.as_ref().and_then(|i| i.release_url.clone());
+                url.unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
+        let has_info = infos.iter().any(|i| i.is_some());
         let plugin_width = plugins
             .iter()
             .map(|s| s.len())
@@ -89,25 +115,58 @@ impl Outdated {
             .unwrap_or_default()
             .max(7)
             + 1;
+        let latest_width = latests
+            .iter()
+            .map(|s| s.len())
+            .max()
+            .unwrap_or_default()
+            .max(6)
+            + 1;
         let pad_plugin = |s| pad_str(s, plugin_width, Alignment::Left, None);
         let pad_requested = |s| pad_str(s, requested_width, Alignment::Left, None);
         let pad_current = |s| pad_str(s, current_width, Alignment::Left, None);
-        miseprintln!(
-            "{} {} {} {}",
-            style(pad_plugin("Tool")).dim(),
-            style(pad_requested("Requested")).dim(),
-            style(pad_current("Current")).dim(),
-            style("Latest").dim(),
-        );
-        for i in 0..outdated.len() {
+        let pad_latest = |s| pad_str(s, latest_width, Alignment::Left, None);
+        let pad_released = |s| pad_str(s, 11, Alignment::Left, None);
+        if has_info {
+            miseprintln!(
+                "{} {} {} {} {} {}",
+                style(pad_plugin("Tool")).dim(),
+                style(pad_requested("Requested")).dim(),
+                style(pad_current("Current")).dim(),
+                style(pad_latest("Latest")).dim(),
+                style(pad_released("Released")).dim(),
+                style("Release Notes").dim(),
+            );
+        } else {
             miseprintln!(
                 "{} {} {} {}",
-                pad_plugin(plugins[i]),
-                pad_requested(&requests[i]),
-                pad_current(&currents[i]),
-                latests[i]
+                style(pad_plugin("Tool")).dim(),
+                style(pad_requested("Requested")).dim(),
+                style(pad_current("Current")).dim(),
+                style("Latest").dim(),
             );
         }
+        for i in 0..outdated.len() {
+            if has_info {
+                miseprintln!(
+                    "{} {} {} {} {} {}",
+                    pad_plugin(plugins[i]),
+                    pad_requested(&requests[i]),
+                    pad_current(&currents[i]),
+                    pad_latest(&latests[i]),
+                    pad_released(&released_dates[i]),
+                    notes[i]
+                );
+            } else {
+                miseprintln!(
+                    "{} {} {} {}",
+                    pad_plugin(plugins[i]),
+                    pad_requested(&requests[i]),
+                    pad_current(&currents[i]),
+                    latests[i]
+                );
+            }
+        }
         Ok(())
     }
 
@@ -117,6 +176,14 @@ impl Outdated {
             let mut inner = serde_json::Map::new();
             inner.insert("requested".to_string(), tv.request.version().into());
             inner.insert("current".to_string(), tv.version.clone().into());
+            if let Some(info) = release_info(t.as_ref(), &c) {
+                if let Some(created_at) = info.created_at {
+                    inner.insert("released_at".to_string(), created_at.into());
+                }
+                if let Some(release_url) = info.release_url {
+                    inner.insert("release_url".to_string(), release_url.into());
+                }
+            }
             inner.insert("latest".to_string(), c.into());
             map.insert(t.id().to_string(), serde_json::Value::Object(inner));
         }
@@ -126,6 +193,21 @@ impl Outdated {
     }
 }
 
+/// release metadata of the latest version, not every backend has it so errors are only logged
+fn release_info(backend: &dyn Backend, version: &str) -> Option<VersionInfo> {
+    match backend.release_info(version) {
+        Ok(Some(info)) if info.created_at.is_some() || info.release_url.is_some() => Some(info),
+        Ok(_) => None,
+        Err(err) => {
+            debug!(
+                "failed to get release info for {}@{version}: {err:#}",
+                backend.id()
+            );
+            None
+        }
+    }
+}
+
 type OutputVec = Vec<(Arc<dyn Backend>, ToolVersion, String)>;
 
 static AFTER_LONG_HELP: &str = color_print::cstr!(
@@ -140,6 +222,10 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
     Plugin  Requested  Current  Latest
     node    20         20.0.0   20.1.0
 
+    $ <bold>mise outdated ubi:BurntSushi/ripgrep</bold>
+    Tool                    Requested  Current  Latest  Released    Release Notes
+    ubi:BurntSushi/ripgrep  latest     14.0.0   14.1.0  2024-01-06  https://github.com/BurntSushi/ripgrep/releases/tag/14.1.0
+
     $ <bold>mise outdated --json</bold>
     {"python": {"requested": "3.11", "current": "3.11.0", "latest": "3.11.1"}, ...}
 "#
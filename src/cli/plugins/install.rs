@@ -162,6 +162,9 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
 
     # install the node plugin using a specific ref
     $ <bold>mise plugins install node https://github.com/mise-plugins/rtx-nodejs.git#v1.0.0</bold>
+
+    # pin the node plugin to a commit, `mise plugins update` will not move it
+    $ <bold>mise plugins install node https://github.com/mise-plugins/rtx-nodejs.git#1a2b3c4d</bold>
 "#
 );
 
@@ -7,6 +7,7 @@ mod install;
 mod link;
 mod ls;
 mod ls_remote;
+mod outdated;
 mod uninstall;
 mod update;
 
@@ -51,6 +52,7 @@ enum Commands {
     Link(link::PluginsLink),
     Ls(ls::PluginsLs),
     LsRemote(ls_remote::PluginsLsRemote),
+    Outdated(outdated::PluginsOutdated),
     Uninstall(uninstall::PluginsUninstall),
     Update(update::Update),
 }
@@ -62,6 +64,7 @@ impl Commands {
             Self::Link(cmd) => cmd.run(),
             Self::Ls(cmd) => cmd.run(config),
             Self::LsRemote(cmd) => cmd.run(config),
+            Self::Outdated(cmd) => cmd.run(),
             Self::Uninstall(cmd) => cmd.run(),
             Self::Update(cmd) => cmd.run(),
         }
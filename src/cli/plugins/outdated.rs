@@ -0,0 +1,87 @@
+use eyre::Result;
+use rayon::prelude::*;
+use tabled::{Table, Tabled};
+
+use crate::git::Git;
+use crate::plugins::PluginType;
+use crate::ui::table;
+use crate::{backend, dirs};
+
+/// Shows plugins whose git remote has new commits
+///
+/// Plugins installed at a commit, e.g.: `mise plugins install node <url>#<sha>`,
+/// are pinned and compared against the remote HEAD. They are not updated by
+/// `mise plugins update` unless a new ref is given.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct PluginsOutdated {
+    /// Plugin(s) to check, defaults to every installed plugin
+    plugin: Vec<String>,
+}
+
+#[derive(Tabled)]
+#[tabled(rename_all = "PascalCase")]
+struct Row {
+    plugin: String,
+    #[tabled(rename = "Ref")]
+    gitref: String,
+    current: String,
+    remote: String,
+}
+
+impl PluginsOutdated {
+    pub fn run(self) -> Result<()> {
+        let rows = backend::list()
+            .into_par_iter()
+            .filter(|p| p.get_plugin_type() == PluginType::Asdf && p.is_installed())
+            .filter(|p| self.plugin.is_empty() || self.plugin.contains(&p.id().to_string()))
+            .map(|p| {
+                let git = Git::new(dirs::PLUGINS.join(p.id()));
+                let pinned = git.pinned_sha();
+                let gitref = match &pinned {
+                    Some(_) => "HEAD".to_string(),
+                    None => git.current_branch()?,
+                };
+                let current = git.current_sha()?;
+                let remote = git.remote_sha(&gitref)?.unwrap_or_default();
+                if current == remote {
+                    return Ok(None);
+                }
+                Ok(Some(Row {
+                    plugin: p.id().to_string(),
+                    gitref: match pinned {
+                        Some(_) => "pinned".to_string(),
+                        None => gitref,
+                    },
+                    current: short(&current),
+                    remote: short(&remote),
+                }))
+            })
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        if rows.is_empty() {
+            info!("all plugins are up to date");
+            return Ok(());
+        }
+        let mut table = Table::new(rows);
+        table::default_style(&mut table, false);
+        miseprintln!("{table}");
+        Ok(())
+    }
+}
+
+fn short(sha: &str) -> String {
+    sha.chars().take(7).collect()
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise plugins outdated</bold>
+    Plugin  Ref     Current  Remote
+    node    pinned  1a2b3c4  5d6e7f8
+    ruby    master  0a1b2c3  4d5e6f7
+"#
+);
@@ -8,9 +8,11 @@ use crate::backend::Backend;
 use crate::cli::args::BackendArg;
 use crate::config::tracking::Tracker;
 use crate::config::{Config, Settings};
+use crate::file::display_path;
 use crate::toolset::{ToolVersion, Toolset, ToolsetBuilder};
 use crate::ui::multi_progress_report::MultiProgressReport;
 use crate::ui::prompt;
+use crate::{file, store};
 
 use super::trust::Trust;
 
@@ -38,10 +40,17 @@ pub struct Prune {
     /// Prune only unused versions of tools
     #[clap(long)]
     pub tools: bool,
+
+    /// Prune only files in the content store that are no longer used by any install
+    #[clap(long)]
+    pub store: bool,
 }
 
 impl Prune {
     pub fn run(self) -> Result<()> {
+        if self.store {
+            return self.prune_store();
+        }
         if self.configs || !self.tools {
             self.prune_configs()?;
         }
@@ -51,6 +60,23 @@ impl Prune {
         Ok(())
     }
 
+    /// not part of the default prune since the store is what makes reinstalling pruned versions fast
+    fn prune_store(&self) -> Result<()> {
+        for path in store::unused()? {
+            if self.dry_run {
+                info!(
+                    "rm -rf {} {}",
+                    display_path(&path),
+                    style("[dryrun]").bold()
+                );
+            } else {
+                debug!("rm -rf {}", display_path(&path));
+                file::remove_all(&path)?;
+            }
+        }
+        Ok(())
+    }
+
     fn prune_configs(&self) -> Result<()> {
         if self.dry_run {
             info!("pruned configuration links {}", style("[dryrun]").bold());
@@ -110,6 +136,8 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
     $ <bold>mise prune --dry-run</bold>
     rm -rf ~/.local/share/mise/versions/node/20.0.0
     rm -rf ~/.local/share/mise/versions/node/20.0.1
+
+    $ <bold>mise prune --store</bold>
 "#
 );
 
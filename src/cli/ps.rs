@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+use eyre::Result;
+use tabled::{Table, Tabled};
+
+use crate::config::Settings;
+use crate::file::display_path;
+use crate::service::{self, Service};
+use crate::ui::table;
+
+/// [experimental] List running service tasks
+///
+/// Services are tasks with `service = true` that `mise run` keeps running
+/// and restarts when they exit. Stop them with `mise stop`.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct Ps {
+    /// Output in JSON format
+    #[clap(short = 'J', long, verbatim_doc_comment)]
+    pub json: bool,
+}
+
+impl Ps {
+    pub fn run(self) -> Result<()> {
+        Settings::get().ensure_experimental("`mise ps`")?;
+        let services = service::list()?;
+        if self.json {
+            miseprintln!("{}", serde_json::to_string_pretty(&services)?);
+            return Ok(());
+        }
+        if services.is_empty() {
+            info!("no services are running");
+            return Ok(());
+        }
+        let rows = services.iter().map(Row::from).collect::<Vec<_>>();
+        let mut table = Table::new(rows);
+        table::default_style(&mut table, false);
+        miseprintln!("{table}");
+        Ok(())
+    }
+}
+
+#[derive(Tabled)]
+#[tabled(rename_all = "PascalCase")]
+struct Row {
+    name: String,
+    pid: String,
+    status: String,
+    uptime: String,
+    restarts: u32,
+    #[tabled(rename = "Config Source")]
+    config_source: String,
+}
+
+impl From<&Service> for Row {
+    fn from(service: &Service) -> Self {
+        let running = service.is_running();
+        let uptime = (chrono::Utc::now().timestamp() - service.started_at).max(0) as u64;
+        Self {
+            name: service.name.clone(),
+            pid: service.pid.map(|p| p.to_string()).unwrap_or_default(),
+            status: match running {
+                true => "running".to_string(),
+                false => "restarting".to_string(),
+            },
+            uptime: match running {
+                true => humantime::format_duration(Duration::from_secs(uptime)).to_string(),
+                false => String::new(),
+            },
+            restarts: service.restarts,
+            config_source: display_path(&service.config_source),
+        }
+    }
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise ps</bold>
+    Name     Pid    Status   Uptime  Restarts  Config Source
+    dev:api  41822  running  12m 3s  0         ~/src/app/.mise.toml
+    dev:web  41830  running  2m 41s  1         ~/src/app/.mise.toml
+"#
+);
@@ -0,0 +1,274 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use clap::ValueHint;
+use eyre::Result;
+use indexmap::IndexMap;
+use itertools::Itertools;
+use serde_derive::Serialize;
+
+use crate::cli::version::{ARCH, OS, VERSION};
+use crate::config::Config;
+use crate::file;
+use crate::file::display_path;
+use crate::toolset::ToolsetBuilder;
+
+/// Generate a report of the tools, config files, and tasks on this machine
+///
+/// The report is only written locally, nothing is sent anywhere. Platform teams
+/// can ask developers to attach it when standardizing toolchains.
+/// Paths inside of the home directory are shown relative to `~`.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct Report {
+    /// Format of the report
+    #[clap(long, short, value_enum, default_value_t)]
+    format: ReportFormat,
+
+    /// Write the report to this file instead of stdout
+    #[clap(long, short, value_hint = ValueHint::FilePath)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ReportFormat {
+    #[default]
+    Json,
+    Html,
+}
+
+#[derive(Debug, Serialize)]
+struct ReportData {
+    mise_version: String,
+    os: String,
+    arch: String,
+    generated_at: String,
+    config_files: Vec<String>,
+    tools: Vec<ReportTool>,
+    installed: BTreeMap<String, Vec<String>>,
+    tasks: Vec<ReportTask>,
+}
+
+#[derive(Debug, Serialize)]
+struct ReportTool {
+    tool: String,
+    requested: String,
+    version: String,
+    installed: bool,
+    source: IndexMap<String, String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ReportTask {
+    name: String,
+    description: String,
+    source: String,
+}
+
+impl Report {
+    pub fn run(self) -> Result<()> {
+        let report = self.collect()?;
+        let out = match self.format {
+            ReportFormat::Json => serde_json::to_string_pretty(&report)?,
+            ReportFormat::Html => render_html(&report)?,
+        };
+        match &self.output {
+            Some(output) => {
+                file::write(output, out)?;
+                info!("report written to {}", display_path(output));
+            }
+            None => miseprintln!("{out}"),
+        }
+        Ok(())
+    }
+
+    fn collect(&self) -> Result<ReportData> {
+        let config = Config::try_get()?;
+        let ts = ToolsetBuilder::new().build(&config)?;
+        let tools = ts
+            .list_current_versions()
+            .into_iter()
+            .map(|(p, tv)| ReportTool {
+                tool: p.id().to_string(),
+                requested: tv.request.version(),
+                installed: p.is_version_installed(&tv),
+                source: ts
+                    .versions
+                    .get(&tv.backend)
+                    .map(|tvl| source_json(tvl.source.as_json()))
+                    .unwrap_or_default(),
+                version: tv.version,
+            })
+            .collect();
+        let installed = ts
+            .list_installed_versions()?
+            .into_iter()
+            .map(|(p, tv)| (p.id().to_string(), tv.version))
+            .into_group_map()
+            .into_iter()
+            .collect();
+        let tasks = config
+            .tasks()?
+            .values()
+            .filter(|t| !t.hide)
+            .map(|t| ReportTask {
+                name: t.name.clone(),
+                description: t.description.clone(),
+                source: display_path(&t.config_source),
+            })
+            .collect();
+        Ok(ReportData {
+            mise_version: VERSION.to_string(),
+            os: OS.to_string(),
+            arch: ARCH.to_string(),
+            generated_at: chrono::Utc::now().to_rfc3339(),
+            config_files: config.config_files.keys().map(display_path).collect(),
+            tools,
+            installed,
+            tasks,
+        })
+    }
+}
+
+/// replaces absolute paths with ones relative to the home directory
+fn source_json(mut source: IndexMap<String, String>) -> IndexMap<String, String> {
+    if let Some(path) = source.get_mut("path") {
+        *path = display_path(&*path);
+    }
+    source
+}
+
+fn render_html(report: &ReportData) -> Result<String> {
+    let mut s = String::new();
+    writeln!(s, "<!DOCTYPE html>")?;
+    writeln!(
+        s,
+        "<html><head><meta charset=\"utf-8\"><title>mise report</title>"
+    )?;
+    writeln!(
+        s,
+        "<style>body{{font-family:sans-serif}}table{{border-collapse:collapse}}td,th{{border:1px solid #ccc;padding:4px 8px;text-align:left}}</style>"
+    )?;
+    writeln!(s, "</head><body>")?;
+    writeln!(s, "<h1>mise report</h1>")?;
+    writeln!(
+        s,
+        "<p>mise {} {}-{} generated at {}</p>",
+        escape(&report.mise_version),
+        escape(&report.os),
+        escape(&report.arch),
+        escape(&report.generated_at)
+    )?;
+
+    writeln!(s, "<h2>Tools</h2>")?;
+    let rows = report.tools.iter().map(|t| {
+        let source = t.source.get("path").or_else(|| t.source.get("type"));
+        vec![
+            t.tool.clone(),
+            t.requested.clone(),
+            t.version.clone(),
+            if t.installed { "yes" } else { "no" }.to_string(),
+            source.cloned().unwrap_or_default(),
+        ]
+    });
+    html_table(
+        &mut s,
+        &["Tool", "Requested", "Version", "Installed", "Source"],
+        rows,
+    )?;
+
+    writeln!(s, "<h2>Installed Versions</h2>")?;
+    let rows = report
+        .installed
+        .iter()
+        .map(|(tool, versions)| vec![tool.clone(), versions.join(", ")]);
+    html_table(&mut s, &["Tool", "Versions"], rows)?;
+
+    writeln!(s, "<h2>Config Files</h2>")?;
+    let rows = report.config_files.iter().map(|f| vec![f.clone()]);
+    html_table(&mut s, &["Path"], rows)?;
+
+    writeln!(s, "<h2>Tasks</h2>")?;
+    let rows = report
+        .tasks
+        .iter()
+        .map(|t| vec![t.name.clone(), t.description.clone(), t.source.clone()]);
+    html_table(&mut s, &["Name", "Description", "Source"], rows)?;
+
+    writeln!(s, "</body></html>")?;
+    Ok(s)
+}
+
+fn html_table(
+    s: &mut String,
+    headers: &[&str],
+    rows: impl Iterator<Item = Vec<String>>,
+) -> Result<()> {
+    writeln!(s, "<table>")?;
+    writeln!(
+        s,
+        "<tr>{}</tr>",
+        headers.iter().map(|h| format!("<th>{h}</th>")).join("")
+    )?;
+    for row in rows {
+        writeln!(
+            s,
+            "<tr>{}</tr>",
+            row.iter()
+                .map(|c| format!("<td>{}</td>", escape(c)))
+                .join("")
+        )?;
+    }
+    writeln!(s, "</table>")?;
+    Ok(())
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise report</bold>
+    {
+      "mise_version": "2024.5.0 macos-arm64 (2024-05-01)",
+      "os": "macos",
+      ...
+    }
+
+    $ <bold>mise report --format html --output mise-report.html</bold>
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::test::reset;
+
+    use super::escape;
+
+    #[test]
+    fn test_report() {
+        reset();
+        let out = assert_cli!("report");
+        let report: serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert!(report["tools"].as_array().is_some());
+        assert!(report["config_files"].as_array().is_some());
+        let out = assert_cli!("report", "--format", "html");
+        assert!(out.contains("<h2>Tools</h2>"));
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape("<a href=\"x\">&</a>"),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;"
+        );
+    }
+}
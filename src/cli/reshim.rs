@@ -11,6 +11,8 @@ use crate::toolset::ToolsetBuilder;
 /// other ways to install things (like using yarn or pnpm for node) that mise does
 /// not know about and so it will be necessary to call this explicitly.
 ///
+/// Only shims that were added or removed since the last reshim are changed.
+///
 /// If you think mise should automatically call this for a particular command, please
 /// open an issue on the mise repo. You can also setup a shell function to reshim
 /// automatically (it's really fast so you don't need to worry about overhead):
@@ -26,6 +28,11 @@ pub struct Reshim {
     pub plugin: Option<String>,
     #[clap(hide = true)]
     pub version: Option<String>,
+
+    /// Removes all shims before reshimming
+    /// By default only shims that were added or removed are changed
+    #[clap(long, short, verbatim_doc_comment)]
+    pub force: bool,
 }
 
 impl Reshim {
@@ -33,6 +40,9 @@ impl Reshim {
         let config = Config::try_get()?;
         let ts = ToolsetBuilder::new().build(&config)?;
 
+        if self.force {
+            shims::clear()?;
+        }
         shims::reshim(&ts)
     }
 }
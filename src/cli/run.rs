@@ -1,13 +1,14 @@
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::io::Write;
 use std::iter::once;
 #[cfg(unix)]
 use std::os::unix::prelude::*;
 use std::path::{Path, PathBuf};
 use std::process::{exit, Stdio};
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Mutex;
-use std::time::SystemTime;
+use std::thread;
+use std::time::{Duration, Instant};
 
 use clap::ValueHint;
 use console::Color;
@@ -15,19 +16,20 @@ use demand::{DemandOption, Select};
 use duct::IntoExecutablePath;
 use either::Either;
 use eyre::{bail, ensure, eyre, Result};
-use globwalk::GlobWalkerBuilder;
 use itertools::Itertools;
 use once_cell::sync::Lazy;
 
-use crate::cmd::CmdLineRunner;
+use crate::cli::Cli;
+use crate::cmd::{CmdLineRunner, OutputMode};
 use crate::config::{Config, Settings};
 use crate::errors::Error;
-use crate::errors::Error::ScriptFailed;
+use crate::errors::Error::{ScriptFailed, ScriptTimedOut};
 use crate::file::display_path;
-use crate::task::{Deps, GetMatchingExt, Task};
-use crate::toolset::{InstallOptions, ToolsetBuilder};
-use crate::ui::{ctrlc, style};
-use crate::{env, file, ui};
+use crate::service::{self, Service};
+use crate::task::{Deps, GetMatchingExt, RunEntry, Task};
+use crate::toolset::{InstallOptions, Toolset, ToolsetBuilder};
+use crate::ui::{ctrlc, prompt, style};
+use crate::{env, file, hermetic, msys, task_cache, task_container, task_usage, ui};
 
 use super::args::ToolArg;
 
@@ -56,7 +58,7 @@ use super::args::ToolArg;
 ///     EOF
 ///     $ mise run build
 #[derive(Debug, clap::Args)]
-#[clap(visible_alias = "r", verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+#[clap(visible_alias = "r", verbatim_doc_comment, disable_help_flag = true, after_long_help = AFTER_LONG_HELP)]
 pub struct Run {
     /// Tasks to run
     /// Can specify multiple tasks by separating with `:::`
@@ -83,15 +85,24 @@ pub struct Run {
     /// Print stdout/stderr by line, prefixed with the tasks's label
     /// Defaults to true if --jobs > 1
     /// Configure with `task_output` config or `MISE_TASK_OUTPUT` env var
-    #[clap(long, short, verbatim_doc_comment, overrides_with = "interleave")]
+    #[clap(long, short, verbatim_doc_comment, overrides_with_all = ["interleave", "output"])]
     pub prefix: bool,
 
     /// Print directly to stdout/stderr instead of by line
     /// Defaults to true if --jobs == 1
     /// Configure with `task_output` config or `MISE_TASK_OUTPUT` env var
-    #[clap(long, short, verbatim_doc_comment, overrides_with = "prefix")]
+    #[clap(long, short, verbatim_doc_comment, overrides_with_all = ["prefix", "output"])]
     pub interleave: bool,
 
+    /// How to print the output of tasks
+    /// prefix: by line, prefixed with the task's label
+    /// interleave: directly to stdout/stderr
+    /// group: prefixed, all at once when each task finishes
+    /// quiet: only the output of tasks that fail
+    /// Configure with `task_output` config or `MISE_TASK_OUTPUT` env var
+    #[clap(long, short, verbatim_doc_comment, value_name = "MODE", value_parser = ["prefix", "interleave", "group", "quiet"], overrides_with_all = ["prefix", "interleave"])]
+    pub output: Option<String>,
+
     /// Tool(s) to also add
     /// e.g.: node@20 python@3.10
     #[clap(short, long, value_name = "TOOL@VERSION")]
@@ -103,6 +114,19 @@ pub struct Run {
     #[clap(long, short, env = "MISE_JOBS", verbatim_doc_comment)]
     pub jobs: Option<usize>,
 
+    /// Stop running tasks as soon as one fails, this is the default
+    #[clap(long, verbatim_doc_comment, overrides_with = "no_fail_fast")]
+    pub fail_fast: bool,
+
+    /// Keep running tasks that don't depend on a failed task
+    /// The tasks that depend on it are skipped
+    #[clap(long, verbatim_doc_comment, overrides_with = "fail_fast")]
+    pub no_fail_fast: bool,
+
+    /// Keep running every task after one fails, including tasks that depend on it
+    #[clap(long, verbatim_doc_comment)]
+    pub continue_on_error: bool,
+
     /// Read/write directly to stdin/stdout/stderr instead of by line
     /// Configure with `raw` config or `MISE_RAW` env var
     #[clap(long, short, verbatim_doc_comment)]
@@ -112,8 +136,30 @@ pub struct Run {
     #[clap(long, alias = "timing", verbatim_doc_comment)]
     pub timings: bool,
 
+    /// Only put tools managed by mise and commands in `hermetic_allow` on PATH
+    /// Configure with `hermetic` config or `MISE_HERMETIC` env var
+    #[clap(long, verbatim_doc_comment)]
+    pub hermetic: bool,
+
+    /// Print help, or the help of the task if it declares `usage`
+    #[clap(short, long, verbatim_doc_comment)]
+    pub help: bool,
+
     #[clap(skip)]
     pub is_linear: bool,
+
+    /// the tools of the project and the versions they resolved to, `matrix` values of these
+    /// select the tool's version
+    #[clap(skip)]
+    pub tools: BTreeMap<String, String>,
+
+    /// values entered for the `prompt` of each task
+    #[clap(skip)]
+    pub inputs: HashMap<String, BTreeMap<String, String>>,
+
+    /// when each running task with a `timeout` has to be done
+    #[clap(skip)]
+    pub deadlines: Mutex<HashMap<String, Instant>>,
 }
 
 impl Run {
@@ -121,10 +167,26 @@ impl Run {
         let config = Config::try_get()?;
         let settings = Settings::try_get()?;
         settings.ensure_experimental("`mise run`")?;
+        if self.help {
+            return self.print_help(&config);
+        }
         let task_list = self.get_task_lists(&config)?;
         self.parallelize_tasks(&config, task_list)
     }
 
+    fn print_help(&self, config: &Config) -> Result<()> {
+        if let Some(task) = config.tasks_with_aliases()?.get(&self.task) {
+            if task_usage::print_help(task)? {
+                return Ok(());
+            }
+        }
+        let mut cmd = Cli::command();
+        if let Some(run) = cmd.find_subcommand_mut("run") {
+            run.print_long_help()?;
+        }
+        Ok(())
+    }
+
     fn get_task_lists(&self, config: &Config) -> Result<Vec<Task>> {
         once(&self.task)
             .chain(self.args.iter())
@@ -151,10 +213,15 @@ impl Run {
 
                     Ok(vec![self.prompt_for_task(config)?])
                 } else {
-                    Ok(tasks
+                    let tasks = tasks
                         .into_iter()
                         .map(|t| t.clone().with_args(args.to_vec()))
-                        .collect())
+                        .collect_vec();
+                    // invalid args are reported before any task starts
+                    for t in &tasks {
+                        task_usage::values(t)?;
+                    }
+                    Ok(tasks)
                 }
             })
             .flatten_ok()
@@ -162,29 +229,41 @@ impl Run {
     }
 
     fn parallelize_tasks(mut self, config: &Config, tasks: Vec<Task>) -> Result<()> {
-        let mut ts = ToolsetBuilder::new().with_args(&self.tool).build(config)?;
-
-        ts.install_arg_versions(config, &InstallOptions::new())?;
-        ts.notify_if_versions_missing();
-        let mut env = ts.env_with_path(config)?;
-        if let Some(root) = &config.project_root {
-            env.insert("MISE_PROJECT_ROOT".into(), root.display().to_string());
-            env.insert("root".into(), root.display().to_string());
-        }
+        let ts = self.toolset(config, &[])?;
+        let env = self.toolset_env(config, &ts)?;
+        self.tools = resolved_versions(&ts);
 
         let tasks = Deps::new(config, tasks)?;
+        // everything is asked before the first task starts
+        let mut inputs = HashMap::new();
         for task in tasks.all() {
             self.validate_task(task)?;
+            inputs.insert(task.name.clone(), self.ask(task, &env)?);
         }
+        self.inputs = inputs;
 
         let num_tasks = tasks.all().count();
+        let num_services = tasks.all().filter(|t| t.service).count();
+        if num_services > 0 {
+            service::handle_shutdown()?;
+        }
         self.is_linear = tasks.is_linear();
 
+        // failures are reported in the order the tasks were resolved so the exit code doesn't
+        // depend on which task finished first
+        let order: HashMap<String, usize> = tasks
+            .all()
+            .enumerate()
+            .map(|(i, t)| (t.name.clone(), i))
+            .collect();
+        let failures = Mutex::new(vec![]);
+        let combinations = Mutex::new(vec![]);
         let tasks = Mutex::new(tasks);
         let timer = std::time::Instant::now();
 
         let pool = rayon::ThreadPoolBuilder::new()
-            .num_threads(self.jobs() + 1)
+            // services run until they are stopped so they don't take up a job
+            .num_threads(self.jobs() + 1 + num_services)
             .build()?;
         pool.scope(|s| {
             let run = |task: &Task| {
@@ -192,12 +271,36 @@ impl Run {
                 s.spawn(|_| {
                     let task = t;
                     trace!("running tasks: {task}");
-                    if let Err(err) = self.run_task(config, &env, &task) {
-                        error!("{err}");
-                        exit(1);
+                    let result = match task.service {
+                        // tasks that depend on a service start once it is ready
+                        true => self.run_service(config, &env, &task, &|| {
+                            tasks.lock().unwrap().remove(&task);
+                        }),
+                        false => self.run_task(config, &env, &task),
+                    };
+                    if !task.combination.is_empty() {
+                        let i = order.get(&task.name).copied().unwrap_or_default();
+                        combinations
+                            .lock()
+                            .unwrap()
+                            .push((i, task.name.clone(), result.is_ok()));
                     }
                     let mut tasks = tasks.lock().unwrap();
-                    tasks.remove(&task);
+                    match result {
+                        Err(err) if self.fail_fast() => exit_with_error(&task.prefix(), err),
+                        Err(err) => {
+                            if self.continue_on_error {
+                                tasks.remove(&task);
+                            } else {
+                                for t in tasks.remove_with_dependents(&task) {
+                                    warn!("{} skipped, it depends on {}", t.prefix(), task.name);
+                                }
+                            }
+                            let i = order.get(&task.name).copied().unwrap_or_default();
+                            failures.lock().unwrap().push((i, task.prefix(), err));
+                        }
+                        Ok(()) => tasks.remove(&task),
+                    }
                 });
             };
             let rx = tasks.lock().unwrap().subscribe();
@@ -211,46 +314,85 @@ impl Run {
             info!("{}", style::edim(msg));
         };
 
+        let combinations = combinations.into_inner().unwrap();
+        if combinations.len() > 1 {
+            let results = combinations
+                .into_iter()
+                .sorted_by_key(|(i, _, _)| *i)
+                .map(|(_, name, ok)| match ok {
+                    true => format!("{} {name}", style::egreen("✓")),
+                    false => format!("{} {name}", style::ered("✗")),
+                })
+                .join("\n");
+            miseprintln!("{results}");
+        }
+
+        let mut failures = failures
+            .into_inner()
+            .unwrap()
+            .into_iter()
+            .sorted_by_key(|(i, _, _)| *i);
+        if let Some((_, prefix, err)) = failures.next() {
+            let code = report_error(&prefix, &err);
+            for (_, prefix, err) in failures {
+                report_error(&prefix, &err);
+            }
+            exit(code);
+        }
+
         Ok(())
     }
 
     fn run_task(&self, config: &Config, env: &BTreeMap<String, String>, task: &Task) -> Result<()> {
         let prefix = style::estyle(task.prefix()).fg(get_color()).to_string();
-        if !self.force && self.sources_are_fresh(config, task) {
+        let tools = match task.sources.is_empty() || task.outputs.is_empty() {
+            true => BTreeMap::new(),
+            false => self.task_versions(config, task)?,
+        };
+        if !self.force && self.sources_are_fresh(config, task, &tools) {
             info_unprefix_trunc!("{prefix} sources up-to-date, skipping");
             return Ok(());
         }
+        if !self.force && self.restore_outputs(config, task, &tools) {
+            info_unprefix_trunc!("{prefix} outputs restored from the task cache, skipping");
+            return Ok(());
+        }
 
-        let string_env = task.env.iter().filter_map(|(k, v)| match &v.0 {
-            Either::Left(v) => Some((k, v)),
-            _ => None,
-        });
-        let rm_env = task
-            .env
-            .iter()
-            .filter(|(_, v)| v.0 == Either::Right(false))
-            .map(|(k, _)| k)
-            .collect::<HashSet<_>>();
-        let env: BTreeMap<String, String> = env
-            .iter()
-            .chain(string_env)
-            .filter(|(k, _)| !rm_env.contains(k))
-            .map(|(k, v)| (k.clone(), v.clone()))
-            .collect();
-
+        let env = self.run_env(config, env, task)?;
         let timer = std::time::Instant::now();
 
-        if let Some(file) = &task.file {
-            self.exec_file(file, task, &env, &prefix)?;
-        } else {
-            for (i, cmd) in task.run.iter().enumerate() {
-                let args = match i == task.run.len() - 1 {
-                    true => task.args.iter().cloned().collect_vec(),
-                    false => vec![],
-                };
-                self.exec_script(cmd, &args, task, &env, &prefix)?;
+        let timeout = task.timeout()?;
+        let retry = task.retry.clone().unwrap_or_default();
+        let delay = retry.delay()?;
+        let mut attempt = 0;
+        let result = loop {
+            if let Some(timeout) = timeout {
+                let deadline = Instant::now() + timeout;
+                self.deadlines
+                    .lock()
+                    .unwrap()
+                    .insert(task.name.clone(), deadline);
             }
-        }
+            match self.run_steps(config, &env, task, &prefix, None) {
+                Err(err) if attempt < retry.count => {
+                    attempt += 1;
+                    warn!(
+                        "{prefix} {err}, retrying in {} ({attempt}/{})",
+                        humantime::format_duration(delay),
+                        retry.count
+                    );
+                    thread::sleep(delay);
+                }
+                result => break result,
+            }
+        };
+        // commands of later steps get what is left of the timeout, it is reported as a whole
+        result.map_err(|err| match (err.downcast_ref::<Error>(), timeout) {
+            (Some(ScriptTimedOut(program, _)), Some(timeout)) => {
+                ScriptTimedOut(program.clone(), timeout).into()
+            }
+            _ => err,
+        })?;
 
         if self.timings {
             miseprintln!(
@@ -260,11 +402,183 @@ impl Run {
             );
         }
 
-        self.save_checksum(task)?;
+        task_cache::save(task, &self.cwd(config, task), &tools)?;
+        if let Err(err) = task_cache::upload(task, &self.cwd(config, task), &tools) {
+            warn!(
+                "failed to upload outputs of {} to the task cache: {err:#}",
+                task.name
+            );
+        }
 
         Ok(())
     }
 
+    fn run_steps(
+        &self,
+        config: &Config,
+        env: &BTreeMap<String, String>,
+        task: &Task,
+        prefix: &str,
+        service: Option<&Mutex<Service>>,
+    ) -> Result<()> {
+        if let Some(file) = &task.file {
+            self.exec_file(file, task, env, prefix, service)?;
+        } else {
+            let mut failure = None;
+            for (i, entry) in task.run.iter().enumerate() {
+                if !entry.should_run(failure.is_some()) {
+                    continue;
+                }
+                // args of tasks that declare `usage` are only passed through `usage_*` env vars
+                // and templates
+                let args = match i == task.run.len() - 1 && task.usage.trim().is_empty() {
+                    true => task.args.iter().cloned().collect_vec(),
+                    false => vec![],
+                };
+                let result = match entry {
+                    RunEntry::Script(script)
+                    | RunEntry::Step {
+                        run: Some(script), ..
+                    } => task_usage::render(task, script).and_then(|script| {
+                        self.exec_script(&script, &args, task, env, prefix, service)
+                    }),
+                    RunEntry::Step { task: Some(t), .. } => {
+                        self.run_step_task(config, env, task, t)
+                    }
+                    RunEntry::Step { .. } => {
+                        Err(eyre!("run steps must have either `run` or `task`"))
+                    }
+                };
+                if let Err(err) = result {
+                    if failure.is_some() {
+                        error!("{err}");
+                    } else {
+                        failure = Some(err);
+                    }
+                }
+            }
+            // the task fails even if steps with `if = "failure"` ran
+            if let Some(err) = failure {
+                return Err(err);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// runs a service until it is stopped, starting it again when it exits based on `restart`
+    fn run_service(
+        &self,
+        config: &Config,
+        env: &BTreeMap<String, String>,
+        task: &Task,
+        on_ready: &(dyn Fn() + Sync),
+    ) -> Result<()> {
+        let prefix = style::estyle(task.prefix()).fg(get_color()).to_string();
+        let env = self.run_env(config, env, task)?;
+        if self.dry_run {
+            return self.run_steps(config, &env, task, &prefix, None);
+        }
+        let service = Mutex::new(Service::new(task));
+        service.lock().unwrap().save()?;
+        let ready = AtomicBool::new(task.ready.is_none());
+        let exited = AtomicBool::new(false);
+        let result = thread::scope(|s| {
+            match &task.ready {
+                Some(check) => {
+                    s.spawn(|| {
+                        if self.wait_until_ready(config, task, &env, check, &exited) {
+                            info_unprefix_trunc!("{prefix} ready");
+                            ready.store(true, Ordering::Relaxed);
+                            on_ready();
+                        }
+                    });
+                }
+                None => on_ready(),
+            }
+            let result = loop {
+                let result = self.run_steps(config, &env, task, &prefix, Some(&service));
+                let mut service = service.lock().unwrap();
+                if service.is_stopping() {
+                    break Ok(());
+                }
+                if !task.restart.should_restart(result.is_err()) {
+                    break result;
+                }
+                if let Err(err) = &result {
+                    warn!("{prefix} {err}");
+                }
+                service.restarts += 1;
+                info_unprefix_trunc!("{prefix} restarting (restarts: {})", service.restarts);
+                if let Err(err) = service.save() {
+                    break Err(err);
+                }
+                drop(service);
+                thread::sleep(Duration::from_secs(1));
+            };
+            exited.store(true, Ordering::Relaxed);
+            result
+        });
+        let service = service.into_inner().unwrap();
+        let stopped = service.is_stopping();
+        service.remove()?;
+        result?;
+        ensure!(
+            stopped || ready.load(Ordering::Relaxed),
+            "{} exited before it was ready",
+            style::ered(&task.name)
+        );
+        Ok(())
+    }
+
+    /// runs the `ready` command until it succeeds, returns false if the service exited first
+    fn wait_until_ready(
+        &self,
+        config: &Config,
+        task: &Task,
+        env: &BTreeMap<String, String>,
+        check: &str,
+        exited: &AtomicBool,
+    ) -> bool {
+        let dir = self.cwd(config, task);
+        while !exited.load(Ordering::Relaxed) {
+            let status = cmd!("sh", "-c", check)
+                .dir(&dir)
+                .full_env(env)
+                .stdout_null()
+                .stderr_null()
+                .unchecked()
+                .run();
+            if status.is_ok_and(|o| o.status.success()) {
+                return true;
+            }
+            thread::sleep(Duration::from_millis(500));
+        }
+        false
+    }
+
+    /// runs another task as a step of this one, e.g.: `{ task = "deploy", if = "success" }`
+    fn run_step_task(
+        &self,
+        config: &Config,
+        env: &BTreeMap<String, String>,
+        parent: &Task,
+        name: &str,
+    ) -> Result<()> {
+        let tasks = config.tasks_with_aliases()?;
+        if let Some(cycle) = step_cycle(&tasks, &mut vec![parent.name.clone()]) {
+            bail!(
+                "{} runs itself through its steps: {}",
+                style::ered(&parent.name),
+                cycle.join(" -> ")
+            );
+        }
+        let task = tasks
+            .get(name)
+            .ok_or_else(|| eyre!("no task {} found", style::ered(name)))?;
+        self.run_task(config, env, task)
+    }
+
     fn exec_script(
         &self,
         script: &str,
@@ -272,6 +586,7 @@ impl Run {
         task: &Task,
         env: &BTreeMap<String, String>,
         prefix: &str,
+        service: Option<&Mutex<Service>>,
     ) -> Result<()> {
         let script = script.trim_start();
         let cmd = style::ebold(format!("$ {script}")).bright().to_string();
@@ -286,11 +601,11 @@ impl Run {
             drop(tmp);
             file::make_executable(&file)?;
             let filename = file.display().to_string();
-            self.exec(&filename, args, task, env, prefix)
+            self.exec(&filename, args, task, env, prefix, service)
         } else {
             let script = format!("{} {}", script, shell_words::join(args));
             let args = vec!["-c".to_string(), script];
-            self.exec("sh", &args, task, env, prefix)
+            self.exec("sh", &args, task, env, prefix, service)
         }
     }
 
@@ -300,6 +615,7 @@ impl Run {
         task: &Task,
         env: &BTreeMap<String, String>,
         prefix: &str,
+        service: Option<&Mutex<Service>>,
     ) -> Result<()> {
         let command = file.to_string_lossy().to_string();
         let args = task.args.iter().cloned().collect_vec();
@@ -308,7 +624,7 @@ impl Run {
         let cmd = style::ebold(format!("$ {cmd}")).bright().to_string();
         info_unprefix_trunc!("{prefix} {cmd}");
 
-        self.exec(&command, &args, task, env, prefix)
+        self.exec(&command, &args, task, env, prefix, service)
     }
 
     fn exec(
@@ -318,10 +634,28 @@ impl Run {
         task: &Task,
         env: &BTreeMap<String, String>,
         prefix: &str,
+        service: Option<&Mutex<Service>>,
     ) -> Result<()> {
+        let (program, args) = match &task.container {
+            Some(image) => self.container_command(image, program, args, task, env)?,
+            None => (program.to_string(), args.to_vec()),
+        };
         let program = program.to_executable();
-        let mut cmd = CmdLineRunner::new(program.clone()).args(args).envs(env);
+        let mut cmd = if msys::enabled(task.translate_paths) {
+            let args = args.iter().map(|a| msys::to_posix(a));
+            let env = env.iter().map(|(k, v)| (k, msys::env_to_posix(k, v)));
+            CmdLineRunner::new(program.clone()).args(args).envs(env)
+        } else {
+            CmdLineRunner::new(program.clone()).args(args).envs(env)
+        };
         cmd.with_pass_signals();
+        if let Some(service) = service {
+            cmd = cmd.on_spawn(move |pid| {
+                if let Err(err) = service.lock().unwrap().started(pid) {
+                    warn!("{prefix} failed to save service state: {err:#}");
+                }
+            });
+        }
         match &self.output(task)? {
             TaskOutput::Prefix => cmd = cmd.prefix(format!("{prefix} ")),
             TaskOutput::Interleave => {
@@ -330,55 +664,227 @@ impl Run {
                     .stdout(Stdio::inherit())
                     .stderr(Stdio::inherit())
             }
+            TaskOutput::Group => {
+                cmd = cmd.prefix(format!("{prefix} "));
+                cmd.with_output(OutputMode::Group);
+            }
+            TaskOutput::Quiet => {
+                cmd = cmd.prefix(format!("{prefix} "));
+                cmd.with_output(OutputMode::Quiet);
+            }
         }
         if self.raw(task) {
             cmd.with_raw();
         }
+        if let Some(deadline) = self.deadlines.lock().unwrap().get(&task.name) {
+            cmd.with_timeout(deadline.saturating_duration_since(Instant::now()));
+        }
         if let Some(cd) = &self.cd.as_ref().or(task.dir.as_ref()) {
             cmd = cmd.current_dir(cd);
         }
         if self.dry_run {
             return Ok(());
         }
+        // task files and shebang scripts are run by path, their interpreter has to be on PATH
+        if self.hermetic() && task.container.is_none() && !Path::new(&program).is_absolute() {
+            hermetic::ensure_available(&program.to_string_lossy(), env)?;
+        }
         if let Err(err) = cmd.execute() {
             if let Some(ScriptFailed(_, Some(status))) = err.downcast_ref::<Error>() {
-                if let Some(code) = status.code() {
-                    error!("{prefix} exited with code {code}");
-                    exit(code);
-                } else {
-                    #[cfg(unix)]
-                    if let Some(signal) = status.signal() {
-                        error!("{prefix} killed by signal {signal}");
-                        exit(1);
-                    }
+                // 127 is the exit code from sh when a command is not found
+                if self.hermetic() && status.code() == Some(127) {
+                    return Err(err.wrap_err(hermetic::report_script(&task.name)));
                 }
             }
-            error!("{err}");
-            exit(1);
+            return Err(err);
         }
         trace!("{prefix} exited successfully");
         Ok(())
     }
 
+    /// the env of the task with the values entered for its `prompt`, see `task_env`
+    fn run_env(
+        &self,
+        config: &Config,
+        env: &BTreeMap<String, String>,
+        task: &Task,
+    ) -> Result<BTreeMap<String, String>> {
+        let mut env = match task.tools.is_empty() && task.combination.is_empty() {
+            true => task_env(env, task)?,
+            false => task_env(&self.tools_env(config, task)?, task)?,
+        };
+        // tasks run as a step of another task are asked when they start
+        let inputs = match self.inputs.get(&task.name) {
+            Some(inputs) => inputs.clone(),
+            None => self.ask(task, &env)?,
+        };
+        env.extend(inputs);
+        Ok(env)
+    }
+
+    /// asks to confirm the task if it has `confirm` and for the env vars of its `prompt` that
+    /// aren't set, with `--yes` the defaults of the prompts are used instead
+    fn ask(&self, task: &Task, env: &BTreeMap<String, String>) -> Result<BTreeMap<String, String>> {
+        let mut values = BTreeMap::new();
+        if self.dry_run {
+            return Ok(values);
+        }
+        let yes = Settings::get().yes;
+        if let Some(message) = &task.confirm {
+            if !yes && !ui::confirm(format!("{} {message}", task.prefix()))? {
+                bail!(
+                    "{} was not confirmed, use --yes to run it without asking",
+                    task.name
+                );
+            }
+        }
+        for (key, prompt) in &task.prompt {
+            if env.contains_key(key) || task.env.contains_key(key) || env::var(key).is_ok() {
+                continue;
+            }
+            let value = match yes {
+                true => None,
+                false => prompt::input(prompt.message(), prompt.default())?,
+            };
+            let value = value
+                .filter(|v| !v.is_empty())
+                .or_else(|| prompt.default().map(String::from))
+                .ok_or_else(|| eyre!("{} needs a value for {key}, set it in the env", task.name))?;
+            values.insert(key.clone(), value);
+        }
+        Ok(values)
+    }
+
+    /// the project's toolset with `--tool` and `tools` added, missing versions are installed
+    /// based on `auto_install`
+    fn toolset(&self, config: &Config, tools: &[ToolArg]) -> Result<Toolset> {
+        let tools = self.tool.iter().chain(tools).cloned().collect_vec();
+        let mut ts = ToolsetBuilder::new().with_args(&tools).build(config)?;
+        let mode = Settings::get().auto_install.run;
+        ts.auto_install_arg_versions(config, mode, &InstallOptions::new())?;
+        ts.notify_if_versions_missing();
+        Ok(ts)
+    }
+
+    fn toolset_env(&self, config: &Config, ts: &Toolset) -> Result<BTreeMap<String, String>> {
+        let mut env = ts.env_with_path(config)?;
+        if let Some(root) = &config.project_root {
+            env.insert("MISE_PROJECT_ROOT".into(), root.display().to_string());
+            env.insert("root".into(), root.display().to_string());
+        }
+        if self.hermetic() {
+            hermetic::apply(config, ts, &mut env)?;
+        }
+        Ok(env)
+    }
+
+    /// the env of a task with its own `tools` or that was expanded from a matrix, values of matrix
+    /// keys that are tools select the version of that tool and every value is set as
+    /// `MISE_MATRIX_<KEY>`
+    fn tools_env(&self, config: &Config, task: &Task) -> Result<BTreeMap<String, String>> {
+        let ts = self.toolset(config, &self.task_tools(task)?)?;
+        let mut env = self.toolset_env(config, &ts)?;
+        for (k, v) in &task.combination {
+            let k = k
+                .to_uppercase()
+                .replace(|c: char| !c.is_ascii_alphanumeric(), "_");
+            env.insert(format!("MISE_MATRIX_{k}"), v.clone());
+        }
+        Ok(env)
+    }
+
+    /// the versions of the task's own `tools` and of tools selected by its matrix combination
+    fn task_tools(&self, task: &Task) -> Result<Vec<ToolArg>> {
+        let mut versions = task.tools.clone();
+        for (k, v) in &task.combination {
+            if self.tools.contains_key(k) || task.tools.contains_key(k) {
+                versions.insert(k.clone(), v.clone());
+            }
+        }
+        versions
+            .iter()
+            .map(|(k, v)| format!("{k}@{v}").parse())
+            .collect()
+    }
+
+    /// the versions the tools of a task resolved to, part of the key of its outputs
+    fn task_versions(&self, config: &Config, task: &Task) -> Result<BTreeMap<String, String>> {
+        if task.tools.is_empty() && task.combination.is_empty() {
+            return Ok(self.tools.clone());
+        }
+        let ts = self.toolset(config, &self.task_tools(task)?)?;
+        Ok(resolved_versions(&ts))
+    }
+
+    /// the engine and its args that run `program` in the task's `container` with the project, the
+    /// working directory, and scripts outside of the project mounted
+    fn container_command(
+        &self,
+        image: &str,
+        program: &str,
+        args: &[String],
+        task: &Task,
+        env: &BTreeMap<String, String>,
+    ) -> Result<(String, Vec<String>)> {
+        let config = Config::get();
+        let dir = self
+            .cd
+            .as_ref()
+            .or(task.dir.as_ref())
+            .cloned()
+            .unwrap_or_else(|| env::current_dir().unwrap());
+        let mut mounts = vec![config.project_root.clone().unwrap_or_else(|| dir.clone())];
+        mounts.push(dir.clone());
+        let program_path = Path::new(program);
+        if program_path.is_absolute() && !mounts.iter().any(|m| program_path.starts_with(m)) {
+            mounts.push(program_path.parent().unwrap().to_path_buf());
+        }
+        let command = once(program.to_string())
+            .chain(args.iter().cloned())
+            .collect_vec();
+        let args = task_container::args(image, &mounts, &dir, env, &command);
+        Ok((task_container::engine()?, args))
+    }
+
     fn output(&self, task: &Task) -> Result<TaskOutput> {
         let settings = Settings::get();
         if self.prefix {
             Ok(TaskOutput::Prefix)
         } else if self.interleave {
             Ok(TaskOutput::Interleave)
+        } else if let Some(output) = &self.output {
+            Ok(output.parse()?)
+        } else if let Some(output) = &task.output {
+            Ok(output.parse()?)
+        } else if task.service {
+            // services run alongside other tasks so their output is always prefixed
+            Ok(TaskOutput::Prefix)
         } else if let Some(output) = &settings.task_output {
             Ok(output.parse()?)
-        } else if self.raw(task) || self.jobs() == 1 || self.is_linear {
+        } else if self.raw(task)
+            || self.jobs() == 1
+            // combinations of a matrix run in parallel even without dependencies
+            || (self.is_linear && task.combination.is_empty())
+        {
             Ok(TaskOutput::Interleave)
         } else {
             Ok(TaskOutput::Prefix)
         }
     }
 
+    fn hermetic(&self) -> bool {
+        self.hermetic || Settings::get().hermetic
+    }
+
     fn raw(&self, task: &Task) -> bool {
         self.raw || task.raw || Settings::get().raw
     }
 
+    /// `--no-fail-fast` and `--continue-on-error` wait for the other tasks before exiting
+    fn fail_fast(&self) -> bool {
+        !self.no_fail_fast && !self.continue_on_error
+    }
+
     fn jobs(&self) -> usize {
         if self.raw {
             1
@@ -423,40 +929,31 @@ impl Run {
         Ok(())
     }
 
-    fn sources_are_fresh(&self, config: &Config, task: &Task) -> bool {
-        let run = || -> Result<bool> {
-            let sources = self.get_last_modified(&self.cwd(config, task), &task.sources)?;
-            let outputs = self.get_last_modified(&self.cwd(config, task), &task.outputs)?;
-            trace!("sources: {sources:?}, outputs: {outputs:?}",);
-            match (sources, outputs) {
-                (Some(sources), Some(outputs)) => Ok(sources < outputs),
-                _ => Ok(false),
-            }
-        };
-        run().unwrap_or_else(|err| {
+    fn sources_are_fresh(
+        &self,
+        config: &Config,
+        task: &Task,
+        tools: &BTreeMap<String, String>,
+    ) -> bool {
+        task_cache::is_fresh(task, &self.cwd(config, task), tools).unwrap_or_else(|err| {
             warn!("sources_are_fresh: {err}");
             false
         })
     }
 
-    fn get_last_modified(
+    fn restore_outputs(
         &self,
-        root: &Path,
-        patterns_or_paths: &[String],
-    ) -> Result<Option<SystemTime>> {
-        let (patterns, paths): (Vec<&String>, Vec<&String>) =
-            patterns_or_paths.iter().partition(|p| is_glob_pattern(p));
-
-        let last_mod = std::cmp::max(
-            last_modified_glob_match(root, &patterns)?,
-            last_modified_path(root, &paths)?,
-        );
-
-        trace!(
-            "last_modified of {}: {last_mod:?}",
-            patterns_or_paths.iter().join(" ")
-        );
-        Ok(last_mod)
+        config: &Config,
+        task: &Task,
+        tools: &BTreeMap<String, String>,
+    ) -> bool {
+        task_cache::restore(task, &self.cwd(config, task), tools).unwrap_or_else(|err| {
+            warn!(
+                "failed to restore outputs of {} from the task cache: {err:#}",
+                task.name
+            );
+            false
+        })
     }
 
     fn cwd(&self, config: &Config, task: &Task) -> PathBuf {
@@ -467,72 +964,67 @@ impl Run {
             .or_else(|| config.project_root.clone())
             .unwrap_or_else(|| env::current_dir().unwrap().clone())
     }
-
-    fn save_checksum(&self, task: &Task) -> Result<()> {
-        if task.sources.is_empty() {
-            return Ok(());
-        }
-        // TODO
-        Ok(())
-    }
 }
 
-fn is_glob_pattern(path: &str) -> bool {
-    // This is the character set used for glob
-    // detection by globwalk
-    let glob_chars = ['*', '{', '}'];
-
-    path.chars().any(|c| glob_chars.contains(&c))
+/// the env of the toolset with the task's `env` added, `false` values remove a variable
+fn task_env(env: &BTreeMap<String, String>, task: &Task) -> Result<BTreeMap<String, String>> {
+    let string_env = task.env.iter().filter_map(|(k, v)| match &v.0 {
+        Either::Left(v) => Some((k, v)),
+        _ => None,
+    });
+    let rm_env = task
+        .env
+        .iter()
+        .filter(|(_, v)| v.0 == Either::Right(false))
+        .map(|(k, _)| k)
+        .collect::<HashSet<_>>();
+    let env = env
+        .iter()
+        .chain(string_env)
+        .filter(|(k, _)| !rm_env.contains(k))
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .chain(task_usage::env(task)?)
+        .collect();
+    Ok(env)
 }
 
-fn last_modified_path(
-    root: impl AsRef<std::ffi::OsStr>,
-    paths: &[&String],
-) -> Result<Option<SystemTime>> {
-    let files = paths
+/// the versions of each tool of a toolset, tools with multiple versions are space separated
+fn resolved_versions(ts: &Toolset) -> BTreeMap<String, String> {
+    ts.versions
         .iter()
-        .map(|p| {
-            let base = Path::new(p);
-
-            if base.is_relative() {
-                base.to_path_buf()
-            } else {
-                Path::new(&root).join(base)
-            }
+        .map(|(fa, tvl)| {
+            let versions = tvl.versions.iter().map(|tv| &tv.version).join(" ");
+            (fa.id.clone(), versions)
         })
-        .filter(|p| p.is_file());
-
-    last_modified_file(files)
+        .collect()
 }
 
-fn last_modified_glob_match(
-    root: impl AsRef<Path>,
-    patterns: &[&String],
-) -> Result<Option<SystemTime>> {
-    if patterns.is_empty() {
-        return Ok(None);
-    }
-    let files = GlobWalkerBuilder::from_patterns(root, patterns)
-        .follow_links(true)
-        .build()?
-        .filter_map(|e| e.ok())
-        .filter(|e| e.file_type().is_file())
-        .map(|e| e.path().to_owned());
-
-    last_modified_file(files)
+/// exits with the status of a failed script so it is passed through `mise run`
+fn exit_with_error(prefix: &str, err: eyre::Report) -> ! {
+    exit(report_error(prefix, &err));
 }
 
-fn last_modified_file(files: impl IntoIterator<Item = PathBuf>) -> Result<Option<SystemTime>> {
-    Ok(files
-        .into_iter()
-        .unique()
-        .map(|p| p.metadata().map_err(|err| eyre!(err)))
-        .collect::<Result<Vec<_>>>()?
-        .into_iter()
-        .map(|m| m.modified().map_err(|err| eyre!(err)))
-        .collect::<Result<Vec<_>>>()?
-        .into_iter()
-        .max())
+/// logs a failed task, returns the exit code of its script, 124 if it timed out, or 1
+fn report_error(prefix: &str, err: &eyre::Report) -> i32 {
+    if let Some(ScriptTimedOut(_, timeout)) = err.downcast_ref::<Error>() {
+        let timeout = humantime::format_duration(*timeout);
+        error!("{prefix} timed out after {timeout}");
+        return 124;
+    }
+    if let Some(ScriptFailed(_, Some(status))) = err.downcast_ref::<Error>() {
+        if let Some(code) = status.code() {
+            error!("{prefix} exited with code {code}");
+            return code;
+        } else {
+            #[cfg(unix)]
+            if let Some(signal) = status.signal() {
+                error!("{prefix} killed by signal {signal}");
+                return 1;
+            }
+        }
+    }
+    error!("{err}");
+    1
 }
 
 static AFTER_LONG_HELP: &str = color_print::cstr!(
@@ -562,6 +1054,8 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
 enum TaskOutput {
     Prefix,
     Interleave,
+    Group,
+    Quiet,
 }
 
 fn get_color() -> Color {
@@ -587,13 +1081,70 @@ fn format_duration(dur: std::time::Duration) -> String {
     }
 }
 
+/// follows the `task` steps of the last task in `chain` and returns the first chain of steps that
+/// leads back to a task already in it, e.g.: `a -> b -> a`
+fn step_cycle(tasks: &BTreeMap<String, &Task>, chain: &mut Vec<String>) -> Option<Vec<String>> {
+    let task = tasks.get(chain.last()?)?;
+    for entry in &task.run {
+        let RunEntry::Step { task: Some(t), .. } = entry else {
+            continue;
+        };
+        let name = tasks.get(t).map_or(t.clone(), |t| t.name.clone());
+        chain.push(name.clone());
+        if chain[..chain.len() - 1].contains(&name) {
+            return Some(chain.clone());
+        }
+        if let Some(cycle) = step_cycle(tasks, chain) {
+            return Some(cycle);
+        }
+        chain.pop();
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+
     use insta::assert_snapshot;
 
     use crate::file;
+    use crate::task::{RunEntry, Task};
     use crate::test::reset;
 
+    use super::step_cycle;
+
+    #[test]
+    fn test_step_cycle() {
+        let task = |name: &str, steps: &[&str]| {
+            let mut t = Task::new(name.to_string(), PathBuf::from("mise.toml"));
+            t.run = steps
+                .iter()
+                .map(|s| RunEntry::Step {
+                    run: None,
+                    task: Some(s.to_string()),
+                    condition: Default::default(),
+                })
+                .collect();
+            t
+        };
+        let (a, b, c) = (task("a", &["b"]), task("b", &["c", "a"]), task("c", &[]));
+        let tasks: BTreeMap<String, &Task> =
+            [("a".into(), &a), ("b".into(), &b), ("c".into(), &c)].into();
+        assert_eq!(
+            step_cycle(&tasks, &mut vec!["a".into()]),
+            Some(vec!["a".into(), "b".into(), "a".into()])
+        );
+        assert_eq!(step_cycle(&tasks, &mut vec!["c".into()]), None);
+        let d = task("d", &["d"]);
+        let tasks: BTreeMap<String, &Task> = [("d".into(), &d)].into();
+        assert_eq!(
+            step_cycle(&tasks, &mut vec!["d".into()]),
+            Some(vec!["d".into(), "d".into()])
+        );
+    }
+
     #[test]
     fn test_task_run() {
         reset();
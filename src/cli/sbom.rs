@@ -0,0 +1,276 @@
+use std::path::PathBuf;
+
+use clap::ValueHint;
+use eyre::Result;
+use serde_json::{json, Value};
+
+use crate::backend::BackendType;
+use crate::cli::version::VERSION;
+use crate::config::{lockfile, Config};
+use crate::file;
+use crate::file::display_path;
+use crate::registry;
+use crate::toolset::ToolsetBuilder;
+
+/// Generate a software bill of materials of the active tools
+///
+/// Each tool has its backend, version, package url, and the license and
+/// source from the registry or the `license` and `source_url` tool options.
+/// The url and checksum of the downloaded artifact are included when they are
+/// in mise.lock or were downloaded by this command.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct Sbom {
+    /// Format of the SBOM
+    #[clap(long, short, value_enum, default_value_t)]
+    format: SbomFormat,
+
+    /// Write the SBOM to this file instead of stdout
+    #[clap(long, short, value_hint = ValueHint::FilePath)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SbomFormat {
+    /// CycloneDX 1.5 JSON
+    #[default]
+    Cyclonedx,
+    /// SPDX 2.3 JSON
+    Spdx,
+}
+
+#[derive(Debug, Default)]
+struct Component {
+    name: String,
+    backend: String,
+    version: String,
+    purl: String,
+    license: Option<String>,
+    source: Option<String>,
+    url: Option<String>,
+    sha256: Option<String>,
+}
+
+impl Sbom {
+    pub fn run(self) -> Result<()> {
+        let components = components()?;
+        let timestamp = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        let sbom = match self.format {
+            SbomFormat::Cyclonedx => cyclonedx(&components, &timestamp),
+            SbomFormat::Spdx => spdx(&components, &timestamp),
+        };
+        let out = serde_json::to_string_pretty(&sbom)?;
+        match &self.output {
+            Some(output) => {
+                file::write(output, out)?;
+                info!("sbom written to {}", display_path(output));
+            }
+            None => miseprintln!("{out}"),
+        }
+        Ok(())
+    }
+}
+
+fn components() -> Result<Vec<Component>> {
+    let config = Config::try_get()?;
+    let ts = ToolsetBuilder::new().build(&config)?;
+    let components = ts
+        .list_current_versions()
+        .into_iter()
+        .map(|(p, tv)| {
+            let fa = p.fa();
+            let metadata = registry::metadata(fa, &tv.request.options()).unwrap_or_default();
+            let artifact = lockfile::artifact(&tv);
+            Component {
+                name: fa.id.clone(),
+                backend: fa.backend_type.to_string(),
+                purl: purl(fa.backend_type, &fa.name, &tv.version),
+                license: metadata.license,
+                source: metadata.source,
+                sha256: artifact
+                    .as_ref()
+                    .and_then(|a| a.checksum.strip_prefix("sha256:").map(|c| c.to_string())),
+                url: artifact.map(|a| a.url),
+                version: tv.version,
+            }
+        })
+        .collect();
+    Ok(components)
+}
+
+/// package url of a tool, https://github.com/package-url/purl-spec
+fn purl(backend_type: BackendType, name: &str, version: &str) -> String {
+    let name = name.trim_start_matches('@').replace('@', "%40");
+    let (ty, name) = match backend_type {
+        BackendType::Cargo => ("cargo", name),
+        BackendType::Go => ("golang", name),
+        BackendType::Npm if name.contains('/') => ("npm", format!("%40{name}")),
+        BackendType::Npm => ("npm", name),
+        BackendType::Pipx => ("pypi", name),
+        BackendType::Ubi => ("github", name),
+        BackendType::Gitlab => ("gitlab", name),
+        BackendType::Gitea => ("gitea", name),
+        _ => ("generic", name),
+    };
+    format!("pkg:{ty}/{name}@{version}")
+}
+
+fn cyclonedx(components: &[Component], timestamp: &str) -> Value {
+    let components = components
+        .iter()
+        .map(|c| {
+            let mut component = json!({
+                "type": "application",
+                "bom-ref": c.purl,
+                "name": c.name,
+                "version": c.version,
+                "purl": c.purl,
+                "properties": [{"name": "mise:backend", "value": c.backend}],
+            });
+            if let Some(license) = &c.license {
+                component["licenses"] = json!([{"expression": license}]);
+            }
+            if let Some(sha256) = &c.sha256 {
+                component["hashes"] = json!([{"alg": "SHA-256", "content": sha256}]);
+            }
+            let mut refs = vec![];
+            if let Some(url) = &c.url {
+                refs.push(json!({"type": "distribution", "url": url}));
+            }
+            if let Some(source) = &c.source {
+                refs.push(json!({"type": "vcs", "url": source}));
+            }
+            if !refs.is_empty() {
+                component["externalReferences"] = json!(refs);
+            }
+            component
+        })
+        .collect::<Vec<_>>();
+    json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.5",
+        "serialNumber": format!("urn:uuid:{}", uuid()),
+        "version": 1,
+        "metadata": {
+            "timestamp": timestamp,
+            "tools": {
+                "components": [{"type": "application", "name": "mise", "version": *VERSION}],
+            },
+        },
+        "components": components,
+    })
+}
+
+fn spdx(components: &[Component], timestamp: &str) -> Value {
+    let noassertion = || "NOASSERTION".to_string();
+    let packages = components
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            let mut package = json!({
+                "name": c.name,
+                "SPDXID": format!("SPDXRef-Package-{}", i + 1),
+                "versionInfo": c.version,
+                "downloadLocation": c.url.clone().unwrap_or_else(noassertion),
+                "licenseConcluded": "NOASSERTION",
+                "licenseDeclared": c.license.clone().unwrap_or_else(noassertion),
+                "copyrightText": "NOASSERTION",
+                "filesAnalyzed": false,
+                "comment": format!("installed with the mise {} backend", c.backend),
+                "externalRefs": [{
+                    "referenceCategory": "PACKAGE-MANAGER",
+                    "referenceType": "purl",
+                    "referenceLocator": c.purl,
+                }],
+            });
+            if let Some(sha256) = &c.sha256 {
+                package["checksums"] = json!([{"algorithm": "SHA256", "checksumValue": sha256}]);
+            }
+            if let Some(source) = &c.source {
+                package["homepage"] = json!(source);
+            }
+            package
+        })
+        .collect::<Vec<_>>();
+    let relationships = packages
+        .iter()
+        .map(|p| {
+            json!({
+                "spdxElementId": "SPDXRef-DOCUMENT",
+                "relationshipType": "DESCRIBES",
+                "relatedSpdxElement": p["SPDXID"],
+            })
+        })
+        .collect::<Vec<_>>();
+    json!({
+        "spdxVersion": "SPDX-2.3",
+        "dataLicense": "CC0-1.0",
+        "SPDXID": "SPDXRef-DOCUMENT",
+        "name": "mise",
+        "documentNamespace": format!("https://mise.jdx.dev/spdx/{}", uuid()),
+        "creationInfo": {
+            "created": timestamp,
+            "creators": [format!("Tool: mise-{}", *VERSION)],
+        },
+        "packages": packages,
+        "relationships": relationships,
+    })
+}
+
+/// random (version 4) uuid for the serial number of the document
+fn uuid() -> String {
+    let mut b: [u8; 16] = rand::random();
+    b[6] = (b[6] & 0x0f) | 0x40;
+    b[8] = (b[8] & 0x3f) | 0x80;
+    let hex = b.iter().map(|b| format!("{b:02x}")).collect::<String>();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise sbom</bold>
+    $ <bold>mise sbom --format spdx -o sbom.spdx.json</bold>
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_purl() {
+        assert_eq!(
+            purl(BackendType::Npm, "@antfu/ni", "0.21.0"),
+            "pkg:npm/%40antfu/ni@0.21.0"
+        );
+        assert_eq!(
+            purl(BackendType::Npm, "prettier", "3.0.0"),
+            "pkg:npm/prettier@3.0.0"
+        );
+        assert_eq!(
+            purl(BackendType::Ubi, "BurntSushi/ripgrep", "14.1.0"),
+            "pkg:github/BurntSushi/ripgrep@14.1.0"
+        );
+        assert_eq!(
+            purl(BackendType::Core, "node", "20.0.0"),
+            "pkg:generic/node@20.0.0"
+        );
+    }
+
+    #[test]
+    fn test_uuid() {
+        let uuid = uuid();
+        assert_eq!(uuid.len(), 36);
+        assert_eq!(&uuid[14..15], "4");
+    }
+}
@@ -0,0 +1,53 @@
+use eyre::Result;
+
+use crate::config::{Config, Settings};
+use crate::schedule::{self, Scheduler};
+
+/// Register the scheduled tasks of the project with the system scheduler
+///
+/// Jobs installed for the project before are replaced, so this also removes
+/// tasks that no longer have a `schedule`.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, visible_alias = "i", after_long_help = AFTER_LONG_HELP)]
+pub struct ScheduleInstall {
+    /// Scheduler to register the tasks with
+    /// Defaults to launchd on macOS, systemd user timers if available, otherwise cron
+    #[clap(long, value_enum, verbatim_doc_comment)]
+    pub scheduler: Option<Scheduler>,
+}
+
+impl ScheduleInstall {
+    pub fn run(self) -> Result<()> {
+        Settings::get().ensure_experimental("`mise schedule`")?;
+        let config = Config::try_get()?;
+        let root = super::project_root(&config)?;
+        let tasks = super::scheduled_tasks(&config)?;
+        if tasks.is_empty() {
+            if schedule::uninstall(root)?.is_some() {
+                info!("removed the scheduled tasks of {}", root.display());
+            } else {
+                info!("no tasks have a schedule");
+            }
+            return Ok(());
+        }
+        let scheduler = self.scheduler.unwrap_or_else(Scheduler::detect);
+        let installation = schedule::install(root, &tasks, scheduler)?;
+        for job in &installation.jobs {
+            info!(
+                "scheduled {} at {} with {scheduler}",
+                job.task, job.schedule
+            );
+        }
+        Ok(())
+    }
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise schedule install</bold>
+    mise scheduled prune-cache at 0 9 * * 1-5 with systemd
+
+    $ <bold>mise schedule install --scheduler cron</bold>
+"#
+);
@@ -0,0 +1,92 @@
+use eyre::Result;
+use serde_derive::Serialize;
+use tabled::{Table, Tabled};
+
+use crate::config::{Config, Settings};
+use crate::schedule;
+use crate::ui::table;
+
+/// List the scheduled tasks of the project
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, visible_alias = "list", after_long_help = AFTER_LONG_HELP)]
+pub struct ScheduleLs {
+    /// Output in JSON format
+    #[clap(short = 'J', long, verbatim_doc_comment)]
+    pub json: bool,
+}
+
+impl ScheduleLs {
+    pub fn run(self) -> Result<()> {
+        Settings::get().ensure_experimental("`mise schedule`")?;
+        let config = Config::try_get()?;
+        let root = super::project_root(&config)?;
+        let installation = schedule::installed(root)?;
+        let rows = super::scheduled_tasks(&config)?
+            .into_iter()
+            .map(|task| {
+                let label = schedule::label(root, &task.name);
+                let job = installation
+                    .as_ref()
+                    .and_then(|i| i.jobs.iter().find(|j| j.task == task.name));
+                let last_run = schedule::last_run(&label);
+                Row {
+                    name: task.name.clone(),
+                    schedule: task.schedule.clone().unwrap_or_default(),
+                    scheduler: match (job, &installation) {
+                        (Some(job), Some(i)) if Some(&job.schedule) == task.schedule.as_ref() => {
+                            i.scheduler.to_string()
+                        }
+                        (Some(_), Some(i)) => format!("{} (outdated)", i.scheduler),
+                        _ => "not installed".to_string(),
+                    },
+                    last_run: last_run
+                        .map(|r| {
+                            let at = chrono::DateTime::from_timestamp(r.started_at, 0)
+                                .map(|t| {
+                                    t.with_timezone(&chrono::Local)
+                                        .format("%Y-%m-%d %H:%M")
+                                        .to_string()
+                                })
+                                .unwrap_or_default();
+                            match r.success {
+                                true => at,
+                                false => format!("{at} (failed)"),
+                            }
+                        })
+                        .unwrap_or_default(),
+                }
+            })
+            .collect::<Vec<_>>();
+        if self.json {
+            miseprintln!("{}", serde_json::to_string_pretty(&rows)?);
+            return Ok(());
+        }
+        if rows.is_empty() {
+            info!("no tasks have a schedule");
+            return Ok(());
+        }
+        let mut table = Table::new(rows);
+        table::default_style(&mut table, false);
+        miseprintln!("{table}");
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Tabled)]
+#[tabled(rename_all = "PascalCase")]
+struct Row {
+    name: String,
+    schedule: String,
+    scheduler: String,
+    #[tabled(rename = "Last Run")]
+    last_run: String,
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise schedule ls</bold>
+    Name         Schedule     Scheduler  Last Run
+    prune-cache  0 9 * * 1-5  systemd    2024-06-03 09:00
+"#
+);
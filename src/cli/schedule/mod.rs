@@ -0,0 +1,70 @@
+use std::path::Path;
+
+use clap::Subcommand;
+use eyre::{eyre, Result};
+use itertools::Itertools;
+
+use crate::config::Config;
+use crate::task::Task;
+
+mod install;
+mod ls;
+mod run;
+mod uninstall;
+
+/// [experimental] Run tasks on a schedule
+///
+/// Tasks with a `schedule` cron expression are registered with launchd, systemd user timers,
+/// or cron by `mise schedule install`. They run in the project directory with its tools and env.
+///
+/// Run `mise schedule` with no args to list the scheduled tasks of the project.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment)]
+pub struct Schedule {
+    #[clap(subcommand)]
+    command: Option<Commands>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Commands {
+    Install(install::ScheduleInstall),
+    Ls(ls::ScheduleLs),
+    Run(run::ScheduleRun),
+    Uninstall(uninstall::ScheduleUninstall),
+}
+
+impl Commands {
+    pub fn run(self) -> Result<()> {
+        match self {
+            Self::Install(cmd) => cmd.run(),
+            Self::Ls(cmd) => cmd.run(),
+            Self::Run(cmd) => cmd.run(),
+            Self::Uninstall(cmd) => cmd.run(),
+        }
+    }
+}
+
+impl Schedule {
+    pub fn run(self) -> Result<()> {
+        let cmd = self
+            .command
+            .unwrap_or(Commands::Ls(ls::ScheduleLs { json: false }));
+        cmd.run()
+    }
+}
+
+/// the tasks of the current project that have a `schedule`
+fn scheduled_tasks(config: &Config) -> Result<Vec<&Task>> {
+    let root = project_root(config)?;
+    Ok(config
+        .tasks()?
+        .values()
+        .filter(|t| t.schedule.is_some() && t.config_source.starts_with(root))
+        .collect_vec())
+}
+
+fn project_root(config: &Config) -> Result<&Path> {
+    config.project_root.as_deref().ok_or_else(|| {
+        eyre!("no project found, scheduled tasks are defined in a project's mise.toml")
+    })
+}
@@ -0,0 +1,35 @@
+use std::process::exit;
+
+use eyre::Result;
+
+use crate::config::Config;
+use crate::env;
+use crate::schedule::{self, LastRun};
+
+/// Run a scheduled task, this is what the system scheduler runs
+///
+/// Like `mise run`, but the result is recorded for `mise schedule ls`.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, hide = true)]
+pub struct ScheduleRun {
+    /// Task to run
+    pub task: String,
+}
+
+impl ScheduleRun {
+    pub fn run(self) -> Result<()> {
+        let config = Config::try_get()?;
+        let root = super::project_root(&config)?;
+        let started_at = chrono::Utc::now().timestamp();
+        let output = cmd!(&*env::MISE_BIN, "run", &self.task).unchecked().run()?;
+        let last_run = LastRun {
+            started_at,
+            success: output.status.success(),
+        };
+        schedule::save_last_run(&schedule::label(root, &self.task), &last_run)?;
+        if !last_run.success {
+            exit(output.status.code().unwrap_or(1));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,26 @@
+use eyre::Result;
+
+use crate::config::{Config, Settings};
+use crate::schedule;
+
+/// Remove the scheduled tasks of the project from the system scheduler
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment)]
+pub struct ScheduleUninstall {}
+
+impl ScheduleUninstall {
+    pub fn run(self) -> Result<()> {
+        Settings::get().ensure_experimental("`mise schedule`")?;
+        let config = Config::try_get()?;
+        let root = super::project_root(&config)?;
+        match schedule::uninstall(root)? {
+            Some(installation) => {
+                for job in &installation.jobs {
+                    info!("unscheduled {}", job.task);
+                }
+            }
+            None => info!("no scheduled tasks are installed for {}", root.display()),
+        }
+        Ok(())
+    }
+}
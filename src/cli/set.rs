@@ -2,13 +2,14 @@ use std::path::{Path, PathBuf};
 
 use eyre::{bail, Result};
 use tabled::Tabled;
+use toml_edit::InlineTable;
 
 use crate::config::config_file::mise_toml::MiseToml;
 use crate::config::config_file::ConfigFile;
 use crate::config::Config;
-use crate::env;
 use crate::file::display_path;
 use crate::ui::table;
+use crate::{age, env};
 
 use super::args::EnvVarArg;
 
@@ -34,6 +35,21 @@ pub struct Set {
     #[clap(long, value_name = "ENV_VAR", verbatim_doc_comment, aliases = ["rm", "unset"], hide = true)]
     remove: Option<Vec<String>>,
 
+    /// Encrypt the values with age so they can be committed
+    /// They are decrypted with `age_identity_file` when the env is loaded
+    #[clap(long, verbatim_doc_comment)]
+    encrypt: bool,
+
+    /// Encrypt for this age recipient (public key) instead of the identity file
+    /// Can be used multiple times to encrypt for several people.
+    #[clap(
+        long,
+        value_name = "RECIPIENT",
+        requires = "encrypt",
+        verbatim_doc_comment
+    )]
+    age_recipient: Vec<String>,
+
     /// Environment variable(s) to set
     /// e.g.: NODE_ENV=production
     #[clap(verbatim_doc_comment)]
@@ -83,6 +99,11 @@ impl Set {
             }
             for ev in env_vars {
                 match ev.value {
+                    Some(value) if self.encrypt => {
+                        let mut encrypted = InlineTable::new();
+                        encrypted.insert("age", age::encrypt(&value, &self.age_recipient)?.into());
+                        mise_toml.update_env(&ev.key, encrypted)?
+                    }
                     Some(value) => mise_toml.update_env(&ev.key, value)?,
                     None => bail!("{} has no value", ev.key),
                 }
@@ -118,6 +139,9 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
     $ <bold>mise set NODE_ENV</bold>
     production
 
+    # stores the value encrypted with age, only people with the identity can decrypt it
+    $ <bold>mise set --encrypt --file mise.local.toml API_TOKEN=secret</bold>
+
     $ <bold>mise set</bold>
     key       value       source
     NODE_ENV  production  ~/.config/mise/config.toml
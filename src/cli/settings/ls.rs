@@ -64,33 +64,66 @@ mod tests {
         always_keep_download = true
         always_keep_install = true
         asdf_compat = false
+        binary_cache_upload = false
         cargo_binstall = true
+        cargo_quickinstall = false
         color = true
+        content_store = false
         disable_default_shorthands = false
         disable_tools = []
+        exec_cache = false
         experimental = true
+        gitea_hosts = []
+        gitlab_hosts = []
         go_default_packages_file = "~/.default-go-packages"
         go_download_mirror = "https://dl.google.com/go"
         go_repo = "https://github.com/golang/go"
         go_set_gopath = false
         go_set_goroot = true
         go_skip_checksum = false
+        hermetic = false
+        hermetic_allow = ["awk", "basename", "bash", "cat", "chmod", "cp", "cut", "date", "dirname", "echo", "env", "find", "grep", "head", "ls", "mkdir", "mktemp", "mv", "pwd", "rm", "sed", "sh", "sort", "tail", "tee", "touch", "tr", "uname", "uniq", "wc", "xargs"]
+        http_download_segments = 1
+        http_retries = 2
+        http_retry_backoff = 500
+        http_retry_status_codes = [408, 429, 500, 502, 503, 504]
         http_timeout = 30
         jobs = 2
         legacy_version_file = true
         legacy_version_file_disable_tools = []
+        locked = false
+        lockfile = false
         node_compile = false
         not_found_auto_install = true
+        offline = false
+        package_manager_version_file = false
         paranoid = false
+        pipx_uvx = false
+        platform_dirs = false
         plugin_autoupdate_last_check_duration = "20m"
+        prerelease = "false"
         python_default_packages_file = "~/.default-python-packages"
         python_pyenv_repo = "https://github.com/pyenv/pyenv.git"
         quiet = false
         raw = false
+        signature_policy = "auto"
+        task_remote_cache_upload = false
+        template_sources = []
         trusted_config_paths = []
+        ubi_checksum_mismatch = "error"
         verbose = true
+        windows_path_translation = true
+        windows_x64_emulation = true
         yes = true
 
+        [auto_install]
+        disable_tools = []
+        exec = "always"
+        run = "always"
+        shim = "always"
+
+        [mirrors]
+
         [status]
         missing_tools = "if_other_versions_installed"
         show_env = false
@@ -107,35 +140,66 @@ mod tests {
         always_keep_download
         always_keep_install
         asdf_compat
+        auto_install
+        auto_install.disable_tools
+        auto_install.exec
+        auto_install.run
+        auto_install.shim
+        binary_cache_upload
         cargo_binstall
+        cargo_quickinstall
         color
+        content_store
         disable_default_shorthands
         disable_tools
+        exec_cache
         experimental
+        gitea_hosts
+        gitlab_hosts
         go_default_packages_file
         go_download_mirror
         go_repo
         go_set_gopath
         go_set_goroot
         go_skip_checksum
+        hermetic
+        hermetic_allow
+        http_download_segments
+        http_retries
+        http_retry_backoff
+        http_retry_status_codes
         http_timeout
         jobs
         legacy_version_file
         legacy_version_file_disable_tools
+        locked
+        lockfile
+        mirrors
         node_compile
         not_found_auto_install
+        offline
+        package_manager_version_file
         paranoid
+        pipx_uvx
+        platform_dirs
         plugin_autoupdate_last_check_duration
+        prerelease
         python_default_packages_file
         python_pyenv_repo
         quiet
         raw
+        signature_policy
         status
         status.missing_tools
         status.show_env
         status.show_tools
+        task_remote_cache_upload
+        template_sources
         trusted_config_paths
+        ubi_checksum_mismatch
         verbose
+        windows_path_translation
+        windows_x64_emulation
         yes
         "###);
     }
@@ -21,14 +21,27 @@ impl SettingsSet {
     pub fn run(self) -> Result<()> {
         let value: toml_edit::Value = match self.setting.as_str() {
             "activate_aggressive" => parse_bool(&self.value)?,
+            "age_identity_file" => self.value.into(),
             "all_compile" => parse_bool(&self.value)?,
             "always_keep_download" => parse_bool(&self.value)?,
             "always_keep_install" => parse_bool(&self.value)?,
             "asdf_compat" => parse_bool(&self.value)?,
+            "auto_install.disable_tools" => self.value.split(',').map(|s| s.to_string()).collect(),
+            "auto_install.exec" => self.value.into(),
+            "auto_install.run" => self.value.into(),
+            "auto_install.shim" => self.value.into(),
+            "binary_cache" => self.value.into(),
+            "binary_cache_upload" => parse_bool(&self.value)?,
+            "cargo_binstall" => parse_bool(&self.value)?,
+            "cargo_quickinstall" => parse_bool(&self.value)?,
             "color" => parse_bool(&self.value)?,
+            "content_store" => parse_bool(&self.value)?,
             "disable_default_shorthands" => parse_bool(&self.value)?,
             "disable_tools" => self.value.split(',').map(|s| s.to_string()).collect(),
+            "exec_cache" => parse_bool(&self.value)?,
             "experimental" => parse_bool(&self.value)?,
+            "gitea_hosts" => self.value.split(',').map(|s| s.to_string()).collect(),
+            "gitlab_hosts" => self.value.split(',').map(|s| s.to_string()).collect(),
             "go_default_packages_file" => self.value.into(),
             "go_download_mirror" => self.value.into(),
             "go_repo" => self.value.into(),
@@ -36,24 +49,55 @@ impl SettingsSet {
             "go_set_gopath" => parse_bool(&self.value)?,
             "go_set_goroot" => parse_bool(&self.value)?,
             "go_skip_checksum" => parse_bool(&self.value)?,
+            "hermetic" => parse_bool(&self.value)?,
+            "hermetic_allow" => self.value.split(',').map(|s| s.to_string()).collect(),
+            "http_download_segments" => parse_i64(&self.value)?,
+            "http_retries" => parse_i64(&self.value)?,
+            "http_retry_backoff" => parse_i64(&self.value)?,
+            "http_retry_status_codes" => self
+                .value
+                .split(',')
+                .map(|s| parse_i64(s.trim()))
+                .collect::<Result<toml_edit::Array>>()?
+                .into(),
             "http_timeout" => parse_i64(&self.value)?,
             "jobs" => parse_i64(&self.value)?,
             "legacy_version_file" => parse_bool(&self.value)?,
+            "locked" => parse_bool(&self.value)?,
+            "lockfile" => parse_bool(&self.value)?,
             "node_compile" => parse_bool(&self.value)?,
+            "mirrors.crates" => self.value.into(),
+            "mirrors.github_releases" => self.value.into(),
+            "mirrors.go" => self.value.into(),
+            "mirrors.npm" => self.value.into(),
+            "mirrors.pypi" => self.value.into(),
             "not_found_auto_install" => parse_bool(&self.value)?,
+            "offline" => parse_bool(&self.value)?,
+            "package_manager_version_file" => parse_bool(&self.value)?,
             "paranoid" => parse_bool(&self.value)?,
+            "pipx_uvx" => parse_bool(&self.value)?,
+            "platform_dirs" => parse_bool(&self.value)?,
             "plugin_autoupdate_last_check_duration" => self.value.into(),
+            "prerelease" => self.value.into(),
             "python_compile" => parse_bool(&self.value)?,
             "python_venv_auto_create" => parse_bool(&self.value)?,
             "quiet" => parse_bool(&self.value)?,
             "raw" => parse_bool(&self.value)?,
             "shorthands_file" => self.value.into(),
+            "signature_policy" => self.value.into(),
             "status.missing_tools" => self.value.into(),
             "status.show_env" => parse_bool(&self.value)?,
             "status.show_tools" => parse_bool(&self.value)?,
+            "task_container_engine" => self.value.into(),
             "task_output" => self.value.into(),
+            "task_remote_cache" => self.value.into(),
+            "task_remote_cache_upload" => parse_bool(&self.value)?,
+            "template_sources" => self.value.split(',').map(|s| s.to_string()).collect(),
             "trusted_config_paths" => self.value.split(':').map(|s| s.to_string()).collect(),
+            "ubi_checksum_mismatch" => self.value.into(),
             "verbose" => parse_bool(&self.value)?,
+            "windows_path_translation" => parse_bool(&self.value)?,
+            "windows_x64_emulation" => parse_bool(&self.value)?,
             "yes" => parse_bool(&self.value)?,
             _ => return Err(eyre!("Unknown setting: {}", self.setting)),
         };
@@ -66,13 +110,13 @@ impl SettingsSet {
             config["settings"] = toml_edit::Item::Table(toml_edit::Table::new());
         }
         let settings = config["settings"].as_table_mut().unwrap();
-        if self.setting.as_str().starts_with("status.") {
-            let status = settings
-                .entry("status")
+        if let Some((parent, key)) = self.setting.split_once('.') {
+            let parent = settings
+                .entry(parent)
                 .or_insert(toml_edit::Item::Table(toml_edit::Table::new()))
                 .as_table_mut()
                 .unwrap();
-            status.insert(&self.setting[7..], toml_edit::Item::Value(value));
+            parent.insert(key, toml_edit::Item::Value(value));
         } else {
             settings.insert(&self.setting, toml_edit::Item::Value(value));
         }
@@ -129,33 +173,66 @@ pub mod tests {
         always_keep_download = true
         always_keep_install = true
         asdf_compat = false
+        binary_cache_upload = false
         cargo_binstall = true
+        cargo_quickinstall = false
         color = true
+        content_store = false
         disable_default_shorthands = false
         disable_tools = []
+        exec_cache = false
         experimental = true
+        gitea_hosts = []
+        gitlab_hosts = []
         go_default_packages_file = "~/.default-go-packages"
         go_download_mirror = "https://dl.google.com/go"
         go_repo = "https://github.com/golang/go"
         go_set_gopath = false
         go_set_goroot = true
         go_skip_checksum = false
+        hermetic = false
+        hermetic_allow = ["awk", "basename", "bash", "cat", "chmod", "cp", "cut", "date", "dirname", "echo", "env", "find", "grep", "head", "ls", "mkdir", "mktemp", "mv", "pwd", "rm", "sed", "sh", "sort", "tail", "tee", "touch", "tr", "uname", "uniq", "wc", "xargs"]
+        http_download_segments = 1
+        http_retries = 2
+        http_retry_backoff = 500
+        http_retry_status_codes = [408, 429, 500, 502, 503, 504]
         http_timeout = 30
         jobs = 2
         legacy_version_file = false
         legacy_version_file_disable_tools = []
+        locked = false
+        lockfile = false
         node_compile = false
         not_found_auto_install = true
+        offline = false
+        package_manager_version_file = false
         paranoid = false
+        pipx_uvx = false
+        platform_dirs = false
         plugin_autoupdate_last_check_duration = "1"
+        prerelease = "false"
         python_default_packages_file = "~/.default-python-packages"
         python_pyenv_repo = "https://github.com/pyenv/pyenv.git"
         quiet = false
         raw = false
+        signature_policy = "auto"
+        task_remote_cache_upload = false
+        template_sources = []
         trusted_config_paths = []
+        ubi_checksum_mismatch = "error"
         verbose = true
+        windows_path_translation = true
+        windows_x64_emulation = true
         yes = true
 
+        [auto_install]
+        disable_tools = []
+        exec = "always"
+        run = "always"
+        shim = "always"
+
+        [mirrors]
+
         [status]
         missing_tools = "never"
         show_env = false
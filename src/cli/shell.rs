@@ -49,6 +49,7 @@ impl Shell {
             force: false,
             jobs: self.jobs,
             raw: self.raw,
+            keep_download: false,
             latest_versions: false,
         };
         ts.install_arg_versions(&config, &opts)?;
@@ -0,0 +1,68 @@
+use eyre::{bail, Result};
+use itertools::Itertools;
+
+use crate::config::Settings;
+use crate::service;
+use crate::ui::style;
+
+/// [experimental] Stop running service tasks
+///
+/// Stops services started by `mise run` without them being restarted.
+/// Once all of its services are stopped, `mise run` exits.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct Stop {
+    /// Service(s) to stop
+    /// If not specified, all running services are stopped
+    #[clap(verbatim_doc_comment)]
+    pub service: Vec<String>,
+}
+
+impl Stop {
+    pub fn run(self) -> Result<()> {
+        Settings::get().ensure_experimental("`mise stop`")?;
+        let services = service::list()?;
+        let missing = self
+            .service
+            .iter()
+            .filter(|name| !services.iter().any(|s| &&s.name == name))
+            .collect_vec();
+        if !missing.is_empty() {
+            bail!(
+                "no service {} is running",
+                missing.iter().map(style::ered).join(", ")
+            );
+        }
+        for s in services
+            .iter()
+            .filter(|s| self.service.is_empty() || self.service.contains(&s.name))
+        {
+            s.stop()?;
+            info!("stopped {}", s.name);
+        }
+        Ok(())
+    }
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    # stop the dev:api service
+    $ <bold>mise stop dev:api</bold>
+
+    # stop all running services
+    $ <bold>mise stop</bold>
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::test::reset;
+
+    #[test]
+    fn test_stop_missing() {
+        reset();
+        let err = assert_cli_err!("stop", "not-a-service");
+        assert!(err.to_string().contains("not-a-service"));
+    }
+}
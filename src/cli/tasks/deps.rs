@@ -23,8 +23,12 @@ pub struct TasksDeps {
     pub hidden: bool,
 
     /// Display dependencies in DOT format
-    #[clap(long, alias = "dot", verbatim_doc_comment)]
+    #[clap(long, alias = "dot", verbatim_doc_comment, conflicts_with = "mermaid")]
     pub dot: bool,
+
+    /// Display dependencies as a Mermaid flowchart
+    #[clap(long, verbatim_doc_comment)]
+    pub mermaid: bool,
 }
 
 impl TasksDeps {
@@ -41,6 +45,8 @@ impl TasksDeps {
 
         if self.dot {
             self.print_deps_dot(&config, tasks)?;
+        } else if self.mermaid {
+            self.print_deps_mermaid(&config, tasks)?;
         } else {
             self.print_deps_tree(&config, tasks)?;
         }
@@ -134,6 +140,35 @@ impl TasksDeps {
         Ok(())
     }
 
+    ///
+    /// Print dependencies as a Mermaid flowchart
+    ///
+    /// Example:
+    /// ```
+    /// flowchart TD
+    ///     n0["task1"]
+    ///     n1["task2"]
+    ///     n0 --> n1
+    /// ```
+    ///
+    fn print_deps_mermaid(&self, config: &Config, tasks: Vec<Task>) -> Result<()> {
+        let deps = Deps::new(config, tasks)?;
+        let mut lines = vec!["flowchart TD".to_string()];
+        for idx in deps.graph.node_indices() {
+            let name = deps.graph[idx].name.replace('"', "#quot;");
+            lines.push(format!("    n{}[\"{name}\"]", idx.index()));
+        }
+        for edge in deps.graph.raw_edges() {
+            lines.push(format!(
+                "    n{} --> n{}",
+                edge.source().index(),
+                edge.target().index()
+            ));
+        }
+        miseprintln!("{}", lines.join("\n"));
+        Ok(())
+    }
+
     fn err_no_task(&self, config: &Config, t: &str) -> eyre::Report {
         let tasks = config
             .tasks()
@@ -156,6 +191,9 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
 
     # Show dependencies in DOT format
     $ <bold>mise tasks deps --dot</bold>
+
+    # Show dependencies as a Mermaid flowchart, e.g. for a markdown file
+    $ <bold>mise tasks deps --mermaid</bold>
 "#
 );
 
@@ -205,4 +243,19 @@ mod tests {
         "###
         );
     }
+
+    #[test]
+    fn test_tasks_deps_mermaid() {
+        reset();
+        assert_cli_snapshot!("tasks", "deps", "--mermaid", @r###"
+        flowchart TD
+            n0["configtask"]
+            n1["filetask"]
+            n2["lint"]
+            n3["test"]
+            n1 --> n2
+            n1 --> n3
+        "###
+        );
+    }
 }
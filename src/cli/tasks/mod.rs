@@ -23,7 +23,7 @@ enum Commands {
     Deps(deps::TasksDeps),
     Edit(edit::TasksEdit),
     Ls(ls::TasksLs),
-    Run(run::Run),
+    Run(Box<run::Run>),
 }
 
 impl Commands {
@@ -10,7 +10,7 @@ use crate::cli::args::ToolArg;
 use crate::config::Config;
 use crate::toolset::{ToolRequest, ToolVersion, ToolsetBuilder};
 use crate::ui::multi_progress_report::MultiProgressReport;
-use crate::{backend, runtime_symlinks, shims};
+use crate::{backend, runtime_symlinks, shims, trash};
 
 /// Removes runtime versions
 #[derive(Debug, clap::Args)]
@@ -32,6 +32,7 @@ pub struct Uninstall {
 impl Uninstall {
     pub fn run(self) -> Result<()> {
         let config = Config::try_get()?;
+        trash::empty();
         let tool_versions = if self.installed_tool.is_empty() && self.all {
             self.get_all_tool_versions(&config)?
         } else {
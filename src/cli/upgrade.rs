@@ -4,12 +4,13 @@ use std::sync::Arc;
 use demand::DemandOption;
 use eyre::{Context, Result};
 
-use crate::backend::Backend;
+use crate::backend::{Backend, BackendType};
 use crate::cli::args::ToolArg;
-use crate::config::Config;
+use crate::config::{lockfile, Config};
 use crate::toolset::{InstallOptions, ToolVersion, ToolsetBuilder};
 use crate::ui::multi_progress_report::MultiProgressReport;
 use crate::ui::progress_report::SingleReport;
+use crate::{github, registry, trash};
 use crate::{runtime_symlinks, shims, ui};
 
 /// Upgrades outdated tool versions
@@ -39,6 +40,19 @@ pub struct Upgrade {
     /// Sets --jobs=1
     #[clap(long, overrides_with = "jobs")]
     raw: bool,
+
+    /// Print a summary of the upgrade
+    /// markdown prints a table of tools with old and new versions and links to the release notes
+    /// which can be used as the body of a pull request
+    #[clap(long, value_enum, default_value_t, verbatim_doc_comment)]
+    format: UpgradeFormat,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum UpgradeFormat {
+    #[default]
+    Text,
+    Markdown,
 }
 
 impl Upgrade {
@@ -67,8 +81,13 @@ impl Upgrade {
     }
 
     fn upgrade(&self, config: &Config, outdated: OutputVec) -> Result<()> {
+        trash::empty();
         let mpr = MultiProgressReport::get();
         let mut ts = ToolsetBuilder::new().with_args(&self.tool).build(config)?;
+        let summary = match self.format {
+            UpgradeFormat::Text => None,
+            UpgradeFormat::Markdown => Some(markdown_summary(&outdated)),
+        };
 
         let new_versions = outdated
             .iter()
@@ -92,14 +111,21 @@ impl Upgrade {
             for tv in &new_versions {
                 info!("Would install {tv}");
             }
+            if let Some(summary) = summary {
+                miseprint!("{summary}")?;
+            }
             return Ok(());
         }
         let opts = InstallOptions {
             force: false,
             jobs: self.jobs,
             raw: self.raw,
+            keep_download: false,
             latest_versions: true,
         };
+        for tv in &new_versions {
+            lockfile::unlock(&tv.backend.to_string())?;
+        }
         let new_versions = new_versions.into_iter().map(|tv| tv.request).collect();
         ts.install_versions(config, new_versions, &mpr, &opts)?;
         for (tool, tv) in to_remove {
@@ -107,9 +133,13 @@ impl Upgrade {
             self.uninstall_old_version(tool.clone(), &tv, pr.as_ref())?;
         }
 
+        lockfile::update(config)?;
         let ts = ToolsetBuilder::new().with_args(&self.tool).build(config)?;
         shims::reshim(&ts).wrap_err("failed to reshim")?;
         runtime_symlinks::rebuild(config)?;
+        if let Some(summary) = summary {
+            miseprint!("{summary}")?;
+        }
         Ok(())
     }
 
@@ -145,6 +175,52 @@ impl Upgrade {
 
 type OutputVec = Vec<(Arc<dyn Backend>, ToolVersion, String)>;
 
+fn markdown_summary(outdated: &OutputVec) -> String {
+    let mut out = String::from("## Tool upgrades\n\n");
+    out.push_str("| Tool | Old | New | Release notes |\n");
+    out.push_str("| --- | --- | --- | --- |\n");
+    for (tool, tv, latest) in outdated {
+        let notes = release_notes_url(tool.as_ref(), tv, latest)
+            .map(|url| format!("[{latest}]({url})"))
+            .unwrap_or_default();
+        out.push_str(&format!(
+            "| {} | {} | {latest} | {notes} |\n",
+            tool.id(),
+            tv.version
+        ));
+    }
+    out
+}
+
+/// link to the GitHub release of a version if the tool is hosted on GitHub, falls back to the
+/// list of releases if no release has a matching tag
+fn release_notes_url(tool: &dyn Backend, tv: &ToolVersion, version: &str) -> Option<String> {
+    let repo = match tool.get_type() {
+        BackendType::Ubi => tool.name().to_string(),
+        _ => registry::metadata(tool.fa(), &tv.request.options())?
+            .source?
+            .strip_prefix("https://github.com/")?
+            .trim_end_matches(".git")
+            .to_string(),
+    };
+    let releases = match github::list_releases(&repo) {
+        Ok(releases) => releases,
+        Err(e) => {
+            debug!("failed to fetch releases for {repo}: {e:#}");
+            return Some(format!("https://github.com/{repo}/releases"));
+        }
+    };
+    let release = releases.into_iter().find(|r| {
+        r.tag_name == version
+            || r.tag_name == format!("v{version}")
+            || r.tag_name.ends_with(&format!("-{version}"))
+    });
+    match release {
+        Some(release) => Some(release.html_url),
+        None => Some(format!("https://github.com/{repo}/releases")),
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use crate::dirs;
@@ -155,6 +231,9 @@ pub mod tests {
         reset();
         change_installed_version("tiny", "3.1.0", "3.0.0");
         assert_cli_snapshot!("upgrade", "--dry-run");
+        let out = assert_cli!("upgrade", "--dry-run", "--format", "markdown");
+        assert!(out.contains("| Tool | Old | New | Release notes |"));
+        assert!(out.contains("| tiny | 3.0.0 | 3.1.0 |  |"));
         assert_cli_snapshot!("upgrade");
         assert!(dirs::INSTALLS.join("tiny").join("3.1.0").exists());
     }
@@ -6,7 +6,7 @@ use itertools::Itertools;
 
 use crate::cli::args::{BackendArg, ToolArg};
 use crate::config::config_file::ConfigFile;
-use crate::config::{config_file, Config, Settings, LOCAL_CONFIG_FILENAMES};
+use crate::config::{config_file, lockfile, Config, Settings, LOCAL_CONFIG_FILENAMES};
 use crate::env::{MISE_DEFAULT_CONFIG_FILENAME, MISE_GLOBAL_CONFIG_FILE};
 use crate::file::display_path;
 use crate::toolset::{InstallOptions, ToolRequest, ToolSource, ToolVersion, ToolsetBuilder};
@@ -98,6 +98,7 @@ impl Use {
                 force: self.force,
                 jobs: self.jobs,
                 raw: self.raw,
+                keep_download: false,
                 latest_versions: false,
             },
         )?;
@@ -127,6 +128,7 @@ impl Use {
             cf.remove_plugin(plugin_name)?;
         }
         cf.save()?;
+        lockfile::update_file(cf.as_ref())?;
         self.render_success_message(cf.as_ref(), &versions)?;
         Ok(())
     }
@@ -0,0 +1,154 @@
+use eyre::{bail, Result};
+use itertools::Itertools;
+
+use crate::backend::receipt;
+use crate::cli::args::ToolArg;
+use crate::config::Config;
+use crate::toolset::{InstallOptions, Probe, ToolRequest, ToolsetBuilder};
+use crate::ui::multi_progress_report::MultiProgressReport;
+use crate::ui::style;
+
+/// Check installed tools for modified or missing files
+///
+/// Every file in an install is hashed after it is installed. This compares the
+/// installed files against those hashes to find installs that were changed
+/// after disk issues or tampering. Tools installed before receipts were
+/// recorded are skipped.
+///
+/// Tools with a `probe` tool option also run that command in the current
+/// directory to check that the active version is the pinned one.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct Verify {
+    /// Tool(s) to verify, defaults to every installed tool
+    /// e.g.: node@20
+    #[clap(value_name = "INSTALLED_TOOL@VERSION", verbatim_doc_comment)]
+    installed_tool: Vec<ToolArg>,
+
+    /// Reinstall tools that fail verification
+    #[clap(long)]
+    repair: bool,
+}
+
+impl Verify {
+    pub fn run(self) -> Result<()> {
+        let config = Config::try_get()?;
+        let mut ts = ToolsetBuilder::new().build(&config)?;
+        let tool_versions = ts
+            .list_installed_versions()?
+            .into_iter()
+            .filter(|(_, tv)| self.matches(tv.backend.to_string(), &tv.version))
+            .sorted_by_key(|(_, tv)| tv.to_string())
+            .collect_vec();
+
+        let mut corrupted = vec![];
+        for (_, tv) in tool_versions {
+            match receipt::verify(&tv)? {
+                None => miseprintln!("{tv}  {}", style::ndim("no receipt, skipped")),
+                Some(problems) if problems.is_empty() => {
+                    miseprintln!("{tv}  {}", style::ngreen("ok"))
+                }
+                Some(problems) => {
+                    miseprintln!("{tv}  {}", style::nred("failed"));
+                    for problem in problems {
+                        miseprintln!("  {problem}");
+                    }
+                    corrupted.push(tv);
+                }
+            }
+        }
+
+        let mut failed_probes = 0;
+        for (_, tv) in ts.list_current_versions() {
+            if !self.matches(tv.backend.to_string(), &tv.version) {
+                continue;
+            }
+            let Some(probe) = Probe::for_tv(&tv) else {
+                continue;
+            };
+            match probe.check() {
+                Ok(_) => miseprintln!("{tv}  {}", style::ngreen("probe ok")),
+                Err(err) => {
+                    miseprintln!("{tv}  {}", style::nred("probe failed"));
+                    miseprintln!("  {err}");
+                    failed_probes += 1;
+                }
+            }
+        }
+
+        if corrupted.is_empty() {
+            if failed_probes > 0 {
+                bail!("{failed_probes} probe(s) failed");
+            }
+            return Ok(());
+        }
+        if !self.repair {
+            bail!(
+                "{} install(s) failed verification, run {} to reinstall them",
+                corrupted.len(),
+                style::eyellow("mise verify --repair")
+            );
+        }
+        let requests = corrupted
+            .iter()
+            .map(|tv| ToolRequest::new(tv.backend.clone(), &tv.version))
+            .collect::<Result<Vec<_>>>()?;
+        let mpr = MultiProgressReport::get();
+        let opts = InstallOptions {
+            force: true,
+            ..InstallOptions::new()
+        };
+        ts.install_versions(&config, requests, &mpr, &opts)?;
+        Ok(())
+    }
+
+    fn matches(&self, backend: String, version: &str) -> bool {
+        self.installed_tool.is_empty()
+            || self.installed_tool.iter().any(|ta| {
+                ta.backend.to_string() == backend
+                    && match &ta.tvr {
+                        Some(tvr) => {
+                            let v = tvr.version();
+                            version == v || version.starts_with(&format!("{v}."))
+                        }
+                        None => true,
+                    }
+            })
+    }
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise verify</bold>
+    node@20.13.1  ok
+    python@3.12.3  failed
+      modified: bin/python3.12
+
+    $ <bold>mise verify --repair python</bold>
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::dirs;
+    use crate::file;
+    use crate::test::reset;
+
+    #[test]
+    fn test_verify() {
+        reset();
+        assert_cli!("install", "--force", "tiny@3.1.0");
+        let out = assert_cli!("verify", "tiny@3.1.0");
+        assert!(out.contains("tiny@3.1.0  ok"));
+
+        let bin = dirs::INSTALLS.join("tiny/3.1.0/bin/rtx-tiny");
+        file::write(&bin, "modified").unwrap();
+        let err = assert_cli_err!("verify", "tiny@3.1.0");
+        assert!(err.to_string().contains("1 install(s) failed verification"));
+
+        assert_cli!("verify", "--repair", "tiny@3.1.0");
+        let out = assert_cli!("verify", "tiny@3.1.0");
+        assert!(out.contains("tiny@3.1.0  ok"));
+    }
+}
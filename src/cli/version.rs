@@ -16,14 +16,33 @@ pub struct Version {}
 
 pub static OS: Lazy<String> = Lazy::new(|| std::env::consts::OS.into());
 pub static ARCH: Lazy<String> = Lazy::new(|| {
-    match std::env::consts::ARCH {
+    match *NATIVE_ARCH {
         "x86_64" => "x64",
         "aarch64" => "arm64",
-        _ => std::env::consts::ARCH,
+        arch => arch,
     }
     .to_string()
 });
 
+/// the architecture of the machine rather than the one mise was built for, x64 builds of mise
+/// run under emulation on windows arm64 but should still install arm64 tools
+pub static NATIVE_ARCH: Lazy<&'static str> = Lazy::new(|| {
+    let arch = std::env::consts::ARCH;
+    if cfg!(windows) && arch == "x86_64" && is_windows_arm64() {
+        "aarch64"
+    } else {
+        arch
+    }
+});
+
+fn is_windows_arm64() -> bool {
+    // emulated processes see AMD64 in PROCESSOR_ARCHITECTURE but the identifier is the real cpu
+    ["PROCESSOR_ARCHITECTURE", "PROCESSOR_ARCHITEW6432"]
+        .iter()
+        .any(|k| std::env::var(k).is_ok_and(|v| v.eq_ignore_ascii_case("arm64")))
+        || std::env::var("PROCESSOR_IDENTIFIER").is_ok_and(|v| v.starts_with("ARM"))
+}
+
 pub static VERSION: Lazy<String> = Lazy::new(|| {
     let mut v = V.to_string();
     if cfg!(debug_assertions) {
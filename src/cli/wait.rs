@@ -0,0 +1,143 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use eyre::{bail, Result};
+use itertools::Itertools;
+use serde_derive::Serialize;
+
+use crate::cli::args::BackendArg;
+use crate::config::Config;
+use crate::toolset::{Probe, ToolVersion, ToolsetBuilder};
+use crate::ui::style;
+
+/// Wait until the tools in the config are installed
+///
+/// This is useful in CI or containers when another process, e.g.: a
+/// background `mise install`, is installing the tools. A tool is ready
+/// once it is installed and, if it has a `probe` tool option, the probe passes.
+///
+/// Exits with a non-zero status if the tools are not ready before the timeout.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct Wait {
+    /// Tool(s) to wait for, defaults to every tool in the config
+    #[clap(value_name = "TOOL", verbatim_doc_comment)]
+    tool: Vec<BackendArg>,
+
+    /// How long to wait before giving up
+    #[clap(long, short, default_value = "10m")]
+    timeout: humantime::Duration,
+
+    /// Output the status of each tool in JSON format
+    #[clap(long, short = 'J')]
+    json: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct Status {
+    ready: bool,
+    tools: Vec<ToolStatus>,
+}
+
+#[derive(Debug, Serialize)]
+struct ToolStatus {
+    tool: String,
+    version: String,
+    installed: bool,
+    healthy: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl Wait {
+    pub fn run(self) -> Result<()> {
+        let config = Config::try_get()?;
+        let ts = ToolsetBuilder::new().build(&config)?;
+        let tool_versions = ts
+            .list_current_versions()
+            .into_iter()
+            .filter(|(_, tv)| self.tool.is_empty() || self.tool.contains(&tv.backend))
+            .sorted_by_key(|(_, tv)| tv.to_string())
+            .collect_vec();
+
+        let timeout: Duration = self.timeout.into();
+        let start = Instant::now();
+        let status = loop {
+            let tools = tool_versions
+                .iter()
+                .map(|(backend, tv)| check(tv, backend.is_version_installed(tv)))
+                .collect_vec();
+            let ready = tools.iter().all(|t| t.healthy);
+            if ready || start.elapsed() >= timeout {
+                break Status { ready, tools };
+            }
+            thread::sleep(Duration::from_secs(1));
+        };
+
+        if self.json {
+            miseprintln!("{}", serde_json::to_string_pretty(&status)?);
+        } else {
+            for t in &status.tools {
+                let msg = match (&t.error, t.healthy) {
+                    (_, true) => style::ngreen("ready").to_string(),
+                    (Some(err), false) => style::nred(err).to_string(),
+                    (None, false) => style::nyellow("not installed").to_string(),
+                };
+                miseprintln!("{}@{}  {msg}", t.tool, t.version);
+            }
+        }
+        if !status.ready {
+            let pending = status
+                .tools
+                .iter()
+                .filter(|t| !t.healthy)
+                .map(|t| format!("{}@{}", t.tool, t.version))
+                .join(", ");
+            bail!("timed out after {} waiting for {pending}", self.timeout);
+        }
+        Ok(())
+    }
+}
+
+fn check(tv: &ToolVersion, installed: bool) -> ToolStatus {
+    let mut status = ToolStatus {
+        tool: tv.backend.to_string(),
+        version: tv.version.clone(),
+        installed,
+        healthy: false,
+        error: None,
+    };
+    if !installed {
+        return status;
+    }
+    match Probe::for_tv(tv).map(|p| p.check()) {
+        Some(Err(err)) => status.error = Some(format!("probe failed: {err}")),
+        _ => status.healthy = true,
+    }
+    status
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise install &</bold>
+    $ <bold>mise wait --timeout 5m</bold>
+    node@20.13.1  ready
+    python@3.12.3  ready
+
+    $ <bold>mise wait --json node</bold>
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::test::reset;
+
+    #[test]
+    fn test_wait() {
+        reset();
+        assert_cli!("install", "tiny");
+        let out = assert_cli!("wait", "--timeout", "1s", "--json", "tiny");
+        assert!(out.contains(r#""ready": true"#));
+    }
+}
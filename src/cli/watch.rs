@@ -1,11 +1,16 @@
-use std::process::exit;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::Duration;
 
-use console::style;
 use eyre::{eyre, Result};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use itertools::Itertools;
+use notify::{EventKind, RecursiveMode, Watcher};
 
-use crate::cli::args::BackendArg;
-use crate::cmd;
 use crate::config::{Config, Settings};
+use crate::env;
 use crate::toolset::ToolsetBuilder;
 
 /// [experimental] Run a tasks watching for changes
@@ -16,7 +21,7 @@ pub struct Watch {
     #[clap(short, long, verbatim_doc_comment, default_value = "default")]
     task: Vec<String>,
 
-    /// Extra arguments
+    /// Arguments passed to the last task
     #[clap(allow_hyphen_values = true)]
     args: Vec<String>,
 
@@ -24,6 +29,15 @@ pub struct Watch {
     /// Defaults to sources from the tasks(s)
     #[clap(short, long, verbatim_doc_comment)]
     glob: Vec<String>,
+
+    /// How long to wait for more changes before running the tasks
+    #[clap(long, short, default_value = "50ms", verbatim_doc_comment)]
+    debounce: humantime::Duration,
+
+    /// Kill the running tasks and start them again when files change
+    /// By default, changes while the tasks are running start them again once they finish
+    #[clap(long, short, verbatim_doc_comment)]
+    restart: bool,
     // /// Change to this directory before executing the command
     // #[clap(short = 'C', long, value_hint = ValueHint::DirPath, long)]
     // pub cd: Option<PathBuf>,
@@ -71,15 +85,6 @@ impl Watch {
         let settings = Settings::try_get()?;
         let ts = ToolsetBuilder::new().build(&config)?;
         settings.ensure_experimental("`mise watch`")?;
-        if let Err(err) = which::which("watchexec") {
-            let watchexec: BackendArg = "watchexec".into();
-            if !ts.versions.contains_key(&watchexec) {
-                eprintln!("{}: {}", style("Error").red().bold(), err);
-                eprintln!("{}: Install watchexec with:", style("Hint").bold());
-                eprintln!("  mise use -g watchexec@latest");
-                exit(1);
-            }
-        }
         let tasks = self
             .task
             .iter()
@@ -91,34 +96,115 @@ impl Watch {
                     .ok_or_else(|| eyre!("Tasks not found: {t}"))
             })
             .collect::<Result<Vec<_>>>()?;
-        let mut args = vec![];
-        let globs = if self.glob.is_empty() {
+        let root = config
+            .project_root
+            .clone()
+            .unwrap_or_else(|| env::current_dir().unwrap());
+        // task sources are relative to the directory the task runs in
+        let patterns = if self.glob.is_empty() {
             tasks
                 .iter()
-                .flat_map(|t| t.sources.clone())
-                .collect::<Vec<_>>()
+                .flat_map(|t| {
+                    let dir = t.dir.clone().unwrap_or_else(|| root.clone());
+                    t.sources.iter().map(move |s| (dir.clone(), s.clone()))
+                })
+                .collect_vec()
         } else {
-            self.glob.clone()
+            self.glob
+                .iter()
+                .map(|g| (root.clone(), g.clone()))
+                .collect()
         };
-        if !globs.is_empty() {
-            args.push("-f".to_string());
-            args.extend(itertools::intersperse(globs, "-f".to_string()).collect::<Vec<_>>());
+        let globs = build_globs(&patterns)?;
+        let mut dirs = patterns
+            .iter()
+            .map(|(dir, _)| dir.clone())
+            .collect::<BTreeSet<_>>();
+        dirs.insert(root.clone());
+
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(tx)?;
+        for dir in &dirs {
+            watcher.watch(dir, RecursiveMode::Recursive)?;
         }
+
+        let mut args = vec!["run".to_string()];
+        args.extend(itertools::intersperse(
+            tasks.iter().map(|t| t.name.clone()),
+            ":::".to_string(),
+        ));
         args.extend(self.args.clone());
-        args.extend(["--".to_string(), "mise".to_string(), "run".to_string()]);
-        for arg in itertools::intersperse(tasks.iter().map(|t| t.name.as_str()), ":::") {
-            args.push(arg.to_string());
-        }
-        info!("$ watchexec {}", args.join(" "));
-        let mut cmd = cmd::cmd("watchexec", &args);
-        for (k, v) in ts.env_with_path(&config)? {
-            cmd = cmd.env(k, v);
+        let env = ts.env_with_path(&config)?;
+        let spawn = || -> Result<Child> {
+            info!("$ mise {}", args.join(" "));
+            Ok(Command::new(&*env::MISE_BIN)
+                .args(&args)
+                .envs(&env)
+                .current_dir(&root)
+                .spawn()?)
+        };
+
+        let debounce: Duration = self.debounce.into();
+        let mut child = spawn()?;
+        loop {
+            let changed = wait_for_change(&rx, &globs, debounce)?;
+            debug!(
+                "changed: {}",
+                changed.iter().map(|p| p.display()).join(", ")
+            );
+            if self.restart {
+                child.kill()?;
+            }
+            child.wait()?;
+            // changes made while the tasks ran are covered by the next run
+            while rx.try_recv().is_ok() {}
+            child = spawn()?;
         }
-        if let Some(root) = &config.project_root {
-            cmd = cmd.dir(root);
+    }
+}
+
+/// globs from tasks are relative to their own directory, so they are matched against absolute paths
+fn build_globs(patterns: &[(PathBuf, String)]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for (dir, pattern) in patterns {
+        let pattern = dir.join(pattern);
+        let glob = GlobBuilder::new(&pattern.to_string_lossy())
+            .literal_separator(true)
+            .build()?;
+        builder.add(glob);
+    }
+    Ok(builder.build()?)
+}
+
+fn is_match(globs: &GlobSet, path: &Path) -> bool {
+    if path.components().any(|c| c.as_os_str() == ".git") {
+        return false;
+    }
+    // without any globs every file is watched
+    globs.is_empty() || globs.is_match(path)
+}
+
+/// blocks until a matching file changes and no more changes happen for `debounce`
+fn wait_for_change(
+    rx: &Receiver<notify::Result<notify::Event>>,
+    globs: &GlobSet,
+    debounce: Duration,
+) -> Result<BTreeSet<PathBuf>> {
+    let mut changed = BTreeSet::new();
+    loop {
+        let event = match changed.is_empty() {
+            true => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            false => rx.recv_timeout(debounce),
+        };
+        let event = match event {
+            Ok(event) => event?,
+            Err(RecvTimeoutError::Timeout) => return Ok(changed),
+            Err(RecvTimeoutError::Disconnected) => return Err(eyre!("file watcher stopped")),
+        };
+        if matches!(event.kind, EventKind::Access(_)) {
+            continue;
         }
-        cmd.run()?;
-        Ok(())
+        changed.extend(event.paths.into_iter().filter(|p| is_match(globs, p)));
     }
 }
 
@@ -132,7 +218,32 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
     Runs the "build" tasks but specify the files to watch with a glob pattern.
     This overrides the "sources" from the tasks definition.
 
-    $ <bold>mise run -t build --clear</bold>
-    Extra arguments are passed to watchexec. See `watchexec --help` for details.
+    $ <bold>mise watch -t serve --restart</bold>
+    Kills the "serve" tasks and starts it again when its sources change.
 "#
 );
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::{build_globs, is_match};
+
+    #[test]
+    fn test_is_match() {
+        let globs = build_globs(&[
+            (PathBuf::from("/p"), "src/**/*.rs".to_string()),
+            (PathBuf::from("/p/web"), "*.ts".to_string()),
+        ])
+        .unwrap();
+        assert!(is_match(&globs, Path::new("/p/src/main.rs")));
+        assert!(is_match(&globs, Path::new("/p/src/cli/mod.rs")));
+        assert!(is_match(&globs, Path::new("/p/web/index.ts")));
+        assert!(!is_match(&globs, Path::new("/p/web/src/index.ts")));
+        assert!(!is_match(&globs, Path::new("/p/README.md")));
+
+        let all = build_globs(&[]).unwrap();
+        assert!(is_match(&all, Path::new("/p/README.md")));
+        assert!(!is_match(&all, Path::new("/p/.git/index")));
+    }
+}
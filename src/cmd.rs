@@ -3,9 +3,10 @@ use std::fmt::{Display, Formatter};
 use std::io::{BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
 use std::process::{Command, ExitStatus, Stdio};
-use std::sync::mpsc::channel;
+use std::sync::mpsc::{channel, RecvTimeoutError};
 use std::sync::{Mutex, RwLock};
 use std::thread;
+use std::time::{Duration, Instant};
 
 use color_eyre::Result;
 use duct::{Expression, IntoExecutablePath};
@@ -17,7 +18,7 @@ use signal_hook::iterator::Signals;
 
 use crate::config::Settings;
 use crate::env;
-use crate::errors::Error::ScriptFailed;
+use crate::errors::Error::{ScriptFailed, ScriptTimedOut};
 use crate::file::display_path;
 use crate::ui::progress_report::SingleReport;
 
@@ -100,6 +101,21 @@ pub struct CmdLineRunner<'a> {
     prefix: String,
     raw: bool,
     pass_signals: bool,
+    on_spawn: Option<Box<dyn Fn(u32) + 'a>>,
+    output: OutputMode,
+    timeout: Option<Duration>,
+}
+
+/// how the output of a command without a progress report is printed
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OutputMode {
+    /// each line as soon as it is written
+    #[default]
+    Stream,
+    /// every line at once after the command exits so it isn't mixed with other commands
+    Group,
+    /// nothing unless the command fails
+    Quiet,
 }
 
 static OUTPUT_LOCK: Mutex<()> = Mutex::new(());
@@ -118,6 +134,9 @@ impl<'a> CmdLineRunner<'a> {
             prefix: String::new(),
             raw: false,
             pass_signals: false,
+            on_spawn: None,
+            output: OutputMode::Stream,
+            timeout: None,
         }
     }
 
@@ -214,11 +233,30 @@ impl<'a> CmdLineRunner<'a> {
         self
     }
 
+    pub fn with_output(&mut self, output: OutputMode) -> &mut Self {
+        self.output = output;
+        self
+    }
+
+    /// stops the command if it is still running after `timeout`, on unix it runs in its own
+    /// process group so the processes it started are stopped too. Raw commands stay in mise's
+    /// process group so they can use the terminal, only the command itself is stopped.
+    pub fn with_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
     pub fn with_pass_signals(&mut self) -> &mut Self {
         self.pass_signals = true;
         self
     }
 
+    /// called with the pid of the process once it has started
+    pub fn on_spawn(mut self, f: impl Fn(u32) + 'a) -> Self {
+        self.on_spawn = Some(Box::new(f));
+        self
+    }
+
     pub fn stdin_string(mut self, input: impl Into<String>) -> Self {
         self.cmd.stdin(Stdio::piped());
         self.stdin = Some(input.into());
@@ -236,10 +274,19 @@ impl<'a> CmdLineRunner<'a> {
             let _write_lock = RAW_LOCK.write().unwrap();
             return self.execute_raw();
         }
+        let group = cfg!(unix) && self.timeout.is_some();
+        #[cfg(unix)]
+        if group {
+            use std::os::unix::process::CommandExt;
+            self.cmd.process_group(0);
+        }
         let mut cp = self
             .cmd
             .spawn()
             .wrap_err_with(|| format!("failed to execute command: {self}"))?;
+        if let Some(on_spawn) = &self.on_spawn {
+            on_spawn(cp.id());
+        }
         let (tx, rx) = channel();
         if let Some(stdout) = cp.stdout.take() {
             thread::spawn({
@@ -283,7 +330,6 @@ impl<'a> CmdLineRunner<'a> {
                 }
             });
         }
-        #[cfg(not(any(test, target_os = "windows")))]
         let id = cp.id();
         thread::spawn(move || {
             let status = cp.wait().unwrap();
@@ -294,30 +340,88 @@ impl<'a> CmdLineRunner<'a> {
             tx.send(ChildProcessOutput::ExitStatus(status)).unwrap();
         });
         let mut combined_output = vec![];
+        let mut held = vec![];
         let mut status = None;
-        for line in rx {
+        let deadline = self.timeout.map(|t| Instant::now() + t);
+        let mut timed_out = false;
+        let mut killed = false;
+        loop {
+            let line = match deadline {
+                Some(deadline) if !killed => {
+                    // after SIGTERM the command has KILL_GRACE to exit before it gets SIGKILL
+                    let deadline = match timed_out {
+                        true => deadline + KILL_GRACE,
+                        false => deadline,
+                    };
+                    match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                        Ok(line) => line,
+                        Err(RecvTimeoutError::Timeout) => {
+                            killed = timed_out;
+                            timed_out = true;
+                            kill(id, group, killed);
+                            continue;
+                        }
+                        Err(RecvTimeoutError::Disconnected) => break,
+                    }
+                }
+                _ => match rx.recv() {
+                    Ok(line) => line,
+                    Err(_) => break,
+                },
+            };
             match line {
                 ChildProcessOutput::Stdout(line) => {
-                    self.on_stdout(&line);
+                    match self.output {
+                        OutputMode::Stream => self.on_stdout(&line),
+                        _ => held.push((false, line.clone())),
+                    }
                     combined_output.push(line);
                 }
                 ChildProcessOutput::Stderr(line) => {
-                    self.on_stderr(&line);
+                    match self.output {
+                        OutputMode::Stream => self.on_stderr(&line),
+                        _ => held.push((true, line.clone())),
+                    }
                     combined_output.push(line);
                 }
                 ChildProcessOutput::ExitStatus(s) => {
                     status = Some(s);
+                    // processes started by the command may still have its output open
+                    if timed_out {
+                        if group && !killed {
+                            kill(id, group, true);
+                        }
+                        break;
+                    }
                 }
                 #[cfg(not(any(test, target_os = "windows")))]
                 ChildProcessOutput::Signal(sig) => {
-                    if sig != SIGINT {
-                        cmd!("kill", format!("-{sig}"), id.to_string()).run()?;
+                    // commands in their own process group don't get ctrl-c from the terminal
+                    if sig != SIGINT || group {
+                        let target = match group {
+                            true => format!("-{id}"),
+                            false => id.to_string(),
+                        };
+                        cmd!("kill", format!("-{sig}"), "--", target).run()?;
                     }
                 }
             }
         }
         let status = status.unwrap();
 
+        if self.output == OutputMode::Group || !status.success() {
+            // held lines are printed together so they aren't mixed with other commands
+            let _lock = OUTPUT_LOCK.lock().unwrap();
+            for (is_stderr, line) in held {
+                match is_stderr {
+                    true => self.print_stderr(&line),
+                    false => self.print_stdout(&line),
+                }
+            }
+        }
+        if let (true, Some(timeout)) = (timed_out, self.timeout) {
+            Err(ScriptTimedOut(self.get_program(), timeout))?;
+        }
         if !status.success() {
             self.on_error(combined_output.join("\n"), status)?;
         }
@@ -326,7 +430,30 @@ impl<'a> CmdLineRunner<'a> {
     }
 
     fn execute_raw(mut self) -> Result<()> {
-        let status = self.cmd.spawn()?.wait()?;
+        let mut cp = self.cmd.spawn()?;
+        if let Some(on_spawn) = &self.on_spawn {
+            on_spawn(cp.id());
+        }
+        let status = match self.timeout {
+            Some(timeout) => {
+                let id = cp.id();
+                let (tx, rx) = channel();
+                thread::spawn(move || {
+                    let _ = tx.send(cp.wait());
+                });
+                match rx.recv_timeout(timeout) {
+                    Ok(status) => status?,
+                    Err(_) => {
+                        kill(id, false, false);
+                        if rx.recv_timeout(KILL_GRACE).is_err() {
+                            kill(id, false, true);
+                        }
+                        Err(ScriptTimedOut(self.get_program(), timeout))?
+                    }
+                }
+            }
+            None => cp.wait()?,
+        };
         match status.success() {
             true => Ok(()),
             false => self.on_error(String::new(), status),
@@ -339,10 +466,8 @@ impl<'a> CmdLineRunner<'a> {
             if !line.trim().is_empty() {
                 pr.set_message(line.into())
             }
-        } else if console::colors_enabled() {
-            println!("{}{line}\x1b[0m", self.prefix);
         } else {
-            println!("{}{line}", self.prefix);
+            self.print_stdout(line);
         }
     }
 
@@ -354,13 +479,23 @@ impl<'a> CmdLineRunner<'a> {
                     pr.println(line.into())
                 }
             }
-            None => {
-                if console::colors_enabled() {
-                    eprintln!("{}{line}\x1b[0m", self.prefix);
-                } else {
-                    eprintln!("{}{line}", self.prefix);
-                }
-            }
+            None => self.print_stderr(line),
+        }
+    }
+
+    fn print_stdout(&self, line: &str) {
+        if console::colors_enabled() {
+            println!("{}{line}\x1b[0m", self.prefix);
+        } else {
+            println!("{}{line}", self.prefix);
+        }
+    }
+
+    fn print_stderr(&self, line: &str) {
+        if console::colors_enabled() {
+            eprintln!("{}{line}\x1b[0m", self.prefix);
+        } else {
+            eprintln!("{}{line}", self.prefix);
         }
     }
 
@@ -399,6 +534,37 @@ impl Display for CmdLineRunner<'_> {
     }
 }
 
+/// how long a command that ran out of time has to exit after SIGTERM before it gets SIGKILL
+const KILL_GRACE: Duration = Duration::from_secs(5);
+
+/// stops a command that ran out of time, it may have exited already
+/// `group` signals its whole process group, `force` sends SIGKILL instead of SIGTERM
+#[cfg(unix)]
+fn kill(id: u32, group: bool, force: bool) {
+    let target = match group {
+        true => format!("-{id}"),
+        false => id.to_string(),
+    };
+    let sig = match force {
+        true => "-KILL",
+        false => "-TERM",
+    };
+    let _ = cmd!("kill", sig, "--", target)
+        .stdout_null()
+        .stderr_null()
+        .unchecked()
+        .run();
+}
+
+#[cfg(windows)]
+fn kill(id: u32, _group: bool, _force: bool) {
+    let _ = cmd!("taskkill", "/PID", id.to_string(), "/T", "/F")
+        .stdout_null()
+        .stderr_null()
+        .unchecked()
+        .run();
+}
+
 enum ChildProcessOutput {
     Stdout(String),
     Stderr(String),
@@ -411,11 +577,39 @@ enum ChildProcessOutput {
 mod tests {
     use pretty_assertions::assert_eq;
 
+    use std::time::{Duration, Instant};
+
     use crate::cmd;
+    use crate::errors::Error::{self, ScriptTimedOut};
+    use crate::test::reset;
+
+    use super::CmdLineRunner;
 
     #[test]
     fn test_cmd() {
         let output = cmd!("echo", "foo", "bar").read().unwrap();
         assert_eq!("foo bar", output);
     }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_timeout() {
+        reset();
+        for raw in [false, true] {
+            let start = Instant::now();
+            let mut cmd = CmdLineRunner::new("sh")
+                .arg("-c")
+                .arg("sleep 10 & sleep 10");
+            cmd.with_timeout(Duration::from_millis(100));
+            if raw {
+                cmd.with_raw();
+            }
+            let err = cmd.execute().unwrap_err();
+            assert!(matches!(
+                err.downcast_ref::<Error>(),
+                Some(ScriptTimedOut(..))
+            ));
+            assert!(start.elapsed() < Duration::from_secs(5));
+        }
+    }
 }
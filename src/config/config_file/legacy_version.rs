@@ -5,6 +5,8 @@ use eyre::Result;
 use crate::backend::BackendList;
 use crate::cli::args::BackendArg;
 use crate::config::config_file::ConfigFile;
+use crate::config::Settings;
+use crate::file;
 use crate::toolset::{ToolRequest, ToolRequestSet, ToolSource};
 
 #[derive(Debug)]
@@ -25,11 +27,36 @@ impl LegacyVersionFile {
                 tools.add_version(tr, &source);
             }
         }
+        if path.file_name().is_some_and(|f| f == "package.json")
+            && Settings::get().package_manager_version_file
+        {
+            if let Some((fa, version)) = package_manager(&file::read_to_string(&path)?)? {
+                tools.add_version(ToolRequest::new(fa, &version)?, &source);
+            }
+        }
 
         Ok(Self { tools, path })
     }
 }
 
+/// parses the corepack `packageManager` field of package.json, e.g.: "pnpm@9.1.0+sha512.abc"
+fn package_manager(package_json: &str) -> Result<Option<(BackendArg, String)>> {
+    let package_json: serde_json::Value = serde_json::from_str(package_json)?;
+    let Some(package_manager) = package_json["packageManager"].as_str() else {
+        return Ok(None);
+    };
+    let Some((name, version)) = package_manager.split_once('@') else {
+        return Ok(None);
+    };
+    let version = version.split('+').next().unwrap_or_default().to_string();
+    // yarn 2+ is not published as "yarn" on npm
+    let package = match name {
+        "yarn" if !version.starts_with("1.") => "@yarnpkg/cli-dist",
+        name => name,
+    };
+    Ok(Some((format!("npm:{package}").into(), version)))
+}
+
 impl ConfigFile for LegacyVersionFile {
     fn get_path(&self) -> &Path {
         self.path.as_path()
@@ -55,3 +82,32 @@ impl ConfigFile for LegacyVersionFile {
         Ok(self.tools.clone())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_package_manager() {
+        let pm = |s: &str| {
+            package_manager(s)
+                .unwrap()
+                .map(|(fa, v)| (fa.to_string(), v))
+        };
+        assert_eq!(
+            pm(r#"{"packageManager": "pnpm@9.1.0+sha512.abc"}"#),
+            Some(("npm:pnpm".to_string(), "9.1.0".to_string()))
+        );
+        assert_eq!(
+            pm(r#"{"packageManager": "yarn@4.2.1"}"#),
+            Some(("npm:@yarnpkg/cli-dist".to_string(), "4.2.1".to_string()))
+        );
+        assert_eq!(
+            pm(r#"{"packageManager": "yarn@1.22.22"}"#),
+            Some(("npm:yarn".to_string(), "1.22.22".to_string()))
+        );
+        assert_eq!(pm(r#"{"name": "foo"}"#), None);
+    }
+}
@@ -13,16 +13,21 @@ use tera::Context as TeraContext;
 use toml_edit::{table, value, Array, DocumentMut, Item, Value};
 use versions::Versioning;
 
-use crate::cli::args::{BackendArg, ToolVersionType};
+use crate::backend::BackendType;
+use crate::cli::args::{BackendArg, ToolArg, ToolVersionType};
+use crate::cli::version::{ARCH, OS};
 use crate::config::config_file::toml::deserialize_arr;
-use crate::config::config_file::{trust_check, ConfigFile, TaskConfig};
+use crate::config::config_file::{trust_check, ConfigFile, Hooks, TaskConfig};
 use crate::config::env_directive::EnvDirective;
 use crate::config::settings::SettingsPartial;
 use crate::config::AliasMap;
 use crate::file::{create_dir_all, display_path};
-use crate::task::Task;
+use crate::registry::ToolAlias;
+use crate::task::{RunEntry, Task};
 use crate::tera::{get_tera, BASE_CONTEXT};
-use crate::toolset::{ToolRequest, ToolRequestSet, ToolSource, ToolVersionOptions};
+use crate::toolset::{
+    platform_matches, ToolRequest, ToolRequestSet, ToolSource, ToolVersionOptions,
+};
 use crate::{dirs, file};
 
 #[derive(Default, Deserialize)]
@@ -30,6 +35,10 @@ use crate::{dirs, file};
 pub struct MiseToml {
     #[serde(default, deserialize_with = "deserialize_version")]
     min_version: Option<Versioning>,
+    #[serde(default)]
+    inherit: Option<bool>,
+    #[serde(default)]
+    inherit_env: Vec<String>,
     #[serde(skip)]
     context: TeraContext,
     #[serde(skip)]
@@ -53,6 +62,8 @@ pub struct MiseToml {
     #[serde(default)]
     tasks: Tasks,
     #[serde(default)]
+    hooks: Hooks,
+    #[serde(default)]
     settings: SettingsPartial,
 }
 
@@ -246,6 +257,14 @@ impl ConfigFile for MiseToml {
         Ok(all)
     }
 
+    fn inherit(&self) -> bool {
+        self.inherit.unwrap_or(true)
+    }
+
+    fn inherit_env(&self) -> &[String] {
+        &self.inherit_env
+    }
+
     fn tasks(&self) -> Vec<&Task> {
         self.tasks.0.values().collect()
     }
@@ -317,8 +336,28 @@ impl ConfigFile for MiseToml {
                 for v in options.values_mut() {
                     *v = self.parse_template(v)?;
                 }
-                let tvr = ToolRequest::new_opts(fa.clone(), &version, options)?;
-                trs.add_version(tvr, &source);
+                if ["patches", "post_extract", "postinstall", "preuninstall"]
+                    .iter()
+                    .any(|k| options.contains_key(*k))
+                {
+                    trust_check(&self.path)?;
+                }
+                if let Some(patches) = options.get_mut("patches") {
+                    let root = self.project_root().or(self.path.parent()).unwrap();
+                    *patches = patches
+                        .split(',')
+                        .map(|p| p.trim())
+                        .filter(|p| !p.is_empty())
+                        .map(|p| root.join(p).to_string_lossy().to_string())
+                        .join(",");
+                }
+                let fa = match is_system(&options) {
+                    true => BackendArg::new(BackendType::System, &fa.name),
+                    false => fa.clone(),
+                };
+                if let Some(tvr) = platform_tool_request(&fa, &version, options)? {
+                    trs.add_version(tvr, &source);
+                }
             }
         }
         Ok(trs)
@@ -331,6 +370,10 @@ impl ConfigFile for MiseToml {
     fn task_config(&self) -> &TaskConfig {
         &self.task_config
     }
+
+    fn hooks(&self) -> &Hooks {
+        &self.hooks
+    }
 }
 
 impl Debug for MiseToml {
@@ -341,6 +384,12 @@ impl Debug for MiseToml {
         if let Some(min_version) = &self.min_version {
             d.field("min_version", &min_version.to_string());
         }
+        if let Some(inherit) = self.inherit {
+            d.field("inherit", &inherit);
+        }
+        if !self.inherit_env.is_empty() {
+            d.field("inherit_env", &self.inherit_env);
+        }
         if !self.env_file.is_empty() {
             d.field("env_file", &self.env_file);
         }
@@ -355,9 +404,12 @@ impl Debug for MiseToml {
         if !self.plugins.is_empty() {
             d.field("plugins", &self.plugins);
         }
-        if self.task_config.includes.is_some() {
+        if self.task_config.includes.is_some() || self.task_config.members.is_some() {
             d.field("task_config", &self.task_config);
         }
+        if !self.hooks.is_empty() {
+            d.field("hooks", &self.hooks);
+        }
         d.finish()
     }
 }
@@ -366,6 +418,8 @@ impl Clone for MiseToml {
     fn clone(&self) -> Self {
         Self {
             min_version: self.min_version.clone(),
+            inherit: self.inherit,
+            inherit_env: self.inherit_env.clone(),
             context: self.context.clone(),
             path: self.path.clone(),
             env_file: self.env_file.clone(),
@@ -377,6 +431,7 @@ impl Clone for MiseToml {
             plugins: self.plugins.clone(),
             tasks: self.tasks.clone(),
             task_config: self.task_config.clone(),
+            hooks: self.hooks.clone(),
             settings: self.settings.clone(),
         }
     }
@@ -544,6 +599,7 @@ impl<'de> de::Deserialize<'de> for EnvList {
                                 Int(i64),
                                 Str(String),
                                 Bool(bool),
+                                Age(String),
                             }
 
                             impl<'de> de::Deserialize<'de> for Val {
@@ -590,6 +646,29 @@ impl<'de> de::Deserialize<'de> for EnvList {
                                         {
                                             Ok(Val::Str(v.to_string()))
                                         }
+
+                                        fn visit_map<M>(
+                                            self,
+                                            mut map: M,
+                                        ) -> Result<Self::Value, M::Error>
+                                        where
+                                            M: de::MapAccess<'de>,
+                                        {
+                                            let mut age = None;
+                                            while let Some(key) = map.next_key::<String>()? {
+                                                match key.as_str() {
+                                                    "age" => age = Some(map.next_value()?),
+                                                    _ => {
+                                                        return Err(de::Error::unknown_field(
+                                                            &key,
+                                                            &["age"],
+                                                        ));
+                                                    }
+                                                }
+                                            }
+                                            age.map(Val::Age)
+                                                .ok_or_else(|| de::Error::missing_field("age"))
+                                        }
                                     }
 
                                     deserializer.deserialize_any(ValVisitor)
@@ -605,6 +684,9 @@ impl<'de> de::Deserialize<'de> for EnvList {
                                     env.push(EnvDirective::Val(key, s));
                                 }
                                 Val::Bool(_b) => env.push(EnvDirective::Rm(key)),
+                                Val::Age(ciphertext) => {
+                                    env.push(EnvDirective::Age(key, ciphertext));
+                                }
                             }
                         }
                     }
@@ -658,13 +740,15 @@ impl<'de> de::Deserialize<'de> for MiseTomlToolList {
             where
                 M: de::MapAccess<'de>,
             {
-                let mut options: BTreeMap<String, String> =
+                let options: BTreeMap<String, MiseTomlToolOption> =
                     de::Deserialize::deserialize(de::value::MapAccessDeserializer::new(map))?;
+                let mut options = flatten_tool_options(options);
                 let tt: ToolVersionType = options
                     .remove("version")
                     .or_else(|| options.remove("path").map(|p| format!("path:{p}")))
                     .or_else(|| options.remove("prefix").map(|p| format!("prefix:{p}")))
                     .or_else(|| options.remove("ref").map(|p| format!("ref:{p}")))
+                    .or_else(|| is_system(&options).then(|| "system".to_string()))
                     .ok_or_else(|| de::Error::custom("missing version"))?
                     .parse()
                     .map_err(de::Error::custom)?;
@@ -706,13 +790,15 @@ impl<'de> de::Deserialize<'de> for MiseTomlTool {
             where
                 M: de::MapAccess<'de>,
             {
-                let mut options: BTreeMap<String, String> =
+                let options: BTreeMap<String, MiseTomlToolOption> =
                     de::Deserialize::deserialize(de::value::MapAccessDeserializer::new(map))?;
+                let mut options = flatten_tool_options(options);
                 let tt: ToolVersionType = options
                     .remove("version")
                     .or_else(|| options.remove("path").map(|p| format!("path:{p}")))
                     .or_else(|| options.remove("prefix").map(|p| format!("prefix:{p}")))
                     .or_else(|| options.remove("ref").map(|p| format!("ref:{p}")))
+                    .or_else(|| is_system(&options).then(|| "system".to_string()))
                     .ok_or_else(|| de::Error::custom("missing version"))?
                     .parse()
                     .map_err(de::Error::custom)?;
@@ -759,7 +845,7 @@ impl<'de> de::Deserialize<'de> for Tasks {
                                 E: de::Error,
                             {
                                 Ok(TaskDef(Task {
-                                    run: vec![v.to_string()],
+                                    run: vec![v.to_string().into()],
                                     ..Default::default()
                                 }))
                             }
@@ -769,8 +855,8 @@ impl<'de> de::Deserialize<'de> for Tasks {
                                 S: de::SeqAccess<'de>,
                             {
                                 let mut run = vec![];
-                                while let Some(s) = seq.next_element::<String>()? {
-                                    run.push(s);
+                                while let Some(entry) = seq.next_element::<RunEntry>()? {
+                                    run.push(entry);
                                 }
                                 Ok(TaskDef(Task {
                                     run,
@@ -830,6 +916,72 @@ impl<'de> de::Deserialize<'de> for BackendArg {
     }
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum MiseTomlToolOption {
+    String(String),
+    List(Vec<String>),
+    Bool(bool),
+}
+
+/// tool options are strings, lists like `patches = ["a.patch", "b.patch"]` become "a.patch,b.patch"
+/// and booleans like `binstall = false` become "false"
+fn flatten_tool_options(options: BTreeMap<String, MiseTomlToolOption>) -> ToolVersionOptions {
+    options
+        .into_iter()
+        .map(|(k, v)| match v {
+            MiseTomlToolOption::String(s) => (k, s),
+            MiseTomlToolOption::List(l) => (k, l.join(",")),
+            MiseTomlToolOption::Bool(b) => (k, b.to_string()),
+        })
+        .collect()
+}
+
+/// handles tools that are not available on every os/arch with the `platforms` tool option
+/// e.g.: `platforms = "linux, macos-arm64"`
+/// on other platforms `unsupported` decides what happens:
+/// "skip" (default), "error", or another tool to use instead like "cargo:tool@1"
+/// "error" keeps the tool so that installing or using it fails instead of loading the config
+/// `unsupported_message` is displayed when the tool is skipped or fails
+/// `backend = "system"` turns any tool into a system tool that mise checks but does not install
+fn is_system(options: &ToolVersionOptions) -> bool {
+    options.get("backend").is_some_and(|b| b == "system")
+}
+
+fn platform_tool_request(
+    fa: &BackendArg,
+    version: &str,
+    options: ToolVersionOptions,
+) -> eyre::Result<Option<ToolRequest>> {
+    let supported = match options.get("platforms") {
+        Some(platforms) => platform_matches(platforms, &OS, &ARCH),
+        None => true,
+    };
+    if supported {
+        return Ok(Some(ToolRequest::new_opts(fa.clone(), version, options)?));
+    }
+    let message = options.get("unsupported_message");
+    let default_message = || format!("{fa} is not available on {}-{}", *OS, *ARCH);
+    match options.get("unsupported").map(|s| s.as_str()) {
+        None | Some("skip") => {
+            match message {
+                Some(message) => warn!("{message}"),
+                None => debug!("{}, skipping", default_message()),
+            }
+            Ok(None)
+        }
+        Some("error") => Ok(Some(ToolRequest::new_opts(fa.clone(), version, options)?)),
+        Some(alternative) => {
+            let ta: ToolArg = alternative.parse()?;
+            debug!("{}, using {alternative} instead", default_message());
+            match ta.tvr {
+                Some(tvr) => Ok(Some(tvr)),
+                None => Ok(Some(ToolRequest::new(ta.backend, version)?)),
+            }
+        }
+    }
+}
+
 fn deserialize_alias<'de, D>(deserializer: D) -> Result<AliasMap, D::Error>
 where
     D: Deserializer<'de>,
@@ -848,9 +1000,18 @@ where
         {
             let mut aliases = AliasMap::new();
             while let Some(plugin) = map.next_key::<String>()? {
+                let value = map.next_value::<toml::Value>()?;
+                // tool aliases like `sourcery = "spm:krzysztofzablocki/Sourcery"` are read by the
+                // registry before config files are parsed
+                if ToolAlias::parse(&value).is_some() {
+                    continue;
+                }
                 let fa: BackendArg = plugin.as_str().into();
                 let plugin_aliases = aliases.entry(fa).or_default();
-                for (from, to) in map.next_value::<BTreeMap<String, String>>()? {
+                for (from, to) in value
+                    .try_into::<BTreeMap<String, String>>()
+                    .map_err(de::Error::custom)?
+                {
                     plugin_aliases.insert(from, to);
                 }
             }
@@ -1195,6 +1356,51 @@ mod tests {
         "###);
     }
 
+    #[test]
+    fn test_platform_tool_request() {
+        reset();
+        let fa: BackendArg = "tiny".into();
+        let opts = |o: &[(&str, &str)]| -> ToolVersionOptions {
+            o.iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+        let current = format!("{}-{}", *OS, *ARCH);
+        let tvr = platform_tool_request(&fa, "3", opts(&[("platforms", &current)])).unwrap();
+        assert_eq!(tvr.unwrap().backend(), &fa);
+
+        let unsupported = |o: &[(&str, &str)]| {
+            let mut o = o.to_vec();
+            o.push(("platforms", "plan9"));
+            platform_tool_request(&fa, "3", opts(&o))
+        };
+        assert!(unsupported(&[]).unwrap().is_none());
+        assert!(unsupported(&[("unsupported", "skip")]).unwrap().is_none());
+        let tvr = unsupported(&[("unsupported", "error"), ("unsupported_message", "no tiny")])
+            .unwrap()
+            .unwrap();
+        assert_eq!(tvr.ensure_supported().unwrap_err().to_string(), "no tiny");
+        let tvr = unsupported(&[("unsupported", "dummy")]).unwrap().unwrap();
+        assert_eq!(tvr.to_string(), "dummy@3");
+        let tvr = unsupported(&[("unsupported", "dummy@1.0.0")])
+            .unwrap()
+            .unwrap();
+        assert_eq!(tvr.to_string(), "dummy@1.0.0");
+    }
+
+    #[test]
+    fn test_tool_option_list() {
+        reset();
+        let cfg = parse(formatdoc! {r#"
+        [tools]
+        tiny = {{ version = "3", patches = ["a.patch", "b.patch"], post_extract = "true" }}
+        "#});
+        let fa: BackendArg = "tiny".into();
+        let options = &cfg.tools.get(&fa).unwrap().0[0].options;
+        assert_eq!(options.get("patches").unwrap(), "a.patch,b.patch");
+        assert_eq!(options.get("post_extract").unwrap(), "true");
+    }
+
     fn parse(s: String) -> MiseToml {
         let p = CWD.as_ref().unwrap().join(".test.mise.toml");
         file::write(&p, s).unwrap();
@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::ffi::OsStr;
 use std::fmt::{Debug, Display};
 use std::hash::Hash;
@@ -69,6 +69,14 @@ pub trait ConfigFile: Debug + Send + Sync {
     fn tasks(&self) -> Vec<&Task> {
         Default::default()
     }
+    /// if false, configs from parent directories are not used
+    fn inherit(&self) -> bool {
+        true
+    }
+    /// env vars that are still inherited from parent directory configs when `inherit = false`
+    fn inherit_env(&self) -> &[String] {
+        &[]
+    }
     fn remove_plugin(&mut self, _fa: &BackendArg) -> eyre::Result<()>;
     fn replace_versions(&mut self, fa: &BackendArg, versions: &[String]) -> eyre::Result<()>;
     fn save(&self) -> eyre::Result<()>;
@@ -84,6 +92,10 @@ pub trait ConfigFile: Debug + Send + Sync {
         static DEFAULT_TASK_CONFIG: Lazy<TaskConfig> = Lazy::new(TaskConfig::default);
         &DEFAULT_TASK_CONFIG
     }
+    fn hooks(&self) -> &Hooks {
+        static DEFAULT_HOOKS: Lazy<Hooks> = Lazy::new(Hooks::default);
+        &DEFAULT_HOOKS
+    }
 }
 
 impl dyn ConfigFile {
@@ -345,6 +357,42 @@ impl Hash for dyn ConfigFile {
 #[derive(Clone, Debug, Default, Deserialize)]
 pub struct TaskConfig {
     pub includes: Option<Vec<PathBuf>>,
+    /// globs of the directories of a workspace whose tasks are available as `<member>:<task>`
+    pub members: Option<Vec<String>>,
+}
+
+/// the `[hooks]` section of mise.toml
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Hooks {
+    /// scripts run by `hook-env` when the active version of a tool changes, keyed by tool
+    #[serde(default)]
+    pub version_change: BTreeMap<String, HookScript>,
+    /// scripts run by `hook-env` when the shell enters the project of the config file
+    pub enter: Option<HookScript>,
+    /// scripts run by `hook-env` when the shell leaves the project of the config file
+    pub leave: Option<HookScript>,
+}
+
+impl Hooks {
+    pub fn is_empty(&self) -> bool {
+        self.version_change.is_empty() && self.enter.is_none() && self.leave.is_none()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+pub enum HookScript {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+impl HookScript {
+    pub fn scripts(&self) -> Vec<&str> {
+        match self {
+            HookScript::Single(s) => vec![s],
+            HookScript::Multiple(v) => v.iter().map(|s| s.as_str()).collect(),
+        }
+    }
 }
 
 #[cfg(test)]
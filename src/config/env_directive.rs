@@ -12,7 +12,7 @@ use crate::env_diff::{EnvDiff, EnvDiffOperation};
 use crate::file::display_path;
 use crate::tera::{get_tera, BASE_CONTEXT};
 use crate::toolset::ToolsetBuilder;
-use crate::{dirs, env};
+use crate::{age, dirs, env, file, sops};
 
 #[derive(Debug, Clone)]
 pub enum EnvDirective {
@@ -20,6 +20,8 @@ pub enum EnvDirective {
     Val(String, String),
     /// remove a key
     Rm(String),
+    /// key/value pair with the value encrypted by `mise set --encrypt`
+    Age(String, String),
     /// dotenv file
     File(PathBuf),
     /// add a path to the PATH
@@ -49,6 +51,7 @@ impl Display for EnvDirective {
         match self {
             EnvDirective::Val(k, v) => write!(f, "{k}={v}"),
             EnvDirective::Rm(k) => write!(f, "unset {k}"),
+            EnvDirective::Age(k, _) => write!(f, "{k}=<encrypted>"),
             EnvDirective::File(path) => write!(f, "dotenv {}", display_path(path)),
             EnvDirective::Path(path) => write!(f, "path_add {}", display_path(path)),
             EnvDirective::Source(path) => write!(f, "source {}", display_path(path)),
@@ -70,6 +73,8 @@ pub struct EnvResults {
     pub env_files: Vec<PathBuf>,
     pub env_paths: Vec<PathBuf>,
     pub env_scripts: Vec<PathBuf>,
+    /// whether a value was decrypted with age or sops, the env shouldn't be cached on disk then
+    pub decrypted: bool,
 }
 
 impl EnvResults {
@@ -89,6 +94,7 @@ impl EnvResults {
             env_files: Vec::new(),
             env_paths: Vec::new(),
             env_scripts: Vec::new(),
+            decrypted: false,
         };
         for (directive, source) in input {
             let config_root = source
@@ -120,6 +126,18 @@ impl EnvResults {
                     env.shift_remove(&k);
                     r.env_remove.insert(k);
                 }
+                EnvDirective::Age(k, v) => {
+                    trust_check(&source)?;
+                    // people without the identity still get the rest of the env
+                    match age::decrypt(&v) {
+                        Ok(v) => {
+                            r.decrypted = true;
+                            r.env_remove.remove(&k);
+                            env.insert(k, (v, Some(source.clone())));
+                        }
+                        Err(err) => warn!("failed to decrypt {k}: {err:#}"),
+                    }
+                }
                 EnvDirective::Path(input) => {
                     let s = r.parse_template(&ctx, &source, input.to_string_lossy().as_ref())?;
                     env::split_paths(&s)
@@ -132,8 +150,17 @@ impl EnvResults {
                     for p in xx::file::glob(normalize_path(s.into()))? {
                         r.env_files.push(p.clone());
                         let errfn = || eyre!("failed to parse dotenv file: {}", display_path(&p));
-                        for item in dotenvy::from_path_iter(&p).wrap_err_with(errfn)? {
-                            let (k, v) = item.wrap_err_with(errfn)?;
+                        let contents = file::read_to_string(&p)?;
+                        let vars = match sops::is_encrypted(&contents) {
+                            true => {
+                                r.decrypted = true;
+                                sops::decrypt(&p, &contents)?
+                            }
+                            false => dotenvy::from_read_iter(contents.as_bytes())
+                                .collect::<Result<Vec<_>, _>>()
+                                .wrap_err_with(errfn)?,
+                        };
+                        for (k, v) in vars {
                             r.env_remove.remove(&k);
                             env.insert(k, (v, Some(p.clone())));
                         }
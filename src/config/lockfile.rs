@@ -0,0 +1,374 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use eyre::{bail, eyre, Result};
+use once_cell::sync::Lazy;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::cli::version::{ARCH, OS};
+use crate::config::config_file::ConfigFile;
+use crate::config::{Config, Settings};
+use crate::file::display_path;
+use crate::hash::file_hash_sha256;
+use crate::toolset::{ToolRequest, ToolVersion, Toolset};
+use crate::{file, http, registry};
+
+/// `mise.lock`, written next to a mise.toml when the `lockfile` setting is enabled. It has the
+/// version each tool request resolved to and the artifact that was downloaded for each platform:
+///
+/// [[tools.node]]
+/// requested = "20"
+/// version = "20.11.0"
+/// license = "MIT"
+/// source = "https://github.com/nodejs/node"
+///
+/// [tools.node.platforms.linux-x64]
+/// url = "https://nodejs.org/dist/v20.11.0/node-v20.11.0-linux-x64.tar.gz"
+/// checksum = "sha256:..."
+///
+/// [tools.node.patches]
+/// "fix-shebang.patch" = "sha256:..."
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Lockfile {
+    #[serde(default)]
+    tools: BTreeMap<String, Vec<LockedTool>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockedTool {
+    pub requested: String,
+    pub version: String,
+    /// SPDX license expression and upstream source from the registry or tool options
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub platforms: BTreeMap<String, Artifact>,
+    /// checksums of the `patches` and `post_extract` script applied to the install
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub patches: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Artifact {
+    pub url: String,
+    pub checksum: String,
+}
+
+type Patches = BTreeMap<String, String>;
+
+/// locked versions by tool and requested version, from the lockfiles of all config files
+static LOCKED: Lazy<Mutex<HashMap<(String, String), LockedTool>>> = Lazy::new(Default::default);
+
+/// artifacts downloaded by this process by tool and version, written to mise.lock by `update`
+static ARTIFACTS: Lazy<Mutex<HashMap<(String, String), Artifact>>> = Lazy::new(Default::default);
+
+/// patches applied by this process by tool and version, written to mise.lock by `update`
+static PATCHES: Lazy<Mutex<HashMap<(String, String), Patches>>> = Lazy::new(Default::default);
+
+impl Lockfile {
+    fn read(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let body = file::read_to_string(path)?;
+        toml::from_str(&body).map_err(|e| eyre!("failed to parse {}: {e}", display_path(path)))
+    }
+
+    fn write(&self, path: &Path) -> Result<()> {
+        if self.tools.is_empty() {
+            return file::remove_all(path);
+        }
+        file::write(path, toml::to_string_pretty(self)?)
+    }
+}
+
+/// mise.toml -> mise.lock, .mise.toml -> .mise.lock, .mise/config.toml -> .mise/config.lock
+pub fn lockfile_path(config_path: &Path) -> PathBuf {
+    config_path.with_extension("lock")
+}
+
+fn platform() -> String {
+    format!("{}-{}", *OS, *ARCH)
+}
+
+fn is_lockable(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e == "toml")
+}
+
+/// loads the lockfiles of the config files, closer config files take precedence
+pub fn init<'a>(config_paths: impl IntoIterator<Item = &'a PathBuf>) -> Result<()> {
+    let mut locked = LOCKED.lock().unwrap();
+    locked.clear();
+    if !Settings::get().lockfile {
+        return Ok(());
+    }
+    for path in config_paths.into_iter().filter(|p| is_lockable(p)) {
+        for (tool, entries) in Lockfile::read(&lockfile_path(path))?.tools {
+            for entry in entries {
+                locked
+                    .entry((tool.clone(), entry.requested.clone()))
+                    .or_insert(entry);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// the locked version for a request, `mise install --locked` fails for requests without one
+pub fn get(request: &ToolRequest) -> Result<Option<LockedTool>> {
+    let settings = Settings::get();
+    if !settings.lockfile {
+        return Ok(None);
+    }
+    let key = (request.backend().to_string(), request.version());
+    if let Some(locked) = LOCKED.lock().unwrap().get(&key) {
+        return Ok(Some(locked.clone()));
+    }
+    let needs_resolving = matches!(
+        request,
+        ToolRequest::Version { .. } | ToolRequest::Prefix { .. } | ToolRequest::Sub { .. }
+    );
+    if settings.locked && needs_resolving {
+        bail!(
+            "{}@{} is not in mise.lock, run `mise install` without --locked to add it",
+            key.0,
+            key.1
+        );
+    }
+    Ok(None)
+}
+
+/// forgets the locked versions of a tool so it resolves to a new version, e.g.: for `mise upgrade`
+pub fn unlock(tool: &str) -> Result<()> {
+    if Settings::get().locked {
+        bail!("{tool} can't be upgraded with --locked");
+    }
+    LOCKED.lock().unwrap().retain(|(t, _), _| t != tool);
+    Ok(())
+}
+
+/// records the artifact downloaded for a new install and checks it against mise.lock, the
+/// largest download is used since other downloads are usually checksum or signature files
+pub fn record_install(tv: &ToolVersion) -> Result<()> {
+    let settings = Settings::get();
+    if !settings.lockfile {
+        return Ok(());
+    }
+    let artifact = http::downloads_in(&tv.download_path())
+        .into_iter()
+        .filter_map(|(path, url)| Some((path.metadata().ok()?.len(), path, url)))
+        .max_by_key(|(len, _, _)| *len);
+    let Some((_, path, url)) = artifact else {
+        return Ok(());
+    };
+    let artifact = Artifact {
+        url,
+        checksum: format!("sha256:{}", file_hash_sha256(&path)?),
+    };
+    record_artifact(tv, artifact)
+}
+
+/// checks an artifact of `tv` against mise.lock and records it to be written to it, fails if the
+/// checksum doesn't match
+pub fn record_artifact(tv: &ToolVersion, artifact: Artifact) -> Result<()> {
+    let settings = Settings::get();
+    if !settings.lockfile {
+        return Ok(());
+    }
+    let key = (tv.backend.to_string(), tv.request.version());
+    let expected = LOCKED
+        .lock()
+        .unwrap()
+        .get(&key)
+        .filter(|l| l.version == tv.version)
+        .and_then(|l| l.platforms.get(&platform()).cloned());
+    if let Some(expected) = expected {
+        if expected.url == artifact.url && expected.checksum != artifact.checksum {
+            bail!(
+                "checksum of {} does not match mise.lock\nexpected: {}\nactual:   {}",
+                artifact.url,
+                expected.checksum,
+                artifact.checksum
+            );
+        }
+        if settings.locked && expected.url != artifact.url {
+            bail!(
+                "{tv} was downloaded from {} but mise.lock has {}",
+                artifact.url,
+                expected.url
+            );
+        }
+    }
+    ARTIFACTS
+        .lock()
+        .unwrap()
+        .insert((tv.backend.to_string(), tv.version.clone()), artifact);
+    Ok(())
+}
+
+/// records the checksums of the patches applied to a new install, with `--locked` they must match
+/// mise.lock
+pub fn record_patches(tv: &ToolVersion, patches: Patches) -> Result<()> {
+    let settings = Settings::get();
+    if !settings.lockfile {
+        return Ok(());
+    }
+    let key = (tv.backend.to_string(), tv.request.version());
+    let expected = LOCKED
+        .lock()
+        .unwrap()
+        .get(&key)
+        .filter(|l| l.version == tv.version)
+        .map(|l| l.patches.clone());
+    if let Some(expected) = expected.filter(|_| settings.locked) {
+        if expected != patches {
+            bail!(
+                "patches of {tv} do not match mise.lock\nexpected: {}\nactual:   {}",
+                render_patches(&expected),
+                render_patches(&patches)
+            );
+        }
+    }
+    PATCHES
+        .lock()
+        .unwrap()
+        .insert((tv.backend.to_string(), tv.version.clone()), patches);
+    Ok(())
+}
+
+fn render_patches(patches: &Patches) -> String {
+    match patches.is_empty() {
+        true => "none".to_string(),
+        false => patches
+            .iter()
+            .map(|(name, checksum)| format!("{name}={checksum}"))
+            .collect::<Vec<_>>()
+            .join(", "),
+    }
+}
+
+/// the artifact of a tool version on this platform if it was downloaded by this process or is in
+/// mise.lock
+pub fn artifact(tv: &ToolVersion) -> Option<Artifact> {
+    let key = (tv.backend.to_string(), tv.version.clone());
+    if let Some(artifact) = ARTIFACTS.lock().unwrap().get(&key) {
+        return Some(artifact.clone());
+    }
+    LOCKED
+        .lock()
+        .unwrap()
+        .get(&(key.0, tv.request.version()))
+        .filter(|l| l.version == tv.version)
+        .and_then(|l| l.platforms.get(&platform()).cloned())
+}
+
+/// writes the lockfiles of every mise.toml config file
+pub fn update(config: &Config) -> Result<()> {
+    for cf in config.config_files.values() {
+        update_file(cf.as_ref())?;
+    }
+    Ok(())
+}
+
+/// writes the lockfile of a config file with the versions its tools currently resolve to
+pub fn update_file(cf: &dyn ConfigFile) -> Result<()> {
+    let settings = Settings::get();
+    if !settings.lockfile || settings.locked || !is_lockable(cf.get_path()) {
+        return Ok(());
+    }
+    let path = lockfile_path(cf.get_path());
+    let prev = Lockfile::read(&path)?;
+    let mut ts: Toolset = cf.to_tool_request_set()?.into();
+    ts.resolve()?;
+    let artifacts = ARTIFACTS.lock().unwrap();
+    let patches = PATCHES.lock().unwrap();
+    let mut lockfile = Lockfile::default();
+    for tvl in ts.versions.values() {
+        let tool = tvl.backend.to_string();
+        let entries = tvl
+            .versions
+            .iter()
+            .filter(|tv| !matches!(tv.request, ToolRequest::Path(..) | ToolRequest::System(_)))
+            .map(|tv| {
+                let requested = tv.request.version();
+                // keep the artifacts of other platforms if the version didn't change
+                let prev = prev
+                    .tools
+                    .get(&tool)
+                    .and_then(|e| e.iter().find(|e| e.requested == requested))
+                    .filter(|e| e.version == tv.version);
+                let key = (tool.clone(), tv.version.clone());
+                let mut platforms = prev.map(|e| e.platforms.clone()).unwrap_or_default();
+                if let Some(artifact) = artifacts.get(&key) {
+                    platforms.insert(platform(), artifact.clone());
+                }
+                let patches = match patches.get(&key) {
+                    Some(patches) => patches.clone(),
+                    None => prev.map(|e| e.patches.clone()).unwrap_or_default(),
+                };
+                let metadata =
+                    registry::metadata(&tvl.backend, &tv.request.options()).unwrap_or_default();
+                LockedTool {
+                    requested,
+                    version: tv.version.clone(),
+                    license: metadata.license,
+                    source: metadata.source,
+                    platforms,
+                    patches,
+                }
+            })
+            .collect::<Vec<_>>();
+        if !entries.is_empty() {
+            lockfile.tools.insert(tool, entries);
+        }
+    }
+    if lockfile != prev {
+        debug!("writing {}", display_path(&path));
+        lockfile.write(&path)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_lockfile_path() {
+        let p = |s: &str| lockfile_path(Path::new(s)).to_string_lossy().to_string();
+        assert_eq!(p("/src/mise.toml"), "/src/mise.lock");
+        assert_eq!(p("/src/.mise.toml"), "/src/.mise.lock");
+        assert_eq!(p("/src/.mise/config.toml"), "/src/.mise/config.lock");
+    }
+
+    #[test]
+    fn test_lockfile_roundtrip() {
+        let body = r#"
+[[tools.node]]
+requested = "20"
+version = "20.11.0"
+license = "MIT"
+
+[tools.node.platforms.linux-x64]
+url = "https://nodejs.org/dist/v20.11.0/node-v20.11.0-linux-x64.tar.gz"
+checksum = "sha256:abc"
+
+[tools.node.patches]
+"fix.patch" = "sha256:def"
+"#;
+        let lockfile: Lockfile = toml::from_str(body).unwrap();
+        let node = &lockfile.tools["node"][0];
+        assert_eq!(node.version, "20.11.0");
+        assert_eq!(node.license.as_deref(), Some("MIT"));
+        assert_eq!(node.source, None);
+        assert_eq!(node.platforms["linux-x64"].checksum, "sha256:abc");
+        assert_eq!(node.patches["fix.patch"], "sha256:def");
+        let out: Lockfile = toml::from_str(&toml::to_string_pretty(&lockfile).unwrap()).unwrap();
+        assert_eq!(out, lockfile);
+    }
+}
@@ -5,6 +5,7 @@ use std::path::{Path, PathBuf};
 use std::sync::{Arc, OnceLock, RwLock};
 
 use eyre::{ensure, eyre, Context, Result};
+use globwalk::GlobWalkerBuilder;
 use indexmap::IndexMap;
 use itertools::Itertools;
 use once_cell::sync::{Lazy, OnceCell};
@@ -18,29 +19,35 @@ use crate::cli::version;
 use crate::config::config_file::legacy_version::LegacyVersionFile;
 use crate::config::config_file::mise_toml::MiseToml;
 use crate::config::config_file::ConfigFile;
-use crate::config::env_directive::EnvResults;
+use crate::config::env_directive::{EnvDirective, EnvResults};
 use crate::config::tracking::Tracker;
 use crate::file::display_path;
 use crate::shorthands::{get_shorthands, Shorthands};
-use crate::task::Task;
+use crate::task::{self, Task};
 use crate::toolset::{ToolRequestSet, ToolRequestSetBuilder};
 use crate::ui::style;
 use crate::{backend, dirs, env, file};
 
 pub mod config_file;
 mod env_directive;
+pub mod lockfile;
 pub mod settings;
 pub mod tracking;
 
 type AliasMap = BTreeMap<BackendArg, BTreeMap<String, String>>;
 type ConfigMap = IndexMap<PathBuf, Box<dyn ConfigFile>>;
 type EnvWithSources = IndexMap<String, (String, PathBuf)>;
+type EnvEntries = Vec<(EnvDirective, PathBuf)>;
 
 #[derive(Default)]
 pub struct Config {
     pub aliases: AliasMap,
     pub config_files: ConfigMap,
     pub project_root: Option<PathBuf>,
+    /// set by a config with `inherit = false`, parent directories of it are not used
+    inherit_root: Option<PathBuf>,
+    /// env from parent directory configs allowed by `inherit_env`
+    inherited_env: EnvEntries,
     env: OnceCell<EnvResults>,
     env_with_sources: OnceCell<EnvWithSources>,
     all_aliases: OnceLock<AliasMap>,
@@ -76,6 +83,8 @@ impl Config {
             .collect_vec();
         let config_paths = load_config_paths(&config_filenames);
         let config_files = load_all_config_files(&config_paths, &legacy_files)?;
+        let (config_files, inherit_root, inherited_env) = apply_inherit(config_files)?;
+        lockfile::init(config_files.keys())?;
 
         let repo_urls = config_files.values().flat_map(|cf| cf.plugins()).collect();
 
@@ -83,6 +92,8 @@ impl Config {
             aliases: load_aliases(&config_files),
             project_root: get_project_root(&config_files),
             config_files,
+            inherit_root,
+            inherited_env,
             repo_urls,
             ..Default::default()
         };
@@ -216,7 +227,7 @@ impl Config {
     }
 
     pub fn load_all_tasks(&self) -> Result<BTreeMap<String, Task>> {
-        Ok(file::all_dirs()?
+        let dirs = file::all_dirs()?
             .into_iter()
             .filter(|d| {
                 if cfg!(test) {
@@ -225,7 +236,13 @@ impl Config {
                     true
                 }
             })
-            .collect_vec()
+            .filter(|d| match &self.inherit_root {
+                Some(root) => d.starts_with(root),
+                None => true,
+            })
+            .collect_vec();
+        let member_tasks = self.load_member_tasks(&dirs)?;
+        let mut tasks: BTreeMap<String, Task> = dirs
             .into_par_iter()
             .map(|d| self.load_tasks_in_dir(&d))
             .collect::<Result<Vec<_>>>()?
@@ -233,14 +250,22 @@ impl Config {
             .flatten()
             .chain(self.load_global_tasks()?)
             .chain(self.load_system_tasks()?)
+            // tasks with the same name as a member task take precedence
+            .chain(member_tasks)
             .rev()
             .inspect(|t| trace!("loading task {t} from {}", display_path(&t.config_source)))
             .map(|t| (t.name.clone(), t))
-            .collect())
+            .collect();
+        task::resolve_extends(&mut tasks)?;
+        Ok(tasks)
     }
 
     fn load_tasks_in_dir(&self, dir: &Path) -> Result<Vec<Task>> {
         let configs = self.configs_at_root(dir);
+        Ok(self.load_tasks_of_configs(dir, &configs))
+    }
+
+    fn load_tasks_of_configs(&self, dir: &Path, configs: &[&dyn ConfigFile]) -> Vec<Task> {
         let config_tasks = configs.iter().flat_map(|cf| cf.tasks()).cloned();
         let includes = configs
             .iter()
@@ -256,7 +281,49 @@ impl Config {
                 vec![]
             })
         });
-        Ok(file_tasks.into_iter().chain(config_tasks).collect())
+        file_tasks.into_iter().chain(config_tasks).collect()
+    }
+
+    /// tasks of the `members` of workspaces in `dirs`, named `<member>:<task>` so they can be run
+    /// from anywhere in the workspace
+    fn load_member_tasks(&self, dirs: &[PathBuf]) -> Result<Vec<Task>> {
+        let mut tasks = vec![];
+        for dir in dirs {
+            let members = self
+                .configs_at_root(dir)
+                .iter()
+                .find_map(|cf| cf.task_config().members.clone());
+            let Some(members) = members else {
+                continue;
+            };
+            for member in member_dirs(dir, &members)? {
+                tasks.extend(self.load_tasks_in_member(&member));
+            }
+        }
+        Ok(tasks)
+    }
+
+    fn load_tasks_in_member(&self, dir: &Path) -> Vec<Task> {
+        let configs = DEFAULT_CONFIG_FILENAMES
+            .iter()
+            .map(|f| dir.join(f))
+            .filter(|f| f.is_file())
+            .filter_map(|f| match config_file::parse(&f) {
+                Ok(cf) => Some(cf),
+                Err(err) => {
+                    warn!("loading tasks in {}: {err:#}", display_path(&f));
+                    None
+                }
+            })
+            .collect_vec();
+        let configs = configs.iter().map(|cf| cf.as_ref()).collect_vec();
+        let tasks = self.load_tasks_of_configs(dir, &configs);
+        let member = dir.file_name().unwrap().to_string_lossy();
+        let local = tasks.iter().map(|t| t.name.clone()).collect();
+        tasks
+            .into_iter()
+            .map(|t| t.in_member(&member, dir, &local))
+            .collect()
     }
 
     fn load_global_tasks(&self) -> Result<Vec<Task>> {
@@ -362,6 +429,21 @@ impl Config {
         }
     }
 
+    /// the closest .mise.toml that is not a global config, or a new one in the current directory
+    pub fn local_config(&self) -> Result<MiseToml> {
+        let path = self
+            .config_files
+            .keys()
+            .find(|p| p.extension().is_some_and(|e| e == "toml") && !is_global_config(p));
+        match path {
+            Some(path) => MiseToml::from_file(path)
+                .wrap_err_with(|| eyre!("Error parsing {}", display_path(path))),
+            None => Ok(MiseToml::init(
+                &env::current_dir()?.join(&*env::MISE_DEFAULT_CONFIG_FILENAME),
+            )),
+        }
+    }
+
     fn validate(&self) -> eyre::Result<()> {
         for cf in self.config_files.values() {
             if let Some(min) = cf.min_version() {
@@ -388,8 +470,8 @@ impl Config {
             })
             .collect::<Result<Vec<_>>>()?
             .into_iter()
-            .flatten()
-            .collect();
+            .flatten();
+        let entries = self.inherited_env.iter().cloned().chain(entries).collect();
         EnvResults::resolve(&env::PRISTINE_ENV, entries)
     }
 
@@ -412,6 +494,49 @@ impl Config {
     }
 }
 
+/// the first config with `inherit = false` stops configs in parent directories from being used,
+/// global and system configs are kept. Env vars listed in `inherit_env` are still read from them.
+fn apply_inherit(config_files: ConfigMap) -> Result<(ConfigMap, Option<PathBuf>, EnvEntries)> {
+    let Some(idx) = config_files.values().position(|cf| !cf.inherit()) else {
+        return Ok((config_files, None, vec![]));
+    };
+    let (path, cf) = config_files.get_index(idx).unwrap();
+    let root = cf
+        .project_root()
+        .or(path.parent())
+        .map(|p| p.to_path_buf())
+        .unwrap_or_default();
+    let inherit_env = cf.inherit_env().to_vec();
+    debug!("{} does not inherit parent configs", display_path(path));
+    let mut kept = ConfigMap::new();
+    let mut parents = vec![];
+    for (i, (path, cf)) in config_files.into_iter().enumerate() {
+        let same_root = cf.project_root().or(path.parent()) == Some(root.as_path());
+        if i <= idx || same_root || is_global_config(&path) {
+            kept.insert(path, cf);
+        } else {
+            trace!("not inheriting {}", display_path(&path));
+            parents.push((path, cf));
+        }
+    }
+    let mut inherited_env = vec![];
+    for (path, cf) in parents.into_iter().rev() {
+        for ed in cf.env_entries()? {
+            let key = match &ed {
+                EnvDirective::Val(k, _) | EnvDirective::Age(k, _) | EnvDirective::Rm(k) => {
+                    k.as_str()
+                }
+                EnvDirective::Path(_) => "PATH",
+                _ => continue,
+            };
+            if inherit_env.iter().any(|k| k == key) {
+                inherited_env.push((ed, path.clone()));
+            }
+        }
+    }
+    Ok((kept, Some(root), inherited_env))
+}
+
 fn get_project_root(config_files: &ConfigMap) -> Option<PathBuf> {
     config_files
         .values()
@@ -454,6 +579,12 @@ fn load_legacy_files(settings: &Settings) -> BTreeMap<String, Vec<String>> {
             .or_insert_with(Vec::new)
             .push(plugin);
     }
+    if settings.package_manager_version_file {
+        // the packageManager field is parsed by LegacyVersionFile itself
+        legacy_filenames
+            .entry("package.json".to_string())
+            .or_insert_with(Vec::new);
+    }
     legacy_filenames
 }
 
@@ -606,7 +737,9 @@ fn parse_config_file(
 fn load_aliases(config_files: &ConfigMap) -> AliasMap {
     let mut aliases: AliasMap = AliasMap::new();
 
-    for config_file in config_files.values() {
+    // config files are ordered closest first so go in reverse to let project aliases
+    // override global ones
+    for config_file in config_files.values().rev() {
         for (plugin, plugin_aliases) in config_file.aliases() {
             for (from, to) in plugin_aliases {
                 aliases.entry(plugin.clone()).or_default().insert(from, to);
@@ -650,6 +783,19 @@ impl Debug for Config {
     }
 }
 
+/// directories matching the `members` globs of a workspace
+fn member_dirs(root: &Path, members: &[String]) -> Result<Vec<PathBuf>> {
+    let walker = GlobWalkerBuilder::from_patterns(root, members)
+        .follow_links(true)
+        .build()?;
+    Ok(walker
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_dir())
+        .map(|e| e.into_path())
+        .sorted()
+        .collect())
+}
+
 fn default_task_includes() -> Vec<PathBuf> {
     vec![
         ".mise/tasks".into(),
@@ -24,6 +24,10 @@ pub struct Settings {
     /// push tools to the front of PATH instead of allowing modifications of PATH after activation to take precedence
     #[config(env = "MISE_ACTIVATE_AGGRESSIVE", default = false)]
     pub activate_aggressive: bool,
+    /// age identity that decrypts env vars set with `mise set --encrypt`, defaults to
+    /// ~/.config/mise/age.txt
+    #[config(env = "MISE_AGE_IDENTITY_FILE")]
+    pub age_identity_file: Option<PathBuf>,
     #[config(env = "MISE_ALL_COMPILE", default = false)]
     pub all_compile: bool,
     #[config(env = "MISE_ALWAYS_KEEP_DOWNLOAD", default = false)]
@@ -35,17 +39,43 @@ pub struct Settings {
     /// also, the default behavior of `mise global` will be --pin
     #[config(env = "MISE_ASDF_COMPAT", default = false)]
     pub asdf_compat: bool,
-    /// use cargo-binstall instead of cargo install if available
+    /// when to automatically install missing tools
+    #[config(nested)]
+    pub auto_install: SettingsAutoInstall,
+    /// url of a remote cache of tools built from source (cargo, go, spm), either an http(s) url
+    /// or an s3://bucket/prefix url. `mise install` downloads prebuilt installs from it
+    #[config(env = "MISE_BINARY_CACHE")]
+    pub binary_cache: Option<String>,
+    /// upload installs built from source to `binary_cache`
+    #[config(env = "MISE_BINARY_CACHE_UPLOAD", default = false)]
+    pub binary_cache_upload: bool,
+    /// use cargo-binstall binaries instead of compiling with cargo install
     #[config(env = "MISE_CARGO_BINSTALL", default = true)]
     pub cargo_binstall: bool,
+    /// download unsigned cargo-quickinstall binaries when cargo-binstall isn't installed
+    #[config(env = "MISE_CARGO_QUICKINSTALL", default = false)]
+    pub cargo_quickinstall: bool,
     #[config(env = "MISE_COLOR", default = true)]
     pub color: bool,
+    /// keep downloads and extracted archives in a content-addressed store and copy them into
+    /// installs so reinstalling the same version doesn't download or extract it again
+    #[config(env = "MISE_CONTENT_STORE", default = false)]
+    pub content_store: bool,
     #[config(env = "MISE_DISABLE_DEFAULT_SHORTHANDS", default = false)]
     pub disable_default_shorthands: bool,
     #[config(env = "MISE_DISABLE_TOOLS", default = [], parse_env = list_by_comma)]
     pub disable_tools: BTreeSet<String>,
+    /// reuse the env `mise exec` resolved for the same directory, config files, and tools
+    #[config(env = "MISE_EXEC_CACHE", default = false)]
+    pub exec_cache: bool,
     #[config(env = "MISE_EXPERIMENTAL", default = false)]
     pub experimental: bool,
+    /// self-hosted Gitea and Forgejo instances that repository urls can be on
+    #[config(env = "MISE_GITEA_HOSTS", default = [], parse_env = list_by_comma)]
+    pub gitea_hosts: BTreeSet<String>,
+    /// self-hosted GitLab instances that repository urls can be on, e.g.: for the spm backend
+    #[config(env = "MISE_GITLAB_HOSTS", default = [], parse_env = list_by_comma)]
+    pub gitlab_hosts: BTreeSet<String>,
     /// after installing a go version, run `go install` on packages listed in this file
     #[config(env = "MISE_GO_DEFAULT_PACKAGES_FILE", default = "~/.default-go-packages")]
     pub go_default_packages_file: PathBuf,
@@ -74,6 +104,24 @@ pub struct Settings {
     /// set to true to skip checksum verification when downloading go sdk tarballs
     #[config(env = "MISE_GO_SKIP_CHECKSUM", default = false)]
     pub go_skip_checksum: bool,
+    /// only put tools managed by mise and `hermetic_allow` on PATH for `mise run` and `mise exec`
+    #[config(env = "MISE_HERMETIC", default = false)]
+    pub hermetic: bool,
+    /// commands from the system PATH that are still available in hermetic mode
+    #[config(env = "MISE_HERMETIC_ALLOW", default = ["awk", "basename", "bash", "cat", "chmod", "cp", "cut", "date", "dirname", "echo", "env", "find", "grep", "head", "ls", "mkdir", "mktemp", "mv", "pwd", "rm", "sed", "sh", "sort", "tail", "tee", "touch", "tr", "uname", "uniq", "wc", "xargs"], parse_env = list_by_comma)]
+    pub hermetic_allow: BTreeSet<String>,
+    /// download large files with this many concurrent range requests
+    #[config(env = "MISE_HTTP_DOWNLOAD_SEGMENTS", default = 1)]
+    pub http_download_segments: usize,
+    /// number of times failed http requests are retried
+    #[config(env = "MISE_HTTP_RETRIES", default = 2)]
+    pub http_retries: u32,
+    /// milliseconds to wait before the first retry, doubled for each retry after that
+    #[config(env = "MISE_HTTP_RETRY_BACKOFF", default = 500)]
+    pub http_retry_backoff: u64,
+    /// http status codes that are retried
+    #[config(env = "MISE_HTTP_RETRY_STATUS_CODES", default = [408, 429, 500, 502, 503, 504], parse_env = list_by_comma)]
+    pub http_retry_status_codes: BTreeSet<u16>,
     #[config(env = "MISE_HTTP_TIMEOUT", default = 30)]
     pub http_timeout: u64,
     #[config(env = "MISE_JOBS", default = 4)]
@@ -82,14 +130,43 @@ pub struct Settings {
     pub legacy_version_file: bool,
     #[config(env = "MISE_LEGACY_VERSION_FILE_DISABLE_TOOLS", default = [], parse_env = list_by_comma)]
     pub legacy_version_file_disable_tools: BTreeSet<String>,
+    /// only install the versions in mise.lock, tools which are not in it fail to install
+    /// instead of being resolved, like `mise install --locked`
+    #[config(env = "MISE_LOCKED", default = false)]
+    pub locked: bool,
+    /// write the resolved versions of tools to a mise.lock next to mise.toml and use them
+    /// instead of resolving the versions again
+    #[config(env = "MISE_LOCKFILE", default = false)]
+    pub lockfile: bool,
     #[config(env = "MISE_NODE_COMPILE", default = false)]
     pub node_compile: bool,
     #[config(env = "MISE_NOT_FOUND_AUTO_INSTALL", default = true)]
     pub not_found_auto_install: bool,
+    /// internal mirrors that downloads and package managers use instead of the public ones
+    #[config(nested)]
+    pub mirrors: SettingsMirrors,
+    /// forbid all network access, versions are resolved from installed versions, caches, and
+    /// mise.lock, like `mise --offline`
+    #[config(env = "MISE_OFFLINE", default = false)]
+    pub offline: bool,
+    /// install the package manager from the `packageManager` field in package.json with npm
+    #[config(env = "MISE_PACKAGE_MANAGER_VERSION_FILE", default = false)]
+    pub package_manager_version_file: bool,
     #[config(env = "MISE_PARANOID", default = false)]
     pub paranoid: bool,
+    /// use uv tool install instead of pipx for the pipx backend
+    #[config(env = "MISE_PIPX_UVX", default = false)]
+    pub pipx_uvx: bool,
+    /// keep installs and shims in directories for each os and arch so one data dir can be
+    /// shared by machines with different platforms
+    #[config(env = "MISE_PLATFORM_DIRS", default = false)]
+    pub platform_dirs: bool,
     #[config(env = "MISE_PLUGIN_AUTOUPDATE_LAST_CHECK_DURATION", default = "7d")]
     pub plugin_autoupdate_last_check_duration: String,
+    /// whether `latest` and version ranges may resolve to prereleases: "false", "true", or
+    /// "rc-only", the `prerelease` tool option takes precedence
+    #[config(env = "MISE_PRERELEASE", default = "false")]
+    pub prerelease: String,
     #[config(env = "MISE_PYTHON_COMPILE")]
     pub python_compile: Option<bool>,
     #[config(env = "MISE_PYTHON_DEFAULT_PACKAGES_FILE")]
@@ -108,17 +185,45 @@ pub struct Settings {
     pub raw: bool,
     #[config(env = "MISE_SHORTHANDS_FILE")]
     pub shorthands_file: Option<PathBuf>,
+    /// when signatures of downloaded artifacts are verified, "auto" verifies tools with the
+    /// `verify` option, "require" fails for tools without it, "off" never verifies
+    #[config(env = "MISE_SIGNATURE_POLICY", default = "auto")]
+    pub signature_policy: String,
     /// what level of status messages to display when entering directories
     #[config(nested)]
     pub status: SettingsStatus,
+    /// git repositories to search for templates used by `mise new`
+    #[config(env = "MISE_TEMPLATE_SOURCES", default = [], parse_env = list_by_comma)]
+    pub template_sources: BTreeSet<String>,
+    /// docker or podman, used to run tasks with `container`, defaults to whichever is on PATH
+    #[config(env = "MISE_TASK_CONTAINER_ENGINE")]
+    pub task_container_engine: Option<String>,
     #[config(env = "MISE_TASK_OUTPUT")]
     pub task_output: Option<String>,
+    /// url of a remote cache of task outputs, either an http(s) url or an s3://bucket/prefix url.
+    /// `mise run` restores the outputs of tasks with `sources` and `outputs` from it
+    #[config(env = "MISE_TASK_REMOTE_CACHE")]
+    pub task_remote_cache: Option<String>,
+    /// upload the outputs of tasks to `task_remote_cache` after they run
+    #[config(env = "MISE_TASK_REMOTE_CACHE_UPLOAD", default = false)]
+    pub task_remote_cache_upload: bool,
     #[config(env = "MISE_TRUSTED_CONFIG_PATHS", default = [], parse_env = list_by_colon)]
     pub trusted_config_paths: BTreeSet<PathBuf>,
+    /// what to do when a ubi asset does not match the checksum published with the release,
+    /// "error" or "warn"
+    #[config(env = "MISE_UBI_CHECKSUM_MISMATCH", default = "error")]
+    pub ubi_checksum_mismatch: String,
     #[config(env = "MISE_QUIET", default = false)]
     pub quiet: bool,
     #[config(env = "MISE_VERBOSE", default = false)]
     pub verbose: bool,
+    /// on windows, translate paths between C:\ and /c/ when running bash plugin scripts and tasks
+    #[config(env = "MISE_WINDOWS_PATH_TRANSLATION", default = true)]
+    pub windows_path_translation: bool,
+    /// on windows arm64, install x64 builds of tools that have no arm64 build, they run under
+    /// emulation
+    #[config(env = "MISE_WINDOWS_X64_EMULATION", default = true)]
+    pub windows_x64_emulation: bool,
     #[config(env = "MISE_YES", default = false)]
     pub yes: bool,
 
@@ -135,6 +240,8 @@ pub struct Settings {
     pub trace: bool,
     #[config(env = "MISE_LOG_LEVEL", default = "info")]
     pub log_level: String,
+    #[config(env = "MISE_PROGRESS_EVENTS")]
+    pub progress_events: Option<String>,
     #[config(env = "MISE_PYTHON_VENV_AUTO_CREATE", default = false)]
     pub python_venv_auto_create: bool,
 }
@@ -157,6 +264,70 @@ pub struct SettingsStatus {
     pub show_tools: bool,
 }
 
+#[derive(Config, Default, Debug, Clone, Serialize)]
+#[config(partial_attr(derive(Clone, Serialize, Default)))]
+#[config(partial_attr(serde(deny_unknown_fields)))]
+pub struct SettingsAutoInstall {
+    /// install missing tools passed to `mise exec`
+    #[config(env = "MISE_AUTO_INSTALL_EXEC", default = "always")]
+    pub exec: SettingsAutoInstallMode,
+    /// install missing tools when a shim or the shell not-found hook is run
+    /// `not_found_auto_install = false` also disables this
+    #[config(env = "MISE_AUTO_INSTALL_SHIM", default = "always")]
+    pub shim: SettingsAutoInstallMode,
+    /// install missing tools passed to `mise run --tool`
+    #[config(env = "MISE_AUTO_INSTALL_RUN", default = "always")]
+    pub run: SettingsAutoInstallMode,
+    /// tools that are never installed automatically
+    #[config(env = "MISE_AUTO_INSTALL_DISABLE_TOOLS", default = [], parse_env = list_by_comma)]
+    pub disable_tools: BTreeSet<String>,
+}
+
+#[derive(Config, Default, Debug, Clone, Serialize)]
+#[config(partial_attr(derive(Clone, Serialize, Default)))]
+#[config(partial_attr(serde(deny_unknown_fields)))]
+pub struct SettingsMirrors {
+    /// replaces https://github.com in github release downloads
+    #[config(env = "MISE_MIRRORS_GITHUB_RELEASES")]
+    pub github_releases: Option<String>,
+    /// sparse index used instead of crates.io
+    #[config(env = "MISE_MIRRORS_CRATES")]
+    pub crates: Option<String>,
+    /// GOPROXY for the go backend
+    #[config(env = "MISE_MIRRORS_GO")]
+    pub go: Option<String>,
+    /// registry used instead of registry.npmjs.org
+    #[config(env = "MISE_MIRRORS_NPM")]
+    pub npm: Option<String>,
+    /// simple index used instead of pypi.org
+    #[config(env = "MISE_MIRRORS_PYPI")]
+    pub pypi: Option<String>,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Default,
+    strum::EnumString,
+    strum::Display,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum SettingsAutoInstallMode {
+    /// install missing tools without asking
+    #[default]
+    Always,
+    /// ask before installing, missing tools are skipped if the shell is not interactive
+    Prompt,
+    /// never install missing tools automatically
+    Never,
+}
+
 #[derive(
     Debug, Clone, Copy, Serialize, Deserialize, Default, strum::EnumString, strum::Display,
 )]
@@ -277,6 +448,12 @@ impl Settings {
             if arg == "--raw" {
                 s.raw = Some(true);
             }
+            if arg == "--locked" {
+                s.locked = Some(true);
+            }
+        }
+        if let Some(true) = m.get_one::<bool>("offline") {
+            s.offline = Some(true);
         }
         if let Some(cd) = m.get_one::<PathBuf>("cd") {
             s.cd = Some(cd.clone());
@@ -293,6 +470,9 @@ impl Settings {
         if let Some(true) = m.get_one::<bool>("debug") {
             s.log_level = Some("debug".to_string());
         }
+        if let Some(target) = m.get_one::<String>("progress-events") {
+            s.progress_events = Some(target.clone());
+        }
         if let Some(log_level) = m.get_one::<String>("log-level") {
             s.log_level = Some(log_level.to_string());
         }
@@ -373,6 +553,7 @@ impl Settings {
                 "env_file",
                 "trace",
                 "log_level",
+                "progress_events",
                 "python_venv_auto_create",
             ]
             .into()
@@ -392,6 +573,16 @@ impl Settings {
         Ok(())
     }
 
+    /// fails with what would have been done over the network when offline mode is enabled
+    pub fn ensure_online(&self, what: &str) -> Result<()> {
+        if self.offline {
+            bail!(
+                "mise is offline, not {what}. Remove --offline or unset MISE_OFFLINE to allow it"
+            );
+        }
+        Ok(())
+    }
+
     pub fn trusted_config_paths(&self) -> impl Iterator<Item = PathBuf> + '_ {
         self.trusted_config_paths.iter().map(file::replace_path)
     }
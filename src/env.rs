@@ -9,6 +9,8 @@ use log::LevelFilter;
 use once_cell::sync::Lazy;
 use url::Url;
 
+use crate::cli::version::NATIVE_ARCH;
+use crate::config::Settings;
 use crate::duration::HOURLY;
 use crate::env_diff::{EnvDiff, EnvDiffOperation, EnvDiffPatches};
 use crate::file::replace_path;
@@ -51,13 +53,13 @@ pub static MISE_SYSTEM_DIR: Lazy<PathBuf> =
 
 // data subdirs
 pub static MISE_INSTALLS_DIR: Lazy<PathBuf> =
-    Lazy::new(|| var_path("MISE_INSTALLS_DIR").unwrap_or_else(|| MISE_DATA_DIR.join("installs")));
+    Lazy::new(|| var_path("MISE_INSTALLS_DIR").unwrap_or_else(|| platform_data_dir("installs")));
 pub static MISE_DOWNLOADS_DIR: Lazy<PathBuf> =
     Lazy::new(|| var_path("MISE_DOWNLOADS_DIR").unwrap_or_else(|| MISE_DATA_DIR.join("downloads")));
 pub static MISE_PLUGINS_DIR: Lazy<PathBuf> =
     Lazy::new(|| var_path("MISE_PLUGINS_DIR").unwrap_or_else(|| MISE_DATA_DIR.join("plugins")));
 pub static MISE_SHIMS_DIR: Lazy<PathBuf> =
-    Lazy::new(|| var_path("MISE_SHIMS_DIR").unwrap_or_else(|| MISE_DATA_DIR.join("shims")));
+    Lazy::new(|| var_path("MISE_SHIMS_DIR").unwrap_or_else(|| platform_data_dir("shims")));
 
 pub static MISE_DEFAULT_TOOL_VERSIONS_FILENAME: Lazy<String> = Lazy::new(|| {
     var("MISE_DEFAULT_TOOL_VERSIONS_FILENAME").unwrap_or_else(|_| ".tool-versions".into())
@@ -149,6 +151,21 @@ pub static GITHUB_TOKEN: Lazy<Option<String>> = Lazy::new(|| {
         .or_else(|_| var("GITHUB_API_TOKEN"))
         .ok()
 });
+pub static MISE_GITLAB_HOST: Lazy<String> =
+    Lazy::new(|| var("MISE_GITLAB_HOST").unwrap_or_else(|_| "gitlab.com".into()));
+pub static GITLAB_TOKEN: Lazy<Option<String>> = Lazy::new(|| {
+    var("MISE_GITLAB_TOKEN")
+        .or_else(|_| var("GITLAB_TOKEN"))
+        .ok()
+});
+pub static MISE_GITEA_HOST: Lazy<String> =
+    Lazy::new(|| var("MISE_GITEA_HOST").unwrap_or_else(|_| "codeberg.org".into()));
+pub static GITEA_TOKEN: Lazy<Option<String>> =
+    Lazy::new(|| var("MISE_GITEA_TOKEN").or_else(|_| var("GITEA_TOKEN")).ok());
+pub static MISE_BINARY_CACHE_TOKEN: Lazy<Option<String>> =
+    Lazy::new(|| var("MISE_BINARY_CACHE_TOKEN").ok());
+pub static MISE_TASK_REMOTE_CACHE_TOKEN: Lazy<Option<String>> =
+    Lazy::new(|| var("MISE_TASK_REMOTE_CACHE_TOKEN").ok());
 
 pub static MISE_USE_VERSIONS_HOST: Lazy<bool> =
     Lazy::new(|| !var_is_false("MISE_USE_VERSIONS_HOST"));
@@ -281,6 +298,20 @@ fn var_option_bool(key: &str) -> Option<bool> {
     }
 }
 
+/// with the `platform_dirs` setting, installs and shims are in ~/.local/share/mise/platforms/<os>-<arch>
+/// since binaries for one platform can't run on another
+pub fn platform_data_dir(name: &str) -> PathBuf {
+    // settings files are only parsed as toml so this does not depend on the installs dir
+    if Settings::get().platform_dirs {
+        MISE_DATA_DIR
+            .join("platforms")
+            .join(format!("{}-{}", consts::OS, *NATIVE_ARCH))
+            .join(name)
+    } else {
+        MISE_DATA_DIR.join(name)
+    }
+}
+
 pub fn var_path(key: &str) -> Option<PathBuf> {
     var_os(key).map(PathBuf::from).map(replace_path)
 }
@@ -383,6 +414,23 @@ pub fn is_activated() -> bool {
     var("__MISE_DIFF").is_ok()
 }
 
+/// makes the package managers used by backends and plugins use their caches instead of the
+/// network in offline mode, vars which are already set are left alone
+pub fn set_offline_vars() {
+    let vars = [
+        ("CARGO_NET_OFFLINE", "true"),
+        ("GOPROXY", "off"),
+        ("NPM_CONFIG_OFFLINE", "true"),
+        ("PIP_NO_INDEX", "1"),
+        ("UV_OFFLINE", "1"),
+    ];
+    for (k, v) in vars {
+        if var_os(k).is_none() {
+            set_var(k, v);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
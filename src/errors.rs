@@ -1,5 +1,6 @@
 use eyre::Report;
 use std::process::ExitStatus;
+use std::time::Duration;
 
 use crate::toolset::{ToolRequest, ToolSource};
 use thiserror::Error;
@@ -8,7 +9,7 @@ use thiserror::Error;
 pub enum Error {
     #[error("Failed to resolve {tr} from {ts}: {source:#}")]
     FailedToResolveVersion {
-        tr: ToolRequest,
+        tr: Box<ToolRequest>,
         ts: ToolSource,
         source: Report,
     },
@@ -18,6 +19,8 @@ pub enum Error {
     VersionNotInstalled(String, String),
     #[error("{} exited with non-zero status: {}", .0, render_exit_status(.1))]
     ScriptFailed(String, Option<ExitStatus>),
+    #[error("{} timed out after {}", .0, humantime::format_duration(*.1))]
+    ScriptTimedOut(String, Duration),
     #[error("Config file is not trusted.\nTrust it with `mise trust`.")]
     UntrustedConfig(),
 }
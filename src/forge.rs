@@ -0,0 +1,200 @@
+use url::Url;
+
+use crate::config::Settings;
+use crate::gitea::GiteaRelease;
+use crate::github::GithubRelease;
+use crate::gitlab::GitlabRelease;
+use crate::{env, gitea, github, gitlab};
+
+/// A host of git repositories and their releases: github.com, gitlab.com, codeberg.org, or a
+/// self-hosted GitLab or Gitea/Forgejo instance from MISE_GITLAB_HOST, MISE_GITEA_HOST, or the
+/// `gitlab_hosts` and `gitea_hosts` settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Forge {
+    Github,
+    Gitlab(String),
+    Gitea(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Release {
+    pub tag_name: String,
+    pub prerelease: bool,
+    pub assets: Vec<Asset>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Asset {
+    pub name: String,
+    pub url: String,
+}
+
+impl Forge {
+    /// the forge and project of a repository url like https://gitlab.com/group/project.git
+    pub fn parse_url(url: &str) -> Option<(Self, String)> {
+        let url = Url::parse(url).ok()?;
+        let host = url.host_str()?;
+        let project = url.path().trim_matches('/').trim_end_matches(".git");
+        if project.split('/').count() < 2 {
+            return None;
+        }
+        let forge = match host {
+            "github.com" => Forge::Github,
+            _ if is_gitlab_host(host) => Forge::Gitlab(host.to_string()),
+            _ if is_gitea_host(host) => Forge::Gitea(host.to_string()),
+            _ => return None,
+        };
+        Some((forge, project.to_string()))
+    }
+
+    pub fn host(&self) -> &str {
+        match self {
+            Forge::Github => "github.com",
+            Forge::Gitlab(host) | Forge::Gitea(host) => host,
+        }
+    }
+
+    pub fn repo_url(&self, project: &str) -> String {
+        format!("https://{}/{project}.git", self.host())
+    }
+
+    /// releases of a project, newest first
+    pub fn list_releases(&self, project: &str) -> eyre::Result<Vec<Release>> {
+        let releases = match self {
+            Forge::Github => github::list_releases(project)?
+                .into_iter()
+                .map(Release::from)
+                .collect(),
+            Forge::Gitlab(host) => gitlab::list_releases(host, project)?
+                .into_iter()
+                .map(Release::from)
+                .collect(),
+            Forge::Gitea(host) => gitea::list_releases(host, project)?
+                .into_iter()
+                .map(Release::from)
+                .collect(),
+        };
+        Ok(releases)
+    }
+}
+
+/// gitlab.com, MISE_GITLAB_HOST, and the hosts in the `gitlab_hosts` setting
+pub fn is_gitlab_host(host: &str) -> bool {
+    host == "gitlab.com"
+        || host == *env::MISE_GITLAB_HOST
+        || Settings::get().gitlab_hosts.contains(host)
+}
+
+/// codeberg.org, MISE_GITEA_HOST, and the hosts in the `gitea_hosts` setting
+pub fn is_gitea_host(host: &str) -> bool {
+    host == "codeberg.org"
+        || host == *env::MISE_GITEA_HOST
+        || Settings::get().gitea_hosts.contains(host)
+}
+
+/// the name of the variable with the token for a host, e.g.: MISE_GITLAB_TOKEN_GITLAB_EXAMPLE_COM
+pub fn token_var(prefix: &str, host: &str) -> String {
+    let name = host
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c.to_ascii_uppercase(),
+            false => '_',
+        })
+        .collect::<String>();
+    format!("{prefix}_{name}")
+}
+
+impl From<GithubRelease> for Release {
+    fn from(release: GithubRelease) -> Self {
+        Self {
+            tag_name: release.tag_name,
+            prerelease: release.prerelease,
+            assets: release
+                .assets
+                .into_iter()
+                .map(|a| Asset {
+                    name: a.name,
+                    url: a.browser_download_url,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<GitlabRelease> for Release {
+    fn from(release: GitlabRelease) -> Self {
+        Self {
+            tag_name: release.tag_name,
+            prerelease: release.upcoming_release,
+            assets: release
+                .assets
+                .links
+                .iter()
+                .map(|l| Asset {
+                    name: l.name.clone(),
+                    url: l.download_url().to_string(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<GiteaRelease> for Release {
+    fn from(release: GiteaRelease) -> Self {
+        Self {
+            tag_name: release.tag_name,
+            prerelease: release.prerelease,
+            assets: release
+                .assets
+                .into_iter()
+                .map(|a| Asset {
+                    name: a.name,
+                    url: a.browser_download_url,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_url() {
+        assert_eq!(
+            Forge::parse_url("https://github.com/nicklockwood/SwiftFormat.git"),
+            Some((Forge::Github, "nicklockwood/SwiftFormat".to_string()))
+        );
+        assert_eq!(
+            Forge::parse_url("https://gitlab.com/group/sub/project.git"),
+            Some((
+                Forge::Gitlab("gitlab.com".to_string()),
+                "group/sub/project".to_string()
+            ))
+        );
+        assert_eq!(
+            Forge::parse_url("https://codeberg.org/owner/repo.git"),
+            Some((
+                Forge::Gitea("codeberg.org".to_string()),
+                "owner/repo".to_string()
+            ))
+        );
+        assert_eq!(Forge::parse_url("https://example.com/owner/repo.git"), None);
+        assert_eq!(Forge::parse_url("https://github.com/owner"), None);
+        assert_eq!(
+            Forge::Gitlab("gitlab.com".to_string()).repo_url("group/project"),
+            "https://gitlab.com/group/project.git"
+        );
+    }
+
+    #[test]
+    fn test_token_var() {
+        assert_eq!(
+            token_var("MISE_GITEA_TOKEN", "git.example-corp.com"),
+            "MISE_GITEA_TOKEN_GIT_EXAMPLE_CORP_COM"
+        );
+    }
+}
@@ -7,6 +7,7 @@ use once_cell::sync::OnceCell;
 use xx::file;
 
 use crate::cmd;
+use crate::config::Settings;
 use crate::file::touch_dir;
 
 pub struct Git {
@@ -58,6 +59,7 @@ impl Git {
     pub fn update(&self, gitref: Option<String>) -> Result<(String, String)> {
         let gitref = gitref.map_or_else(|| self.current_branch(), Ok)?;
         debug!("updating {} to {}", self.dir.display(), gitref);
+        Settings::get().ensure_online(&format!("updating {}", self.dir.display()))?;
         let exec = |cmd: Expression| match cmd.stderr_to_stdout().stdout_capture().unchecked().run()
         {
             Ok(res) => {
@@ -72,14 +74,25 @@ impl Git {
             }
             Err(err) => Err(eyre!("git failed: {cmd:?} {err:#}")),
         };
-        exec(git_cmd!(
-            &self.dir,
-            "fetch",
-            "--prune",
-            "--update-head-ok",
-            "origin",
-            &format!("{}:{}", gitref, gitref),
-        ))?;
+        if is_sha(&gitref) {
+            // commits can't be used as the destination of a refspec
+            if git_cmd!(&self.dir, "cat-file", "-e", format!("{gitref}^{{commit}}"))
+                .stderr_null()
+                .run()
+                .is_err()
+            {
+                exec(git_cmd!(&self.dir, "fetch", "origin", &gitref))?;
+            }
+        } else {
+            exec(git_cmd!(
+                &self.dir,
+                "fetch",
+                "--prune",
+                "--update-head-ok",
+                "origin",
+                &format!("{}:{}", gitref, gitref),
+            ))?;
+        }
         let prev_rev = self.current_sha()?;
         exec(git_cmd!(
             &self.dir,
@@ -99,6 +112,7 @@ impl Git {
 
     pub fn clone(&self, url: &str) -> Result<()> {
         debug!("cloning {} to {}", url, self.dir.display());
+        Settings::get().ensure_online(&format!("cloning {url}"))?;
         if let Some(parent) = self.dir.parent() {
             file::mkdirp(parent)?;
         }
@@ -118,6 +132,17 @@ impl Git {
         Ok(())
     }
 
+    /// commits that changed a file, newest first
+    pub fn file_history(&self, path: &str) -> Result<Vec<String>> {
+        let out = git_cmd_read!(&self.dir, "log", "--format=%H", "--", path)?;
+        Ok(out.lines().map(|l| l.to_string()).collect())
+    }
+
+    /// contents of a file at a commit
+    pub fn show_file(&self, rev: &str, path: &str) -> Result<String> {
+        git_cmd_read!(&self.dir, "show", &format!("{rev}:{path}"))
+    }
+
     pub fn current_branch(&self) -> Result<String> {
         let dir = &self.dir;
         if let Ok(repo) = self.repo() {
@@ -194,6 +219,38 @@ impl Git {
         }
     }
 
+    /// the commit the repo is pinned to with `mise plugins install <name> <url>#<sha>`,
+    /// stored in the repo's git config so it is removed along with the plugin
+    pub fn pinned_sha(&self) -> Option<String> {
+        git_cmd_read!(&self.dir, "config", "--get", "mise.pinned")
+            .ok()
+            .filter(|sha| !sha.is_empty())
+    }
+
+    pub fn set_pinned_sha(&self, sha: Option<&str>) -> Result<()> {
+        match sha {
+            Some(sha) => {
+                git_cmd_read!(&self.dir, "config", "mise.pinned", sha)?;
+            }
+            None if self.pinned_sha().is_some() => {
+                git_cmd_read!(&self.dir, "config", "--unset", "mise.pinned")?;
+            }
+            None => {}
+        }
+        Ok(())
+    }
+
+    /// the commit a ref points to on the remote, e.g.: "HEAD" or "main"
+    pub fn remote_sha(&self, gitref: &str) -> Result<Option<String>> {
+        Settings::get().ensure_online(&format!("checking {gitref} of {}", self.dir.display()))?;
+        let out = git_cmd_read!(&self.dir, "ls-remote", "origin", gitref)?;
+        Ok(out
+            .lines()
+            .next()
+            .and_then(|l| l.split_whitespace().next())
+            .map(|sha| sha.to_string()))
+    }
+
     pub fn split_url_and_ref(url: &str) -> (String, Option<String>) {
         match url.split_once('#') {
             Some((url, _ref)) => (url.to_string(), Some(_ref.to_string())),
@@ -213,6 +270,11 @@ impl Git {
     }
 }
 
+/// true if the ref is a commit sha rather than a branch or tag
+pub fn is_sha(gitref: &str) -> bool {
+    regex!(r"^[0-9a-f]{7,40}$").is_match(gitref)
+}
+
 fn get_git_version() -> Result<String> {
     let version = cmd!("git", "--version").read()?;
     Ok(version.trim().into())
@@ -0,0 +1,56 @@
+use serde_derive::Deserialize;
+
+use crate::env::{GITEA_TOKEN, MISE_GITEA_HOST};
+use crate::{env, forge};
+
+#[derive(Debug, Deserialize)]
+pub struct GiteaRelease {
+    pub tag_name: String,
+    pub draft: bool,
+    pub prerelease: bool,
+    pub assets: Vec<GiteaAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GiteaAsset {
+    pub name: String,
+    pub browser_download_url: String,
+}
+
+/// releases of a repository on codeberg.org or another Gitea/Forgejo instance, newest first
+pub fn list_releases(host: &str, repo: &str) -> eyre::Result<Vec<GiteaRelease>> {
+    let url = format!("{}/releases?limit=50", repo_url(host, repo));
+    let releases: Vec<GiteaRelease> = crate::http::HTTP_FETCH.json(url)?;
+    Ok(releases.into_iter().filter(|r| !r.draft).collect())
+}
+
+pub fn get_release(host: &str, repo: &str, tag: &str) -> eyre::Result<GiteaRelease> {
+    let url = match tag {
+        "latest" => format!("{}/releases/latest", repo_url(host, repo)),
+        _ => format!("{}/releases/tags/{tag}", repo_url(host, repo)),
+    };
+    crate::http::HTTP_FETCH.json(url)
+}
+
+/// the token for a host from MISE_GITEA_TOKEN_<HOST>, e.g.: MISE_GITEA_TOKEN_CODEBERG_ORG, or
+/// GITEA_TOKEN for MISE_GITEA_HOST so it isn't sent to other instances
+pub fn token(host: &str) -> Option<String> {
+    env::var(forge::token_var("MISE_GITEA_TOKEN", host))
+        .ok()
+        .or_else(|| GITEA_TOKEN.clone().filter(|_| host == *MISE_GITEA_HOST))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GiteaUser {
+    pub login: String,
+}
+
+/// returns the user that GITEA_TOKEN belongs to, fails if the token is invalid
+pub fn current_user() -> eyre::Result<GiteaUser> {
+    let url = format!("https://{}/api/v1/user", *MISE_GITEA_HOST);
+    crate::http::HTTP_FETCH.json(url)
+}
+
+fn repo_url(host: &str, repo: &str) -> String {
+    format!("https://{host}/api/v1/repos/{repo}")
+}
@@ -1,4 +1,14 @@
-use serde_derive::Deserialize;
+use std::path::PathBuf;
+
+use reqwest::header::{HeaderMap, IF_NONE_MATCH};
+use serde::de::DeserializeOwned;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::config::Settings;
+use crate::file::display_path;
+use crate::hash::hash_to_str;
+use crate::http::HTTP_FETCH;
+use crate::{dirs, file};
 
 #[derive(Debug, Deserialize)]
 pub struct GithubRelease {
@@ -8,9 +18,99 @@ pub struct GithubRelease {
     pub prerelease: bool,
     pub created_at: String,
     pub published_at: String,
+    pub html_url: String,
+    #[serde(default)]
+    pub assets: Vec<GithubAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GithubAsset {
+    pub name: String,
+    pub browser_download_url: String,
 }
 
 pub fn list_releases(repo: &str) -> eyre::Result<Vec<GithubRelease>> {
     let url = format!("https://api.github.com/repos/{}/releases", repo);
-    crate::http::HTTP_FETCH.json(url)
+    get(&url)
+}
+
+/// a release by its tag, or the latest release if the tag is "latest"
+pub fn get_release(repo: &str, tag: &str) -> eyre::Result<GithubRelease> {
+    let url = match tag {
+        "latest" => format!("https://api.github.com/repos/{repo}/releases/latest"),
+        _ => format!("https://api.github.com/repos/{repo}/releases/tags/{tag}"),
+    };
+    get(&url)
+}
+
+/// a response and its ETag, cached so the next request for it can be conditional
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedResponse {
+    etag: String,
+    body: String,
+}
+
+fn cache_path(url: &str) -> PathBuf {
+    dirs::CACHE
+        .join("github")
+        .join(format!("{}.json", hash_to_str(&url)))
+}
+
+/// GETs an api url with `If-None-Match` if it was fetched before, a 304 Not Modified response
+/// doesn't count against the rate limit. The cached response is also used when offline.
+fn get<T: DeserializeOwned>(url: &str) -> eyre::Result<T> {
+    let path = cache_path(url);
+    let cached = file::read_to_string(&path)
+        .ok()
+        .and_then(|body| serde_json::from_str::<CachedResponse>(&body).ok());
+    let mut headers = HeaderMap::new();
+    if let Some(cached) = &cached {
+        if Settings::get().offline {
+            return Ok(serde_json::from_str(&cached.body)?);
+        }
+        headers.insert(IF_NONE_MATCH, cached.etag.parse()?);
+    }
+    let body = match (HTTP_FETCH.get_text_conditional(url, &headers)?, cached) {
+        (None, Some(cached)) => {
+            debug!("{url} not modified, using {}", display_path(&path));
+            cached.body
+        }
+        (None, None) => eyre::bail!("{url} responded 304 Not Modified to an unconditional request"),
+        (Some((body, etag)), _) => {
+            if let Some(etag) = etag {
+                let cached = CachedResponse {
+                    etag,
+                    body: body.clone(),
+                };
+                file::create_dir_all(path.parent().unwrap())?;
+                file::write(&path, serde_json::to_string(&cached)?)?;
+            }
+            body
+        }
+    };
+    Ok(serde_json::from_str(&body)?)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GithubRateLimit {
+    pub resources: GithubRateLimitResources,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GithubRateLimitResources {
+    pub core: GithubRateLimitResource,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GithubRateLimitResource {
+    pub limit: u64,
+    pub remaining: u64,
+    /// when the limit resets in seconds since the epoch
+    pub reset: i64,
+}
+
+/// this endpoint does not count against the rate limit
+pub fn rate_limit() -> eyre::Result<GithubRateLimitResource> {
+    let rate_limit: GithubRateLimit = HTTP_FETCH.json("https://api.github.com/rate_limit")?;
+    Ok(rate_limit.resources.core)
 }
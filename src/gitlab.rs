@@ -0,0 +1,110 @@
+use serde_derive::Deserialize;
+
+use crate::env::{GITLAB_TOKEN, MISE_GITLAB_HOST};
+use crate::{env, forge};
+
+#[derive(Debug, Deserialize)]
+pub struct GitlabRelease {
+    pub tag_name: String,
+    pub upcoming_release: bool,
+    pub assets: GitlabAssets,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GitlabAssets {
+    pub links: Vec<GitlabAssetLink>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GitlabAssetLink {
+    pub name: String,
+    pub url: String,
+    pub direct_asset_url: Option<String>,
+}
+
+impl GitlabAssetLink {
+    pub fn download_url(&self) -> &str {
+        self.direct_asset_url.as_deref().unwrap_or(&self.url)
+    }
+}
+
+/// every release of a project, the pages are followed with the `x-next-page` header
+pub fn list_releases(host: &str, project: &str) -> eyre::Result<Vec<GitlabRelease>> {
+    let mut releases = vec![];
+    let mut page = "1".to_string();
+    loop {
+        let url = format!(
+            "{}/releases?per_page=100&page={page}",
+            project_url(host, project)
+        );
+        let (mut items, headers): (Vec<GitlabRelease>, _) =
+            crate::http::HTTP_FETCH.json_headers(url)?;
+        releases.append(&mut items);
+        match headers.get("x-next-page").and_then(|h| h.to_str().ok()) {
+            Some(next) if !next.is_empty() => page = next.to_string(),
+            _ => return Ok(releases),
+        }
+    }
+}
+
+pub fn get_release(host: &str, project: &str, tag: &str) -> eyre::Result<GitlabRelease> {
+    let url = format!("{}/releases/{}", project_url(host, project), encode(tag));
+    crate::http::HTTP_FETCH.json(url)
+}
+
+/// the token for a host from MISE_GITLAB_TOKEN_<HOST>, e.g.: MISE_GITLAB_TOKEN_GITLAB_EXAMPLE_COM,
+/// or GITLAB_TOKEN for MISE_GITLAB_HOST so it isn't sent to other instances
+pub fn token(host: &str) -> Option<String> {
+    env::var(forge::token_var("MISE_GITLAB_TOKEN", host))
+        .ok()
+        .or_else(|| GITLAB_TOKEN.clone().filter(|_| host == *MISE_GITLAB_HOST))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GitlabUser {
+    pub username: String,
+}
+
+/// returns the user that GITLAB_TOKEN belongs to, fails if the token is invalid
+pub fn current_user() -> eyre::Result<GitlabUser> {
+    let url = format!("https://{}/api/v4/user", *MISE_GITLAB_HOST);
+    crate::http::HTTP_FETCH.json(url)
+}
+
+fn project_url(host: &str, project: &str) -> String {
+    format!("https://{host}/api/v4/projects/{}", encode(project))
+}
+
+/// percent-encodes a path segment, e.g.: a project path or a tag with a `/` or `+` in it
+fn encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_project_url() {
+        assert_eq!(
+            project_url("gitlab.example.com", "group/sub/project"),
+            "https://gitlab.example.com/api/v4/projects/group%2Fsub%2Fproject"
+        );
+    }
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encode("v1.2.3"), "v1.2.3");
+        assert_eq!(encode("release/1.0+build"), "release%2F1.0%2Bbuild");
+        assert_eq!(encode("a b?#"), "a%20b%3F%23");
+    }
+}
@@ -19,6 +19,13 @@ pub fn hash_to_str<T: Hash>(t: &T) -> String {
     format!("{bytes:x}")
 }
 
+pub fn hash_sha256_to_str(s: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(s.as_bytes());
+    let hash = hasher.finalize();
+    format!("{hash:x}")
+}
+
 pub fn file_hash_sha256(path: &Path) -> Result<String> {
     file_hash_sha256_prog(path, None)
 }
@@ -85,6 +92,14 @@ mod tests {
         assert_eq!(hash_to_str(&"foo"), "3e8b8c44c3ca73b7");
     }
 
+    #[test]
+    fn test_hash_sha256_to_str() {
+        assert_eq!(
+            hash_sha256_to_str("foo"),
+            "2c26b46b68ffc68ff99b453c1d30413413422d706483bfa0f98a5e886266e7ae"
+        );
+    }
+
     #[test]
     fn test_hash_sha256() {
         reset();
@@ -0,0 +1,155 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use eyre::{bail, Result};
+use itertools::Itertools;
+
+use crate::config::{Config, Settings};
+use crate::hash::hash_to_str;
+use crate::lock_file::LockFile;
+use crate::path_env::PathEnv;
+use crate::toolset::Toolset;
+use crate::ui::style;
+use crate::{dirs, env, file};
+
+/// replaces PATH in `env` with only the tools managed by mise and a directory of links to the
+/// commands in `hermetic_allow` so anything else can't be executed
+pub fn apply(config: &Config, ts: &Toolset, env: &mut BTreeMap<String, String>) -> Result<()> {
+    if cfg!(windows) {
+        bail!("hermetic mode is not supported on Windows");
+    }
+    let mut path_env = PathEnv::from_iter(vec![allow_dir()?]);
+    for p in config.path_dirs()?.clone() {
+        path_env.add(p);
+    }
+    for p in ts.list_paths() {
+        path_env.add(p);
+    }
+    env.insert("PATH".to_string(), path_env.to_string());
+    Ok(())
+}
+
+/// checks that a program can be run in hermetic mode before running it, absolute paths have to be
+/// in a directory on the hermetic PATH (e.g. a mise install) or be one of the allowed commands
+pub fn ensure_available(program: &str, env: &BTreeMap<String, String>) -> Result<()> {
+    let path = Path::new(program);
+    let available = if path.is_absolute() {
+        path.starts_with(*dirs::INSTALLS)
+            || path_dirs(env).any(|dir| path.parent() == Some(dir.as_path()))
+            || is_allowed_target(path)
+    } else {
+        find(program, env).is_some()
+    };
+    if available {
+        return Ok(());
+    }
+    bail!("{}", report(program))
+}
+
+/// explains why a command was not found in hermetic mode
+pub fn report(program: &str) -> String {
+    let settings = Settings::get();
+    let system = match which(program) {
+        Some(path) => format!("{} is installed at {}", program, path.display()),
+        None => format!("{program} is not installed"),
+    };
+    format!(
+        "hermetic mode: {} is not managed by mise. {system}, add it to hermetic_allow or install it with mise.\nallowed commands: {}",
+        style::ered(program),
+        settings.hermetic_allow.iter().join(", ")
+    )
+}
+
+/// explains why a script run by a task exited with 127, the command that wasn't found is unknown
+pub fn report_script(task: &str) -> String {
+    format!(
+        "hermetic mode: {} ran a command that was not found, only tools managed by mise and commands in hermetic_allow are on PATH.\nallowed commands: {}",
+        style::ered(task),
+        Settings::get().hermetic_allow.iter().join(", ")
+    )
+}
+
+fn find(program: &str, env: &BTreeMap<String, String>) -> Option<PathBuf> {
+    path_dirs(env)
+        .map(|p| p.join(program))
+        .find(|p| file::is_executable(p))
+}
+
+fn path_dirs(env: &BTreeMap<String, String>) -> impl Iterator<Item = PathBuf> + '_ {
+    env.get("PATH")
+        .into_iter()
+        .flat_map(|path| std::env::split_paths(path).collect_vec())
+}
+
+fn is_allowed_target(path: &Path) -> bool {
+    let Ok(path) = path.canonicalize() else {
+        return false;
+    };
+    Settings::get()
+        .hermetic_allow
+        .iter()
+        .filter_map(|name| which(name))
+        .any(|target| target.canonicalize().is_ok_and(|t| t == path))
+}
+
+/// finds a command on the PATH mise was started with, leaving out the hermetic-bin directories
+/// so an allowed command can't resolve to a link to itself
+fn which(name: &str) -> Option<PathBuf> {
+    let root = dirs::STATE.join("hermetic-bin");
+    env::PATH
+        .iter()
+        .filter(|p| !p.starts_with(&root))
+        .map(|p| p.join(name))
+        .find(|p| file::is_executable(p))
+}
+
+/// links the allowed commands from the system PATH into a directory, each allowlist gets its own
+/// directory so runs with different settings don't change each other's links
+fn allow_dir() -> Result<PathBuf> {
+    let settings = Settings::get();
+    let dir = dirs::STATE
+        .join("hermetic-bin")
+        .join(hash_to_str(&settings.hermetic_allow));
+    let _lock = LockFile::new(&dir).lock()?;
+    file::create_dir_all(&dir)?;
+    for name in &settings.hermetic_allow {
+        let link = dir.join(name);
+        match which(name) {
+            Some(target) if link.read_link().ok().as_ref() == Some(&target) => {}
+            Some(target) => file::make_symlink(&target, &link)?,
+            None => {
+                debug!("hermetic: {name} not found on PATH");
+                if link.is_symlink() {
+                    file::remove_file(&link)?;
+                }
+            }
+        }
+    }
+    Ok(dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test::reset;
+
+    use super::*;
+
+    #[test]
+    fn test_ensure_available_absolute() {
+        reset();
+        let bin = tempfile::tempdir().unwrap();
+        let tool = bin.path().join("tool");
+        file::write(&tool, "#!/bin/sh\n").unwrap();
+        file::make_executable(&tool).unwrap();
+        let mut env = BTreeMap::new();
+        env.insert("PATH".to_string(), "/nonexistent".to_string());
+        assert!(ensure_available(&tool.to_string_lossy(), &env).is_err());
+        assert!(ensure_available("tool", &env).is_err());
+
+        env.insert("PATH".to_string(), bin.path().to_string_lossy().to_string());
+        assert!(ensure_available(&tool.to_string_lossy(), &env).is_ok());
+        assert!(ensure_available("tool", &env).is_ok());
+        let install = dirs::INSTALLS.join("dummy/1.0.0/bin/dummy");
+        assert!(ensure_available(&install.to_string_lossy(), &env).is_ok());
+    }
+}
@@ -72,6 +72,16 @@ fn have_config_files_been_modified(
     false
 }
 
+/// true if none of the watched files or MISE_ env vars changed, used to reuse what `mise exec`
+/// resolved before
+pub fn are_watches_fresh(watches: &HookEnvWatches) -> bool {
+    let unmodified = watches
+        .files
+        .iter()
+        .all(|(fp, modtime)| fp.metadata().and_then(|m| m.modified()).ok() == Some(*modtime));
+    unmodified && !have_mise_env_vars_been_modified(watches)
+}
+
 fn have_mise_env_vars_been_modified(watches: &HookEnvWatches) -> bool {
     if get_mise_env_vars_hashed() != watches.env_var_hash {
         return true;
@@ -79,10 +89,19 @@ fn have_mise_env_vars_been_modified(watches: &HookEnvWatches) -> bool {
     false
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct HookEnvWatches {
     files: BTreeMap<PathBuf, SystemTime>,
     env_var_hash: String,
+    /// the active version of each tool, used to run `[hooks.version_change]`
+    #[serde(default)]
+    pub tools: BTreeMap<String, String>,
+    /// the directory `hook-env` last ran in, used by `[hooks] enter` and `leave`
+    #[serde(default)]
+    pub dir: Option<PathBuf>,
+    /// config files with `[hooks] enter` or `leave` scripts that were active
+    #[serde(default)]
+    pub dir_hook_files: BTreeSet<PathBuf>,
 }
 
 pub fn serialize_watches(watches: &HookEnvWatches) -> Result<String> {
@@ -102,6 +121,7 @@ pub fn deserialize_watches(raw: String) -> Result<HookEnvWatches> {
 
 pub fn build_watches(
     watch_files: impl IntoIterator<Item = impl AsRef<Path>>,
+    tools: BTreeMap<String, String>,
 ) -> Result<HookEnvWatches> {
     let mut watches = BTreeMap::new();
     for cf in get_watch_files(watch_files) {
@@ -111,6 +131,9 @@ pub fn build_watches(
     Ok(HookEnvWatches {
         files: watches,
         env_var_hash: get_mise_env_vars_hashed(),
+        tools,
+        dir: dirs::CWD.clone(),
+        dir_hook_files: Default::default(),
     })
 }
 
@@ -182,6 +205,7 @@ mod tests {
         let watches = HookEnvWatches {
             files: BTreeMap::new(),
             env_var_hash: "".into(),
+            ..Default::default()
         };
         assert!(!have_config_files_been_modified(&watches, files));
 
@@ -189,6 +213,7 @@ mod tests {
         let watches = HookEnvWatches {
             files: BTreeMap::from([(fp.clone(), UNIX_EPOCH)]),
             env_var_hash: "".into(),
+            ..Default::default()
         };
         let files = BTreeSet::from([fp.clone()]);
         assert!(have_config_files_been_modified(&watches, files));
@@ -197,6 +222,7 @@ mod tests {
         let watches = HookEnvWatches {
             files: BTreeMap::from([(fp.clone(), modtime)]),
             env_var_hash: "".into(),
+            ..Default::default()
         };
         let files = BTreeSet::from([fp]);
         assert!(!have_config_files_been_modified(&watches, files));
@@ -208,6 +234,7 @@ mod tests {
         let watches = HookEnvWatches {
             files: BTreeMap::new(),
             env_var_hash: "".into(),
+            ..Default::default()
         };
         let serialized = serialize_watches(&watches).unwrap();
         let deserialized = deserialize_watches(serialized).unwrap();
@@ -220,6 +247,7 @@ mod tests {
         let serialized = serialize_watches(&HookEnvWatches {
             files: BTreeMap::from([("foo".into(), UNIX_EPOCH)]),
             env_var_hash: "testing-123".into(),
+            ..Default::default()
         })
         .unwrap();
         let deserialized = deserialize_watches(serialized).unwrap();
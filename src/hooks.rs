@@ -0,0 +1,209 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use indexmap::IndexMap;
+use itertools::Itertools;
+
+use crate::config::config_file::{self, ConfigFile, HookScript};
+use crate::config::Config;
+use crate::dirs;
+use crate::toolset::Toolset;
+
+/// runs the `[hooks.version_change]` scripts of tools whose active version is different from
+/// the last time `hook-env` ran in this shell, e.g.:
+///
+/// [hooks.version_change]
+/// node = "corepack enable"
+///
+/// The scripts get MISE_TOOL, MISE_PREVIOUS_VERSION, and MISE_TOOL_VERSION, the versions are
+/// empty if the tool was not active before or is not active anymore. Only trusted config files
+/// run hooks and failures are only warnings so they don't break the shell prompt.
+pub fn run_version_change_hooks(
+    config: &Config,
+    ts: &Toolset,
+    previous: &BTreeMap<String, String>,
+    current: &BTreeMap<String, String>,
+) {
+    let changed = previous
+        .keys()
+        .chain(current.keys())
+        .filter(|tool| previous.get(*tool) != current.get(*tool))
+        .collect::<BTreeSet<_>>();
+    if changed.is_empty() {
+        return;
+    }
+    let hooks = version_change_hooks(&config.config_files);
+    let env = match ts.env_with_path(config) {
+        Ok(env) => env,
+        Err(err) => {
+            warn!("failed to run version_change hooks: {err:#}");
+            return;
+        }
+    };
+    for tool in changed {
+        let Some((script, dir)) = hooks.get(tool.as_str()) else {
+            continue;
+        };
+        let prev = previous.get(tool).cloned().unwrap_or_default();
+        let version = current.get(tool).cloned().unwrap_or_default();
+        debug!("{tool} changed from {prev:?} to {version:?}, running version_change hook");
+        for script in script.scripts() {
+            // stdout of hook-env is evaluated by the shell so the output goes to stderr
+            let mut cmd = cmd!("sh", "-c", script)
+                .full_env(&env)
+                .env("MISE_TOOL", tool)
+                .env("MISE_PREVIOUS_VERSION", &prev)
+                .env("MISE_TOOL_VERSION", &version)
+                .stdout_to_stderr();
+            if let Some(dir) = dir {
+                cmd = cmd.dir(dir);
+            }
+            if let Err(err) = cmd.run() {
+                warn!("version_change hook for {tool} failed: {err}");
+                break;
+            }
+        }
+    }
+}
+
+/// the `[hooks.version_change]` scripts by tool and the project root they run in, only trusted
+/// config files run hooks since hook-env runs them as soon as the shell enters a directory
+fn version_change_hooks(
+    config_files: &IndexMap<PathBuf, Box<dyn ConfigFile>>,
+) -> BTreeMap<&str, (&HookScript, Option<PathBuf>)> {
+    // config files are ordered closest first so project hooks override global ones
+    let mut hooks = BTreeMap::new();
+    for (path, cf) in config_files {
+        if cf.hooks().version_change.is_empty() || !config_file::is_trusted(path) {
+            continue;
+        }
+        for (tool, script) in &cf.hooks().version_change {
+            hooks
+                .entry(tool.as_str())
+                .or_insert((script, cf.project_root().map(|p| p.to_path_buf())));
+        }
+    }
+    hooks
+}
+
+/// trusted config files with `[hooks] enter` or `leave` scripts
+pub fn dir_hook_files(config: &Config) -> BTreeSet<PathBuf> {
+    config
+        .config_files
+        .iter()
+        .filter(|(_, cf)| cf.hooks().enter.is_some() || cf.hooks().leave.is_some())
+        .map(|(path, _)| path.clone())
+        .filter(|path| config_file::is_trusted(path))
+        .collect()
+}
+
+/// runs the `[hooks] leave` scripts of projects the shell left and then the `[hooks] enter` scripts
+/// of projects it entered since the last time `hook-env` ran, e.g.:
+///
+/// [hooks]
+/// enter = "docker compose up -d"
+/// leave = "docker compose stop"
+///
+/// Projects are left innermost first and entered outermost first. The scripts run in the project
+/// root and get MISE_CWD and MISE_PREVIOUS_DIR. Only trusted config files run hooks and failures
+/// are only warnings so they don't break the shell prompt.
+pub fn run_dir_hooks(
+    config: &Config,
+    ts: &Toolset,
+    previous_dir: Option<&Path>,
+    previous: &BTreeSet<PathBuf>,
+    current: &BTreeSet<PathBuf>,
+) {
+    let left = previous
+        .difference(current)
+        .sorted_by_key(|p| std::cmp::Reverse(p.components().count()))
+        .collect_vec();
+    let entered = current
+        .difference(previous)
+        .sorted_by_key(|p| p.components().count())
+        .collect_vec();
+    if left.is_empty() && entered.is_empty() {
+        return;
+    }
+    let env = match ts.env_with_path(config) {
+        Ok(env) => env,
+        Err(err) => {
+            warn!("failed to run enter/leave hooks: {err:#}");
+            return;
+        }
+    };
+    let run = |hook: &str, path: &Path, root: Option<&Path>, script: &HookScript| {
+        debug!("running {hook} hook of {}", path.display());
+        for script in script.scripts() {
+            let mut cmd = cmd!("sh", "-c", script).full_env(&env).stdout_to_stderr();
+            if let Some(cwd) = &*dirs::CWD {
+                cmd = cmd.env("MISE_CWD", cwd);
+            }
+            if let Some(dir) = previous_dir {
+                cmd = cmd.env("MISE_PREVIOUS_DIR", dir);
+            }
+            if let Some(root) = root {
+                cmd = cmd.dir(root);
+            }
+            if let Err(err) = cmd.run() {
+                warn!("{hook} hook of {} failed: {err}", path.display());
+                break;
+            }
+        }
+    };
+    for path in left {
+        if !path.exists() {
+            continue;
+        }
+        // the config file of a project that was left isn't loaded anymore
+        let cf = match config_file::parse(path) {
+            Ok(cf) => cf,
+            Err(err) => {
+                warn!("failed to run leave hook of {}: {err:#}", path.display());
+                continue;
+            }
+        };
+        if let Some(script) = &cf.hooks().leave {
+            run("leave", path, cf.project_root(), script);
+        }
+    }
+    for path in entered {
+        let Some(cf) = config.config_files.get(path) else {
+            continue;
+        };
+        if let Some(script) = &cf.hooks().enter {
+            run("enter", path, cf.project_root(), script);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexMap;
+
+    use crate::config::config_file::mise_toml::MiseToml;
+    use crate::config::config_file::{self, ConfigFile};
+    use crate::file;
+    use crate::test::reset;
+
+    use super::version_change_hooks;
+
+    #[test]
+    fn test_version_change_hooks_untrusted() {
+        reset();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mise.toml");
+        file::write(
+            &path,
+            "[hooks.version_change]\nnode = \"corepack enable\"\n",
+        )
+        .unwrap();
+        let cf: Box<dyn ConfigFile> = Box::new(MiseToml::from_file(&path).unwrap());
+        let config_files = IndexMap::from([(path.clone(), cf)]);
+        assert!(version_change_hooks(&config_files).is_empty());
+
+        config_file::trust(&path).unwrap();
+        assert!(version_change_hooks(&config_files).contains_key("node"));
+        config_file::untrust(&path).unwrap();
+    }
+}
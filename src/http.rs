@@ -1,11 +1,17 @@
-use std::fs::File;
-use std::io::Write;
-use std::path::Path;
+use std::fs::{File, OpenOptions};
+use std::io::{Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use std::time::Duration;
+use std::{panic, thread};
 
-use eyre::{bail, Report, Result};
+use eyre::{bail, eyre, Report, Result};
 use once_cell::sync::Lazy;
-use reqwest::{ClientBuilder, IntoUrl, Response};
+use reqwest::header::{
+    HeaderMap, HeaderValue, ACCEPT_ENCODING, CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE,
+    RETRY_AFTER, WWW_AUTHENTICATE,
+};
+use reqwest::{ClientBuilder, IntoUrl, Response, StatusCode};
 use tokio::runtime::Runtime;
 use url::Url;
 
@@ -13,8 +19,9 @@ use crate::cli::version;
 use crate::config::Settings;
 use crate::env::MISE_FETCH_REMOTE_VERSIONS_TIMEOUT;
 use crate::file::display_path;
+use crate::hash::hash_sha256_to_str;
 use crate::ui::progress_report::SingleReport;
-use crate::{env, file};
+use crate::{dirs, env, file, forge, gitea, gitlab, mirrors, store};
 
 #[cfg(not(test))]
 pub static HTTP_VERSION_CHECK: Lazy<Client> =
@@ -26,6 +33,20 @@ pub static HTTP: Lazy<Client> =
 pub static HTTP_FETCH: Lazy<Client> =
     Lazy::new(|| Client::new(*MISE_FETCH_REMOTE_VERSIONS_TIMEOUT).unwrap());
 
+/// files downloaded by this process and the urls they came from, see `downloads_in`
+static DOWNLOADS: Lazy<Mutex<Vec<(PathBuf, String)>>> = Lazy::new(Default::default);
+
+/// the files downloaded into `dir` and their urls, used to record artifacts in mise.lock
+pub fn downloads_in(dir: &Path) -> Vec<(PathBuf, String)> {
+    DOWNLOADS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(path, _)| path.starts_with(dir) && path.exists())
+        .cloned()
+        .collect()
+}
+
 #[derive(Debug)]
 pub struct Client {
     reqwest: reqwest::Client,
@@ -47,27 +68,15 @@ impl Client {
             .gzip(true)
     }
 
-    async fn get<U: IntoUrl>(&self, url: U) -> Result<Response> {
-        let get = |url: Url| async move {
-            debug!("GET {}", &url);
-            let mut req = self.reqwest.get(url.clone());
-            if url.host_str() == Some("api.github.com") {
-                if let Some(token) = &*env::GITHUB_API_TOKEN {
-                    req = req.header("authorization", format!("token {}", token));
-                }
-            }
-            let resp = req.send().await?;
-            debug!("GET {url} {}", resp.status());
-            resp.error_for_status_ref()?;
-            Ok(resp)
-        };
+    async fn get_with_headers<U: IntoUrl>(&self, url: U, headers: &HeaderMap) -> Result<Response> {
         let mut url = url.into_url().unwrap();
-        let resp = match get(url.clone()).await {
+        Settings::get().ensure_online(&format!("fetching {url}"))?;
+        let resp = match self.get_with_retries(url.clone(), headers).await {
             Ok(resp) => resp,
             Err(_) if url.scheme() == "http" => {
                 // try with https since http may be blocked
                 url.set_scheme("https").unwrap();
-                get(url).await?
+                self.get_with_retries(url, headers).await?
             }
             Err(err) => return Err(err),
         };
@@ -76,66 +85,443 @@ impl Client {
         Ok(resp)
     }
 
+    /// retries connection errors, timeouts, and responses with one of the
+    /// `http_retry_status_codes` up to `http_retries` times
+    async fn get_with_retries(&self, url: Url, headers: &HeaderMap) -> Result<Response> {
+        let settings = Settings::get();
+        let mut attempt = 0;
+        loop {
+            let retryable = attempt < settings.http_retries;
+            let delay = match self.get(url.clone(), headers).await {
+                Ok(resp) if is_rate_limited(&resp) => match rate_limit_wait(&resp) {
+                    Some(wait) if retryable && wait <= MAX_RATE_LIMIT_WAIT => wait,
+                    wait => return Err(rate_limit_error(&url, wait)),
+                },
+                Ok(resp)
+                    if retryable
+                        && settings
+                            .http_retry_status_codes
+                            .contains(&resp.status().as_u16()) =>
+                {
+                    retry_after(&resp)
+                        .unwrap_or_else(|| retry_delay(settings.http_retry_backoff, attempt))
+                }
+                Err(err) if retryable && (err.is_connect() || err.is_timeout()) => {
+                    debug!("GET {url} {err}");
+                    retry_delay(settings.http_retry_backoff, attempt)
+                }
+                Ok(resp) => {
+                    resp.error_for_status_ref()?;
+                    return Ok(resp);
+                }
+                Err(err) => return Err(err.into()),
+            };
+            attempt += 1;
+            debug!(
+                "retrying GET {url} in {}ms ({attempt}/{})",
+                delay.as_millis(),
+                settings.http_retries
+            );
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    async fn get(&self, url: Url, headers: &HeaderMap) -> reqwest::Result<Response> {
+        debug!("GET {}", &url);
+        let mut req = self.reqwest.get(url.clone()).headers(headers.clone());
+        if url.host_str() == Some("api.github.com") {
+            if let Some(token) = &*env::GITHUB_API_TOKEN {
+                req = req.header("authorization", format!("token {}", token));
+            }
+        }
+        if let Some(host) = url.host_str().filter(|h| forge::is_gitlab_host(h)) {
+            if let Some(token) = gitlab::token(host) {
+                req = req.header("private-token", token);
+            }
+        }
+        if let Some(host) = url.host_str().filter(|h| forge::is_gitea_host(h)) {
+            if let Some(token) = gitea::token(host) {
+                req = req.header("authorization", format!("token {}", token));
+            }
+        }
+        let resp = req.send().await?;
+        debug!("GET {url} {}", resp.status());
+        Ok(resp)
+    }
+
     pub fn get_text<U: IntoUrl>(&self, url: U) -> Result<String> {
+        self.get_text_with_headers(url, &HeaderMap::new())
+    }
+
+    pub fn get_text_with_headers<U: IntoUrl>(&self, url: U, headers: &HeaderMap) -> Result<String> {
         let mut url = url.into_url().unwrap();
         let rt = self.runtime()?;
         let text = rt.block_on(async {
-            let resp = self.get(url.clone()).await?;
+            let resp = self.get_with_headers(url.clone(), headers).await?;
             Ok::<String, eyre::Error>(resp.text().await?)
         })?;
         if text.starts_with("<!DOCTYPE html>") {
             if url.scheme() == "http" {
                 // try with https since http may be blocked
                 url.set_scheme("https").unwrap();
-                return self.get_text(url);
+                return self.get_text_with_headers(url, headers);
             }
             bail!("Got HTML instead of text from {}", url);
         }
         Ok(text)
     }
 
+    /// GET with conditional request headers like `If-None-Match`, returns None if the response is
+    /// 304 Not Modified, otherwise the body and its `ETag`
+    pub fn get_text_conditional<U: IntoUrl>(
+        &self,
+        url: U,
+        headers: &HeaderMap,
+    ) -> Result<Option<(String, Option<String>)>> {
+        let url = url.into_url()?;
+        let rt = self.runtime()?;
+        rt.block_on(async {
+            let resp = self.get_with_headers(url, headers).await?;
+            if resp.status() == StatusCode::NOT_MODIFIED {
+                return Ok(None);
+            }
+            let etag = resp
+                .headers()
+                .get(ETAG)
+                .and_then(|h| h.to_str().ok())
+                .map(|h| h.to_string());
+            Ok(Some((resp.text().await?, etag)))
+        })
+    }
+
     pub fn json<T, U: IntoUrl>(&self, url: U) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        self.json_with_headers(url, &HeaderMap::new())
+    }
+
+    pub fn json_with_headers<T, U: IntoUrl>(&self, url: U, headers: &HeaderMap) -> Result<T>
     where
         T: serde::de::DeserializeOwned,
     {
         let url = url.into_url().unwrap();
         let rt = self.runtime()?;
         let json = rt.block_on(async {
-            let resp = self.get(url).await?;
+            let resp = self.get_with_headers(url, headers).await?;
             Ok::<T, eyre::Error>(resp.json().await?)
         })?;
         Ok(json)
     }
 
+    /// like `json` and also returns the headers of the response, e.g.: for pagination
+    pub fn json_headers<T, U: IntoUrl>(&self, url: U) -> Result<(T, HeaderMap)>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let url = url.into_url()?;
+        self.runtime()?.block_on(async {
+            let resp = self.get_with_headers(url, &HeaderMap::new()).await?;
+            let headers = resp.headers().clone();
+            Ok((resp.json().await?, headers))
+        })
+    }
+
+    /// returns the WWW-Authenticate challenge if the url responds with 401 Unauthorized
+    pub fn auth_challenge<U: IntoUrl>(&self, url: U) -> Result<Option<String>> {
+        let url = url.into_url()?;
+        Settings::get().ensure_online(&format!("fetching {url}"))?;
+        let rt = self.runtime()?;
+        rt.block_on(async {
+            debug!("GET {}", &url);
+            let resp = self.reqwest.get(url.clone()).send().await?;
+            debug!("GET {url} {}", resp.status());
+            if resp.status() != StatusCode::UNAUTHORIZED {
+                return Ok(None);
+            }
+            Ok(resp
+                .headers()
+                .get(WWW_AUTHENTICATE)
+                .and_then(|h| h.to_str().ok())
+                .map(|h| h.to_string()))
+        })
+    }
+
     pub fn download_file<U: IntoUrl>(
         &self,
         url: U,
         path: &Path,
         pr: Option<&dyn SingleReport>,
+    ) -> Result<()> {
+        self.download_file_with_headers(url, path, pr, &HeaderMap::new())
+    }
+
+    pub fn download_file_with_headers<U: IntoUrl>(
+        &self,
+        url: U,
+        path: &Path,
+        pr: Option<&dyn SingleReport>,
+        headers: &HeaderMap,
     ) -> Result<()> {
         let url = url.into_url()?;
+        let url_str = url.to_string();
+        if Settings::get().offline && path.exists() {
+            // e.g.: kept with always_keep_download
+            debug!("offline, using {} for {url}", display_path(path));
+            DOWNLOADS
+                .lock()
+                .unwrap()
+                .push((path.to_path_buf(), url_str));
+            return Ok(());
+        }
+        if store::is_enabled() && store::link_download(&url_str, path)? {
+            DOWNLOADS
+                .lock()
+                .unwrap()
+                .push((path.to_path_buf(), url_str));
+            return Ok(());
+        }
+        let url = Url::parse(&mirrors::rewrite_url(&url_str))?;
         debug!("GET Downloading {} to {}", &url, display_path(path));
 
+        file::create_dir_all(path.parent().unwrap())?;
+        let part = part_path(&url_str);
+        file::create_dir_all(part.parent().unwrap())?;
+        let segments = Settings::get().http_download_segments;
+        // partial downloads are resumed instead since the segments may have holes
+        if segments <= 1
+            || part.exists()
+            || !self.download_segmented(&url, &part, pr, headers, segments)?
+        {
+            self.download_resumable(&url, &part, pr, headers)?;
+        }
+        let validator = validator_path(&part);
+        if validator.exists() {
+            file::remove_file(&validator)?;
+        }
+        // the cache may be on another filesystem
+        if file::rename(&part, path).is_err() {
+            file::copy(&part, path)?;
+            file::remove_file(&part)?;
+        }
+        if store::is_enabled() {
+            store::add_download(&url_str, path)?;
+        }
+        DOWNLOADS
+            .lock()
+            .unwrap()
+            .push((path.to_path_buf(), url_str));
+        Ok(())
+    }
+
+    /// uploads a file with `PUT`, e.g.: to the binary cache
+    pub fn upload_file<U: IntoUrl>(&self, url: U, path: &Path, headers: &HeaderMap) -> Result<()> {
+        let url = url.into_url()?;
+        Settings::get().ensure_online(&format!("uploading to {url}"))?;
+        let body = std::fs::read(path)?;
         let rt = self.runtime()?;
         rt.block_on(async {
-            let mut resp = self.get(url).await?;
-            if let Some(length) = resp.content_length() {
-                if let Some(pr) = pr {
-                    pr.set_length(length);
+            debug!("PUT {} to {}", display_path(path), &url);
+            let resp = self
+                .reqwest
+                .put(url.clone())
+                .headers(headers.clone())
+                .body(body)
+                .send()
+                .await?;
+            debug!("PUT {url} {}", resp.status());
+            resp.error_for_status()?;
+            Ok(())
+        })
+    }
+
+    /// downloads to `part`, continuing after its contents if a previous download was interrupted
+    /// and the server supports range requests. Downloads interrupted while reading the body are
+    /// resumed up to `http_retries` times.
+    fn download_resumable(
+        &self,
+        url: &Url,
+        part: &Path,
+        pr: Option<&dyn SingleReport>,
+        headers: &HeaderMap,
+    ) -> Result<()> {
+        let settings = Settings::get();
+        let rt = self.runtime()?;
+        let mut attempt = 0;
+        // bytes shown in the progress bar so resuming doesn't count them again
+        let mut reported = 0;
+        loop {
+            match rt.block_on(self.download_part(url, part, pr, headers, &mut reported))? {
+                Err(err) if attempt < settings.http_retries => {
+                    let delay = retry_delay(settings.http_retry_backoff, attempt);
+                    attempt += 1;
+                    debug!(
+                        "download of {url} was interrupted: {err}, resuming in {}ms ({attempt}/{})",
+                        delay.as_millis(),
+                        settings.http_retries
+                    );
+                    thread::sleep(delay);
                 }
+                result => return Ok(result?),
             }
+        }
+    }
 
-            file::create_dir_all(path.parent().unwrap())?;
-            let mut file = File::create(path)?;
+    /// requests the rest of `part` and appends the body to it, the inner error is returned if the
+    /// body was interrupted so the download can be resumed
+    async fn download_part(
+        &self,
+        url: &Url,
+        part: &Path,
+        pr: Option<&dyn SingleReport>,
+        headers: &HeaderMap,
+        reported: &mut u64,
+    ) -> Result<reqwest::Result<()>> {
+        let validator_path = validator_path(part);
+        let validator = file::read_to_string(&validator_path).ok();
+        let mut offset = part.metadata().map(|m| m.len()).unwrap_or_default();
+        let mut headers = headers.clone();
+        match validator {
+            // the server only sends the rest if the file hasn't changed since, otherwise all of it
+            Some(validator) if offset > 0 => {
+                debug!("resuming download of {url} at {offset} bytes");
+                headers.insert(RANGE, format!("bytes={offset}-").parse()?);
+                headers.insert(IF_RANGE, validator.trim().parse()?);
+                headers.insert(ACCEPT_ENCODING, HeaderValue::from_static("identity"));
+            }
+            _ if offset > 0 => {
+                debug!("can't tell if {url} changed since it was partially downloaded, restarting");
+                offset = 0;
+            }
+            _ => {}
+        }
+        let mut resp = match self.get_with_headers(url.clone(), &headers).await {
+            // the partial download is already complete or from a different file
+            Err(err) if offset > 0 && error_code(&err) == Some(416) => {
+                headers.remove(RANGE);
+                headers.remove(IF_RANGE);
+                self.get_with_headers(url.clone(), &headers).await?
+            }
+            resp => resp?,
+        };
+        let resumed = offset > 0 && resp.status() == StatusCode::PARTIAL_CONTENT;
+        let mut file = match resumed {
+            true => OpenOptions::new().append(true).open(part)?,
+            false => {
+                if offset > 0 {
+                    debug!("{url} changed since it was partially downloaded, restarting");
+                }
+                match response_validator(&resp) {
+                    Some(validator) => file::write(&validator_path, validator)?,
+                    None if validator_path.exists() => file::remove_file(&validator_path)?,
+                    None => {}
+                }
+                File::create(part)?
+            }
+        };
+        if let (Some(length), Some(pr)) = (resp.content_length(), pr) {
+            match resumed {
+                true => {
+                    pr.set_length(offset + length);
+                    pr.inc(offset.saturating_sub(*reported));
+                    *reported = offset;
+                }
+                false => pr.set_length(length),
+            }
+        }
+        loop {
+            match resp.chunk().await {
+                Ok(Some(chunk)) => {
+                    file.write_all(&chunk)?;
+                    *reported += chunk.len() as u64;
+                    if let Some(pr) = pr {
+                        pr.inc(chunk.len() as u64);
+                    }
+                }
+                Ok(None) => return Ok(Ok(())),
+                Err(err) => return Ok(Err(err)),
+            }
+        }
+    }
+
+    /// downloads a large file with concurrent range requests, returns false if the server doesn't
+    /// support range requests or the file is too small to be worth it
+    fn download_segmented(
+        &self,
+        url: &Url,
+        part: &Path,
+        pr: Option<&dyn SingleReport>,
+        headers: &HeaderMap,
+        segments: usize,
+    ) -> Result<bool> {
+        let mut probe = headers.clone();
+        probe.insert(RANGE, HeaderValue::from_static("bytes=0-0"));
+        probe.insert(ACCEPT_ENCODING, HeaderValue::from_static("identity"));
+        let resp = self
+            .runtime()?
+            .block_on(self.get_with_headers(url.clone(), &probe))?;
+        let total = resp
+            .headers()
+            .get(CONTENT_RANGE)
+            .and_then(|h| h.to_str().ok())
+            .and_then(content_range_total)
+            .filter(|_| resp.status() == StatusCode::PARTIAL_CONTENT)
+            .filter(|total| *total >= MIN_SEGMENTED_SIZE);
+        let Some(total) = total else {
+            return Ok(false);
+        };
+        debug!("downloading {url} in {segments} segments");
+        if let Some(pr) = pr {
+            pr.set_length(total);
+        }
+        File::create(part)?.set_len(total)?;
+        let result = thread::scope(|s| {
+            segment_ranges(total, segments)
+                .into_iter()
+                .map(|(start, end)| {
+                    s.spawn(move || self.download_range(url, part, pr, headers, start, end))
+                })
+                .collect::<Vec<_>>()
+                .into_iter()
+                .try_for_each(|t| match t.join() {
+                    Ok(x) => x,
+                    Err(e) => panic::resume_unwind(e),
+                })
+        });
+        if let Err(err) = result {
+            file::remove_file(part)?;
+            return Err(err);
+        }
+        Ok(true)
+    }
+
+    fn download_range(
+        &self,
+        url: &Url,
+        part: &Path,
+        pr: Option<&dyn SingleReport>,
+        headers: &HeaderMap,
+        start: u64,
+        end: u64,
+    ) -> Result<()> {
+        let mut headers = headers.clone();
+        headers.insert(RANGE, format!("bytes={start}-{end}").parse()?);
+        headers.insert(ACCEPT_ENCODING, HeaderValue::from_static("identity"));
+        let mut file = OpenOptions::new().write(true).open(part)?;
+        file.seek(SeekFrom::Start(start))?;
+        self.runtime()?.block_on(async {
+            let mut resp = self.get_with_headers(url.clone(), &headers).await?;
+            if resp.status() != StatusCode::PARTIAL_CONTENT {
+                bail!("{url} does not support range requests");
+            }
             while let Some(chunk) = resp.chunk().await? {
                 file.write_all(&chunk)?;
                 if let Some(pr) = pr {
                     pr.inc(chunk.len() as u64);
                 }
             }
-            Ok::<(), eyre::Error>(())
-        })?;
-        Ok(())
+            Ok(())
+        })
     }
 
     fn runtime(&self) -> Result<Runtime, Report> {
@@ -147,6 +533,99 @@ impl Client {
     }
 }
 
+/// exponential backoff: `backoff`, then twice that, then four times that, ...
+fn retry_delay(backoff: u64, attempt: u32) -> Duration {
+    Duration::from_millis(backoff.saturating_mul(2u64.saturating_pow(attempt)))
+}
+
+/// the `Retry-After` header in seconds, ignored if it is more than a minute
+fn retry_after(resp: &Response) -> Option<Duration> {
+    let secs = resp
+        .headers()
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()?;
+    (secs <= 60).then(|| Duration::from_secs(secs))
+}
+
+/// longer waits for a rate limit to reset fail instead
+const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(60);
+
+/// GitHub responds with 403 or 429 and `X-RateLimit-Remaining: 0` when the rate limit is exceeded
+/// or with `Retry-After` for secondary rate limits
+fn is_rate_limited(resp: &Response) -> bool {
+    let headers = resp.headers();
+    matches!(resp.status().as_u16(), 403 | 429)
+        && (headers.contains_key(RETRY_AFTER)
+            || headers
+                .get("x-ratelimit-remaining")
+                .is_some_and(|h| h == "0"))
+}
+
+/// how long until requests are allowed again from `Retry-After` or `X-RateLimit-Reset`
+fn rate_limit_wait(resp: &Response) -> Option<Duration> {
+    let header = |name: &str| resp.headers().get(name)?.to_str().ok()?.parse::<u64>().ok();
+    if let Some(secs) = header(RETRY_AFTER.as_str()) {
+        return Some(Duration::from_secs(secs));
+    }
+    let reset = header("x-ratelimit-reset")?;
+    let now = chrono::Utc::now().timestamp() as u64;
+    Some(Duration::from_secs(reset.saturating_sub(now) + 1))
+}
+
+fn rate_limit_error(url: &Url, wait: Option<Duration>) -> Report {
+    let host = url.host_str().unwrap_or_default();
+    let mut msg = format!("{host} rate limit exceeded");
+    if let Some(wait) = wait {
+        msg += &format!(", it resets in {}s", wait.as_secs());
+    }
+    if host == "api.github.com" && env::GITHUB_API_TOKEN.is_none() {
+        msg += ", set GITHUB_API_TOKEN to raise the limit";
+    }
+    eyre!(msg)
+}
+
+/// files smaller than this are downloaded with a single request
+const MIN_SEGMENTED_SIZE: u64 = 16 * 1024 * 1024;
+
+/// interrupted downloads are kept in the cache so they can be resumed, download paths are removed
+/// when an install fails
+fn part_path(url: &str) -> PathBuf {
+    dirs::CACHE
+        .join("downloads")
+        .join(format!("{}.part", hash_sha256_to_str(url)))
+}
+
+/// the ETag or Last-Modified of a partial download, sent as `If-Range` when it is resumed
+fn validator_path(part: &Path) -> PathBuf {
+    part.with_extension("part.validator")
+}
+
+/// a strong ETag or the Last-Modified date of a response, weak ETags can't be used for `If-Range`
+fn response_validator(resp: &Response) -> Option<String> {
+    let header = |name| resp.headers().get(name)?.to_str().ok();
+    header(ETAG)
+        .filter(|etag| !etag.starts_with("W/"))
+        .or_else(|| header(LAST_MODIFIED))
+        .map(String::from)
+}
+
+/// the size of the file from a `Content-Range: bytes 0-0/1234` header
+fn content_range_total(header: &str) -> Option<u64> {
+    header.rsplit_once('/')?.1.parse().ok()
+}
+
+/// inclusive byte ranges of `segments` parts of a file
+fn segment_ranges(total: u64, segments: usize) -> Vec<(u64, u64)> {
+    let size = total.div_ceil(segments as u64).max(1);
+    (0..total)
+        .step_by(size as usize)
+        .map(|start| (start, (start + size).min(total) - 1))
+        .collect()
+}
+
 pub fn error_code(e: &Report) -> Option<u16> {
     if e.to_string().contains("404") {
         // TODO: not this when I can figure out how to use eyre properly
@@ -158,3 +637,28 @@ pub fn error_code(e: &Report) -> Option<u16> {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(retry_delay(500, 0), Duration::from_millis(500));
+        assert_eq!(retry_delay(500, 2), Duration::from_millis(2000));
+        assert_eq!(retry_delay(u64::MAX, 3), Duration::from_millis(u64::MAX));
+    }
+
+    #[test]
+    fn test_segment_ranges() {
+        assert_eq!(segment_ranges(10, 3), vec![(0, 3), (4, 7), (8, 9)]);
+        assert_eq!(segment_ranges(2, 4), vec![(0, 0), (1, 1)]);
+        assert_eq!(content_range_total("bytes 0-0/104857600"), Some(104857600));
+        assert_eq!(content_range_total("bytes 0-0/*"), None);
+        let part = part_path("https://nodejs.org/dist/v20.11.0/node-v20.11.0.tar.gz");
+        assert!(part.starts_with(dirs::CACHE.join("downloads")));
+        assert!(part.to_string_lossy().ends_with(".part"));
+    }
+}
@@ -6,4 +6,6 @@ pub struct InstallContext<'a> {
     pub tv: ToolVersion,
     pub pr: Box<dyn SingleReport>,
     pub force: bool,
+    /// keep the downloaded files after installing, see `mise export-artifacts`
+    pub keep_download: bool,
 }
@@ -21,7 +21,10 @@ mod regex;
 #[macro_use]
 mod cmd;
 
+mod age;
+mod archive;
 mod backend;
+mod binary_cache;
 pub mod build_time;
 mod cache;
 mod cli;
@@ -36,28 +39,46 @@ mod errors;
 #[cfg_attr(windows, path = "fake_asdf_windows.rs")]
 mod fake_asdf;
 mod file;
+mod forge;
 mod git;
+mod gitea;
 pub mod github;
+mod gitlab;
 mod hash;
+mod hermetic;
 mod hook_env;
+mod hooks;
 mod http;
 mod install_context;
 mod lock_file;
 mod logger;
 mod migrate;
+mod mirrors;
+mod msys;
+mod oci;
 mod path_env;
 mod plugins;
 mod rand;
 mod registry;
 mod runtime_symlinks;
+mod sandbox;
+mod schedule;
+mod service;
 mod shell;
 mod shims;
 mod shorthands;
+mod signature;
+mod sops;
+mod store;
 mod task;
+mod task_cache;
+mod task_container;
+mod task_usage;
 pub mod tera;
 pub mod timeout;
 mod toml;
 mod toolset;
+mod trash;
 mod ui;
 
 fn main() -> eyre::Result<()> {
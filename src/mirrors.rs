@@ -0,0 +1,104 @@
+use std::collections::BTreeMap;
+
+use crate::config::Settings;
+use crate::env;
+
+/// rewrites github release downloads to `mirrors.github_releases`, other urls are unchanged. The
+/// `mirrors` settings are internal mirrors used instead of public registries and github releases:
+///
+/// [settings.mirrors]
+/// github_releases = "https://artifactory.example.com/artifactory/github"
+/// crates = "sparse+https://artifactory.example.com/artifactory/api/cargo/crates/index/"
+/// go = "https://artifactory.example.com/artifactory/api/go/go"
+/// npm = "https://artifactory.example.com/artifactory/api/npm/npm/"
+/// pypi = "https://artifactory.example.com/artifactory/api/pypi/pypi/simple"
+pub fn rewrite_url(url: &str) -> String {
+    match &Settings::get().mirrors.github_releases {
+        Some(mirror) => github_release_url(url, mirror),
+        None => url.to_string(),
+    }
+}
+
+fn github_release_url(url: &str, mirror: &str) -> String {
+    match url.strip_prefix("https://github.com/") {
+        Some(path) if path.split('/').nth(2) == Some("releases") => {
+            format!("{}/{path}", mirror.trim_end_matches('/'))
+        }
+        _ => url.to_string(),
+    }
+}
+
+/// the sparse index of `mirrors.crates` for `cargo install --index`
+pub fn crates_index() -> Option<String> {
+    let index = Settings::get().mirrors.crates.clone()?;
+    match index.starts_with("sparse+") {
+        true => Some(index),
+        false => Some(format!("sparse+{index}")),
+    }
+}
+
+/// the json api of a package on pypi or `mirrors.pypi`, mirrors like artifactory and devpi serve it
+/// next to the simple index
+pub fn pypi_json_url(package: &str) -> String {
+    let base = match &Settings::get().mirrors.pypi {
+        Some(index) => pypi_base(index),
+        None => "https://pypi.org".to_string(),
+    };
+    format!("{base}/pypi/{package}/json")
+}
+
+fn pypi_base(index: &str) -> String {
+    let index = index.trim_end_matches('/');
+    index.strip_suffix("/simple").unwrap_or(index).to_string()
+}
+
+/// environment of the package managers run by backends so they use the mirrors, variables that
+/// are already set take precedence
+pub fn env() -> BTreeMap<String, String> {
+    let mirrors = &Settings::get().mirrors;
+    let vars = [
+        ("GOPROXY", &mirrors.go),
+        ("NPM_CONFIG_REGISTRY", &mirrors.npm),
+        ("PIP_INDEX_URL", &mirrors.pypi),
+        ("UV_INDEX_URL", &mirrors.pypi),
+    ];
+    vars.into_iter()
+        .filter(|(k, _)| env::var_os(k).is_none())
+        .filter_map(|(k, v)| Some((k.to_string(), v.clone()?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_github_release_url() {
+        let mirror = "https://artifactory.example.com/github/";
+        assert_eq!(
+            github_release_url(
+                "https://github.com/BurntSushi/ripgrep/releases/download/14.1.0/rg.tar.gz",
+                mirror
+            ),
+            "https://artifactory.example.com/github/BurntSushi/ripgrep/releases/download/14.1.0/rg.tar.gz"
+        );
+        assert_eq!(
+            github_release_url("https://github.com/BurntSushi/ripgrep.git", mirror),
+            "https://github.com/BurntSushi/ripgrep.git"
+        );
+    }
+
+    #[test]
+    fn test_pypi_base() {
+        assert_eq!(
+            pypi_base("https://artifactory.example.com/api/pypi/pypi/simple/"),
+            "https://artifactory.example.com/api/pypi/pypi"
+        );
+        assert_eq!(
+            pypi_base("https://devpi.example.com/root/pypi/+simple"),
+            "https://devpi.example.com/root/pypi/+simple"
+        );
+    }
+}
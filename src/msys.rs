@@ -0,0 +1,88 @@
+use std::ffi::OsString;
+
+use crate::config::Settings;
+
+/// paths are translated between the windows form (C:\foo) and the posix form used by MSYS2 and
+/// Cygwin bash (/c/foo) when calling bash scripts on windows, `translate_paths` on a task or tool
+/// overrides the `windows_path_translation` setting
+pub fn enabled(translate_paths: Option<bool>) -> bool {
+    cfg!(windows) && translate_paths.unwrap_or_else(|| Settings::get().windows_path_translation)
+}
+
+/// C:\foo\bar -> /c/foo/bar, anything that is not an absolute windows path is unchanged
+pub fn to_posix(s: &str) -> String {
+    match regex!(r"^([A-Za-z]):[\\/](.*)$").captures(s) {
+        Some(caps) => format!("/{}/{}", caps[1].to_lowercase(), caps[2].replace('\\', "/")),
+        None => s.to_string(),
+    }
+}
+
+/// /c/foo/bar or /cygdrive/c/foo/bar -> C:\foo\bar, anything else is unchanged
+pub fn to_windows(s: &str) -> String {
+    match regex!(r"^(?:/cygdrive)?/([A-Za-z])(?:/(.*))?$").captures(s) {
+        Some(caps) => format!(
+            r"{}:\{}",
+            caps[1].to_uppercase(),
+            caps.get(2)
+                .map(|m| m.as_str().replace('/', "\\"))
+                .unwrap_or_default()
+        ),
+        None => s.to_string(),
+    }
+}
+
+/// translates an env var for bash, PATH is converted from ; to : separated
+pub fn env_to_posix(key: &str, value: &str) -> String {
+    if key.eq_ignore_ascii_case("PATH") {
+        value.split(';').map(to_posix).collect::<Vec<_>>().join(":")
+    } else {
+        to_posix(value)
+    }
+}
+
+/// translates an env var set by bash back, PATH is converted from : to ; separated
+pub fn env_to_windows(key: &str, value: &str) -> String {
+    if key.eq_ignore_ascii_case("PATH") {
+        value
+            .split(':')
+            .map(to_windows)
+            .collect::<Vec<_>>()
+            .join(";")
+    } else {
+        to_windows(value)
+    }
+}
+
+pub fn os_env_to_posix(key: &OsString, value: &OsString) -> OsString {
+    env_to_posix(&key.to_string_lossy(), &value.to_string_lossy()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_to_posix() {
+        assert_eq!(to_posix(r"C:\Users\me\bin"), "/c/Users/me/bin");
+        assert_eq!(to_posix("D:/tools"), "/d/tools");
+        assert_eq!(to_posix("relative\\path"), "relative\\path");
+        assert_eq!(to_posix("1.2.3"), "1.2.3");
+    }
+
+    #[test]
+    fn test_to_windows() {
+        assert_eq!(to_windows("/c/Users/me/bin"), r"C:\Users\me\bin");
+        assert_eq!(to_windows("/cygdrive/d/tools"), r"D:\tools");
+        assert_eq!(to_windows("/c"), r"C:\");
+        assert_eq!(to_windows("/usr/bin"), "/usr/bin");
+    }
+
+    #[test]
+    fn test_env_path() {
+        assert_eq!(env_to_posix("Path", r"C:\a;D:\b"), "/c/a:/d/b".to_string());
+        assert_eq!(env_to_windows("PATH", "/c/a:/d/b"), r"C:\a;D:\b");
+        assert_eq!(env_to_posix("HOME", r"C:\Users\me"), "/c/Users/me");
+    }
+}
@@ -0,0 +1,397 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use base64::prelude::*;
+use eyre::{bail, eyre, Result};
+use itertools::Itertools;
+use once_cell::sync::Lazy;
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION};
+use serde_derive::Deserialize;
+
+use crate::env::var_path;
+use crate::file;
+use crate::hash::ensure_checksum_sha256;
+use crate::http::HTTP_FETCH;
+use crate::ui::progress_report::SingleReport;
+
+const MANIFEST_TYPES: &[&str] = &[
+    "application/vnd.oci.image.index.v1+json",
+    "application/vnd.oci.image.manifest.v1+json",
+    "application/vnd.docker.distribution.manifest.list.v2+json",
+    "application/vnd.docker.distribution.manifest.v2+json",
+];
+
+/// a repository in an OCI registry, e.g.: ghcr.io/org/tool
+#[derive(Debug, Clone, PartialEq)]
+pub struct OciRepository {
+    pub registry: String,
+    pub repository: String,
+}
+
+impl FromStr for OciRepository {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim_start_matches("oci://");
+        let (registry, repository) = match s.split_once('/') {
+            Some((registry, repository))
+                if registry.contains(['.', ':']) || registry == "localhost" =>
+            {
+                (registry.to_string(), repository.to_string())
+            }
+            Some(_) => ("docker.io".to_string(), s.to_string()),
+            None => ("docker.io".to_string(), format!("library/{s}")),
+        };
+        if repository.is_empty() || repository.contains(['@', ' ']) {
+            bail!("invalid oci repository: {s}");
+        }
+        Ok(Self {
+            registry,
+            repository,
+        })
+    }
+}
+
+impl Display for OciRepository {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.registry, self.repository)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Descriptor {
+    pub media_type: Option<String>,
+    pub digest: String,
+    pub platform: Option<Platform>,
+    #[serde(default)]
+    pub annotations: HashMap<String, String>,
+}
+
+impl Descriptor {
+    /// the filename of a layer pushed with `oras push`
+    pub fn title(&self) -> Option<&str> {
+        self.annotations
+            .get("org.opencontainers.image.title")
+            .map(|s| s.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Platform {
+    pub os: String,
+    pub architecture: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    media_type: Option<String>,
+    #[serde(default)]
+    manifests: Vec<Descriptor>,
+    #[serde(default)]
+    layers: Vec<Descriptor>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TagList {
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    token: Option<String>,
+    access_token: Option<String>,
+}
+
+/// an authenticated connection to a single repository in an OCI registry
+#[derive(Debug)]
+pub struct OciClient {
+    repo: OciRepository,
+    headers: HeaderMap,
+}
+
+impl OciClient {
+    pub fn new(repo: OciRepository) -> Result<Self> {
+        let mut client = Self {
+            repo,
+            headers: HeaderMap::new(),
+        };
+        client.authenticate()?;
+        Ok(client)
+    }
+
+    pub fn list_tags(&self) -> Result<Vec<String>> {
+        let url = self.url("tags/list?n=10000");
+        let tags: TagList = HTTP_FETCH.json_with_headers(url, &self.headers)?;
+        Ok(tags.tags)
+    }
+
+    /// returns the layers of the image for the current platform
+    pub fn layers(&self, tag: &str) -> Result<Vec<Descriptor>> {
+        let mut manifest = self.manifest(tag)?;
+        if !manifest.manifests.is_empty() {
+            let (os, arch) = (os(), arch());
+            let digest = manifest
+                .manifests
+                .iter()
+                .find(|m| {
+                    m.platform
+                        .as_ref()
+                        .is_some_and(|p| p.os == os && p.architecture == arch)
+                })
+                .map(|m| m.digest.clone())
+                .ok_or_else(|| {
+                    eyre!(
+                        "{}:{tag} has no image for {os}/{arch}, available: {}",
+                        self.repo,
+                        manifest
+                            .manifests
+                            .iter()
+                            .filter_map(|m| m.platform.as_ref())
+                            .map(|p| format!("{}/{}", p.os, p.architecture))
+                            .join(", ")
+                    )
+                })?;
+            manifest = self.manifest(&digest)?;
+        }
+        trace!("oci manifest {}:{tag} {:?}", self.repo, manifest.media_type);
+        Ok(manifest.layers)
+    }
+
+    /// downloads a blob and verifies it against its digest
+    pub fn download_blob(
+        &self,
+        layer: &Descriptor,
+        path: &Path,
+        pr: Option<&dyn SingleReport>,
+    ) -> Result<()> {
+        let url = self.url(&format!("blobs/{}", layer.digest));
+        HTTP_FETCH.download_file_with_headers(url, path, pr, &self.headers)?;
+        match layer.digest.split_once(':') {
+            Some(("sha256", checksum)) => ensure_checksum_sha256(path, checksum, pr),
+            _ => {
+                warn!("unable to verify digest {}", layer.digest);
+                Ok(())
+            }
+        }
+    }
+
+    fn manifest(&self, reference: &str) -> Result<Manifest> {
+        let mut headers = self.headers.clone();
+        headers.insert(ACCEPT, HeaderValue::from_str(&MANIFEST_TYPES.join(", "))?);
+        let url = self.url(&format!("manifests/{reference}"));
+        HTTP_FETCH.json_with_headers(url, &headers)
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!(
+            "https://{}/v2/{}/{path}",
+            registry_host(&self.repo.registry),
+            self.repo.repository
+        )
+    }
+
+    /// handles both anonymous and credentialed token auth as well as basic auth
+    /// https://distribution.github.io/distribution/spec/auth/token/
+    fn authenticate(&mut self) -> Result<()> {
+        let host = registry_host(&self.repo.registry);
+        let challenge = match HTTP_FETCH.auth_challenge(format!("https://{host}/v2/"))? {
+            Some(challenge) => challenge,
+            None => return Ok(()),
+        };
+        let credentials = docker_credentials(&self.repo.registry)?;
+        let (scheme, params) = challenge.split_once(' ').unwrap_or((&challenge, ""));
+        let params = parse_challenge(params);
+        let authorization = match scheme.to_lowercase().as_str() {
+            "basic" => match credentials {
+                Some(credentials) => format!("Basic {}", credentials.basic()),
+                None => bail!("{host} requires credentials, run `docker login {host}`"),
+            },
+            "bearer" => {
+                let realm = params
+                    .get("realm")
+                    .ok_or_else(|| eyre!("invalid auth challenge from {host}: {challenge}"))?;
+                let mut url = url::Url::parse(realm)?;
+                if let Some(service) = params.get("service") {
+                    url.query_pairs_mut().append_pair("service", service);
+                }
+                let scope = format!("repository:{}:pull", self.repo.repository);
+                url.query_pairs_mut().append_pair("scope", &scope);
+                let mut headers = HeaderMap::new();
+                if let Some(credentials) = credentials {
+                    let basic = format!("Basic {}", credentials.basic());
+                    headers.insert(AUTHORIZATION, HeaderValue::from_str(&basic)?);
+                }
+                let resp: TokenResponse = HTTP_FETCH.json_with_headers(url, &headers)?;
+                let token = resp
+                    .token
+                    .or(resp.access_token)
+                    .ok_or_else(|| eyre!("no token returned from {realm}"))?;
+                format!("Bearer {token}")
+            }
+            _ => bail!("unsupported auth scheme from {host}: {scheme}"),
+        };
+        self.headers
+            .insert(AUTHORIZATION, HeaderValue::from_str(&authorization)?);
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Credentials {
+    username: String,
+    secret: String,
+}
+
+impl Credentials {
+    fn basic(&self) -> String {
+        BASE64_STANDARD.encode(format!("{}:{}", self.username, self.secret))
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DockerConfig {
+    #[serde(default)]
+    auths: HashMap<String, DockerAuth>,
+    creds_store: Option<String>,
+    #[serde(default)]
+    cred_helpers: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DockerAuth {
+    auth: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct CredentialHelperResponse {
+    username: String,
+    secret: String,
+}
+
+static DOCKER_CONFIG: Lazy<PathBuf> = Lazy::new(|| {
+    var_path("DOCKER_CONFIG")
+        .unwrap_or_else(|| crate::dirs::HOME.join(".docker"))
+        .join("config.json")
+});
+
+/// reads credentials the same way `docker pull` does: credHelpers, then credsStore, then auths
+fn docker_credentials(registry: &str) -> Result<Option<Credentials>> {
+    if !DOCKER_CONFIG.exists() {
+        return Ok(None);
+    }
+    let config: DockerConfig = serde_json::from_str(&file::read_to_string(&*DOCKER_CONFIG)?)?;
+    let keys = match registry {
+        "docker.io" => vec!["https://index.docker.io/v1/", "docker.io"],
+        r => vec![r],
+    };
+    let helper = keys
+        .iter()
+        .find_map(|k| config.cred_helpers.get(*k))
+        .or(config.creds_store.as_ref());
+    if let Some(helper) = helper {
+        let bin = format!("docker-credential-{helper}");
+        match cmd!(&bin, "get").stdin_bytes(keys[0]).read() {
+            Ok(out) => {
+                let resp: CredentialHelperResponse = serde_json::from_str(&out)?;
+                return Ok(Some(Credentials {
+                    username: resp.username,
+                    secret: resp.secret,
+                }));
+            }
+            Err(err) => debug!("{bin} get {registry}: {err}"),
+        }
+    }
+    for key in keys {
+        if let Some(auth) = config.auths.get(key).and_then(|a| a.auth.as_ref()) {
+            let auth = String::from_utf8(BASE64_STANDARD.decode(auth)?)?;
+            if let Some((username, secret)) = auth.split_once(':') {
+                return Ok(Some(Credentials {
+                    username: username.to_string(),
+                    secret: secret.to_string(),
+                }));
+            }
+        }
+    }
+    Ok(None)
+}
+
+fn registry_host(registry: &str) -> &str {
+    match registry {
+        "docker.io" => "registry-1.docker.io",
+        r => r,
+    }
+}
+
+/// parses `realm="https://ghcr.io/token",service="ghcr.io"` into a map
+fn parse_challenge(params: &str) -> HashMap<String, String> {
+    let re = regex!(r#"(\w+)="([^"]*)""#);
+    re.captures_iter(params)
+        .map(|c| (c[1].to_string(), c[2].to_string()))
+        .collect()
+}
+
+pub fn os() -> &'static str {
+    match std::env::consts::OS {
+        "macos" => "darwin",
+        os => os,
+    }
+}
+
+pub fn arch() -> &'static str {
+    match std::env::consts::ARCH {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        "x86" => "386",
+        arch => arch,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_oci_repository() {
+        let t = |input: &str, registry: &str, repository: &str| {
+            let repo: OciRepository = input.parse().unwrap();
+            assert_eq!(repo.registry, registry);
+            assert_eq!(repo.repository, repository);
+        };
+        t("ghcr.io/org/tool", "ghcr.io", "org/tool");
+        t("oci://ghcr.io/org/tool", "ghcr.io", "org/tool");
+        t("localhost:5000/tool", "localhost:5000", "tool");
+        t("org/tool", "docker.io", "org/tool");
+        t("alpine", "docker.io", "library/alpine");
+        assert!("ghcr.io/org/tool@sha256:abc"
+            .parse::<OciRepository>()
+            .is_err());
+    }
+
+    #[test]
+    fn test_parse_challenge() {
+        let params = parse_challenge(
+            r#"realm="https://ghcr.io/token",service="ghcr.io",scope="repository:org/tool:pull""#,
+        );
+        assert_eq!(params["realm"], "https://ghcr.io/token");
+        assert_eq!(params["service"], "ghcr.io");
+        assert_eq!(params["scope"], "repository:org/tool:pull");
+    }
+
+    #[test]
+    fn test_credentials_basic() {
+        let credentials = Credentials {
+            username: "user".into(),
+            secret: "pass".into(),
+        };
+        assert_eq!(credentials.basic(), "dXNlcjpwYXNz");
+    }
+}
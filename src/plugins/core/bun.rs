@@ -7,7 +7,7 @@ use versions::Versioning;
 
 use crate::backend::Backend;
 use crate::cli::args::BackendArg;
-use crate::cli::version::{ARCH, OS};
+use crate::cli::version::{ARCH, NATIVE_ARCH, OS};
 use crate::cmd::CmdLineRunner;
 use crate::file;
 use crate::github::GithubRelease;
@@ -134,13 +134,13 @@ fn os() -> &'static str {
 }
 
 fn arch() -> &'static str {
-    if cfg!(target_arch = "x86_64") {
+    if *NATIVE_ARCH == "x86_64" {
         if cfg!(target_feature = "avx2") {
             "x64"
         } else {
             "x64-baseline"
         }
-    } else if cfg!(target_arch = "aarch64") {
+    } else if *NATIVE_ARCH == "aarch64" {
         "aarch64"
     } else {
         &ARCH
@@ -8,7 +8,7 @@ use versions::Versioning;
 
 use crate::backend::Backend;
 use crate::cli::args::BackendArg;
-use crate::cli::version::{ARCH, OS};
+use crate::cli::version::{ARCH, NATIVE_ARCH, OS};
 use crate::cmd::CmdLineRunner;
 use crate::config::{Config, Settings};
 use crate::http::HTTP;
@@ -252,11 +252,11 @@ fn platform() -> &'static str {
 }
 
 fn arch() -> &'static str {
-    if cfg!(target_arch = "x86_64") {
+    if *NATIVE_ARCH == "x86_64" {
         "amd64"
     } else if cfg!(target_arch = "arm") {
         "armv6l"
-    } else if cfg!(target_arch = "aarch64") {
+    } else if *NATIVE_ARCH == "aarch64" {
         "arm64"
     } else {
         &ARCH
@@ -15,14 +15,13 @@ use versions::Versioning;
 use crate::backend::Backend;
 use crate::cache::CacheManager;
 use crate::cli::args::BackendArg;
-use crate::cli::version::{ARCH, OS};
+use crate::cli::version::{ARCH, NATIVE_ARCH, OS};
 use crate::cmd::CmdLineRunner;
 use crate::config::Config;
 use crate::http::{HTTP, HTTP_FETCH};
 use crate::install_context::InstallContext;
 use crate::plugins::core::CorePlugin;
-use crate::plugins::VERSION_REGEX;
-use crate::toolset::{ToolRequest, ToolVersion, Toolset};
+use crate::toolset::{PrereleasePolicy, ToolRequest, ToolVersion, Toolset, VersionRange};
 use crate::ui::progress_report::SingleReport;
 use crate::{env, file, hash};
 
@@ -379,6 +378,24 @@ impl Backend for JavaPlugin {
         Ok(())
     }
 
+    /// the macOS integration from the install message needs sudo to remove so it is only reported
+    fn uninstall_version_impl(&self, _pr: &dyn SingleReport, tv: &ToolVersion) -> Result<()> {
+        let jdk = PathBuf::from(format!(
+            "/Library/Java/JavaVirtualMachines/{}.jdk",
+            tv.version
+        ));
+        let points_to_install = fs::read_link(jdk.join("Contents"))
+            .is_ok_and(|target| target.starts_with(tv.install_path()));
+        if points_to_install {
+            warn!(
+                "{} links to {tv} which is being uninstalled, remove it with: sudo rm -rf {}",
+                jdk.display(),
+                jdk.display()
+            );
+        }
+        Ok(())
+    }
+
     fn exec_env(
         &self,
         _config: &Config,
@@ -402,11 +419,11 @@ fn os() -> &'static str {
 }
 
 fn arch() -> &'static str {
-    if cfg!(target_arch = "x86_64") {
+    if *NATIVE_ARCH == "x86_64" {
         "x86_64"
     } else if cfg!(target_arch = "arm") {
         "arm32-vfp-hflt"
-    } else if cfg!(target_arch = "aarch64") {
+    } else if *NATIVE_ARCH == "aarch64" {
         "aarch64"
     } else {
         &ARCH
@@ -414,6 +431,11 @@ fn arch() -> &'static str {
 }
 
 fn fuzzy_match_filter(versions: Vec<String>, query: &str) -> eyre::Result<Vec<String>> {
+    let prerelease = PrereleasePolicy::global()?;
+    if let Some(range) = VersionRange::parse(query) {
+        let versions = versions.into_iter().filter(|v| prerelease.allows(v));
+        return Ok(range?.filter(versions.collect()));
+    }
     let mut query = query;
     if query == "latest" {
         query = "[0-9].*";
@@ -425,7 +447,7 @@ fn fuzzy_match_filter(versions: Vec<String>, query: &str) -> eyre::Result<Vec<St
             if query == v {
                 return true;
             }
-            if VERSION_REGEX.is_match(v) {
+            if !prerelease.allows(v) {
                 return false;
             }
             query_regex.is_match(v)
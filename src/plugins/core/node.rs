@@ -9,6 +9,7 @@ use url::Url;
 use crate::backend::Backend;
 use crate::build_time::built_info;
 use crate::cli::args::BackendArg;
+use crate::cli::version::NATIVE_ARCH;
 use crate::cmd::CmdLineRunner;
 use crate::config::{Config, Settings};
 use crate::env::MISE_NODE_MIRROR_URL;
@@ -402,11 +403,11 @@ fn os() -> &'static str {
 fn arch() -> &'static str {
     if cfg!(target_arch = "x86") {
         "x86"
-    } else if cfg!(target_arch = "x86_64") {
+    } else if *NATIVE_ARCH == "x86_64" {
         "x64"
     } else if cfg!(target_arch = "arm") {
         "armv7l"
-    } else if cfg!(target_arch = "aarch64") {
+    } else if *NATIVE_ARCH == "aarch64" {
         "arm64"
     } else {
         built_info::CFG_TARGET_ARCH
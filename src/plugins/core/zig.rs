@@ -7,7 +7,7 @@ use versions::Versioning;
 
 use crate::backend::Backend;
 use crate::cli::args::BackendArg;
-use crate::cli::version::{ARCH, OS};
+use crate::cli::version::{ARCH, NATIVE_ARCH, OS};
 use crate::cmd::CmdLineRunner;
 use crate::file;
 use crate::github::GithubRelease;
@@ -164,9 +164,9 @@ fn os() -> &'static str {
 }
 
 fn arch() -> &'static str {
-    if cfg!(target_arch = "x86_64") {
+    if *NATIVE_ARCH == "x86_64" {
         "x86_64"
-    } else if cfg!(target_arch = "aarch64") {
+    } else if *NATIVE_ARCH == "aarch64" {
         "aarch64"
     } else if cfg!(target_arch = "arm") {
         "armv7a"
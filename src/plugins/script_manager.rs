@@ -16,12 +16,13 @@ use crate::errors::Error::ScriptFailed;
 use crate::fake_asdf::get_path_with_fake_asdf;
 use crate::file::display_path;
 use crate::ui::progress_report::SingleReport;
-use crate::{dirs, env};
+use crate::{dirs, env, msys};
 
 #[derive(Debug, Clone)]
 pub struct ScriptManager {
     pub plugin_path: PathBuf,
     pub env: HashMap<OsString, OsString>,
+    translate_paths: Option<bool>,
 }
 
 #[derive(Debug, Clone)]
@@ -103,7 +104,11 @@ impl ScriptManager {
             // used for testing failure cases
             env.insert("MISE_FAILURE".into(), failure);
         }
-        Self { env, plugin_path }
+        Self {
+            env,
+            plugin_path,
+            translate_paths: None,
+        }
     }
 
     pub fn with_env<K, V>(mut self, k: K, v: V) -> Self
@@ -115,6 +120,31 @@ impl ScriptManager {
         self
     }
 
+    pub fn with_translate_paths(mut self, translate_paths: bool) -> Self {
+        self.translate_paths = Some(translate_paths);
+        self
+    }
+
+    /// env for the scripts, with paths translated for MSYS2/Cygwin bash on windows
+    pub fn script_env(&self) -> HashMap<OsString, OsString> {
+        if !msys::enabled(self.translate_paths) {
+            return self.env.clone();
+        }
+        self.env
+            .iter()
+            .map(|(k, v)| (k.clone(), msys::os_env_to_posix(k, v)))
+            .collect()
+    }
+
+    /// converts values set by a script back to windows paths if they were translated
+    pub fn untranslate(&self, key: &str, value: String) -> String {
+        if msys::enabled(self.translate_paths) {
+            msys::env_to_windows(key, &value)
+        } else {
+            value
+        }
+    }
+
     pub fn prepend_path(&mut self, path: PathBuf) {
         let k: OsString = "PATH".into();
         let mut paths = env::split_paths(&self.env[&k]).collect::<Vec<_>>();
@@ -135,16 +165,19 @@ impl ScriptManager {
     }
 
     pub fn cmd(&self, script: &Script) -> Expression {
-        let args = match script {
+        let mut args = match script {
             Script::ParseLegacyFile(filename) => vec![filename.clone()],
             Script::RunExternalCommand(_, args) => args.clone(),
             _ => vec![],
         };
+        if msys::enabled(self.translate_paths) {
+            args = args.iter().map(|a| msys::to_posix(a)).collect();
+        }
         let script_path = self.get_script_path(script);
         // if !script_path.exists() {
         //     return Err(PluginNotInstalled(self.plugin_name.clone()).into());
         // }
-        let mut cmd = cmd(script_path, args).full_env(&self.env);
+        let mut cmd = cmd(script_path, args).full_env(self.script_env());
         let settings = &Settings::get();
         if !settings.raw {
             // ignore stdin, otherwise a prompt may show up where the user won't see it
@@ -169,7 +202,7 @@ impl ScriptManager {
         let cmd = CmdLineRunner::new(path.clone())
             .with_pr(pr)
             .env_clear()
-            .envs(&self.env);
+            .envs(self.script_env());
         if let Err(e) = cmd.execute() {
             let status = match e.downcast_ref::<Error>() {
                 Some(ScriptFailed(_, status)) => *status,
@@ -1,6 +1,17 @@
 use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
+use eyre::{bail, Result};
+use itertools::Itertools;
 use once_cell::sync::Lazy;
+use serde_derive::Serialize;
+
+use crate::backend::BackendType;
+use crate::cli::args::BackendArg;
+use crate::config::{config_file, DEFAULT_CONFIG_FILENAMES};
+use crate::file::display_path;
+use crate::toolset::ToolVersionOptions;
+use crate::{config, dirs, env, file};
 
 const _REGISTRY: &[(&str, &str)] = &[
     ("ubi", "cargo:ubi"),
@@ -8,12 +19,267 @@ const _REGISTRY: &[(&str, &str)] = &[
     // ("elixir", "asdf:mise-plugins/mise-elixir"),
 ];
 
-pub static REGISTRY: Lazy<BTreeMap<&str, String>> = Lazy::new(|| {
+/// SPDX license expression and upstream source of tools by their backend id
+const _METADATA: &[(&str, &str, &str)] = &[
+    ("bun", "MIT", "https://github.com/oven-sh/bun"),
+    (
+        "cargo:cargo-binstall",
+        "GPL-3.0-only",
+        "https://github.com/cargo-bins/cargo-binstall",
+    ),
+    (
+        "cargo:ubi",
+        "Apache-2.0 OR MIT",
+        "https://github.com/houseabsolute/ubi",
+    ),
+    ("deno", "MIT", "https://github.com/denoland/deno"),
+    ("erlang", "Apache-2.0", "https://github.com/erlang/otp"),
+    ("go", "BSD-3-Clause", "https://github.com/golang/go"),
+    (
+        "java",
+        "GPL-2.0-only WITH Classpath-exception-2.0",
+        "https://github.com/openjdk/jdk",
+    ),
+    ("node", "MIT", "https://github.com/nodejs/node"),
+    ("python", "PSF-2.0", "https://github.com/python/cpython"),
+    (
+        "ruby",
+        "Ruby OR BSD-2-Clause",
+        "https://github.com/ruby/ruby",
+    ),
+    ("zig", "MIT", "https://github.com/ziglang/zig"),
+];
+
+/// directories of project-level registry overlays, relative to the project and its parents
+const PROJECT_OVERLAY_DIRS: &[&str] = &[
+    ".config/mise/registry.d",
+    "mise/registry.d",
+    ".mise/registry.d",
+];
+
+pub static REGISTRY: Lazy<BTreeMap<String, String>> = Lazy::new(|| {
     // TODO: make sure core plugins can be overridden with this enabled
     // let core = CORE_PLUGINS
     //     .iter()
     //     .map(|p| (p.name(), format!("core:{}", p.name())));
-    let registry = _REGISTRY.iter().map(|(k, v)| (*k, v.to_string()));
-    registry.collect()
+    let mut registry: BTreeMap<String, String> = _REGISTRY
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
     // core.chain(registry).collect()
+    for path in overlay_files() {
+        match read_overlay(&path) {
+            Ok(entries) => registry.extend(entries),
+            Err(err) => warn!("failed to read {}: {err:#}", display_path(&path)),
+        }
+    }
+    registry
 });
+
+/// toml files in ~/.config/mise/registry.d/ and the registry.d/ dirs of the project that add or
+/// override entries of the registry. Files in directories closer to the project override the
+/// others, files in the same directory are read in alphabetical order.
+///
+/// mycompany-cli = "gitlab:mycompany/tools/cli"
+/// shellcheck = "ubi:koalaman/shellcheck"
+fn overlay_files() -> Vec<PathBuf> {
+    let global = dirs::CONFIG.join("registry.d");
+    let project = dirs::CWD
+        .iter()
+        .flat_map(|cwd| cwd.ancestors().collect_vec().into_iter().rev())
+        .flat_map(|dir| PROJECT_OVERLAY_DIRS.iter().map(move |d| dir.join(d)))
+        .filter(|dir| *dir != global)
+        .flat_map(|dir| toml_files(&dir))
+        .filter(|path| match config_file::trust_check(path) {
+            Ok(()) => true,
+            Err(err) => {
+                warn!("ignoring {}: {err:#}", display_path(path));
+                false
+            }
+        });
+    toml_files(&global).into_iter().chain(project).collect()
+}
+
+fn toml_files(dir: &Path) -> Vec<PathBuf> {
+    file::ls(dir)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|p| p.extension().is_some_and(|ext| ext == "toml"))
+        .sorted()
+        .collect()
+}
+
+fn read_overlay(path: &Path) -> Result<BTreeMap<String, String>> {
+    let entries: BTreeMap<String, String> = toml::from_str(&file::read_to_string(path)?)?;
+    for (short, full) in &entries {
+        if !is_backend(full) {
+            bail!("{short} = {full:?} is not a backend like \"ubi:owner/repo\"");
+        }
+    }
+    Ok(entries)
+}
+
+fn is_backend(full: &str) -> bool {
+    match full.split_once(':') {
+        Some((backend, name)) => backend.parse::<BackendType>().is_ok() && !name.is_empty(),
+        None => false,
+    }
+}
+
+/// a friendly name for a backend and its default tool options from `[alias]` in a config file,
+/// tables of version aliases like `[alias.node]` are not tool aliases
+///
+/// [alias]
+/// sourcery = "spm:krzysztofzablocki/Sourcery"
+/// shellcheck = { backend = "ubi:koalaman/shellcheck", exe = "shellcheck" }
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolAlias {
+    pub backend: String,
+    pub options: ToolVersionOptions,
+}
+
+impl ToolAlias {
+    pub fn parse(value: &toml::Value) -> Option<Self> {
+        let (backend, options) = match value {
+            toml::Value::String(backend) => (backend.clone(), Default::default()),
+            toml::Value::Table(table) => {
+                let backend = table.get("backend")?.as_str()?.to_string();
+                let options = table
+                    .iter()
+                    .filter(|(k, _)| *k != "backend")
+                    .map(|(k, v)| match v {
+                        toml::Value::String(s) => (k.clone(), s.clone()),
+                        v => (k.clone(), v.to_string()),
+                    })
+                    .collect();
+                (backend, options)
+            }
+            _ => return None,
+        };
+        is_backend(&backend).then_some(Self { backend, options })
+    }
+}
+
+/// tool aliases of every config file, closer config files take precedence
+pub static TOOL_ALIASES: Lazy<BTreeMap<String, ToolAlias>> = Lazy::new(|| {
+    let mut aliases = BTreeMap::new();
+    for path in config::load_config_paths(&DEFAULT_CONFIG_FILENAMES)
+        .iter()
+        .rev()
+    {
+        let filename = path.file_name().unwrap_or_default().to_string_lossy();
+        if filename == *env::MISE_DEFAULT_TOOL_VERSIONS_FILENAME || filename == ".tool-versions" {
+            continue;
+        }
+        match read_tool_aliases(path) {
+            Ok(entries) => aliases.extend(entries),
+            Err(err) => debug!(
+                "failed to read aliases from {}: {err:#}",
+                display_path(path)
+            ),
+        }
+    }
+    aliases
+});
+
+fn read_tool_aliases(path: &Path) -> Result<BTreeMap<String, ToolAlias>> {
+    let doc: toml::Table = toml::from_str(&file::read_to_string(path)?)?;
+    let Some(toml::Value::Table(aliases)) = doc.get("alias") else {
+        return Ok(Default::default());
+    };
+    Ok(aliases
+        .iter()
+        .filter_map(|(k, v)| Some((k.clone(), ToolAlias::parse(v)?)))
+        .collect())
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct ToolMetadata {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+/// license and source of a tool from the registry, the `license` and `source_url` tool options
+/// take precedence for tools that aren't in the registry
+pub fn metadata(fa: &BackendArg, opts: &ToolVersionOptions) -> Option<ToolMetadata> {
+    let registry = _METADATA.iter().find(|(id, _, _)| *id == fa.id);
+    let metadata = ToolMetadata {
+        license: opts
+            .get("license")
+            .cloned()
+            .or_else(|| registry.map(|(_, license, _)| license.to_string())),
+        source: opts
+            .get("source_url")
+            .cloned()
+            .or_else(|| registry.map(|(_, _, source)| source.to_string())),
+    };
+    if metadata == ToolMetadata::default() {
+        None
+    } else {
+        Some(metadata)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::test::reset;
+    use crate::toolset::ToolVersionOptions;
+
+    use super::*;
+
+    #[test]
+    fn test_metadata() {
+        reset();
+        let node = metadata(&"node".into(), &ToolVersionOptions::new()).unwrap();
+        assert_eq!(node.license.unwrap(), "MIT");
+        assert_eq!(node.source.unwrap(), "https://github.com/nodejs/node");
+
+        assert_eq!(metadata(&"tiny".into(), &ToolVersionOptions::new()), None);
+        let opts = ToolVersionOptions::from([("license".to_string(), "Apache-2.0".to_string())]);
+        let tiny = metadata(&"tiny".into(), &opts).unwrap();
+        assert_eq!(tiny.license.unwrap(), "Apache-2.0");
+        assert_eq!(tiny.source, None);
+    }
+
+    #[test]
+    fn test_read_overlay() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mycompany.toml");
+        file::write(&path, "mycompany-cli = \"gitlab:mycompany/tools/cli\"\n").unwrap();
+        assert_eq!(
+            read_overlay(&path).unwrap(),
+            BTreeMap::from([(
+                "mycompany-cli".to_string(),
+                "gitlab:mycompany/tools/cli".to_string()
+            )])
+        );
+        file::write(&path, "mycompany-cli = \"mycompany/tools/cli\"\n").unwrap();
+        assert!(read_overlay(&path).is_err());
+    }
+
+    #[test]
+    fn test_tool_alias() {
+        let parse = |s: &str| ToolAlias::parse(&toml::from_str::<toml::Table>(s).unwrap()["a"]);
+        assert_eq!(
+            parse(r#"a = "spm:krzysztofzablocki/Sourcery""#).unwrap(),
+            ToolAlias {
+                backend: "spm:krzysztofzablocki/Sourcery".to_string(),
+                options: Default::default(),
+            }
+        );
+        assert_eq!(
+            parse(r#"a = { backend = "ubi:koalaman/shellcheck", exe = "shellcheck" }"#).unwrap(),
+            ToolAlias {
+                backend: "ubi:koalaman/shellcheck".to_string(),
+                options: ToolVersionOptions::from([("exe".to_string(), "shellcheck".to_string())]),
+            }
+        );
+        // version aliases
+        assert_eq!(parse(r#"a = { lts = "20" }"#), None);
+        assert_eq!(parse(r#"a = "20""#), None);
+    }
+}
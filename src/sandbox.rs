@@ -0,0 +1,172 @@
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use eyre::{bail, Result};
+use itertools::Itertools;
+
+use crate::toolset::{ToolVersionOptions, Toolset};
+use crate::{dirs, env, file};
+
+/// restrictions from the `sandbox` tool option that are applied when the tool is run with a shim
+/// or `mise exec`, e.g.: "npm:some-codegen" = { version = "1", sandbox = ["no-network", "read-only"] }
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Profile {
+    /// no network access
+    pub no_network: bool,
+    /// the filesystem is read-only except for `writable`
+    pub read_only: bool,
+    pub writable: Vec<PathBuf>,
+}
+
+impl Profile {
+    pub fn from_options(opts: &ToolVersionOptions) -> Result<Option<Self>> {
+        let Some(sandbox) = opts.get("sandbox") else {
+            return Ok(None);
+        };
+        let mut profile = Profile::default();
+        for r in sandbox
+            .split(',')
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+        {
+            match r {
+                "no-network" => profile.no_network = true,
+                "read-only" => profile.read_only = true,
+                _ => bail!("unknown sandbox restriction: {r}, expected no-network or read-only"),
+            }
+        }
+        // the tool can still write to the current directory and temp files
+        profile.writable = dirs::CWD.iter().cloned().collect();
+        profile.writable.push(env::temp_dir());
+        if let Some(paths) = opts.get("sandbox_writable") {
+            profile.writable.extend(
+                paths
+                    .split(',')
+                    .map(|p| file::replace_path(p.trim()))
+                    .filter(|p| !p.as_os_str().is_empty()),
+            );
+        }
+        Ok(Some(profile))
+    }
+
+    /// arguments for bubblewrap on linux
+    fn bwrap_args(&self) -> Vec<OsString> {
+        let mut args: Vec<OsString> = vec![];
+        let root = if self.read_only {
+            "--ro-bind"
+        } else {
+            "--bind"
+        };
+        args.extend([root.into(), "/".into(), "/".into()]);
+        args.extend([
+            "--dev".into(),
+            "/dev".into(),
+            "--proc".into(),
+            "/proc".into(),
+        ]);
+        if self.read_only {
+            for dir in &self.writable {
+                args.extend(["--bind".into(), dir.into(), dir.into()]);
+            }
+        }
+        if self.no_network {
+            args.push("--unshare-net".into());
+        }
+        args.push("--".into());
+        args
+    }
+
+    /// a seatbelt profile for sandbox-exec on macos
+    fn sbpl(&self) -> String {
+        let mut rules = vec!["(version 1)".to_string(), "(allow default)".to_string()];
+        if self.no_network {
+            rules.push("(deny network*)".to_string());
+        }
+        if self.read_only {
+            rules.push("(deny file-write*)".to_string());
+            let subpaths = self
+                .writable
+                .iter()
+                .map(|p| format!("(subpath {:?})", p.to_string_lossy()))
+                .join(" ");
+            rules.push(format!(
+                "(allow file-write* (literal \"/dev/null\") {subpaths})"
+            ));
+        }
+        rules.join("\n")
+    }
+
+    /// runs the program inside of the sandbox
+    fn wrap(&self, program: OsString, args: Vec<OsString>) -> Result<(OsString, Vec<OsString>)> {
+        let (wrapper, mut wrapper_args): (&str, Vec<OsString>) = if cfg!(target_os = "linux") {
+            ("bwrap", self.bwrap_args())
+        } else if cfg!(target_os = "macos") {
+            ("sandbox-exec", vec!["-p".into(), self.sbpl().into()])
+        } else {
+            bail!("the sandbox tool option is only supported on linux and macos");
+        };
+        // fail closed, the tool must not run without its sandbox
+        let Some(wrapper) = file::which(wrapper) else {
+            bail!("{wrapper} is required to run tools with the sandbox tool option but it was not found on PATH");
+        };
+        wrapper_args.push(program);
+        wrapper_args.extend(args);
+        Ok((wrapper.into(), wrapper_args))
+    }
+}
+
+/// wraps the program in a sandbox if it is a bin of a tool with the `sandbox` option
+pub fn wrap(
+    ts: &Toolset,
+    program: OsString,
+    args: Vec<OsString>,
+) -> Result<(OsString, Vec<OsString>)> {
+    let Some(bin_name) = Path::new(&program).file_name() else {
+        return Ok((program, args));
+    };
+    let Some((_, tv)) = ts.which(&bin_name.to_string_lossy()) else {
+        return Ok((program, args));
+    };
+    match Profile::from_options(&tv.request.options())? {
+        Some(profile) => {
+            debug!("running {} in sandbox: {profile:?}", tv);
+            profile.wrap(program, args)
+        }
+        None => Ok((program, args)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_profile() {
+        let opts = [("sandbox".to_string(), "no-network,read-only".to_string())].into();
+        let profile = Profile::from_options(&opts).unwrap().unwrap();
+        assert!(profile.no_network);
+        assert!(profile.read_only);
+        assert!(profile.writable.contains(&env::temp_dir()));
+
+        let opts = [("sandbox".to_string(), "no-disk".to_string())].into();
+        assert!(Profile::from_options(&opts).is_err());
+        assert_eq!(Profile::from_options(&Default::default()).unwrap(), None);
+    }
+
+    #[test]
+    fn test_bwrap_args() {
+        let profile = Profile {
+            no_network: true,
+            read_only: true,
+            writable: vec!["/tmp".into()],
+        };
+        let args = profile.bwrap_args();
+        let args = args.iter().map(|a| a.to_string_lossy()).join(" ");
+        assert_eq!(
+            args,
+            "--ro-bind / / --dev /dev --proc /proc --bind /tmp /tmp --unshare-net --"
+        );
+    }
+}
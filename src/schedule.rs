@@ -0,0 +1,540 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use eyre::{bail, ensure, Context, Result};
+use itertools::Itertools;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::hash::hash_to_str;
+use crate::task::Task;
+use crate::{dirs, env, file};
+
+/// the scheduled tasks of a project registered by `mise schedule install`,
+/// stored in ~/.local/state/mise/schedules so they can be listed and removed again
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Installation {
+    pub root: PathBuf,
+    pub scheduler: Scheduler,
+    pub jobs: Vec<Job>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Job {
+    pub task: String,
+    pub schedule: String,
+    /// name of the job in the scheduler
+    pub label: String,
+}
+
+/// the last run of a scheduled task by `mise schedule run`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LastRun {
+    /// unix timestamp of when the run started
+    pub started_at: i64,
+    pub success: bool,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    strum::EnumString,
+    strum::Display,
+    clap::ValueEnum,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Scheduler {
+    Launchd,
+    Systemd,
+    Cron,
+}
+
+impl Scheduler {
+    /// launchd on macOS, systemd user timers if a user instance of systemd is running, otherwise
+    /// the user's crontab
+    pub fn detect() -> Scheduler {
+        if cfg!(target_os = "macos") {
+            Scheduler::Launchd
+        } else if file::which("systemctl").is_some()
+            && cmd!("systemctl", "--user", "show-environment")
+                .stdout_null()
+                .stderr_null()
+                .run()
+                .is_ok()
+        {
+            Scheduler::Systemd
+        } else {
+            Scheduler::Cron
+        }
+    }
+}
+
+pub fn dir() -> PathBuf {
+    dirs::STATE.join("schedules")
+}
+
+fn installation_path(root: &Path) -> PathBuf {
+    dir().join(format!("{}.json", hash_to_str(&root)))
+}
+
+fn last_run_path(label: &str) -> PathBuf {
+    dir().join("runs").join(format!("{label}.json"))
+}
+
+/// name of the job of a task in the scheduler, unique per project
+pub fn label(root: &Path, task: &str) -> String {
+    let task = task.replace(|c: char| !c.is_ascii_alphanumeric() && c != '-', "_");
+    format!("mise-{}-{task}", hash_to_str(&root))
+}
+
+/// registers the `schedule` of each task with the scheduler, replacing the jobs installed for the
+/// project before
+pub fn install(root: &Path, tasks: &[&Task], scheduler: Scheduler) -> Result<Installation> {
+    uninstall(root)?;
+    let mut jobs = vec![];
+    for task in tasks {
+        let Some(schedule) = &task.schedule else {
+            continue;
+        };
+        let cron: Cron = schedule
+            .parse()
+            .wrap_err_with(|| format!("invalid schedule of task {}", task.name))?;
+        jobs.push((
+            Job {
+                task: task.name.clone(),
+                schedule: cron.expr.clone(),
+                label: label(root, &task.name),
+            },
+            cron,
+        ));
+    }
+    match scheduler {
+        Scheduler::Launchd => {
+            file::create_dir_all(dir().join("logs"))?;
+            for (job, cron) in &jobs {
+                let path = launchd_plist_path(&job.label);
+                file::create_dir_all(path.parent().unwrap())?;
+                file::write(&path, launchd_plist(root, job, cron))?;
+                cmd!("launchctl", "load", "-w", &path).run()?;
+            }
+        }
+        Scheduler::Systemd => {
+            let dir = systemd_dir();
+            file::create_dir_all(&dir)?;
+            for (job, cron) in &jobs {
+                let (service, timer) = systemd_units(root, job, cron);
+                file::write(dir.join(format!("{}.service", job.label)), service)?;
+                file::write(dir.join(format!("{}.timer", job.label)), timer)?;
+            }
+            cmd!("systemctl", "--user", "daemon-reload").run()?;
+            for (job, _) in &jobs {
+                let timer = format!("{}.timer", job.label);
+                cmd!("systemctl", "--user", "enable", "--now", timer).run()?;
+            }
+        }
+        Scheduler::Cron => {
+            let mut crontab = read_crontab();
+            for (job, _) in &jobs {
+                crontab.push_str(&crontab_line(root, job));
+                crontab.push('\n');
+            }
+            write_crontab(&crontab)?;
+        }
+    }
+    let installation = Installation {
+        root: root.to_path_buf(),
+        scheduler,
+        jobs: jobs.into_iter().map(|(job, _)| job).collect(),
+    };
+    file::create_dir_all(dir())?;
+    file::write(
+        installation_path(root),
+        serde_json::to_string_pretty(&installation)?,
+    )?;
+    Ok(installation)
+}
+
+/// removes the jobs installed for a project from the scheduler they were installed with
+pub fn uninstall(root: &Path) -> Result<Option<Installation>> {
+    let Some(installation) = installed(root)? else {
+        return Ok(None);
+    };
+    match installation.scheduler {
+        Scheduler::Launchd => {
+            for job in &installation.jobs {
+                let path = launchd_plist_path(&job.label);
+                if path.exists() {
+                    cmd!("launchctl", "unload", "-w", &path).unchecked().run()?;
+                    file::remove_file(&path)?;
+                }
+            }
+        }
+        Scheduler::Systemd => {
+            let dir = systemd_dir();
+            for job in &installation.jobs {
+                let timer = format!("{}.timer", job.label);
+                cmd!("systemctl", "--user", "disable", "--now", &timer)
+                    .stderr_null()
+                    .unchecked()
+                    .run()?;
+                for unit in [timer, format!("{}.service", job.label)] {
+                    if dir.join(&unit).exists() {
+                        file::remove_file(dir.join(unit))?;
+                    }
+                }
+            }
+            cmd!("systemctl", "--user", "daemon-reload").run()?;
+        }
+        Scheduler::Cron => {
+            let labels = installation
+                .jobs
+                .iter()
+                .map(|j| format!("# {}", j.label))
+                .collect_vec();
+            let crontab = read_crontab()
+                .lines()
+                .filter(|line| !labels.iter().any(|l| line.ends_with(l)))
+                .map(|line| format!("{line}\n"))
+                .collect::<String>();
+            write_crontab(&crontab)?;
+        }
+    }
+    file::remove_file(installation_path(root))?;
+    Ok(Some(installation))
+}
+
+pub fn installed(root: &Path) -> Result<Option<Installation>> {
+    let path = installation_path(root);
+    if !path.exists() {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::from_str(&file::read_to_string(&path)?)?))
+}
+
+pub fn last_run(label: &str) -> Option<LastRun> {
+    let contents = file::read_to_string(last_run_path(label)).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+pub fn save_last_run(label: &str, last_run: &LastRun) -> Result<()> {
+    let path = last_run_path(label);
+    file::create_dir_all(path.parent().unwrap())?;
+    file::write(path, serde_json::to_string_pretty(last_run)?)
+}
+
+fn launchd_plist_path(label: &str) -> PathBuf {
+    dirs::HOME
+        .join("Library/LaunchAgents")
+        .join(format!("{label}.plist"))
+}
+
+fn launchd_plist(root: &Path, job: &Job, cron: &Cron) -> String {
+    let string = |s: &str| format!("<string>{}</string>", quick_xml::escape::escape(s));
+    let log = dir().join("logs").join(format!("{}.log", job.label));
+    let mut intervals = String::new();
+    for interval in cron.calendar_intervals() {
+        intervals.push_str("    <dict>\n");
+        for (k, v) in interval {
+            let _ = writeln!(intervals, "      <key>{k}</key><integer>{v}</integer>");
+        }
+        intervals.push_str("    </dict>\n");
+    }
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+  <key>Label</key>{label}
+  <key>ProgramArguments</key>
+  <array>
+    {mise}
+    <string>schedule</string>
+    <string>run</string>
+    {task}
+  </array>
+  <key>WorkingDirectory</key>{root}
+  <key>EnvironmentVariables</key>
+  <dict>
+    <key>PATH</key>{path}
+  </dict>
+  <key>StandardOutPath</key>{log}
+  <key>StandardErrorPath</key>{log}
+  <key>StartCalendarInterval</key>
+  <array>
+{intervals}  </array>
+</dict>
+</plist>
+"#,
+        label = string(&job.label),
+        mise = string(&env::MISE_BIN.to_string_lossy()),
+        task = string(&job.task),
+        root = string(&root.to_string_lossy()),
+        path = string(&path_env()),
+        log = string(&log.to_string_lossy()),
+    )
+}
+
+fn systemd_dir() -> PathBuf {
+    env::XDG_CONFIG_HOME.join("systemd/user")
+}
+
+/// the contents of the .service and .timer units of a job
+fn systemd_units(root: &Path, job: &Job, cron: &Cron) -> (String, String) {
+    let description = systemd_escape(&format!("mise task {} in {}", job.task, root.display()));
+    let service = format!(
+        r#"[Unit]
+Description={description}
+
+[Service]
+Type=oneshot
+WorkingDirectory={root}
+Environment={path}
+ExecStart={mise} schedule run {task}
+"#,
+        root = systemd_escape(&root.to_string_lossy()),
+        path = systemd_quote(&format!("PATH={}", path_env()), false),
+        mise = systemd_quote(&env::MISE_BIN.to_string_lossy(), true),
+        task = systemd_quote(&job.task, true),
+    );
+    let on_calendar = cron
+        .on_calendar()
+        .into_iter()
+        .map(|c| format!("OnCalendar={c}\n"))
+        .collect::<String>();
+    let timer = format!(
+        r#"[Unit]
+Description={description}
+
+[Timer]
+{on_calendar}Persistent=true
+
+[Install]
+WantedBy=timers.target
+"#
+    );
+    (service, timer)
+}
+
+/// escapes `%` so systemd doesn't expand it as a specifier like `%h`
+fn systemd_escape(s: &str) -> String {
+    s.replace('%', "%%")
+}
+
+/// quotes a value of a systemd setting, `$` is escaped too for `ExecStart` which expands `$VAR`
+fn systemd_quote(s: &str, exec: bool) -> String {
+    let mut s = systemd_escape(s).replace('\\', "\\\\").replace('"', "\\\"");
+    if exec {
+        s = s.replace('$', "$$");
+    }
+    format!("\"{s}\"")
+}
+
+fn crontab_line(root: &Path, job: &Job) -> String {
+    format!(
+        "{} cd {} && PATH={} {} schedule run {} # {}",
+        job.schedule,
+        shell_words::quote(&root.to_string_lossy()),
+        shell_words::quote(&path_env()),
+        shell_words::quote(&env::MISE_BIN.to_string_lossy()),
+        shell_words::quote(&job.task),
+        job.label,
+    )
+}
+
+/// the current user's crontab, empty if they don't have one
+fn read_crontab() -> String {
+    match cmd!("crontab", "-l").stderr_null().read() {
+        Ok(crontab) if crontab.is_empty() => crontab,
+        Ok(crontab) => format!("{}\n", crontab.trim_end()),
+        Err(_) => String::new(),
+    }
+}
+
+fn write_crontab(crontab: &str) -> Result<()> {
+    cmd!("crontab", "-").stdin_bytes(crontab).run()?;
+    Ok(())
+}
+
+/// schedulers start jobs with a minimal PATH, so the PATH `mise schedule install` ran with is used
+fn path_env() -> String {
+    env::PRISTINE_ENV.get("PATH").cloned().unwrap_or_default()
+}
+
+/// values of a cron field, None for `*`
+type Field = Option<BTreeSet<u32>>;
+
+/// a cron expression with 5 fields: minute, hour, day of month, month, and day of week
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cron {
+    pub expr: String,
+    minutes: Field,
+    hours: Field,
+    days: Field,
+    months: Field,
+    weekdays: Field,
+}
+
+impl FromStr for Cron {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let fields = s.split_whitespace().collect_vec();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            bail!("expected 5 fields (minute hour day-of-month month day-of-week): {s}");
+        };
+        Ok(Self {
+            expr: fields.join(" "),
+            minutes: parse_field(minute, 0, 59)?,
+            hours: parse_field(hour, 0, 23)?,
+            days: parse_field(day, 1, 31)?,
+            months: parse_field(month, 1, 12)?,
+            // 7 is sunday too
+            weekdays: parse_field(weekday, 0, 7)?.map(|w| w.into_iter().map(|d| d % 7).collect()),
+        })
+    }
+}
+
+fn parse_field(field: &str, min: u32, max: u32) -> Result<Field> {
+    if field == "*" {
+        return Ok(None);
+    }
+    let mut values = BTreeSet::new();
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<usize>()?),
+            None => (part, 1),
+        };
+        ensure!(step > 0, "step must be greater than 0: {part}");
+        let (start, end) = match range.split_once('-') {
+            _ if range == "*" => (min, max),
+            Some((start, end)) => (start.parse()?, end.parse()?),
+            // `5/15` is every 15 starting at 5
+            None if step > 1 => (range.parse()?, max),
+            None => (range.parse()?, range.parse()?),
+        };
+        ensure!(
+            min <= start && start <= end && end <= max,
+            "{part} is out of range {min}-{max}"
+        );
+        values.extend((start..=end).step_by(step));
+    }
+    Ok(Some(values))
+}
+
+const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+impl Cron {
+    /// systemd `OnCalendar` values, cron runs a job if either the day of month or the day of week
+    /// matches when both are set so those are separate values
+    pub fn on_calendar(&self) -> Vec<String> {
+        let list = |field: &Field| match field {
+            Some(values) => values.iter().join(","),
+            None => "*".to_string(),
+        };
+        let at = |weekdays: Option<String>, days: String| {
+            let weekdays = weekdays.map(|w| format!("{w} ")).unwrap_or_default();
+            format!(
+                "{weekdays}*-{}-{days} {}:{}:00",
+                list(&self.months),
+                list(&self.hours),
+                list(&self.minutes)
+            )
+        };
+        let weekdays = self
+            .weekdays
+            .as_ref()
+            .map(|w| w.iter().map(|d| WEEKDAYS[*d as usize]).join(","));
+        match (&self.days, weekdays) {
+            (Some(_), Some(weekdays)) => {
+                vec![at(None, list(&self.days)), at(Some(weekdays), "*".into())]
+            }
+            (_, weekdays) => vec![at(weekdays, list(&self.days))],
+        }
+    }
+
+    /// launchd `StartCalendarInterval` entries, one for each combination of values
+    pub fn calendar_intervals(&self) -> Vec<BTreeMap<&'static str, u32>> {
+        let times = [
+            ("Minute", &self.minutes),
+            ("Hour", &self.hours),
+            ("Month", &self.months),
+        ];
+        let days = match (&self.days, &self.weekdays) {
+            (Some(_), Some(_)) => {
+                vec![vec![("Day", &self.days)], vec![("Weekday", &self.weekdays)]]
+            }
+            _ => vec![vec![("Day", &self.days), ("Weekday", &self.weekdays)]],
+        };
+        let mut intervals = vec![];
+        for days in days {
+            let mut expanded = vec![BTreeMap::new()];
+            for (key, field) in times.iter().chain(days.iter()) {
+                let Some(values) = field else {
+                    continue;
+                };
+                let mut next = vec![];
+                for interval in &expanded {
+                    for v in values {
+                        let mut interval = interval.clone();
+                        interval.insert(*key, *v);
+                        next.push(interval);
+                    }
+                }
+                expanded = next;
+            }
+            intervals.extend(expanded);
+        }
+        intervals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_cron() {
+        let cron: Cron = "0 9 * * 1-5".parse().unwrap();
+        assert_eq!(cron.on_calendar(), vec!["Mon,Tue,Wed,Thu,Fri *-*-* 9:0:00"]);
+        assert_eq!(cron.calendar_intervals().len(), 5);
+        assert_eq!(
+            cron.calendar_intervals()[0],
+            BTreeMap::from([("Hour", 9), ("Minute", 0), ("Weekday", 1)])
+        );
+
+        let cron: Cron = "*/15 0,12 1 * 0".parse().unwrap();
+        assert_eq!(
+            cron.on_calendar(),
+            vec!["*-*-1 0,12:0,15,30,45:00", "Sun *-*-* 0,12:0,15,30,45:00"]
+        );
+        assert_eq!(cron.calendar_intervals().len(), 16);
+
+        assert!("0 9 * *".parse::<Cron>().is_err());
+        assert!("60 * * * *".parse::<Cron>().is_err());
+        assert!("* * * * 1-8".parse::<Cron>().is_err());
+        assert_eq!(
+            "0 0 * * 7".parse::<Cron>().unwrap().on_calendar(),
+            vec!["Sun *-*-* 0:0:00"]
+        );
+    }
+
+    #[test]
+    fn test_systemd_quote() {
+        assert_eq!(systemd_quote("build", true), r#""build""#);
+        assert_eq!(
+            systemd_quote(r#"say "100%" \o/ $USER"#, true),
+            r#""say \"100%%\" \\o/ $$USER""#
+        );
+        assert_eq!(systemd_quote("PATH=/a/$b", false), r#""PATH=/a/$b""#);
+        assert_eq!(systemd_escape("%h/src"), "%%h/src");
+    }
+}
@@ -0,0 +1,160 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use eyre::Result;
+use once_cell::sync::Lazy;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::hash::hash_to_str;
+use crate::task::Task;
+use crate::{dirs, file};
+
+/// set when mise receives ctrl-c or SIGTERM so services are not restarted while shutting down
+static SHUTDOWN: Lazy<Arc<AtomicBool>> = Lazy::new(Default::default);
+
+/// a task with `service = true` that is supervised by a running `mise run`,
+/// stored in ~/.local/state/mise/services so `mise ps` and `mise stop` can find it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Service {
+    pub name: String,
+    pub config_source: PathBuf,
+    /// pid of the `mise run` process supervising the service
+    pub supervisor_pid: u32,
+    /// pid of the current run of the service
+    pub pid: Option<u32>,
+    /// unix timestamp of when the current run of the service started
+    pub started_at: i64,
+    pub restarts: u32,
+}
+
+pub fn dir() -> PathBuf {
+    dirs::STATE.join("services")
+}
+
+impl Service {
+    pub fn new(task: &Task) -> Self {
+        Self {
+            name: task.name.clone(),
+            config_source: task.config_source.clone(),
+            supervisor_pid: std::process::id(),
+            pid: None,
+            started_at: chrono::Utc::now().timestamp(),
+            restarts: 0,
+        }
+    }
+
+    fn path(&self) -> PathBuf {
+        dir().join(format!("{}.json", key(&self.name, &self.config_source)))
+    }
+
+    fn stop_marker(&self) -> PathBuf {
+        self.path().with_extension("stop")
+    }
+
+    pub fn save(&self) -> Result<()> {
+        file::write(self.path(), serde_json::to_string_pretty(self)?)
+    }
+
+    /// records the pid of a new run of the service
+    pub fn started(&mut self, pid: u32) -> Result<()> {
+        self.pid = Some(pid);
+        self.started_at = chrono::Utc::now().timestamp();
+        self.save()
+    }
+
+    pub fn remove(&self) -> Result<()> {
+        file::remove_all(self.path())?;
+        file::remove_all(self.stop_marker())
+    }
+
+    /// true if `mise stop` or ctrl-c stopped the service and it should not be restarted
+    pub fn is_stopping(&self) -> bool {
+        SHUTDOWN.load(Ordering::Relaxed) || self.stop_marker().exists()
+    }
+
+    /// stops the service without it being restarted by its supervisor
+    pub fn stop(&self) -> Result<()> {
+        file::write(self.stop_marker(), "")?;
+        if let Some(pid) = self.pid {
+            kill(pid)?;
+        }
+        Ok(())
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.pid.is_some_and(is_alive)
+    }
+}
+
+/// services with a running supervisor, state left behind by a supervisor that was killed is removed
+pub fn list() -> Result<Vec<Service>> {
+    let mut services = vec![];
+    for path in file::ls(&dir())? {
+        if path.extension().is_some_and(|e| e == "json") {
+            let service: Service = serde_json::from_str(&file::read_to_string(&path)?)?;
+            if is_alive(service.supervisor_pid) {
+                services.push(service);
+            } else {
+                debug!("removing stale service {}", service.name);
+                service.remove()?;
+            }
+        }
+    }
+    services.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(services)
+}
+
+/// makes ctrl-c stop services instead of restarting them, the services receive the signal
+/// themselves since they share the terminal with mise
+pub fn handle_shutdown() -> Result<()> {
+    use signal_hook::consts::{SIGINT, SIGTERM};
+    signal_hook::flag::register(SIGINT, SHUTDOWN.clone())?;
+    signal_hook::flag::register(SIGTERM, SHUTDOWN.clone())?;
+    Ok(())
+}
+
+fn key(name: &str, config_source: &Path) -> String {
+    let name = name.replace(|c: char| !c.is_ascii_alphanumeric() && c != '-', "_");
+    format!("{name}-{}", hash_to_str(&config_source))
+}
+
+#[cfg(unix)]
+fn is_alive(pid: u32) -> bool {
+    cmd!("kill", "-0", pid.to_string())
+        .stderr_null()
+        .run()
+        .is_ok()
+}
+
+#[cfg(windows)]
+fn is_alive(pid: u32) -> bool {
+    cmd!("tasklist", "/NH", "/FI", format!("PID eq {pid}"))
+        .read()
+        .is_ok_and(|out| out.contains(&pid.to_string()))
+}
+
+#[cfg(unix)]
+fn kill(pid: u32) -> Result<()> {
+    cmd!("kill", "-TERM", pid.to_string()).run()?;
+    Ok(())
+}
+
+#[cfg(windows)]
+fn kill(pid: u32) -> Result<()> {
+    cmd!("taskkill", "/PID", pid.to_string(), "/T", "/F").run()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key() {
+        let a = key("dev:api", Path::new("/tmp/project/.mise.toml"));
+        let b = key("dev:api", Path::new("/tmp/other/.mise.toml"));
+        assert!(a.starts_with("dev_api-"));
+        assert_ne!(a, b);
+    }
+}
@@ -12,11 +12,12 @@ use itertools::Itertools;
 use rayon::prelude::*;
 
 use crate::backend::Backend;
+use crate::cache::CacheManager;
 use crate::cli::exec::Exec;
 use crate::config::{Config, Settings};
 use crate::file::{create_dir_all, display_path, remove_all};
 use crate::lock_file::LockFile;
-use crate::toolset::{ToolVersion, Toolset, ToolsetBuilder};
+use crate::toolset::{BinFilter, ToolVersion, Toolset, ToolsetBuilder};
 use crate::{backend, dirs, env, fake_asdf, file, logger};
 
 // executes as if it was a shim if the command is not "mise", e.g.: "node"
@@ -38,6 +39,7 @@ pub fn handle_shim() -> Result<()> {
         command: Some(args),
         jobs: None,
         raw: false,
+        hermetic: false,
     };
     exec.run()?;
     exit(0);
@@ -57,7 +59,8 @@ fn which_shim(bin_name: &str) -> Result<PathBuf> {
     }
     let settings = Settings::try_get()?;
     if settings.not_found_auto_install {
-        for tv in ts.install_missing_bin(bin_name)?.unwrap_or_default() {
+        let mode = settings.auto_install.shim;
+        for tv in ts.install_missing_bin(bin_name, mode)?.unwrap_or_default() {
             let p = tv.get_backend();
             if let Some(bin) = p.which(&tv, bin_name)? {
                 trace!(
@@ -98,7 +101,8 @@ pub fn reshim(ts: &Toolset) -> Result<()> {
 
     let (shims_to_add, shims_to_remove) = get_shim_diffs(&mise_bin, ts)?;
 
-    for shim in shims_to_add {
+    // only the shims that changed are touched
+    shims_to_add.par_iter().try_for_each(|shim| {
         let symlink_path = dirs::SHIMS.join(shim);
         file::make_symlink(&mise_bin, &symlink_path).wrap_err_with(|| {
             eyre!(
@@ -106,12 +110,11 @@ pub fn reshim(ts: &Toolset) -> Result<()> {
                 display_path(&mise_bin),
                 display_path(&symlink_path)
             )
-        })?;
-    }
-    for shim in shims_to_remove {
-        let symlink_path = dirs::SHIMS.join(shim);
-        remove_all(&symlink_path)?;
-    }
+        })
+    })?;
+    shims_to_remove
+        .par_iter()
+        .try_for_each(|shim| remove_all(dirs::SHIMS.join(shim)))?;
     for plugin in backend::list() {
         match dirs::PLUGINS.join(plugin.id()).join("shims").read_dir() {
             Ok(files) => {
@@ -131,6 +134,11 @@ pub fn reshim(ts: &Toolset) -> Result<()> {
     Ok(())
 }
 
+/// removes every shim so `reshim()` creates all of them again
+pub fn clear() -> Result<()> {
+    remove_all(*dirs::SHIMS)
+}
+
 // get_shim_diffs contrasts the actual shims on disk
 // with the desired shims specified by the Toolset
 // and returns a tuple of (missing shims, extra shims)
@@ -204,34 +212,53 @@ fn get_desired_shims(toolset: &Toolset) -> Result<HashSet<String>> {
 }
 
 // lists all the paths to bins in a tv that shims will be needed for
+// the list is cached until one of the bin dirs is modified so unchanged tools are not read again
 fn list_tool_bins(t: Arc<dyn Backend>, tv: &ToolVersion) -> Result<Vec<String>> {
-    Ok(t.list_bin_paths(tv)?
+    let bin_paths = t
+        .list_bin_paths(tv)?
         .into_iter()
-        .par_bridge()
         .filter(|path| path.exists())
-        .map(|dir| list_executables_in_dir(&dir))
-        .collect::<Result<Vec<_>>>()?
-        .into_iter()
-        .flatten()
-        .collect())
+        .collect_vec();
+    let cache = bin_paths.iter().fold(
+        CacheManager::<Vec<String>>::new(tv.cache_path().join("bins-$KEY.msgpack.z"))
+            .with_fresh_file(tv.install_path()),
+        |cache, path| cache.with_fresh_file(path.clone()),
+    );
+    let bins = cache
+        .get_or_try_init(|| {
+            Ok(bin_paths
+                .par_iter()
+                .map(|dir| list_executables_in_dir(dir))
+                .collect::<Result<Vec<_>>>()?
+                .into_iter()
+                .flatten()
+                .collect())
+        })
+        .cloned()?;
+    // filtered after reading the cache so changing the options doesn't need a fresh listing
+    Ok(match BinFilter::for_tv(tv) {
+        Some(filter) => bins.into_iter().filter(|b| filter.matches(b)).collect(),
+        None => bins,
+    })
 }
 
 fn make_shim(target: &Path, shim: &Path) -> Result<()> {
-    if shim.exists() {
-        file::remove_file(shim)?;
-    }
-    file::write(
-        shim,
-        formatdoc! {r#"
+    let content = formatdoc! {r#"
         #!/bin/sh
         export ASDF_DATA_DIR={data_dir}
         export PATH="{fake_asdf_dir}:$PATH"
         mise x -- {target} "$@"
         "#,
-        data_dir = dirs::DATA.display(),
-        fake_asdf_dir = fake_asdf::setup()?.display(),
-        target = target.display()},
-    )?;
+    data_dir = dirs::DATA.display(),
+    fake_asdf_dir = fake_asdf::setup()?.display(),
+    target = target.display()};
+    if shim.is_file() && file::read_to_string(shim).is_ok_and(|s| s == content) {
+        return Ok(());
+    }
+    if shim.exists() {
+        file::remove_file(shim)?;
+    }
+    file::write(shim, content)?;
     file::make_executable(shim)?;
     trace!(
         "shim created from {} to {}",
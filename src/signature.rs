@@ -0,0 +1,251 @@
+use std::path::{Path, PathBuf};
+
+use eyre::{bail, eyre, Result};
+
+use crate::cmd::CmdLineRunner;
+use crate::config::Settings;
+use crate::file;
+use crate::file::display_path;
+use crate::http::HTTP;
+use crate::toolset::{ToolVersion, ToolVersionOptions};
+use crate::ui::progress_report::SingleReport;
+
+/// Verifies signatures of artifacts downloaded by backends with the `verify` tool option:
+///
+/// [tools]
+/// "ubi:jedisct1/minisign" = { version = "0.11", verify = "minisign", public_key = "RWQf6LRC..." }
+/// "http:tool" = { version = "1.0", url = "...", verify = "cosign", certificate_identity = "...", certificate_oidc_issuer = "..." }
+/// "ubi:owner/tool" = { version = "1.0", verify = "gpg", public_key = "https://example.com/key.asc" }
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::EnumString, strum::Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum Method {
+    Minisign,
+    Cosign,
+    Gpg,
+}
+
+impl Method {
+    /// the `verify` tool option
+    pub fn from_options(opts: &ToolVersionOptions) -> Result<Option<Self>> {
+        match opts.get("verify") {
+            Some(m) => m.parse().map(Some).map_err(|_| {
+                eyre!("invalid verify option: {m}, expected minisign, cosign, or gpg")
+            }),
+            None => Ok(None),
+        }
+    }
+
+    /// extensions of the signature files usually published next to artifacts
+    fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            Method::Minisign => &["minisig"],
+            Method::Cosign => &["bundle", "sig"],
+            Method::Gpg => &["asc", "sig"],
+        }
+    }
+
+    /// names of the signature files of an artifact, in the order they are tried
+    pub fn signature_names(&self, artifact: &str) -> Vec<String> {
+        self.extensions()
+            .iter()
+            .map(|ext| format!("{artifact}.{ext}"))
+            .collect()
+    }
+}
+
+/// true if artifacts of this tool have to be verified, backends download the artifact themselves
+/// in this case instead of letting another tool install it
+pub fn is_required(opts: &ToolVersionOptions) -> bool {
+    match Settings::get().signature_policy.as_str() {
+        "off" => false,
+        "require" => true,
+        _ => opts.contains_key("verify"),
+    }
+}
+
+/// verifies the signature of an artifact with the method in the `verify` tool option, the
+/// signature is downloaded from the first of `signature_urls` that exists
+pub fn verify(
+    tv: &ToolVersion,
+    artifact: &Path,
+    signature_urls: &[String],
+    pr: &dyn SingleReport,
+) -> Result<()> {
+    let opts = tv.request.options();
+    if !is_required(&opts) {
+        return Ok(());
+    }
+    let Some(method) = Method::from_options(&opts)? else {
+        bail!("{tv} has no signature to verify, set the verify tool option or signature_policy = \"auto\"");
+    };
+    let filename = artifact.file_name().unwrap().to_string_lossy();
+    pr.set_message(format!("verifying {method} signature of {filename}"));
+    let (url, signature) = download_signature(tv, signature_urls)?;
+    let result = match method {
+        Method::Minisign => verify_minisign(&opts, artifact, &signature),
+        Method::Cosign => verify_cosign(&opts, artifact, &url, &signature, pr),
+        Method::Gpg => verify_gpg(&opts, artifact, &signature, pr),
+    };
+    result.map_err(|err| eyre!("{method} signature of {filename} is invalid: {err}"))?;
+    debug!("{tv}: verified {method} signature {url}");
+    Ok(())
+}
+
+fn download_signature(tv: &ToolVersion, urls: &[String]) -> Result<(String, PathBuf)> {
+    if urls.is_empty() {
+        bail!("no signature was published for {tv}");
+    }
+    for url in urls {
+        let name = url.rsplit('/').next().unwrap_or("signature");
+        let path = tv.download_path().join(name);
+        match HTTP.download_file(url, &path, None) {
+            Ok(()) => return Ok((url.clone(), path)),
+            Err(err) => debug!("{tv}: no signature at {url}: {err:#}"),
+        }
+    }
+    bail!("no signature found for {tv}, tried: {}", urls.join(", "))
+}
+
+/// the `public_key` option, either the key itself, a path to it, or an https url
+fn public_key(opts: &ToolVersionOptions) -> Result<Option<String>> {
+    let Some(key) = opts.get("public_key") else {
+        return Ok(None);
+    };
+    if key.starts_with("https://") {
+        return HTTP.get_text(key).map(Some);
+    }
+    let path = file::replace_path(key);
+    if !key.contains('\n') && path.is_file() {
+        return file::read_to_string(path).map(Some);
+    }
+    Ok(Some(key.clone()))
+}
+
+fn verify_minisign(opts: &ToolVersionOptions, artifact: &Path, signature: &Path) -> Result<()> {
+    let Some(key) = public_key(opts)? else {
+        bail!("minisign requires the public_key tool option");
+    };
+    // either the base64 key or the contents of a minisign.pub file
+    let key = match key.lines().count() {
+        1 => minisign_verify::PublicKey::from_base64(key.trim()),
+        _ => minisign_verify::PublicKey::decode(&key),
+    }
+    .map_err(|err| eyre!("invalid minisign public key: {err}"))?;
+    let signature = minisign_verify::Signature::decode(&file::read_to_string(signature)?)
+        .map_err(|err| eyre!("invalid minisign signature: {err}"))?;
+    key.verify(&std::fs::read(artifact)?, &signature, false)
+        .map_err(|err| eyre!("{err}"))
+}
+
+/// verifies with a key from `public_key` or keyless with the `certificate_identity` and
+/// `certificate_oidc_issuer` options, keyless signatures need a .bundle with the certificate
+fn verify_cosign(
+    opts: &ToolVersionOptions,
+    artifact: &Path,
+    url: &str,
+    signature: &Path,
+    pr: &dyn SingleReport,
+) -> Result<()> {
+    if file::which("cosign").is_none() {
+        bail!("cosign is not installed, install it with `mise use -g cosign`");
+    }
+    let tmp = tempfile::tempdir()?;
+    let mut cmd = CmdLineRunner::new("cosign").arg("verify-blob");
+    cmd = match url.ends_with(".bundle") {
+        true => cmd.arg("--bundle").arg(signature),
+        false => cmd.arg("--signature").arg(signature),
+    };
+    match (
+        public_key(opts)?,
+        opts.get("certificate_identity"),
+        opts.get("certificate_oidc_issuer"),
+    ) {
+        (Some(key), _, _) => {
+            let key_path = tmp.path().join("cosign.pub");
+            file::write(&key_path, key)?;
+            cmd = cmd.arg("--key").arg(key_path);
+        }
+        (None, Some(identity), Some(issuer)) => {
+            cmd = cmd
+                .arg("--certificate-identity")
+                .arg(identity)
+                .arg("--certificate-oidc-issuer")
+                .arg(issuer);
+        }
+        _ => bail!("cosign requires the public_key tool option or certificate_identity and certificate_oidc_issuer"),
+    }
+    cmd.arg(artifact).with_pr(pr).execute()
+}
+
+/// verifies with the keys in `public_key` in a temporary keyring so the user's keyring is not used
+fn verify_gpg(
+    opts: &ToolVersionOptions,
+    artifact: &Path,
+    signature: &Path,
+    pr: &dyn SingleReport,
+) -> Result<()> {
+    if file::which("gpg").is_none() {
+        bail!("gpg is not installed");
+    }
+    let Some(key) = public_key(opts)? else {
+        bail!("gpg requires the public_key tool option");
+    };
+    let home = tempfile::tempdir()?;
+    let key_path = home.path().join("key.asc");
+    file::write(&key_path, key)?;
+    let gpg = || {
+        CmdLineRunner::new("gpg")
+            .arg("--batch")
+            .arg("--homedir")
+            .arg(home.path())
+            .with_pr(pr)
+    };
+    gpg().arg("--import").arg(&key_path).execute()?;
+    gpg()
+        .arg("--verify")
+        .arg(signature)
+        .arg(artifact)
+        .execute()
+        .map_err(|err| eyre!("{err}\nsignature: {}", display_path(signature)))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_method() {
+        let opts = |v: &str| ToolVersionOptions::from([("verify".to_string(), v.to_string())]);
+        assert_eq!(
+            Method::from_options(&opts("minisign")).unwrap(),
+            Some(Method::Minisign)
+        );
+        assert_eq!(
+            Method::from_options(&opts("gpg")).unwrap(),
+            Some(Method::Gpg)
+        );
+        assert!(Method::from_options(&opts("pgp")).is_err());
+        assert_eq!(Method::from_options(&Default::default()).unwrap(), None);
+        assert_eq!(
+            Method::Cosign.signature_names("tool.tar.gz"),
+            vec!["tool.tar.gz.bundle", "tool.tar.gz.sig"]
+        );
+    }
+
+    #[test]
+    fn test_verify_minisign_without_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let artifact = dir.path().join("tool");
+        file::write(&artifact, "hello\n").unwrap();
+        let signature = dir.path().join("tool.minisig");
+        file::write(&signature, "untrusted comment: x\nRWQ\n").unwrap();
+        let opts = ToolVersionOptions::from([("verify".to_string(), "minisign".to_string())]);
+        let err = verify_minisign(&opts, &artifact, &signature).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "minisign requires the public_key tool option"
+        );
+    }
+}
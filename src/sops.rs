@@ -0,0 +1,206 @@
+use std::path::{Path, PathBuf};
+
+use eyre::{bail, eyre, Result, WrapErr};
+use itertools::Itertools;
+use rops::cryptography::cipher::AES256GCM;
+use rops::cryptography::hasher::SHA512;
+use rops::file::format::{FileFormat, YamlFileFormat};
+use rops::file::state::EncryptedFile;
+use rops::file::RopsFile;
+
+use crate::config::Settings;
+use crate::file::display_path;
+use crate::{env, file};
+
+/// whether a dotenv, json, or yaml file was encrypted with sops, its metadata is in a top-level
+/// `sops` key or in `sops_*` variables
+pub fn is_encrypted(contents: &str) -> bool {
+    regex!(r#"(?m)^(sops:|sops_mac=|\s*"sops"\s*:)"#).is_match(contents)
+}
+
+/// decrypts the variables of a sops file, the plaintext is never written to disk. yaml and json
+/// files encrypted with age are decrypted in-process, other files and keys (dotenv, pgp, KMS) are
+/// decrypted with the sops CLI which finds its keys itself.
+pub fn decrypt(path: &Path, contents: &str) -> Result<Vec<(String, String)>> {
+    let format = input_type(contents);
+    let keys = age_keys()?;
+    if format != "dotenv" && !keys.is_empty() {
+        match decrypt_in_process(contents, &keys) {
+            Ok(vars) => return Ok(vars),
+            Err(err) if sops_cli().is_some() => {
+                debug!("using sops to decrypt {}: {err:#}", display_path(path));
+            }
+            Err(err) => {
+                return Err(err.wrap_err(format!("failed to decrypt {}", display_path(path))))
+            }
+        }
+    }
+    decrypt_with_cli(path, format)
+}
+
+fn decrypt_in_process(contents: &str, keys: &[String]) -> Result<Vec<(String, String)>> {
+    // rops reads the age keys from ROPS_AGE
+    env::set_var("ROPS_AGE", keys.join(","));
+    // json is parsed as yaml which keeps the keys in the order the mac was computed in
+    decrypt_with_rops::<YamlFileFormat>(contents)?
+        .into_iter()
+        .map(|(k, v)| {
+            let k = match k {
+                serde_yaml::Value::String(k) => k,
+                k => bail!("invalid key in sops file: {k:?}"),
+            };
+            let v = match v {
+                serde_yaml::Value::String(s) => s,
+                serde_yaml::Value::Null => String::new(),
+                serde_yaml::Value::Bool(b) => b.to_string(),
+                serde_yaml::Value::Number(n) => n.to_string(),
+                _ => bail!("{k} is not a string, sops env files can only have flat keys"),
+            };
+            Ok((k, v))
+        })
+        .collect()
+}
+
+fn decrypt_with_rops<F: FileFormat>(contents: &str) -> Result<F::Map> {
+    let file = contents
+        .parse::<RopsFile<EncryptedFile<AES256GCM, SHA512>, F>>()
+        .wrap_err("failed to parse sops file")?;
+    Ok(file.decrypt::<F>()?.into_inner_map())
+}
+
+/// age keys the way sops finds them: SOPS_AGE_KEY, SOPS_AGE_KEY_FILE, and sops' default key file,
+/// as well as the `age_identity_file` used by `mise set --encrypt`
+fn age_keys() -> Result<Vec<String>> {
+    let mut sources = vec![];
+    if let Ok(key) = env::var("SOPS_AGE_KEY") {
+        sources.push(key);
+    }
+    let files = [
+        env::var_path("SOPS_AGE_KEY_FILE"),
+        Some(env::XDG_CONFIG_HOME.join("sops/age/keys.txt")),
+        Settings::get()
+            .age_identity_file
+            .as_ref()
+            .map(file::replace_path),
+    ];
+    for f in files.into_iter().flatten().filter(|f| f.exists()) {
+        sources.push(file::read_to_string(&f)?);
+    }
+    Ok(sources
+        .iter()
+        .flat_map(|s| s.lines())
+        .map(|l| l.trim())
+        .filter(|l| l.starts_with("AGE-SECRET-KEY-"))
+        .map(String::from)
+        .unique()
+        .collect())
+}
+
+fn sops_cli() -> Option<PathBuf> {
+    file::which_non_pristine("sops")
+}
+
+/// decrypts a file with `sops --decrypt` as dotenv, the plaintext is only read from its stdout
+fn decrypt_with_cli(path: &Path, format: &str) -> Result<Vec<(String, String)>> {
+    let sops = sops_cli().ok_or_else(|| {
+        eyre!(
+            "{} needs the sops CLI to be decrypted, only yaml and json files encrypted with an age \
+             key mise can find are decrypted without it\n\
+             install it with `mise use -g sops` or set SOPS_AGE_KEY_FILE",
+            display_path(path)
+        )
+    })?;
+    let output = cmd!(
+        sops,
+        "--decrypt",
+        "--input-type",
+        format,
+        "--output-type",
+        "dotenv",
+        path
+    )
+    .stdout_capture()
+    .stderr_capture()
+    .unchecked()
+    .run()?;
+    if !output.status.success() {
+        bail!(
+            "failed to decrypt {} with sops: {}",
+            display_path(path),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    dotenvy::from_read_iter(output.stdout.as_slice())
+        .map(|item| item.wrap_err_with(|| format!("failed to parse {}", display_path(path))))
+        .collect()
+}
+
+/// the format of a file, sops would guess it from the extension which is often `.enc`
+fn input_type(contents: &str) -> &'static str {
+    if contents.trim_start().starts_with('{') {
+        "json"
+    } else if regex!(r"(?m)^sops_mac=").is_match(contents) {
+        "dotenv"
+    } else {
+        "yaml"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rops::file::builder::RopsFileBuilder;
+    use rops::integration::{AgeIntegration, Integration};
+
+    use crate::test::reset;
+
+    use super::*;
+
+    #[test]
+    fn test_is_encrypted() {
+        let yaml = "DB_PASSWORD: ENC[AES256_GCM,data:abc]\nsops:\n  mac: ENC[...]\n";
+        assert!(is_encrypted(yaml));
+        assert_eq!(input_type(yaml), "yaml");
+        let json =
+            "{\n  \"DB_PASSWORD\": \"ENC[...]\",\n  \"sops\": {\n    \"mac\": \"ENC[...]\"\n  }\n}";
+        assert!(is_encrypted(json));
+        assert_eq!(input_type(json), "json");
+        let dotenv = "DB_PASSWORD=ENC[...]\nsops_mac=ENC[...]\n";
+        assert!(is_encrypted(dotenv));
+        assert_eq!(input_type(dotenv), "dotenv");
+        assert!(!is_encrypted("DB_PASSWORD=hunter2\nSOPS=1\n"));
+    }
+
+    #[test]
+    fn test_decrypt_age() {
+        reset();
+        let recipient = AgeIntegration::parse_key_id(
+            "age1se5ghfycr4n8kcwc3qwf234ymvmr2lex2a99wh8gpfx97glwt9hqch4569",
+        )
+        .unwrap();
+        let key = "AGE-SECRET-KEY-1EQUCGFZH8UZKSZ0Z5N5T234YRNDT4U9H7QNYXWRRNJYDDVXE6FWSCPGNJ7";
+        let encrypted =
+            RopsFileBuilder::<YamlFileFormat>::new("DB_PASSWORD: hunter2\nDB_PORT: 5432\n")
+                .unwrap()
+                .add_integration_key::<AgeIntegration>(recipient)
+                .encrypt::<AES256GCM, SHA512>()
+                .unwrap()
+                .to_string();
+        assert!(is_encrypted(&encrypted));
+        assert!(!encrypted.contains("hunter2"));
+
+        env::set_var("SOPS_AGE_KEY", format!("# created: 2024-01-01\n{key}\n"));
+        let vars = decrypt(Path::new("secrets.enc.yaml"), &encrypted).unwrap();
+        let expected = vec![
+            ("DB_PASSWORD".to_string(), "hunter2".to_string()),
+            ("DB_PORT".to_string(), "5432".to_string()),
+        ];
+        assert_eq!(vars, expected);
+
+        let yaml: serde_yaml::Value = serde_yaml::from_str(&encrypted).unwrap();
+        let json = serde_json::to_string_pretty(&yaml).unwrap();
+        assert_eq!(input_type(&json), "json");
+        let vars = decrypt(Path::new("secrets.enc.json"), &json).unwrap();
+        env::remove_var("SOPS_AGE_KEY");
+        assert_eq!(vars, expected);
+    }
+}
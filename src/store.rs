@@ -0,0 +1,300 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use eyre::{Result, WrapErr};
+use once_cell::sync::Lazy;
+use walkdir::WalkDir;
+
+use crate::config::Settings;
+use crate::file::display_path;
+use crate::hash::{file_hash_sha256, hash_sha256_to_str, hash_to_str};
+use crate::rand::random_string;
+use crate::{dirs, file};
+
+/// Content-addressed store of downloads and the trees extracted from them, enabled with the
+/// `content_store` setting. Downloads and the files of extracted trees are reflinked or hardlinked
+/// into download and install paths so reinstalling the same artifact doesn't download or extract
+/// it again:
+///
+/// ~/.local/share/mise/store/blobs/sha256-<hash>      downloaded files
+/// ~/.local/share/mise/store/trees/sha256-<hash>/     extracted archives
+/// ~/.local/share/mise/store/urls/<hash of url>       digest of the file downloaded from a url
+/// ~/.local/share/mise/store/refs/<hash of path>      digest of the tree copied to an install path
+pub static STORE: Lazy<PathBuf> = Lazy::new(|| dirs::DATA.join("store"));
+
+pub fn is_enabled() -> bool {
+    Settings::get().content_store
+}
+
+fn blob_path(digest: &str) -> PathBuf {
+    STORE.join("blobs").join(digest)
+}
+
+fn tree_path(digest: &str) -> PathBuf {
+    STORE.join("trees").join(digest)
+}
+
+fn url_path(url: &str) -> PathBuf {
+    STORE.join("urls").join(hash_to_str(&url))
+}
+
+fn ref_path(dest: &Path) -> PathBuf {
+    STORE
+        .join("refs")
+        .join(hash_sha256_to_str(&dest.to_string_lossy()))
+}
+
+fn digest(path: &Path) -> Result<String> {
+    Ok(format!("sha256-{}", file_hash_sha256(path)?))
+}
+
+/// links the file previously downloaded from `url` to `path`, returns false if it isn't stored
+pub fn link_download(url: &str, path: &Path) -> Result<bool> {
+    let Ok(digest) = file::read_to_string(url_path(url)) else {
+        return Ok(false);
+    };
+    let blob = blob_path(digest.trim());
+    if !blob.exists() {
+        return Ok(false);
+    }
+    debug!("using {} from the content store", display_path(path));
+    file::create_dir_all(path.parent().unwrap())?;
+    link_file(&blob, path)?;
+    Ok(true)
+}
+
+/// moves a file downloaded from `url` into the store and links it back to `path`
+pub fn add_download(url: &str, path: &Path) -> Result<()> {
+    let digest = digest(path)?;
+    let blob = blob_path(&digest);
+    if blob.exists() {
+        file::remove_file(path)?;
+    } else {
+        file::create_dir_all(blob.parent().unwrap())?;
+        file::rename(path, &blob)?;
+    }
+    link_file(&blob, path)?;
+    let url_path = url_path(url);
+    file::create_dir_all(url_path.parent().unwrap())?;
+    file::write(url_path, digest)
+}
+
+/// copies the tree extracted from `archive` into `dest`, `extract` is only called to populate
+/// the store the first time an archive is seen
+pub fn extract(
+    archive: &Path,
+    dest: &Path,
+    extract: impl FnOnce(&Path) -> Result<()>,
+) -> Result<()> {
+    let digest = digest(archive)?;
+    let tree = tree_path(&digest);
+    if tree.exists() {
+        debug!("using {} from the content store", display_path(&tree));
+    } else {
+        // extracted next to the tree and renamed so a partial tree is never used
+        let partial = tree.with_extension(format!("part-{}", random_string(8)));
+        file::create_dir_all(tree.parent().unwrap())?;
+        if let Err(err) = extract(&partial).and_then(|_| make_read_only(&partial)) {
+            let _ = file::remove_all(&partial);
+            return Err(err);
+        }
+        if let Err(err) = file::rename(&partial, &tree) {
+            // another process stored it first
+            file::remove_all(&partial)?;
+            if !tree.exists() {
+                return Err(err);
+            }
+        }
+    }
+    link_tree(&tree, dest)?;
+    let ref_path = ref_path(dest);
+    file::create_dir_all(ref_path.parent().unwrap())?;
+    file::write(ref_path, format!("{digest}\n{}", dest.to_string_lossy()))
+}
+
+/// recreates the directories and symlinks of `from` in `to` and links the files
+fn link_tree(from: &Path, to: &Path) -> Result<()> {
+    trace!("cp -r {} {}", display_path(from), display_path(to));
+    for entry in WalkDir::new(from).follow_links(false) {
+        let entry = entry?;
+        let dest = to.join(entry.path().strip_prefix(from)?);
+        let file_type = entry.file_type();
+        if file_type.is_dir() {
+            file::create_dir_all(&dest)?;
+        } else if file_type.is_symlink() {
+            file::make_symlink(&fs::read_link(entry.path())?, &dest)?;
+        } else {
+            link_file(entry.path(), &dest)?;
+        }
+    }
+    Ok(())
+}
+
+/// reflinks a file on filesystems that support it like APFS, btrfs, and xfs, otherwise hardlinks
+/// it, or copies it if neither is possible, e.g.: across filesystems
+fn link_file(from: &Path, to: &Path) -> Result<()> {
+    if to.exists() || to.is_symlink() {
+        file::remove_file(to)?;
+    }
+    if reflink_copy::reflink(from, to).is_ok() {
+        // a reflink is a copy of its own, it can be written to like any other file
+        return set_writable(to, fs::metadata(from)?.permissions());
+    }
+    if fs::hard_link(from, to).is_err() {
+        fs::copy(from, to).wrap_err_with(|| {
+            format!(
+                "failed to link {} to {}",
+                display_path(from),
+                display_path(to)
+            )
+        })?;
+    }
+    Ok(())
+}
+
+/// the files of trees in the store are read-only since installs hardlink them, writing to one
+/// in place would change every install of it
+fn make_read_only(tree: &Path) -> Result<()> {
+    for entry in WalkDir::new(tree).follow_links(false) {
+        let entry = entry?;
+        if entry.file_type().is_file() {
+            let mut permissions = entry.metadata()?.permissions();
+            permissions.set_readonly(true);
+            fs::set_permissions(entry.path(), permissions)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn set_writable(path: &Path, permissions: fs::Permissions) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = permissions.mode() | 0o200;
+    fs::set_permissions(path, fs::Permissions::from_mode(mode))?;
+    Ok(())
+}
+
+#[cfg(windows)]
+fn set_writable(path: &Path, mut permissions: fs::Permissions) -> Result<()> {
+    #[allow(clippy::permissions_set_readonly_false)]
+    permissions.set_readonly(false);
+    fs::set_permissions(path, permissions)?;
+    Ok(())
+}
+
+/// replaces the files of an install that are hardlinked from the store with copies of their own
+/// before they're modified, e.g.: by `patches` or a postinstall script
+pub fn detach(install_path: &Path) -> Result<()> {
+    if !is_enabled() || !install_path.exists() {
+        return Ok(());
+    }
+    detach_tree(install_path)
+}
+
+fn detach_tree(install_path: &Path) -> Result<()> {
+    for entry in WalkDir::new(install_path).follow_links(false) {
+        let entry = entry?;
+        if !entry.file_type().is_file() || !is_linked(entry.path()) {
+            continue;
+        }
+        let path = entry.path();
+        let tmp = path.with_extension(format!("mise-{}", random_string(8)));
+        fs::copy(path, &tmp).wrap_err_with(|| format!("failed to copy {}", display_path(path)))?;
+        set_writable(&tmp, entry.metadata()?.permissions())?;
+        file::rename(&tmp, path)?;
+    }
+    Ok(())
+}
+
+/// entries of the store that are not used by any download or install path, removed by
+/// `mise prune --store`
+pub fn unused() -> Result<Vec<PathBuf>> {
+    let mut unused = vec![];
+    let mut used_trees = std::collections::HashSet::new();
+    for ref_path in file::ls(&STORE.join("refs"))? {
+        let contents = file::read_to_string(&ref_path)?;
+        match contents.split_once('\n') {
+            // the install is still there, it may have been reinstalled from another tree since
+            Some((digest, dest))
+                if file::read_to_string(self::ref_path(Path::new(dest)))
+                    .is_ok_and(|c| c == contents)
+                    && Path::new(dest).exists() =>
+            {
+                used_trees.insert(digest.to_string());
+            }
+            _ => unused.push(ref_path),
+        }
+    }
+    for digest in file::dir_subdirs(&STORE.join("trees"))? {
+        let tree = tree_path(&digest);
+        if !used_trees.contains(&digest) {
+            unused.push(tree);
+            let blob = blob_path(&digest);
+            if blob.exists() {
+                unused.push(blob);
+            }
+        }
+    }
+    for blob in file::ls(&STORE.join("blobs"))? {
+        let digest = blob.file_name().unwrap().to_string_lossy().to_string();
+        if !tree_path(&digest).exists() && !is_linked(&blob) {
+            unused.push(blob);
+        }
+    }
+    Ok(unused)
+}
+
+#[cfg(unix)]
+fn is_linked(path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .any(|e| e.metadata().is_ok_and(|m| m.nlink() > 1))
+}
+
+/// files can't be checked for other links so nothing is pruned
+#[cfg(windows)]
+fn is_linked(_path: &Path) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_link_tree() {
+        let dir = tempfile::tempdir().unwrap();
+        let from = dir.path().join("from");
+        file::create_dir_all(from.join("bin")).unwrap();
+        file::write(from.join("bin/tool"), "#!/bin/sh\n").unwrap();
+        #[cfg(unix)]
+        file::make_symlink(Path::new("bin/tool"), &from.join("tool")).unwrap();
+        make_read_only(&from).unwrap();
+        let to = dir.path().join("to");
+        link_tree(&from, &to).unwrap();
+        assert_eq!(
+            file::read_to_string(to.join("bin/tool")).unwrap(),
+            "#!/bin/sh\n"
+        );
+        // patching the install doesn't change the store
+        detach_tree(&to).unwrap();
+        file::write(to.join("bin/tool"), "patched").unwrap();
+        assert_eq!(
+            file::read_to_string(from.join("bin/tool")).unwrap(),
+            "#!/bin/sh\n"
+        );
+        #[cfg(unix)]
+        {
+            assert_eq!(
+                fs::read_link(to.join("tool")).unwrap(),
+                Path::new("bin/tool")
+            );
+            assert!(!is_linked(&from));
+        }
+    }
+}
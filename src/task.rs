@@ -5,11 +5,12 @@ use std::fmt::{Display, Formatter};
 use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
 use std::sync::mpsc;
+use std::time::Duration;
 use std::{ffi, fmt, path};
 
 use console::truncate_str;
 use either::Either;
-use eyre::{eyre, Result};
+use eyre::{bail, eyre, Result};
 use globset::Glob;
 use itertools::Itertools;
 use petgraph::prelude::*;
@@ -41,14 +42,62 @@ pub struct Task {
     pub hide: bool,
     #[serde(default)]
     pub raw: bool,
+    /// how the output of the task is printed: prefix, interleave, group, or quiet
+    #[serde(default)]
+    pub output: Option<String>,
+    /// on windows, translate paths between C:\ and /c/ for bash
+    #[serde(default)]
+    pub translate_paths: Option<bool>,
     #[serde(default)]
     pub sources: Vec<String>,
     #[serde(default)]
     pub outputs: Vec<String>,
+    /// a long-running process that `mise run` keeps running instead of waiting for it to finish
+    #[serde(default)]
+    pub service: bool,
+    /// when a service is started again after it exits
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    /// command that succeeds once a service is ready, tasks that depend on it wait for this
+    #[serde(default)]
+    pub ready: Option<String>,
+    /// usage spec (KDL) of the args and flags the task accepts
+    #[serde(default)]
+    pub usage: String,
+    /// name of a task this one is based on, see `Task::extend`
+    #[serde(default)]
+    pub extends: Option<String>,
+    /// cron expression of when `mise schedule install` runs the task
+    #[serde(default)]
+    pub schedule: Option<String>,
+    /// image of a container the task runs in with docker or podman
+    #[serde(default)]
+    pub container: Option<String>,
+    /// values of variables the task runs once per combination of, see `Task::expand_matrix`
+    #[serde(default)]
+    pub matrix: BTreeMap<String, Vec<String>>,
+    /// the values of `matrix` a task that was expanded from a matrix task runs with
+    #[serde(skip)]
+    pub combination: BTreeMap<String, String>,
+    /// message the user has to confirm before the task runs, `--yes` or `MISE_YES` skip it
+    #[serde(default)]
+    pub confirm: Option<String>,
+    /// env vars the user is asked for before the task runs unless they are already set
+    #[serde(default)]
+    pub prompt: BTreeMap<String, TaskPrompt>,
+    /// versions of tools that are only installed and on PATH for this task
+    #[serde(default)]
+    pub tools: BTreeMap<String, String>,
+    /// how long the task may run before it is stopped, e.g. "10m"
+    #[serde(default)]
+    pub timeout: Option<String>,
+    /// how often a failed task is run again
+    #[serde(default)]
+    pub retry: Option<RetryPolicy>,
 
     // normal type
-    #[serde(default, deserialize_with = "deserialize_arr")]
-    pub run: Vec<String>,
+    #[serde(default, deserialize_with = "deserialize_run")]
+    pub run: Vec<RunEntry>,
 
     // command type
     // pub command: Option<String>,
@@ -66,6 +115,150 @@ pub struct Task {
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
 pub struct EitherStringOrBool(#[serde(with = "either::serde_untagged")] pub Either<String, bool>);
 
+/// a step of a task's `run`, either a script or a table like `{ task = "deploy", if = "success" }`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum RunEntry {
+    Script(String),
+    Step {
+        #[serde(default)]
+        run: Option<String>,
+        #[serde(default)]
+        task: Option<String>,
+        #[serde(default, rename = "if")]
+        condition: RunCondition,
+    },
+}
+
+/// an env var of a task that is asked for, either the message or a table like
+/// `{ message = "Version", default = "latest" }`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum TaskPrompt {
+    Message(String),
+    Input {
+        message: String,
+        #[serde(default)]
+        default: Option<String>,
+    },
+}
+
+impl TaskPrompt {
+    pub fn message(&self) -> &str {
+        match self {
+            Self::Message(message) | Self::Input { message, .. } => message,
+        }
+    }
+
+    /// the value used when the user enters nothing or can't be asked
+    pub fn default(&self) -> Option<&str> {
+        match self {
+            Self::Message(_) => None,
+            Self::Input { default, .. } => default.as_deref(),
+        }
+    }
+}
+
+/// how often and after how long a failed task is run again, like `{ count = 3, delay = "5s" }`
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+pub struct RetryPolicy {
+    pub count: u32,
+    #[serde(default)]
+    pub delay: Option<String>,
+}
+
+impl RetryPolicy {
+    pub fn delay(&self) -> Result<Duration> {
+        match &self.delay {
+            Some(delay) => parse_duration(delay),
+            None => Ok(Duration::ZERO),
+        }
+    }
+}
+
+/// when a step runs based on the steps before it
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunCondition {
+    /// every previous step succeeded
+    #[default]
+    Success,
+    /// a previous step failed
+    Failure,
+    Always,
+}
+
+/// when a service task is restarted after it exits
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, strum::EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum RestartPolicy {
+    /// only if it exits with an error
+    #[default]
+    OnFailure,
+    Always,
+    Never,
+}
+
+impl RestartPolicy {
+    pub fn should_restart(&self, failed: bool) -> bool {
+        match self {
+            RestartPolicy::OnFailure => failed,
+            RestartPolicy::Always => true,
+            RestartPolicy::Never => false,
+        }
+    }
+}
+
+impl RunEntry {
+    pub fn condition(&self) -> RunCondition {
+        match self {
+            RunEntry::Script(_) => RunCondition::Success,
+            RunEntry::Step { condition, .. } => *condition,
+        }
+    }
+
+    pub fn should_run(&self, failed: bool) -> bool {
+        match self.condition() {
+            RunCondition::Success => !failed,
+            RunCondition::Failure => failed,
+            RunCondition::Always => true,
+        }
+    }
+}
+
+impl From<String> for RunEntry {
+    fn from(s: String) -> Self {
+        RunEntry::Script(s)
+    }
+}
+
+impl Display for RunEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RunEntry::Script(s) | RunEntry::Step { run: Some(s), .. } => write!(f, "{s}"),
+            RunEntry::Step { task: Some(t), .. } => write!(f, "mise run {t}"),
+            RunEntry::Step { .. } => Ok(()),
+        }
+    }
+}
+
+fn deserialize_run<'de, D>(deserializer: D) -> std::result::Result<Vec<RunEntry>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(RunEntry),
+        Many(Vec<RunEntry>),
+    }
+    Ok(match serde::Deserialize::deserialize(deserializer)? {
+        OneOrMany::One(entry) => vec![entry],
+        OneOrMany::Many(entries) => entries,
+    })
+}
+
 impl Task {
     pub fn new(name: String, config_source: PathBuf) -> Task {
         Task {
@@ -75,7 +268,14 @@ impl Task {
         }
     }
     pub fn from_path(path: &Path) -> Result<Task> {
-        let info = file::read_to_string(path)?
+        let contents = file::read_to_string(path)?;
+        let usage = contents
+            .lines()
+            .filter_map(|line| regex!(r"^# ?USAGE (.*)$").captures(line))
+            .map(|captures| captures.extract())
+            .map(|(_, [spec])| spec)
+            .join("\n");
+        let info = contents
             .lines()
             .filter_map(|line| regex!(r"^# mise ([a-z]+=.+)$").captures(line))
             .map(|captures| captures.extract())
@@ -107,6 +307,44 @@ impl Task {
             outputs: p.parse_array("outputs")?.unwrap_or_default(),
             depends: p.parse_array("depends")?.unwrap_or_default(),
             dir: p.parse_str("dir")?,
+            translate_paths: p.parse_bool("translate_paths"),
+            service: p.parse_bool("service").unwrap_or_default(),
+            restart: p
+                .parse_str::<String>("restart")?
+                .map(|s| s.parse())
+                .transpose()?
+                .unwrap_or_default(),
+            ready: p.parse_str("ready")?,
+            output: p.parse_str("output")?,
+            usage,
+            extends: p.parse_str("extends")?,
+            schedule: p.parse_str("schedule")?,
+            container: p.parse_str("container")?,
+            matrix: info
+                .get("matrix")
+                .cloned()
+                .map(|m| m.try_into())
+                .transpose()?
+                .unwrap_or_default(),
+            confirm: p.parse_str("confirm")?,
+            timeout: p.parse_str("timeout")?,
+            retry: info
+                .get("retry")
+                .cloned()
+                .map(|m| m.try_into())
+                .transpose()?,
+            tools: info
+                .get("tools")
+                .cloned()
+                .map(|m| m.try_into())
+                .transpose()?
+                .unwrap_or_default(),
+            prompt: info
+                .get("prompt")
+                .cloned()
+                .map(|m| m.try_into())
+                .transpose()?
+                .unwrap_or_default(),
             env: p.parse_env("env")?.unwrap_or_default(),
             file: Some(path.to_path_buf()),
             ..Task::new(name_from_path(config_root, path)?, path.to_path_buf())
@@ -144,6 +382,129 @@ impl Task {
         self
     }
 
+    /// renames a task of a workspace member to `<member>:<name>`, it runs in the directory of the
+    /// member and its `depends` and `extends` on other tasks of the member are renamed the same way
+    pub fn in_member(mut self, member: &str, dir: &Path, local: &HashSet<String>) -> Task {
+        let prefixed = |name: &str| format!("{member}:{name}");
+        self.name = prefixed(&self.name);
+        self.aliases = self
+            .aliases
+            .iter()
+            .filter(|a| !a.is_empty())
+            .map(|a| prefixed(a))
+            .collect();
+        self.depends = self
+            .depends
+            .iter()
+            .map(|d| match local.contains(d) {
+                true => prefixed(d),
+                false => d.clone(),
+            })
+            .collect();
+        self.extends = self.extends.map(|e| match local.contains(&e) {
+            true => prefixed(&e),
+            false => e,
+        });
+        self.dir = Some(match self.dir {
+            Some(d) if d.is_relative() => dir.join(d),
+            Some(d) => d,
+            None => dir.to_path_buf(),
+        });
+        self
+    }
+
+    /// fills in what this task doesn't set from the task it extends: `env`, `prompt`, `tools`,
+    /// and `depends` are merged, the `run` steps of the base run before the task's own, and
+    /// everything else except `alias`, `hide`, and `schedule` is only taken from the base if the
+    /// task doesn't set it
+    pub fn extend(mut self, base: &Task) -> Task {
+        for (k, v) in &base.env {
+            self.env.entry(k.clone()).or_insert_with(|| v.clone());
+        }
+        self.depends = base
+            .depends
+            .iter()
+            .chain(self.depends.iter())
+            .unique()
+            .cloned()
+            .collect();
+        if self.run.is_empty() && self.file.is_none() {
+            self.file.clone_from(&base.file);
+            if self.args.is_empty() {
+                self.args.clone_from(&base.args);
+            }
+        }
+        if self.file.is_none() {
+            self.run = base.run.iter().chain(self.run.iter()).cloned().collect();
+        }
+        if self.description.is_empty() {
+            self.description.clone_from(&base.description);
+        }
+        if self.usage.is_empty() {
+            self.usage.clone_from(&base.usage);
+        }
+        if self.sources.is_empty() {
+            self.sources.clone_from(&base.sources);
+        }
+        if self.outputs.is_empty() {
+            self.outputs.clone_from(&base.outputs);
+        }
+        if self.restart == RestartPolicy::default() {
+            self.restart = base.restart;
+        }
+        self.dir = self.dir.or_else(|| base.dir.clone());
+        self.output = self.output.or_else(|| base.output.clone());
+        self.translate_paths = self.translate_paths.or(base.translate_paths);
+        self.ready = self.ready.or_else(|| base.ready.clone());
+        self.container = self.container.or_else(|| base.container.clone());
+        if self.matrix.is_empty() {
+            self.matrix.clone_from(&base.matrix);
+        }
+        self.confirm = self.confirm.or_else(|| base.confirm.clone());
+        self.timeout = self.timeout.or_else(|| base.timeout.clone());
+        self.retry = self.retry.or_else(|| base.retry.clone());
+        for (k, v) in &base.prompt {
+            self.prompt.entry(k.clone()).or_insert_with(|| v.clone());
+        }
+        for (k, v) in &base.tools {
+            self.tools.entry(k.clone()).or_insert_with(|| v.clone());
+        }
+        self.raw |= base.raw;
+        self.service |= base.service;
+        self
+    }
+
+    /// a task for every combination of the values in `matrix`, named like `test[python=3.12]`, or
+    /// the task itself if it has no matrix
+    pub fn expand_matrix(&self) -> Vec<Task> {
+        if self.matrix.is_empty() {
+            return vec![self.clone()];
+        }
+        self.matrix
+            .iter()
+            .map(|(k, values)| values.iter().map(move |v| (k.clone(), v.clone())))
+            .multi_cartesian_product()
+            .map(|combination| {
+                let combination: BTreeMap<String, String> = combination.into_iter().collect();
+                let label = combination
+                    .iter()
+                    .map(|(k, v)| format!("{k}={v}"))
+                    .join(",");
+                Task {
+                    name: format!("{}[{label}]", self.name),
+                    aliases: vec![],
+                    matrix: BTreeMap::new(),
+                    combination,
+                    ..self.clone()
+                }
+            })
+            .collect()
+    }
+
+    pub fn timeout(&self) -> Result<Option<Duration>> {
+        self.timeout.as_deref().map(parse_duration).transpose()
+    }
+
     pub fn prefix(&self) -> String {
         format!("[{}]", self.name)
     }
@@ -159,6 +520,10 @@ impl Task {
     }
 }
 
+fn parse_duration(s: &str) -> Result<Duration> {
+    humantime::parse_duration(s).map_err(|e| eyre!("invalid duration {s}: {e}"))
+}
+
 fn name_from_path(root: impl AsRef<Path>, path: impl AsRef<Path>) -> Result<String> {
     Ok(path
         .as_ref()
@@ -206,6 +571,41 @@ impl Ord for Task {
     }
 }
 
+/// replaces each task that `extends` another with the result of `Task::extend`, bases are resolved
+/// first so they can extend other tasks too
+pub fn resolve_extends(tasks: &mut BTreeMap<String, Task>) -> Result<()> {
+    fn resolve(
+        name: &str,
+        tasks: &mut BTreeMap<String, Task>,
+        stack: &mut Vec<String>,
+    ) -> Result<()> {
+        let Some(base) = tasks.get(name).and_then(|t| t.extends.clone()) else {
+            return Ok(());
+        };
+        if stack.iter().any(|n| n == name) {
+            bail!(
+                "task {name} extends itself: {} -> {name}",
+                stack.join(" -> ")
+            );
+        }
+        stack.push(name.to_string());
+        resolve(&base, tasks, stack)?;
+        stack.pop();
+        let base = tasks
+            .get(&base)
+            .cloned()
+            .ok_or_else(|| eyre!("task {name} extends {base} which does not exist"))?;
+        let mut task = tasks.remove(name).unwrap().extend(&base);
+        task.extends = None;
+        tasks.insert(name.to_string(), task);
+        Ok(())
+    }
+    for name in tasks.keys().cloned().collect_vec() {
+        resolve(&name, tasks, &mut vec![])?;
+    }
+    Ok(())
+}
+
 impl Hash for Task {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.name.hash(state);
@@ -224,7 +624,7 @@ impl Deps {
         let mut graph = DiGraph::new();
         let mut indexes = HashMap::new();
         let mut stack = vec![];
-        for t in tasks {
+        for t in tasks.iter().flat_map(|t| t.expand_matrix()) {
             stack.push(t.clone());
             indexes
                 .entry(t.name.clone())
@@ -234,14 +634,19 @@ impl Deps {
             let a_idx = *indexes
                 .entry(a.name.clone())
                 .or_insert_with(|| graph.add_node(a.clone()));
-            for b in a.resolve_depends(config)? {
+            // a task that depends on a matrix task waits for every combination of it
+            for b in a
+                .resolve_depends(config)?
+                .into_iter()
+                .flat_map(|b| b.expand_matrix())
+            {
                 let b_idx = *indexes
                     .entry(b.name.clone())
                     .or_insert_with(|| graph.add_node(b.clone()));
                 if !graph.contains_edge(a_idx, b_idx) {
                     graph.add_edge(a_idx, b_idx, ());
                 }
-                stack.push(b.clone());
+                stack.push(b);
             }
         }
         let (tx, _) = mpsc::channel();
@@ -291,6 +696,32 @@ impl Deps {
         }
     }
 
+    /// removes a failed task along with the tasks that depend on it, directly or not, so those
+    /// are never sent, returns the dependents
+    pub fn remove_with_dependents(&mut self, task: &Task) -> Vec<Task> {
+        let Some(idx) = self
+            .graph
+            .node_indices()
+            .find(|&idx| &self.graph[idx] == task)
+        else {
+            return vec![];
+        };
+        let reversed = petgraph::visit::Reversed(&self.graph);
+        let mut dfs = Dfs::new(reversed, idx);
+        let mut removed = vec![];
+        while let Some(i) = dfs.next(reversed) {
+            removed.push(self.graph[i].clone());
+        }
+        // node indexes change as nodes are removed
+        for t in &removed {
+            if let Some(i) = self.graph.node_indices().find(|&i| &self.graph[i] == t) {
+                self.graph.remove_node(i);
+            }
+        }
+        self.emit_leaves();
+        removed.into_iter().filter(|t| t != task).collect()
+    }
+
     pub fn all(&self) -> impl Iterator<Item = &Task> {
         self.graph.node_indices().map(|idx| &self.graph[idx])
     }
@@ -370,14 +801,16 @@ where
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
     use std::path::Path;
+    use std::time::Duration;
 
     use pretty_assertions::assert_eq;
 
-    use crate::task::Task;
+    use crate::task::{RestartPolicy, RunCondition, RunEntry, Task};
     use crate::test::reset;
 
-    use super::{config_root, name_from_path};
+    use super::{config_root, name_from_path, resolve_extends};
 
     #[test]
     fn test_from_path() {
@@ -391,6 +824,148 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_run_entries() {
+        let t: Task = toml::from_str(
+            r#"
+            run = ["build", { task = "deploy", if = "success" }, { run = "notify", if = "failure" }]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(t.run[0], RunEntry::Script("build".into()));
+        assert_eq!(
+            t.run[1],
+            RunEntry::Step {
+                run: None,
+                task: Some("deploy".into()),
+                condition: RunCondition::Success,
+            }
+        );
+        assert_eq!(t.run[2].condition(), RunCondition::Failure);
+        assert!(t.run[1].should_run(false));
+        assert!(!t.run[1].should_run(true));
+        assert!(t.run[2].should_run(true));
+
+        let t: Task = toml::from_str(r#"run = "build""#).unwrap();
+        assert_eq!(t.run, vec![RunEntry::Script("build".into())]);
+    }
+
+    #[test]
+    fn test_extends() {
+        let tasks: BTreeMap<String, Task> = toml::from_str(
+            r#"
+            [setup]
+            run = "npm ci"
+            env = { CI = "1", LEVEL = "base" }
+            depends = ["tools"]
+            hide = true
+
+            [lint-base]
+            extends = "setup"
+            sources = ["src/**"]
+
+            [lint]
+            extends = "lint-base"
+            run = "npm run lint"
+            env = { LEVEL = "lint" }
+            depends = ["codegen"]
+            "#,
+        )
+        .unwrap();
+        let mut tasks = tasks
+            .into_iter()
+            .map(|(name, t)| (name.clone(), Task { name, ..t }))
+            .collect();
+        resolve_extends(&mut tasks).unwrap();
+        let lint = &tasks["lint"];
+        assert_eq!(
+            lint.run,
+            vec![
+                RunEntry::Script("npm ci".into()),
+                RunEntry::Script("npm run lint".into())
+            ]
+        );
+        assert_eq!(lint.depends, vec!["tools", "codegen"]);
+        assert_eq!(lint.env["CI"].0, either::Left("1".into()));
+        assert_eq!(lint.env["LEVEL"].0, either::Left("lint".into()));
+        assert_eq!(lint.sources, vec!["src/**"]);
+        assert!(!lint.hide);
+        assert_eq!(lint.extends, None);
+
+        tasks.get_mut("setup").unwrap().extends = Some("lint".into());
+        tasks.get_mut("lint").unwrap().extends = Some("setup".into());
+        assert!(resolve_extends(&mut tasks).is_err());
+    }
+
+    #[test]
+    fn test_timeout_retry() {
+        let t: Task = toml::from_str(
+            r#"
+            timeout = "10m"
+            retry = { count = 3, delay = "5s" }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(t.timeout().unwrap(), Some(Duration::from_secs(600)));
+        let retry = t.retry.unwrap();
+        assert_eq!(retry.count, 3);
+        assert_eq!(retry.delay().unwrap(), Duration::from_secs(5));
+
+        let t: Task = toml::from_str(r#"timeout = "soon""#).unwrap();
+        assert!(t.timeout().is_err());
+    }
+
+    #[test]
+    fn test_expand_matrix() {
+        let t: Task = toml::from_str(
+            r#"
+            alias = "t"
+            matrix = { python = ["3.11", "3.12"], db = ["sqlite", "postgres"] }
+            "#,
+        )
+        .unwrap();
+        let t = Task {
+            name: "test".into(),
+            ..t
+        };
+        let tasks = t.expand_matrix();
+        assert_eq!(
+            tasks.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(),
+            vec![
+                "test[db=sqlite,python=3.11]",
+                "test[db=sqlite,python=3.12]",
+                "test[db=postgres,python=3.11]",
+                "test[db=postgres,python=3.12]",
+            ]
+        );
+        assert_eq!(tasks[1].combination["python"], "3.12");
+        assert!(tasks[1].matrix.is_empty());
+        assert!(tasks[1].aliases.is_empty());
+
+        let t = Task::new("build".into(), "mise.toml".into());
+        assert_eq!(t.expand_matrix(), vec![t]);
+    }
+
+    #[test]
+    fn test_service() {
+        let t: Task = toml::from_str(
+            r#"
+            run = "npm run dev"
+            service = true
+            ready = "curl -sf http://localhost:3000"
+            "#,
+        )
+        .unwrap();
+        assert!(t.service);
+        assert_eq!(t.restart, RestartPolicy::OnFailure);
+        assert!(t.restart.should_restart(true));
+        assert!(!t.restart.should_restart(false));
+
+        let t: Task = toml::from_str(r#"restart = "always""#).unwrap();
+        assert_eq!(t.restart, RestartPolicy::Always);
+        assert!(t.restart.should_restart(false));
+    }
+
     #[test]
     fn test_name_from_path() {
         reset();
@@ -0,0 +1,283 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use eyre::Result;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use globwalk::GlobWalkerBuilder;
+use itertools::Itertools;
+
+use crate::cli::version::{ARCH, OS};
+use crate::config::Settings;
+use crate::hash::{file_hash_sha256, hash_sha256_to_str, hash_to_str};
+use crate::http::HTTP;
+use crate::task::Task;
+use crate::{binary_cache, dirs, env, file, http};
+
+/// where the key of the last successful run of a task is stored
+fn state_path(task: &Task) -> PathBuf {
+    dirs::CACHE
+        .join("tasks")
+        .join(hash_to_str(&(&task.config_source, &task.name)))
+}
+
+/// true if the task declares `sources` and `outputs`, its outputs exist, and neither its sources,
+/// its definition, nor the versions of its tools changed since it last ran successfully
+pub fn is_fresh(task: &Task, root: &Path, tools: &BTreeMap<String, String>) -> Result<bool> {
+    if task.sources.is_empty() || task.outputs.is_empty() {
+        return Ok(false);
+    }
+    let Ok(last) = file::read_to_string(state_path(task)) else {
+        return Ok(false);
+    };
+    if !outputs_exist(task, root)? {
+        return Ok(false);
+    }
+    Ok(last.trim() == key(task, root, tools)?)
+}
+
+/// records the sources of a task after it ran successfully
+pub fn save(task: &Task, root: &Path, tools: &BTreeMap<String, String>) -> Result<()> {
+    if task.sources.is_empty() || task.outputs.is_empty() {
+        return Ok(());
+    }
+    write_state(task, &key(task, root, tools)?)
+}
+
+fn write_state(task: &Task, key: &str) -> Result<()> {
+    let path = state_path(task);
+    file::create_dir_all(path.parent().unwrap())?;
+    file::write(path, key)
+}
+
+/// sha256 of the task's definition, the versions its tools resolved to, and of the path and
+/// contents of each of its sources
+pub fn key(task: &Task, root: &Path, tools: &BTreeMap<String, String>) -> Result<String> {
+    let env = task
+        .env
+        .iter()
+        .sorted_by_key(|(k, _)| *k)
+        .map(|(k, v)| format!("{k}={:?}", v.0))
+        .join("\n");
+    let mut lines = vec![
+        format!("run={:?}", task.run),
+        format!("args={:?}", task.args),
+        format!("env={env}"),
+        format!("combination={:?}", task.combination),
+        format!("tools={tools:?}"),
+    ];
+    if let Some(f) = &task.file {
+        lines.push(format!("file={}", file_hash_sha256(f)?));
+    }
+    for path in files(root, &task.sources)? {
+        let rel = path.strip_prefix(root).unwrap_or(&path);
+        lines.push(format!("{}={}", rel.display(), file_hash_sha256(&path)?));
+    }
+    Ok(hash_sha256_to_str(&lines.join("\n")))
+}
+
+fn url(base: &str, key: &str) -> String {
+    format!(
+        "{}/{key}-{}-{}.tar.gz",
+        base.trim_end_matches('/'),
+        *OS,
+        *ARCH
+    )
+}
+
+/// extracts the outputs of a task from the `task_remote_cache`, returns false if they aren't in
+/// the cache
+pub fn restore(task: &Task, root: &Path, tools: &BTreeMap<String, String>) -> Result<bool> {
+    let settings = Settings::get();
+    let Some(base) = &settings.task_remote_cache else {
+        return Ok(false);
+    };
+    if settings.offline || task.sources.is_empty() || task.outputs.is_empty() {
+        return Ok(false);
+    }
+    let key = key(task, root, tools)?;
+    let url = url(base, &key);
+    let tmp = tempfile::tempdir()?;
+    let tarball = tmp.path().join("outputs.tar.gz");
+    let found = if url.starts_with("s3://") {
+        // the aws cli doesn't have a separate exit code for missing objects
+        match binary_cache::aws(None)?
+            .arg("cp")
+            .arg(&url)
+            .arg(&tarball)
+            .execute()
+        {
+            Ok(()) => true,
+            Err(err) => {
+                debug!("{}: failed to download {url}: {err:#}", task.name);
+                false
+            }
+        }
+    } else {
+        let headers = binary_cache::headers(env::MISE_TASK_REMOTE_CACHE_TOKEN.as_ref());
+        match HTTP.download_file_with_headers(&url, &tarball, None, &headers) {
+            Ok(()) => true,
+            Err(err) if http::error_code(&err) == Some(404) => false,
+            Err(err) => return Err(err),
+        }
+    };
+    if !found {
+        debug!("{} is not in the task cache", task.name);
+        return Ok(false);
+    }
+    file::untar(&tarball, root)?;
+    write_state(task, &key)?;
+    debug!("{}: restored outputs from {url}", task.name);
+    Ok(true)
+}
+
+/// uploads the outputs of a task to the `task_remote_cache` if `task_remote_cache_upload` is
+/// enabled
+pub fn upload(task: &Task, root: &Path, tools: &BTreeMap<String, String>) -> Result<()> {
+    let settings = Settings::get();
+    let Some(base) = &settings.task_remote_cache else {
+        return Ok(());
+    };
+    if !settings.task_remote_cache_upload || settings.offline {
+        return Ok(());
+    }
+    if task.sources.is_empty() || task.outputs.is_empty() {
+        return Ok(());
+    }
+    let url = url(base, &key(task, root, tools)?);
+    let tmp = tempfile::tempdir()?;
+    let tarball = tmp.path().join("outputs.tar.gz");
+    tar_gz(root, &files(root, &task.outputs)?, &tarball)?;
+    if url.starts_with("s3://") {
+        binary_cache::aws(None)?
+            .arg("cp")
+            .arg(&tarball)
+            .arg(&url)
+            .execute()?;
+    } else {
+        let headers = binary_cache::headers(env::MISE_TASK_REMOTE_CACHE_TOKEN.as_ref());
+        HTTP.upload_file(&url, &tarball, &headers)?;
+    }
+    debug!("{}: uploaded outputs to {url}", task.name);
+    Ok(())
+}
+
+/// outputs are stored relative to the task's directory
+fn tar_gz(root: &Path, files: &[PathBuf], archive: &Path) -> Result<()> {
+    let gz = GzEncoder::new(File::create(archive)?, Compression::default());
+    let mut tar = tar::Builder::new(gz);
+    for f in files {
+        let Ok(rel) = f.strip_prefix(root) else {
+            warn!(
+                "not caching {}, it is outside of {}",
+                f.display(),
+                root.display()
+            );
+            continue;
+        };
+        tar.append_path_with_name(f, rel)?;
+    }
+    tar.into_inner()?.finish()?;
+    Ok(())
+}
+
+fn outputs_exist(task: &Task, root: &Path) -> Result<bool> {
+    for output in &task.outputs {
+        if files(root, std::slice::from_ref(output))?.is_empty() {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// files matching `patterns_or_paths`, sorted so the key doesn't depend on the walk order
+pub fn files(root: &Path, patterns_or_paths: &[String]) -> Result<Vec<PathBuf>> {
+    let (patterns, paths): (Vec<&String>, Vec<&String>) =
+        patterns_or_paths.iter().partition(|p| is_glob_pattern(p));
+    let mut files = paths
+        .into_iter()
+        .map(|p| root.join(p))
+        .filter(|p| p.is_file())
+        .collect_vec();
+    if !patterns.is_empty() {
+        let walker = GlobWalkerBuilder::from_patterns(root, &patterns)
+            .follow_links(true)
+            .build()?;
+        files.extend(
+            walker
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_type().is_file())
+                .map(|e| e.path().to_owned()),
+        );
+    }
+    Ok(files.into_iter().sorted().dedup().collect())
+}
+
+fn is_glob_pattern(path: &str) -> bool {
+    // This is the character set used for glob
+    // detection by globwalk
+    let glob_chars = ['*', '{', '}'];
+
+    path.chars().any(|c| glob_chars.contains(&c))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::{assert_eq, assert_ne};
+
+    use super::*;
+
+    #[test]
+    fn test_key() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        file::write(root.join("a.txt"), "a").unwrap();
+        file::create_dir_all(root.join("src")).unwrap();
+        file::write(root.join("src/b.rs"), "b").unwrap();
+        let mut task = Task::new("build".into(), PathBuf::from("mise.toml"));
+        task.sources = vec!["a.txt".into(), "src/*.rs".into()];
+        task.outputs = vec!["out".into()];
+
+        let mut tools = BTreeMap::from([("node".to_string(), "20.11.0".to_string())]);
+        let k1 = key(&task, root, &tools).unwrap();
+        assert_eq!(key(&task, root, &tools).unwrap(), k1);
+        assert!(!outputs_exist(&task, root).unwrap());
+
+        file::write(root.join("src/b.rs"), "c").unwrap();
+        let k2 = key(&task, root, &tools).unwrap();
+        assert_ne!(k1, k2);
+
+        task.args = vec!["--release".into()];
+        let k3 = key(&task, root, &tools).unwrap();
+        assert_ne!(k3, k2);
+
+        tools.insert("node".into(), "22.1.0".into());
+        let k4 = key(&task, root, &tools).unwrap();
+        assert_ne!(k4, k3);
+
+        task.combination = BTreeMap::from([("os".to_string(), "linux".to_string())]);
+        assert_ne!(key(&task, root, &tools).unwrap(), k4);
+        assert_eq!(
+            files(root, &task.sources).unwrap(),
+            vec![root.join("a.txt"), root.join("src/b.rs")]
+        );
+    }
+
+    #[test]
+    fn test_tar_gz() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("project");
+        file::create_dir_all(root.join("dist")).unwrap();
+        file::write(root.join("dist/app.js"), "app").unwrap();
+        let archive = dir.path().join("outputs.tar.gz");
+        tar_gz(&root, &[root.join("dist/app.js")], &archive).unwrap();
+        let restored = dir.path().join("restored");
+        file::create_dir_all(&restored).unwrap();
+        file::untar(&archive, &restored).unwrap();
+        assert_eq!(
+            file::read_to_string(restored.join("dist/app.js")).unwrap(),
+            "app"
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use eyre::{bail, Result};
+use itertools::Itertools;
+
+use crate::config::Settings;
+use crate::{env, file};
+
+/// docker or podman from `task_container_engine`, otherwise whichever is on PATH
+pub fn engine() -> Result<String> {
+    if let Some(engine) = &Settings::get().task_container_engine {
+        return Ok(engine.clone());
+    }
+    for engine in ["docker", "podman"] {
+        if file::which(engine).is_some() {
+            return Ok(engine.to_string());
+        }
+    }
+    bail!("docker or podman is required to run tasks with `container`")
+}
+
+/// arguments of `docker run` that run `command` in `image` with `mounts` mounted at the same paths
+/// and `dir` as the working directory
+///
+/// env vars set by mise are passed by name so their values come from the env of the engine
+/// and don't show up in its arguments, the host's own env and PATH stay on the host
+pub fn args(
+    image: &str,
+    mounts: &[PathBuf],
+    dir: &Path,
+    env: &BTreeMap<String, String>,
+    command: &[String],
+) -> Vec<String> {
+    let mut args = vec!["run".to_string(), "--rm".to_string(), "-i".to_string()];
+    for mount in mounts.iter().unique() {
+        let mount = mount.to_string_lossy();
+        args.push("-v".into());
+        args.push(format!("{mount}:{mount}"));
+    }
+    args.push("-w".into());
+    args.push(dir.to_string_lossy().to_string());
+    for k in passthrough(env) {
+        args.push("-e".into());
+        args.push(k.clone());
+    }
+    args.push(image.to_string());
+    args.extend(command.iter().cloned());
+    args
+}
+
+fn passthrough(env: &BTreeMap<String, String>) -> impl Iterator<Item = &String> {
+    env.iter()
+        .filter(|(k, v)| *k != "PATH" && env::PRISTINE_ENV.get(*k) != Some(v))
+        .map(|(k, _)| k)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_args() {
+        let env = BTreeMap::from([
+            ("PATH".to_string(), "/mise/bin:/usr/bin".to_string()),
+            ("NODE_ENV".to_string(), "test".to_string()),
+        ]);
+        let args = args(
+            "node:20",
+            &[PathBuf::from("/src/app"), PathBuf::from("/src/app")],
+            Path::new("/src/app/web"),
+            &env,
+            &["sh".into(), "-c".into(), "npm test".into()],
+        );
+        assert_eq!(
+            args.join(" "),
+            "run --rm -i -v /src/app:/src/app -w /src/app/web -e NODE_ENV node:20 sh -c npm test"
+        );
+    }
+}
@@ -0,0 +1,184 @@
+use std::collections::BTreeMap;
+
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use eyre::{eyre, Result};
+use heck::ToSnakeCase;
+use usage::{SpecArg, SpecCommand, SpecFlag};
+
+use crate::task::Task;
+use crate::tera::{get_tera, BASE_CONTEXT};
+
+/// the args and flags a task declares in `usage`, None if it doesn't declare any
+pub fn spec(task: &Task) -> Result<Option<SpecCommand>> {
+    if task.usage.trim().is_empty() {
+        return Ok(None);
+    }
+    let spec = usage::Spec::parse_spec(&task.usage)
+        .map_err(|err| eyre!("invalid usage spec for task {}: {err}", task.name))?;
+    Ok(Some(spec.cmd))
+}
+
+/// prints the help of a task that declares `usage`, returns false if it doesn't declare any
+pub fn print_help(task: &Task) -> Result<bool> {
+    match spec(task)? {
+        Some(spec) => {
+            command(task, &spec).print_long_help()?;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// parses the args the task was run with, keyed by the snake_case name of each arg and flag
+///
+/// exits with the help or an error like any other command if the args don't match the spec
+pub fn values(task: &Task) -> Result<BTreeMap<String, String>> {
+    let Some(spec) = spec(task)? else {
+        return Ok(BTreeMap::new());
+    };
+    let matches = command(task, &spec)
+        .try_get_matches_from(&task.args)
+        .unwrap_or_else(|err| err.exit());
+    let mut values = BTreeMap::new();
+    for arg in &spec.args {
+        if let Some(v) = arg_value(&matches, &arg.name, arg.var) {
+            values.insert(arg.name.to_snake_case(), v);
+        }
+    }
+    for flag in &spec.flags {
+        let v = match &flag.arg {
+            Some(arg) => arg_value(&matches, &flag.name, arg.var || flag.var),
+            None if flag.count => Some(matches.get_count(&flag.name).to_string()),
+            None => Some(matches.get_flag(&flag.name).to_string()),
+        };
+        if let Some(v) = v {
+            values.insert(flag.name.to_snake_case(), v);
+        }
+    }
+    Ok(values)
+}
+
+/// `usage_<name>` env vars for each arg and flag, the same names `usage` itself uses
+pub fn env(task: &Task) -> Result<BTreeMap<String, String>> {
+    Ok(values(task)?
+        .into_iter()
+        .map(|(k, v)| (format!("usage_{k}"), v))
+        .collect())
+}
+
+/// renders `{{usage.<name>}}` in a run script of a task that declares `usage`
+pub fn render(task: &Task, script: &str) -> Result<String> {
+    if task.usage.trim().is_empty() {
+        return Ok(script.to_string());
+    }
+    let mut tera = get_tera(task.dir.as_deref());
+    let mut ctx = BASE_CONTEXT.clone();
+    ctx.insert("usage", &values(task)?);
+    Ok(tera.render_str(script, &ctx)?)
+}
+
+fn arg_value(matches: &ArgMatches, name: &str, var: bool) -> Option<String> {
+    let mut values = matches.get_many::<String>(name)?.cloned();
+    match var {
+        true => Some(shell_words::join(values)),
+        false => values.next(),
+    }
+}
+
+fn command(task: &Task, spec: &SpecCommand) -> Command {
+    let mut cmd = Command::new(task.name.clone())
+        .bin_name(format!("mise run {}", task.name))
+        .no_binary_name(true)
+        .about(task.description.clone());
+    for arg in &spec.args {
+        cmd = cmd.arg(positional(arg));
+    }
+    for flag in &spec.flags {
+        cmd = cmd.arg(option(flag));
+    }
+    cmd
+}
+
+fn positional(spec: &SpecArg) -> Arg {
+    let mut arg = Arg::new(spec.name.clone())
+        .value_name(spec.name.clone())
+        .required(spec.required && spec.default.is_none())
+        .hide(spec.hide);
+    if let Some(help) = &spec.help {
+        arg = arg.help(help.clone());
+    }
+    if spec.var {
+        arg = arg.action(ArgAction::Append).num_args(1..);
+    }
+    if let Some(default) = &spec.default {
+        arg = arg.default_value(default.clone());
+    }
+    arg
+}
+
+fn option(spec: &SpecFlag) -> Arg {
+    let mut arg = Arg::new(spec.name.clone())
+        .required(spec.required)
+        .hide(spec.hide);
+    if let Some((short, aliases)) = spec.short.split_first() {
+        arg = arg.short(*short).short_aliases(aliases.to_vec());
+    }
+    if let Some((long, aliases)) = spec.long.split_first() {
+        arg = arg.long(long.clone()).aliases(aliases.to_vec());
+    }
+    if let Some(help) = &spec.help {
+        arg = arg.help(help.clone());
+    }
+    arg = match &spec.arg {
+        Some(value) if value.var || spec.var => {
+            arg.value_name(value.name.clone()).action(ArgAction::Append)
+        }
+        Some(value) => arg.value_name(value.name.clone()).action(ArgAction::Set),
+        None if spec.count => arg.action(ArgAction::Count),
+        None => arg.action(ArgAction::SetTrue),
+    };
+    if let Some(default) = &spec.default {
+        arg = arg.default_value(default.clone());
+    }
+    arg
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_values() {
+        let mut task = Task::new("deploy".into(), PathBuf::from("mise.toml"));
+        task.usage = r#"
+            arg "<env>" help="where to deploy"
+            arg "[files]..."
+            flag "-f --force"
+            flag "-v --verbose" count=true
+            flag "--region <region>" default="us-east-1"
+        "#
+        .into();
+        task.args = vec![
+            "prod".into(),
+            "a.txt".into(),
+            "b c.txt".into(),
+            "-vv".into(),
+            "--force".into(),
+        ];
+        let values = values(&task).unwrap();
+        assert_eq!(values["env"], "prod");
+        assert_eq!(values["files"], "a.txt 'b c.txt'");
+        assert_eq!(values["force"], "true");
+        assert_eq!(values["verbose"], "2");
+        assert_eq!(values["region"], "us-east-1");
+        assert_eq!(
+            render(&task, "deploy {{usage.env}} {{usage.region}}").unwrap(),
+            "deploy prod us-east-1"
+        );
+        assert_eq!(env(&task).unwrap()["usage_env"], "prod");
+    }
+}
@@ -0,0 +1,121 @@
+use std::path::{Path, PathBuf};
+
+use eyre::Result;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+
+use crate::file;
+use crate::toolset::{ToolVersion, ToolVersionOptions};
+
+/// The `bins` and `exclude_bins` tool options which limit the executables of a tool that are
+/// shimmed and put on PATH, e.g.: terraform = { version = "1", exclude_bins = ["bundled-*"] }
+///
+/// Both are lists of globs matched against the name of the executable.
+#[derive(Debug)]
+pub struct BinFilter {
+    /// the options the filter was built from, used to tell if the PATH directory is stale
+    spec: String,
+    include: Option<GlobSet>,
+    exclude: GlobSet,
+}
+
+impl BinFilter {
+    pub fn from_options(opts: &ToolVersionOptions) -> Result<Option<Self>> {
+        let include = opts.get("bins");
+        let exclude = opts.get("exclude_bins");
+        if include.is_none() && exclude.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            spec: format!(
+                "bins={}\nexclude_bins={}\n",
+                include.map(|s| s.as_str()).unwrap_or_default(),
+                exclude.map(|s| s.as_str()).unwrap_or_default()
+            ),
+            include: include.map(|s| globs(s)).transpose()?,
+            exclude: globs(exclude.map(|s| s.as_str()).unwrap_or_default())?,
+        }))
+    }
+
+    pub fn for_tv(tv: &ToolVersion) -> Option<Self> {
+        Self::from_options(&tv.request.options()).unwrap_or_else(|err| {
+            warn!("{tv}: invalid bins or exclude_bins: {err:#}");
+            None
+        })
+    }
+
+    pub fn matches(&self, bin: &str) -> bool {
+        // on windows "terraform" should match "terraform.exe"
+        let stem = Path::new(bin)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let is_match = |set: &GlobSet| set.is_match(bin) || set.is_match(&stem);
+        self.include.as_ref().map_or(true, is_match) && !is_match(&self.exclude)
+    }
+
+    /// a directory with symlinks to the executables that pass the filter which is put on PATH
+    /// instead of the bin paths of the tool, it is rebuilt if the options or the install change
+    pub fn bin_dir(&self, tv: &ToolVersion, bin_paths: &[PathBuf]) -> Result<PathBuf> {
+        let dir = tv.cache_path().join("filtered-bins");
+        let marker = dir.join(".spec");
+        let fresh = file::read_to_string(&marker).is_ok_and(|spec| spec == self.spec)
+            && newer_than(&marker, &tv.install_path());
+        if fresh {
+            return Ok(dir);
+        }
+        trace!("building filtered bin dir for {tv}");
+        file::remove_all(&dir)?;
+        file::create_dir_all(&dir)?;
+        for bin_path in bin_paths.iter().filter(|p| p.is_dir()) {
+            for bin in file::ls(bin_path)? {
+                let name = bin.file_name().unwrap().to_string_lossy().to_string();
+                let link = dir.join(&name);
+                if file::is_executable(&bin) && self.matches(&name) && !link.exists() {
+                    file::make_symlink(&bin, &link)?;
+                }
+            }
+        }
+        file::write(&marker, &self.spec)?;
+        Ok(dir)
+    }
+}
+
+fn globs(s: &str) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in s.split(',').map(|p| p.trim()).filter(|p| !p.is_empty()) {
+        builder.add(Glob::new(pattern)?);
+    }
+    Ok(builder.build()?)
+}
+
+fn newer_than(a: &Path, b: &Path) -> bool {
+    let modified = |p: &Path| p.metadata().and_then(|m| m.modified()).ok();
+    match (modified(a), modified(b)) {
+        (Some(a), Some(b)) => a >= b,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches() {
+        let opts = [("exclude_bins".to_string(), "bundled-*".to_string())].into();
+        let filter = BinFilter::from_options(&opts).unwrap().unwrap();
+        assert!(filter.matches("terraform"));
+        assert!(!filter.matches("bundled-helper"));
+
+        let opts = [("bins".to_string(), "terraform,tf*".to_string())].into();
+        let filter = BinFilter::from_options(&opts).unwrap().unwrap();
+        assert!(filter.matches("terraform"));
+        assert!(filter.matches("terraform.exe"));
+        assert!(filter.matches("tflint"));
+        assert!(!filter.matches("helper"));
+
+        assert!(BinFilter::from_options(&Default::default())
+            .unwrap()
+            .is_none());
+    }
+}
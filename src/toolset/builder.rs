@@ -36,6 +36,7 @@ impl ToolsetBuilder {
         };
         self.load_config_files(config, &mut toolset)?;
         self.load_runtime_env(&mut toolset, env::vars().collect())?;
+        self.load_tool_version_env(&mut toolset, env::vars().collect())?;
         self.load_runtime_args(&mut toolset)?;
         let start_ms = std::time::Instant::now();
         if let Err(err) = toolset.resolve() {
@@ -84,6 +85,34 @@ impl ToolsetBuilder {
         Ok(())
     }
 
+    /// MISE_TOOL_VERSION_<TOOL> overrides MISE_<TOOL>_VERSION and config files for one command,
+    /// e.g.: `MISE_TOOL_VERSION_NODE=20.11.0 node -v`. The tool is matched against the configured
+    /// tools with non-alphanumeric characters replaced by `_` so `cargo:eza` is `CARGO_EZA`.
+    fn load_tool_version_env(
+        &self,
+        ts: &mut Toolset,
+        env: BTreeMap<String, String>,
+    ) -> eyre::Result<()> {
+        for (k, v) in env {
+            let Some(name) = k.strip_prefix("MISE_TOOL_VERSION_") else {
+                continue;
+            };
+            let fa = ts
+                .versions
+                .keys()
+                .find(|fa| env_name(&fa.id) == name || env_name(&fa.name) == name)
+                .cloned()
+                .unwrap_or_else(|| name.to_lowercase().as_str().into());
+            let source = ToolSource::Environment(k.clone(), v.clone());
+            let mut env_ts = Toolset::new(source);
+            for v in v.split_whitespace() {
+                env_ts.add_version(ToolRequest::new(fa.clone(), v)?);
+            }
+            ts.merge(env_ts);
+        }
+        Ok(())
+    }
+
     fn load_runtime_args(&self, ts: &mut Toolset) -> eyre::Result<()> {
         for (_, args) in self
             .args
@@ -116,3 +145,12 @@ impl ToolsetBuilder {
         Ok(())
     }
 }
+
+fn env_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c.to_ascii_uppercase(),
+            false => '_',
+        })
+        .collect()
+}
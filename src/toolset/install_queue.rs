@@ -0,0 +1,203 @@
+use std::collections::HashSet;
+use std::sync::{Condvar, Mutex};
+
+/// Queue of tools to install that hands a tool to a worker as soon as the tools it depends on are
+/// installed, so independent tools are installed concurrently. Tools whose dependencies failed to
+/// install are skipped.
+#[derive(Debug)]
+pub struct InstallQueue<T> {
+    state: Mutex<State<T>>,
+    ready: Condvar,
+}
+
+#[derive(Debug)]
+struct State<T> {
+    pending: Vec<Node<T>>,
+    running: HashSet<String>,
+    installed: HashSet<String>,
+    failed: HashSet<String>,
+    skipped: Vec<(String, String)>,
+}
+
+#[derive(Debug)]
+struct Node<T> {
+    id: String,
+    item: T,
+    deps: HashSet<String>,
+}
+
+impl<T> InstallQueue<T> {
+    /// `nodes` are (id, item, ids of dependencies), dependencies not in the queue are ignored
+    pub fn new(nodes: Vec<(String, T, Vec<String>)>) -> Self {
+        let ids = nodes
+            .iter()
+            .map(|(id, _, _)| id.clone())
+            .collect::<HashSet<_>>();
+        let pending = nodes
+            .into_iter()
+            .map(|(id, item, deps)| Node {
+                deps: deps
+                    .into_iter()
+                    .filter(|d| ids.contains(d) && *d != id)
+                    .collect(),
+                id,
+                item,
+            })
+            .collect();
+        Self {
+            state: Mutex::new(State {
+                pending,
+                running: HashSet::new(),
+                installed: HashSet::new(),
+                failed: HashSet::new(),
+                skipped: vec![],
+            }),
+            ready: Condvar::new(),
+        }
+    }
+
+    /// blocks until a tool is ready to install, returns None once every tool was handed out
+    pub fn next(&self) -> Option<(String, T)> {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            state.skip_failed();
+            if state.pending.is_empty() {
+                self.ready.notify_all();
+                return None;
+            }
+            let idx = match state
+                .pending
+                .iter()
+                .position(|n| n.deps.is_subset(&state.installed))
+            {
+                Some(idx) => Some(idx),
+                // a dependency cycle, install in the original order instead of waiting forever
+                None if state.running.is_empty() => Some(0),
+                None => None,
+            };
+            if let Some(idx) = idx {
+                let node = state.pending.remove(idx);
+                state.running.insert(node.id.clone());
+                return Some((node.id, node.item));
+            }
+            state = self.ready.wait(state).unwrap();
+        }
+    }
+
+    /// marks a tool from `next` as done so the tools that depend on it can be installed
+    pub fn finish(&self, id: &str, ok: bool) {
+        let mut state = self.state.lock().unwrap();
+        state.running.remove(id);
+        match ok {
+            true => state.installed.insert(id.to_string()),
+            false => state.failed.insert(id.to_string()),
+        };
+        self.ready.notify_all();
+    }
+
+    /// number of tools that are waiting for a dependency or a worker
+    pub fn pending(&self) -> usize {
+        self.state.lock().unwrap().pending.len()
+    }
+
+    /// tools that were skipped and the dependency that failed to install
+    pub fn skipped(&self) -> Vec<(String, String)> {
+        self.state.lock().unwrap().skipped.clone()
+    }
+}
+
+impl<T> State<T> {
+    fn skip_failed(&mut self) {
+        while let Some(idx) = self
+            .pending
+            .iter()
+            .position(|n| !n.deps.is_disjoint(&self.failed))
+        {
+            let node = self.pending.remove(idx);
+            let dep = node.deps.intersection(&self.failed).next().unwrap().clone();
+            self.failed.insert(node.id.clone());
+            self.skipped.push((node.id, dep));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn node(id: &str, deps: &[&str]) -> (String, (), Vec<String>) {
+        let deps = deps.iter().map(|d| d.to_string()).collect();
+        (id.to_string(), (), deps)
+    }
+
+    #[test]
+    fn test_install_queue_order() {
+        let queue = InstallQueue::new(vec![
+            node("npm:prettier", &["node"]),
+            node("node", &[]),
+            node("jq", &[]),
+        ]);
+        let (id, _) = queue.next().unwrap();
+        assert_eq!(id, "node");
+        // prettier waits for node, jq doesn't
+        let (id, _) = queue.next().unwrap();
+        assert_eq!(id, "jq");
+        assert_eq!(queue.pending(), 1);
+        queue.finish("node", true);
+        let (id, _) = queue.next().unwrap();
+        assert_eq!(id, "npm:prettier");
+        queue.finish("npm:prettier", true);
+        queue.finish("jq", true);
+        assert!(queue.next().is_none());
+    }
+
+    #[test]
+    fn test_install_queue_failed_dependency() {
+        let queue = InstallQueue::new(vec![
+            node("node", &[]),
+            node("npm:prettier", &["node"]),
+            node("npm:eslint", &["node"]),
+        ]);
+        let (id, _) = queue.next().unwrap();
+        queue.finish(&id, false);
+        assert!(queue.next().is_none());
+        assert_eq!(
+            queue.skipped(),
+            vec![
+                ("npm:prettier".to_string(), "node".to_string()),
+                ("npm:eslint".to_string(), "node".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_install_queue_workers() {
+        let queue = InstallQueue::new(vec![
+            node("c", &["b"]),
+            node("b", &["a"]),
+            node("a", &[]),
+            node("cycle1", &["cycle2"]),
+            node("cycle2", &["cycle1"]),
+        ]);
+        let installed = Mutex::new(vec![]);
+        thread::scope(|s| {
+            for _ in 0..3 {
+                s.spawn(|| {
+                    while let Some((id, _)) = queue.next() {
+                        installed.lock().unwrap().push(id.clone());
+                        queue.finish(&id, true);
+                    }
+                });
+            }
+        });
+        let installed = installed.into_inner().unwrap();
+        assert_eq!(installed.len(), 5);
+        let pos = |id: &str| installed.iter().position(|i| i == id).unwrap();
+        assert!(pos("a") < pos("b"));
+        assert!(pos("b") < pos("c"));
+    }
+}
@@ -1,9 +1,8 @@
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::{Display, Formatter};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
-use std::thread::sleep;
-use std::time::Duration;
 use std::{panic, thread};
 
 use console::truncate_str;
@@ -12,38 +11,54 @@ use indexmap::IndexMap;
 use itertools::Itertools;
 use rayon::prelude::*;
 
+pub use bin_filter::BinFilter;
 pub use builder::ToolsetBuilder;
+pub use prerelease::PrereleasePolicy;
+pub use probe::Probe;
 pub use tool_request_set::{ToolRequestSet, ToolRequestSetBuilder};
 pub use tool_source::ToolSource;
 pub use tool_version::ToolVersion;
 pub use tool_version_list::ToolVersionList;
-pub use tool_version_request::ToolRequest;
+pub use tool_version_request::{platform_matches, ToolRequest};
+pub use upgrade_policy::UpgradePolicy;
+pub use version_range::VersionRange;
 
 use crate::backend::Backend;
 use crate::cli::args::BackendArg;
-use crate::config::settings::SettingsStatusMissingTools;
+use crate::config::settings::{SettingsAutoInstallMode, SettingsStatusMissingTools};
 use crate::config::{Config, Settings};
 use crate::env::TERM_WIDTH;
 use crate::errors::Error;
 use crate::install_context::InstallContext;
 use crate::path_env::PathEnv;
+use crate::toolset::install_queue::InstallQueue;
 use crate::ui::multi_progress_report::MultiProgressReport;
-use crate::{backend, env, runtime_symlinks, shims};
+use crate::{backend, env, runtime_symlinks, shims, ui};
 
+mod bin_filter;
 mod builder;
+mod install_queue;
+mod prerelease;
+mod probe;
 mod tool_request_set;
 mod tool_source;
 mod tool_version;
 mod tool_version_list;
 mod tool_version_request;
+mod upgrade_policy;
+mod version_range;
 
 pub type ToolVersionOptions = BTreeMap<String, String>;
 
+/// a backend, the versions of it to install, and the tools they depend on
+type ToolInstalls = (Arc<dyn Backend>, Vec<ToolRequest>, Vec<String>);
+
 #[derive(Debug, Default)]
 pub struct InstallOptions {
     pub force: bool,
     pub jobs: Option<usize>,
     pub raw: bool,
+    pub keep_download: bool,
     pub latest_versions: bool,
 }
 
@@ -123,15 +138,36 @@ impl Toolset {
         opts: &InstallOptions,
     ) -> Result<Vec<ToolVersion>> {
         let mpr = MultiProgressReport::get();
-        let versions = self
-            .list_current_versions()
+        let versions = self.list_arg_versions_to_install(opts);
+        self.install_versions(config, versions, &mpr, opts)
+    }
+
+    /// like `install_arg_versions` but only installs the tools allowed by the `auto_install`
+    /// setting for the context this is called from
+    pub fn auto_install_arg_versions(
+        &mut self,
+        config: &Config,
+        mode: SettingsAutoInstallMode,
+        opts: &InstallOptions,
+    ) -> Result<Vec<ToolVersion>> {
+        let mpr = MultiProgressReport::get();
+        let mut versions = vec![];
+        for tvr in self.list_arg_versions_to_install(opts) {
+            if auto_install_allowed(mode, &tvr)? {
+                versions.push(tvr);
+            }
+        }
+        self.install_versions(config, versions, &mpr, opts)
+    }
+
+    fn list_arg_versions_to_install(&self, opts: &InstallOptions) -> Vec<ToolRequest> {
+        self.list_current_versions()
             .into_iter()
             .filter(|(p, tv)| opts.force || !p.is_version_installed(tv))
             .map(|(_, tv)| tv)
             .filter(|tv| matches!(self.versions[&tv.backend].source, ToolSource::Argument))
             .map(|tv| tv.request)
-            .collect_vec();
-        self.install_versions(config, versions, &mpr, opts)
+            .collect_vec()
     }
 
     pub fn list_missing_plugins(&self) -> Vec<String> {
@@ -153,21 +189,19 @@ impl Toolset {
         if versions.is_empty() {
             return Ok(vec![]);
         }
-        let leaf_deps = get_leaf_dependencies(&versions)?;
-        if leaf_deps.len() < versions.len() {
-            debug!("installing {} leaf tools first", leaf_deps.len());
-            self.install_versions(config, leaf_deps.into_iter().cloned().collect(), mpr, opts)?;
-        }
         debug!("install_versions: {}", versions.iter().join(" "));
         let settings = Settings::try_get()?;
-        let queue: Vec<_> = versions
-            .into_iter()
-            .rev()
-            .chunk_by(|v| v.backend().clone())
-            .into_iter()
-            .map(|(fa, v)| (backend::get(&fa), v.collect_vec()))
-            .collect();
-        for (t, _) in &queue {
+        // versions of the same tool are installed one after another by the same worker
+        let mut tools: IndexMap<String, ToolInstalls> = IndexMap::new();
+        for tr in versions {
+            let deps = tr.dependencies()?.into_iter().map(|fa| fa.id);
+            let (_, trs, tool_deps) = tools
+                .entry(tr.backend().id.clone())
+                .or_insert_with(|| (backend::get(tr.backend()), vec![], vec![]));
+            tool_deps.extend(deps);
+            trs.push(tr);
+        }
+        for (t, _, _) in tools.values() {
             if !t.is_installed() {
                 t.ensure_installed(mpr, false).or_else(|err| {
                     if let Some(&Error::PluginNotInstalled(_)) = err.downcast_ref::<Error>() {
@@ -178,60 +212,77 @@ impl Toolset {
                 })?;
             }
         }
-        let queue = Arc::new(Mutex::new(queue));
+        let queue = InstallQueue::new(
+            tools
+                .into_iter()
+                .map(|(id, (t, trs, deps))| (id, (t, trs), deps))
+                .collect(),
+        );
         let raw = opts.raw || settings.raw;
         let jobs = match raw {
             true => 1,
             false => opts.jobs.unwrap_or(settings.jobs),
         };
-        let installing: HashSet<String> = HashSet::new();
-        let installing = Arc::new(Mutex::new(installing));
-        let installed = thread::scope(|s| {
-            #[allow(clippy::map_collect_result_unit)]
-            (0..jobs)
+        let total = queue.pending();
+        let summary = (total > 1).then(|| mpr.add_summary("install"));
+        let done = AtomicUsize::new(0);
+        let installed = Mutex::new(vec![]);
+        let errors = Mutex::new(vec![]);
+        let ts = &*self;
+        thread::scope(|s| {
+            (0..jobs.clamp(1, total))
                 .map(|_| {
-                    let queue = queue.clone();
-                    let installing = installing.clone();
-                    let ts = &*self;
-                    s.spawn(move || {
-                        let next_job = || queue.lock().unwrap().pop();
-                        let mut installed = vec![];
-                        while let Some((t, versions)) = next_job() {
-                            installing.lock().unwrap().insert(t.id().into());
-                            for tv in versions {
-                                // TODO: this logic should be able to be removed now I think
-                                for dep in t.get_all_dependencies(&tv)? {
-                                    while installing.lock().unwrap().contains(&dep.to_string()) {
-                                        trace!(
-                                        "{tv} waiting for dependency {dep} to finish installing"
-                                    );
-                                        sleep(Duration::from_millis(100));
-                                    }
-                                }
-                                let tv = tv.resolve(t.as_ref(), opts.latest_versions)?;
+                    s.spawn(|| {
+                        while let Some((id, (t, trs))) = queue.next() {
+                            let result = trs.into_iter().try_for_each(|tr| {
+                                let tv = tr.resolve(t.as_ref(), opts.latest_versions)?;
                                 let ctx = InstallContext {
                                     ts,
                                     pr: mpr.add(&tv.style()),
                                     tv: tv.clone(),
                                     force: opts.force,
+                                    keep_download: opts.keep_download,
                                 };
                                 t.install_version(ctx)?;
-                                installed.push(tv);
+                                installed.lock().unwrap().push(tv);
+                                Ok::<(), eyre::Report>(())
+                            });
+                            queue.finish(&id, result.is_ok());
+                            if let Err(err) = result {
+                                errors.lock().unwrap().push(err);
+                            }
+                            if let Some(summary) = &summary {
+                                let done = done.fetch_add(1, Ordering::Relaxed) + 1;
+                                summary.set_message(format!("{done}/{total} tools"));
                             }
-                            installing.lock().unwrap().remove(t.id());
                         }
-                        Ok(installed)
                     })
                 })
-                .collect::<Vec<_>>()
+                .collect_vec()
                 .into_iter()
-                .map(|t| match t.join() {
-                    Ok(x) => x,
-                    Err(e) => panic::resume_unwind(e),
-                })
-                .collect::<Result<Vec<Vec<ToolVersion>>>>()
-                .map(|x| x.into_iter().flatten().rev().collect())
-        })?;
+                .for_each(|t| {
+                    if let Err(e) = t.join() {
+                        panic::resume_unwind(e);
+                    }
+                });
+        });
+        let mut errors = errors.into_inner().unwrap();
+        for (id, dep) in queue.skipped() {
+            errors.push(eyre!(
+                "{id} was not installed because {dep} failed to install"
+            ));
+        }
+        if !errors.is_empty() {
+            let err = errors.remove(0);
+            for err in errors {
+                warn!("{err:#}");
+            }
+            return Err(err);
+        }
+        if let Some(summary) = &summary {
+            summary.finish_with_message(format!("{total} tools installed"));
+        }
+        let installed = installed.into_inner().unwrap();
         trace!("install: resolving");
         if let Err(err) = self.resolve() {
             debug!("error resolving versions after install: {err:#}");
@@ -327,6 +378,18 @@ impl Toolset {
                         return None;
                     }
                 };
+                // missing versions are always installed, the policy only limits upgrades
+                let latest = if t.is_version_installed(&tv) {
+                    match UpgradePolicy::for_tv(&tv).and_then(|p| p.latest(&t, &tv, latest)) {
+                        Ok(latest) => latest,
+                        Err(e) => {
+                            warn!("{e:#}");
+                            return None;
+                        }
+                    }
+                } else {
+                    latest
+                };
                 if !t.is_version_installed(&tv) || tv.version != latest {
                     Some((t, tv, latest))
                 } else {
@@ -395,10 +458,22 @@ impl Toolset {
             .into_par_iter()
             .filter(|(_, tv)| !matches!(tv.request, ToolRequest::System(_)))
             .flat_map(|(p, tv)| {
-                p.list_bin_paths(&tv).unwrap_or_else(|e| {
+                let bin_paths = p.list_bin_paths(&tv).unwrap_or_else(|e| {
                     warn!("Error listing bin paths for {tv}: {e:#}");
                     Vec::new()
-                })
+                });
+                match BinFilter::for_tv(&tv) {
+                    // symlinks to the filtered bins replace the bin paths, on windows only
+                    // the shims are filtered
+                    Some(filter) if cfg!(unix) => match filter.bin_dir(&tv, &bin_paths) {
+                        Ok(dir) => vec![dir],
+                        Err(e) => {
+                            warn!("Error filtering bins for {tv}: {e:#}");
+                            bin_paths
+                        }
+                    },
+                    _ => bin_paths,
+                }
             })
             .collect()
     }
@@ -406,6 +481,9 @@ impl Toolset {
         self.list_current_installed_versions()
             .into_par_iter()
             .find_first(|(p, tv)| {
+                if BinFilter::for_tv(tv).is_some_and(|f| !f.matches(bin_name)) {
+                    return false;
+                }
                 if let Ok(x) = p.which(tv, bin_name) {
                     x.is_some()
                 } else {
@@ -413,7 +491,11 @@ impl Toolset {
                 }
             })
     }
-    pub fn install_missing_bin(&mut self, bin_name: &str) -> Result<Option<Vec<ToolVersion>>> {
+    pub fn install_missing_bin(
+        &mut self,
+        bin_name: &str,
+        mode: SettingsAutoInstallMode,
+    ) -> Result<Option<Vec<ToolVersion>>> {
         let config = Config::try_get()?;
         let plugins = self
             .list_installed_versions()?
@@ -427,12 +509,12 @@ impl Toolset {
             })
             .collect_vec();
         for (plugin, _) in plugins {
-            let versions = self
-                .list_missing_versions()
-                .into_iter()
-                .filter(|tv| &tv.backend == plugin.fa())
-                .map(|tv| tv.request)
-                .collect_vec();
+            let mut versions = vec![];
+            for tv in self.list_missing_versions() {
+                if &tv.backend == plugin.fa() && auto_install_allowed(mode, &tv.request)? {
+                    versions.push(tv.request);
+                }
+            }
             if !versions.is_empty() {
                 let mpr = MultiProgressReport::get();
                 let versions =
@@ -521,24 +603,25 @@ impl From<ToolRequestSet> for Toolset {
     }
 }
 
-fn get_leaf_dependencies(requests: &[ToolRequest]) -> eyre::Result<Vec<&ToolRequest>> {
-    let versions_hash = requests
-        .iter()
-        .map(|tr| tr.backend())
-        .collect::<HashSet<_>>();
-    let leaves = requests
-        .iter()
-        .map(|tr| {
-            match tr
-                .dependencies()?
-                .iter()
-                .all(|dep| !versions_hash.contains(dep))
-            {
-                true => Ok(Some(tr)),
-                false => Ok(None),
-            }
-        })
-        .flatten_ok()
-        .collect::<Result<Vec<_>>>()?;
-    Ok(leaves)
+/// checks the `auto_install` settings and the `auto_install` tool option to see if a missing
+/// tool may be installed without the user explicitly asking for it
+fn auto_install_allowed(mode: SettingsAutoInstallMode, tvr: &ToolRequest) -> Result<bool> {
+    let settings = Settings::get();
+    let fa = tvr.backend();
+    if settings.auto_install.disable_tools.contains(&fa.id)
+        || tvr
+            .options()
+            .get("auto_install")
+            .is_some_and(|v| v == "false")
+    {
+        debug!("auto_install disabled for {fa}");
+        return Ok(false);
+    }
+    match mode {
+        SettingsAutoInstallMode::Always => Ok(true),
+        SettingsAutoInstallMode::Never => Ok(false),
+        SettingsAutoInstallMode::Prompt => {
+            Ok(settings.yes || ui::confirm(format!("{tvr} is not installed. Install it now?"))?)
+        }
+    }
 }
@@ -0,0 +1,104 @@
+use eyre::{eyre, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::config::Settings;
+use crate::plugins::VERSION_REGEX;
+use crate::toolset::ToolRequest;
+
+/// whether `latest` and version ranges may resolve to alphas, betas, RCs, and nightlies, set with
+/// the `prerelease` tool option or the `prerelease` setting, e.g.: `zig = { version = "latest",
+/// prerelease = true }`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, strum::EnumString)]
+pub enum PrereleasePolicy {
+    /// only stable versions
+    #[default]
+    #[strum(serialize = "false")]
+    Never,
+    /// stable versions and release candidates
+    #[strum(serialize = "rc-only")]
+    RcOnly,
+    /// any version
+    #[strum(serialize = "true")]
+    Always,
+}
+
+static NIGHTLY_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)(nightly|canary|[-.]edge|[-.]preview)").unwrap());
+static RC_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)[-.]?rc[-.]?[0-9]*$").unwrap());
+
+impl PrereleasePolicy {
+    pub fn for_request(request: &ToolRequest) -> Result<Self> {
+        match request.options().get("prerelease") {
+            Some(policy) => Self::parse(policy),
+            None => Self::global(),
+        }
+    }
+
+    /// the `prerelease` setting
+    pub fn global() -> Result<Self> {
+        Self::parse(&Settings::get().prerelease)
+    }
+
+    fn parse(policy: &str) -> Result<Self> {
+        policy.parse().map_err(|_| {
+            eyre!("invalid prerelease policy: {policy}, expected true, false, or rc-only")
+        })
+    }
+
+    pub fn allows(&self, version: &str) -> bool {
+        match self {
+            Self::Always => true,
+            Self::RcOnly => !is_prerelease(version) || RC_REGEX.is_match(version),
+            Self::Never => !is_prerelease(version),
+        }
+    }
+}
+
+pub fn is_prerelease(version: &str) -> bool {
+    VERSION_REGEX.is_match(version) || NIGHTLY_REGEX.is_match(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            PrereleasePolicy::parse("true").unwrap(),
+            PrereleasePolicy::Always
+        );
+        assert_eq!(
+            PrereleasePolicy::parse("rc-only").unwrap(),
+            PrereleasePolicy::RcOnly
+        );
+        assert!(PrereleasePolicy::parse("beta").is_err());
+    }
+
+    #[test]
+    fn test_allows() {
+        let versions = [
+            "1.2.0",
+            "1.3.0-rc1",
+            "1.3.0-rc.2",
+            "1.3.0-beta.1",
+            "v2.0.0-nightly.20240101",
+        ];
+        let allowed = |policy: PrereleasePolicy| {
+            versions
+                .iter()
+                .filter(|v| policy.allows(v))
+                .copied()
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(allowed(PrereleasePolicy::Never), vec!["1.2.0"]);
+        assert_eq!(
+            allowed(PrereleasePolicy::RcOnly),
+            vec!["1.2.0", "1.3.0-rc1", "1.3.0-rc.2"]
+        );
+        assert_eq!(allowed(PrereleasePolicy::Always), versions.to_vec());
+    }
+}
@@ -0,0 +1,66 @@
+use eyre::{bail, Result};
+
+use crate::dirs;
+use crate::toolset::ToolVersion;
+
+/// A command from the `probe` tool option that checks the version that is actually active,
+/// e.g.: node = { version = "20.11.0", probe = "node -e 'console.log(process.version)'" }
+///
+/// It runs with the environment mise was called with, not the one mise builds, so it catches
+/// PATH and shim problems where something else is found before the pinned version.
+/// The output must contain `probe_expect` which defaults to the version of the tool.
+pub struct Probe {
+    pub command: String,
+    pub expect: String,
+}
+
+impl Probe {
+    pub fn for_tv(tv: &ToolVersion) -> Option<Self> {
+        let opts = tv.request.options();
+        let command = opts.get("probe")?.clone();
+        let expect = opts
+            .get("probe_expect")
+            .cloned()
+            .unwrap_or_else(|| tv.version.clone());
+        Some(Self { command, expect })
+    }
+
+    /// runs the probe in the current directory, returns the output if it matches
+    pub fn check(&self) -> Result<String> {
+        let mut cmd = cmd!("sh", "-c", &self.command)
+            .stderr_to_stdout()
+            .unchecked();
+        if let Some(cwd) = &*dirs::CWD {
+            cmd = cmd.dir(cwd);
+        }
+        let output = cmd.read()?;
+        let output = output.trim().to_string();
+        if !output.contains(&self.expect) {
+            bail!(
+                "probe `{}` returned {output:?}, expected {:?}. Check that PATH is not overriding the version from mise",
+                self.command,
+                self.expect
+            );
+        }
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_probe() {
+        let probe = Probe {
+            command: "echo v20.11.0".into(),
+            expect: "20.11.0".into(),
+        };
+        assert_eq!(probe.check().unwrap(), "v20.11.0");
+        let probe = Probe {
+            command: "echo v18.0.0".into(),
+            expect: "20.11.0".into(),
+        };
+        assert!(probe.check().is_err());
+    }
+}
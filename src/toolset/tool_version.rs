@@ -5,14 +5,16 @@ use std::hash::{Hash, Hasher};
 use std::path::PathBuf;
 
 use console::style;
-use eyre::Result;
+use eyre::{bail, Result};
 
 use crate::backend;
 use crate::backend::{ABackend, Backend};
 use crate::cli::args::BackendArg;
-use crate::config::Config;
+use crate::config::{lockfile, Config, Settings};
 use crate::hash::hash_to_str;
-use crate::toolset::{tool_version_request, ToolRequest, ToolVersionOptions};
+use crate::toolset::{
+    tool_version_request, PrereleasePolicy, ToolRequest, ToolVersionOptions, VersionRange,
+};
 
 /// represents a single version of a tool for a particular plugin
 #[derive(Debug, Clone)]
@@ -35,6 +37,18 @@ impl ToolVersion {
         tool: &dyn Backend,
         request: ToolRequest,
         latest_versions: bool,
+    ) -> Result<Self> {
+        if let Some(locked) = lockfile::get(&request)? {
+            return Ok(Self::new(tool, request, locked.version));
+        }
+        Self::resolve_unlocked(tool, request, latest_versions)
+    }
+
+    /// resolves without mise.lock, e.g.: to find the latest version
+    fn resolve_unlocked(
+        tool: &dyn Backend,
+        request: ToolRequest,
+        latest_versions: bool,
     ) -> Result<Self> {
         if !tool.is_installed() {
             let tv = Self::new(tool, request.clone(), request.version());
@@ -86,7 +100,7 @@ impl ToolVersion {
         self.backend.downloads_path.join(self.tv_pathname())
     }
     pub fn latest_version(&self, tool: &dyn Backend) -> Result<String> {
-        let tv = self.request.resolve(tool, true)?;
+        let tv = Self::resolve_unlocked(tool, self.request.clone(), true)?;
         Ok(tv.version)
     }
     pub fn style(&self) -> String {
@@ -140,6 +154,8 @@ impl ToolVersion {
             _ => (),
         }
 
+        // offline, installed versions are used even for `mise install` and `mise latest`
+        let latest_versions = latest_versions && !Settings::get().offline;
         let build = |v| Ok(Self::new(tool, request.clone(), v));
         if !tool.is_installed() {
             return build(v);
@@ -151,6 +167,20 @@ impl ToolVersion {
             return Ok(existing);
         }
 
+        // the backends filter versions with the global policy, a tool with its own `prerelease`
+        // option is resolved here so `latest`, prefixes, and ranges all follow it
+        let prerelease = PrereleasePolicy::for_request(&request)?;
+        let own_policy = prerelease != PrereleasePolicy::global()?;
+        if own_policy || (v == "latest" && prerelease != PrereleasePolicy::Never) {
+            match Self::resolve_prerelease(tool, &v, latest_versions, prerelease)? {
+                Some(v) => return build(v),
+                None if own_policy && VersionRange::parse(&v).is_some() => {
+                    bail!("no versions of {} match {v}", tool.id())
+                }
+                None if own_policy && v != "latest" => return build(v),
+                None => (),
+            }
+        }
         if v == "latest" {
             if !latest_versions {
                 if let Some(v) = tool.latest_installed_version(None)? {
@@ -177,6 +207,24 @@ impl ToolVersion {
         Self::resolve_prefix(tool, request, &v)
     }
 
+    /// the latest version, the newest version with a prefix, or the newest version in a range that
+    /// the prerelease policy of the request allows, installed versions are preferred unless
+    /// `latest_versions`
+    fn resolve_prerelease(
+        tool: &dyn Backend,
+        query: &str,
+        latest_versions: bool,
+        prerelease: PrereleasePolicy,
+    ) -> Result<Option<String>> {
+        if !latest_versions {
+            let installed = tool.list_installed_versions()?;
+            if let Some(v) = newest_matching(installed, query, prerelease)? {
+                return Ok(Some(v));
+            }
+        }
+        newest_matching(tool.list_remote_versions()?, query, prerelease)
+    }
+
     /// resolve a version like `sub-1:12.0.0` which becomes `11.0.0`, `sub-0.1:12.1.0` becomes `12.0.0`
     fn resolve_sub(
         tool: &dyn Backend,
@@ -197,6 +245,9 @@ impl ToolVersion {
         let matches = tool.list_versions_matching(prefix)?;
         let v = match matches.last() {
             Some(v) => v,
+            None if VersionRange::parse(prefix).is_some() => {
+                bail!("no versions of {} match {prefix}", tool.id())
+            }
             None => prefix,
             // None => Err(VersionNotFound(plugin.name.clone(), prefix.to_string()))?,
         };
@@ -256,3 +307,62 @@ impl Hash for ToolVersion {
         self.version.hash(state);
     }
 }
+
+/// the newest of `versions` matching `query` which is `latest`, a prefix, or a range, an exact
+/// match is always used
+fn newest_matching(
+    versions: Vec<String>,
+    query: &str,
+    prerelease: PrereleasePolicy,
+) -> Result<Option<String>> {
+    if versions.iter().any(|v| v == query) {
+        return Ok(Some(query.to_string()));
+    }
+    let range = VersionRange::parse(query).transpose()?;
+    let prefix = regex::Regex::new(&format!("^{}([-.].+)?$", regex::escape(query)))?;
+    Ok(versions
+        .into_iter()
+        .rev()
+        .filter(|v| prerelease.allows(v))
+        .find(|v| match &range {
+            Some(range) => range.matches(v),
+            None if query == "latest" => regex!(r"^v?[0-9]").is_match(v),
+            None => prefix.is_match(v),
+        }))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_newest_matching() {
+        let versions = || {
+            ["1.9.0", "2.0.0", "2.1.0", "2.2.0-rc.1", "3.0.0-beta.1"]
+                .map(String::from)
+                .to_vec()
+        };
+        let newest = |query, prerelease| newest_matching(versions(), query, prerelease).unwrap();
+        assert_eq!(
+            newest("latest", PrereleasePolicy::Never),
+            Some("2.1.0".into())
+        );
+        assert_eq!(
+            newest("latest", PrereleasePolicy::Always),
+            Some("3.0.0-beta.1".into())
+        );
+        assert_eq!(newest("2", PrereleasePolicy::Never), Some("2.1.0".into()));
+        assert_eq!(
+            newest("2", PrereleasePolicy::RcOnly),
+            Some("2.2.0-rc.1".into())
+        );
+        assert_eq!(newest("3", PrereleasePolicy::Never), None);
+        assert_eq!(newest(">=2", PrereleasePolicy::Never), Some("2.1.0".into()));
+        assert_eq!(
+            newest("3.0.0-beta.1", PrereleasePolicy::Never),
+            Some("3.0.0-beta.1".into())
+        );
+    }
+}
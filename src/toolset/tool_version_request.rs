@@ -6,8 +6,9 @@ use versions::{Chunk, Version};
 use xx::file;
 
 use crate::backend;
-use crate::backend::Backend;
+use crate::backend::{Backend, BackendType};
 use crate::cli::args::BackendArg;
+use crate::cli::version::{ARCH, OS};
 use crate::toolset::{ToolVersion, ToolVersionOptions};
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
@@ -59,8 +60,15 @@ impl ToolRequest {
                 sub: p.split_once('-').unwrap().1.to_string(),
                 orig_version: v.to_string(),
             },
+            // git refs for backends that install from a repository, e.g.: cargo:https://...@branch:main
+            Some(("branch" | "rev" | "tag", _)) => Self::Version {
+                backend,
+                version: s,
+                options: Default::default(),
+            },
             None => {
-                if s == "system" {
+                // the system backend keeps its options, e.g.: bin and min_version
+                if s == "system" && backend.backend_type != BackendType::System {
                     Self::System(backend)
                 } else {
                     Self::Version {
@@ -114,11 +122,28 @@ impl ToolRequest {
         }
     }
 
+    /// options of the request on top of the default options of a tool alias
     pub fn options(&self) -> ToolVersionOptions {
         match self {
-            Self::Version { options: o, .. }
-            | Self::Prefix { options: o, .. }
-            | Self::Ref { options: o, .. } => o.clone(),
+            Self::Version {
+                backend,
+                options: o,
+                ..
+            }
+            | Self::Prefix {
+                backend,
+                options: o,
+                ..
+            }
+            | Self::Ref {
+                backend,
+                options: o,
+                ..
+            } => {
+                let mut options = backend.opts.clone();
+                options.extend(o.clone());
+                options
+            }
             _ => Default::default(),
         }
     }
@@ -177,6 +202,34 @@ impl ToolRequest {
     pub fn resolve(&self, plugin: &dyn Backend, latest_versions: bool) -> Result<ToolVersion> {
         ToolVersion::resolve(plugin, self.clone(), latest_versions)
     }
+
+    /// fails with `unsupported_message` if the tool is not available on this os/arch
+    /// tools with `unsupported = "error"` are kept in the config so this fails when they are used
+    pub fn ensure_supported(&self) -> Result<()> {
+        let options = self.options();
+        let Some(platforms) = options.get("platforms") else {
+            return Ok(());
+        };
+        if platform_matches(platforms, &OS, &ARCH) {
+            return Ok(());
+        }
+        match options.get("unsupported_message") {
+            Some(message) => bail!("{message}"),
+            None => bail!("{} is not available on {}-{}", self.backend(), *OS, *ARCH),
+        }
+    }
+}
+
+/// platforms can be an os like "linux" or an os and arch like "macos-arm64"
+pub fn platform_matches(platforms: &str, os: &str, arch: &str) -> bool {
+    platforms
+        .split([',', ' '])
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .any(|p| match p.split_once('-') {
+            Some((o, a)) => o == os && a == arch,
+            None => p == os,
+        })
 }
 
 /// subtracts sub from orig and removes suffix
@@ -203,7 +256,7 @@ impl Display for ToolRequest {
 
 #[cfg(test)]
 mod tests {
-    use super::version_sub;
+    use super::{platform_matches, version_sub};
     use crate::backend::reset;
     use pretty_assertions::assert_str_eq;
     use test_log::test;
@@ -214,4 +267,13 @@ mod tests {
         assert_str_eq!(version_sub("18.2.3", "2"), "16");
         assert_str_eq!(version_sub("18.2.3", "0.1"), "18.1");
     }
+
+    #[test]
+    fn test_platform_matches() {
+        assert!(platform_matches("linux, macos", "linux", "x64"));
+        assert!(platform_matches("linux-x64", "linux", "x64"));
+        assert!(!platform_matches("linux-arm64", "linux", "x64"));
+        assert!(!platform_matches("macos,windows", "linux", "x64"));
+        assert!(!platform_matches("", "linux", "x64"));
+    }
 }
@@ -0,0 +1,83 @@
+use std::sync::Arc;
+
+use eyre::{eyre, Result};
+
+use crate::backend::Backend;
+use crate::toolset::ToolVersion;
+
+/// limits how far `mise upgrade` and `mise outdated` move a tool, set with the `upgrade` tool
+/// option, e.g.: `terraform = { version = "1", upgrade = "never" }`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, strum::EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum UpgradePolicy {
+    /// the installed version is never upgraded
+    Never,
+    /// only upgrade to versions with the same major and minor version
+    Patch,
+    /// only upgrade to versions with the same major version
+    Minor,
+    /// upgrade to the latest version matching the request
+    #[default]
+    Any,
+}
+
+impl UpgradePolicy {
+    pub fn for_tv(tv: &ToolVersion) -> Result<Self> {
+        match tv.request.options().get("upgrade") {
+            Some(policy) => policy.parse().map_err(|_| {
+                eyre!("invalid upgrade policy for {tv}: {policy}, expected never, patch, minor, or any")
+            }),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// the version an installed tool may be upgraded to given the latest version of its request
+    pub fn latest(
+        &self,
+        tool: &Arc<dyn Backend>,
+        tv: &ToolVersion,
+        latest: String,
+    ) -> Result<String> {
+        let prefix = match self {
+            Self::Any => return Ok(latest),
+            Self::Never => return Ok(tv.version.clone()),
+            Self::Patch => version_prefix(&tv.version, 2),
+            Self::Minor => version_prefix(&tv.version, 1),
+        };
+        Ok(tool
+            .latest_version(Some(prefix))?
+            .unwrap_or_else(|| tv.version.clone()))
+    }
+}
+
+/// the first `parts` components of a version, e.g.: 1.2.3 with 2 parts is 1.2
+fn version_prefix(version: &str, parts: usize) -> String {
+    version.split('.').take(parts).collect::<Vec<_>>().join(".")
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_version_prefix() {
+        assert_eq!(version_prefix("1.2.3", 2), "1.2");
+        assert_eq!(version_prefix("1.2.3", 1), "1");
+        assert_eq!(version_prefix("20", 2), "20");
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            "never".parse::<UpgradePolicy>().unwrap(),
+            UpgradePolicy::Never
+        );
+        assert_eq!(
+            "minor".parse::<UpgradePolicy>().unwrap(),
+            UpgradePolicy::Minor
+        );
+        assert!("major".parse::<UpgradePolicy>().is_err());
+    }
+}
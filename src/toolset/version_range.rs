@@ -0,0 +1,184 @@
+use std::cmp::Ordering;
+
+use eyre::{bail, Result};
+
+/// A semver range in a version request like `^1.2`, `~1.4.0`, or `>=1.2,<2`. Comparators separated
+/// by `,` or spaces must all match. Versions are compared by their numeric components so `1.2` is
+/// the same as `1.2.0` and a leading `v` is ignored. A prerelease is in a range if its release is,
+/// e.g.: 2.0.0-rc1 is in `^2` but not in `<2`, whether it is considered depends on the
+/// `prerelease` policy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionRange {
+    comparators: Vec<(Op, Vec<u64>)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+impl VersionRange {
+    /// returns None if `s` isn't a range, e.g.: a version or prefix like `1.2`
+    pub fn parse(s: &str) -> Option<Result<Self>> {
+        if !s.starts_with(['^', '~', '>', '<', '=']) {
+            return None;
+        }
+        Some(Self::parse_comparators(s))
+    }
+
+    fn parse_comparators(s: &str) -> Result<Self> {
+        let mut comparators = vec![];
+        for c in s.split([',', ' ']).filter(|c| !c.is_empty()) {
+            let (op, v) = if let Some(v) = c.strip_prefix(">=") {
+                (">=", v)
+            } else if let Some(v) = c.strip_prefix("<=") {
+                ("<=", v)
+            } else {
+                c.split_at(c.find(|ch: char| ch.is_ascii_alphanumeric()).unwrap_or(0))
+            };
+            let Some(parts) = numbers(v) else {
+                bail!("invalid version range: {s}");
+            };
+            match op {
+                "^" => {
+                    let idx = parts.iter().position(|p| *p != 0);
+                    let idx = idx.unwrap_or(parts.len() - 1);
+                    comparators.push((Op::Gte, parts.clone()));
+                    comparators.push((Op::Lt, bump(&parts, idx)));
+                }
+                "~" => {
+                    let idx = if parts.len() > 1 { 1 } else { 0 };
+                    comparators.push((Op::Gte, parts.clone()));
+                    comparators.push((Op::Lt, bump(&parts, idx)));
+                }
+                "=" | "" => comparators.push((Op::Eq, parts)),
+                ">" => comparators.push((Op::Gt, parts)),
+                ">=" => comparators.push((Op::Gte, parts)),
+                "<" => comparators.push((Op::Lt, parts)),
+                "<=" => comparators.push((Op::Lte, parts)),
+                _ => bail!("invalid version range: {s}"),
+            }
+        }
+        if comparators.is_empty() {
+            bail!("invalid version range: {s}");
+        }
+        Ok(Self { comparators })
+    }
+
+    pub fn matches(&self, version: &str) -> bool {
+        let Some(v) = numbers(version) else {
+            return false;
+        };
+        self.comparators.iter().all(|(op, parts)| {
+            let ord = compare(&v, parts);
+            match op {
+                Op::Eq => ord == Ordering::Equal,
+                Op::Gt => ord == Ordering::Greater,
+                Op::Gte => ord != Ordering::Less,
+                Op::Lt => ord == Ordering::Less,
+                Op::Lte => ord != Ordering::Greater,
+            }
+        })
+    }
+
+    /// versions in `versions` that are in the range, in the same order
+    pub fn filter(&self, versions: Vec<String>) -> Vec<String> {
+        versions.into_iter().filter(|v| self.matches(v)).collect()
+    }
+}
+
+/// numeric components of a version, e.g.: v1.2.3-rc1 and 1.2.3rc1 are [1, 2, 3]
+fn numbers(version: &str) -> Option<Vec<u64>> {
+    let version = version.strip_prefix('v').unwrap_or(version);
+    let version = version.split(['-', '+']).next().unwrap();
+    version
+        .split('.')
+        .map(|p| {
+            let digits = p.find(|c: char| !c.is_ascii_digit()).unwrap_or(p.len());
+            p[..digits].parse().ok()
+        })
+        .collect()
+}
+
+fn bump(parts: &[u64], idx: usize) -> Vec<u64> {
+    let mut bumped = parts[..=idx].to_vec();
+    bumped[idx] += 1;
+    bumped
+}
+
+fn compare(a: &[u64], b: &[u64]) -> Ordering {
+    let len = a.len().max(b.len());
+    let part = |v: &[u64], i| v.get(i).copied().unwrap_or(0);
+    (0..len)
+        .map(|i| part(a, i).cmp(&part(b, i)))
+        .find(|o| *o != Ordering::Equal)
+        .unwrap_or(Ordering::Equal)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn range(s: &str) -> VersionRange {
+        VersionRange::parse(s).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert!(VersionRange::parse("1.2").is_none());
+        assert!(VersionRange::parse("latest").is_none());
+        assert!(VersionRange::parse("^foo").unwrap().is_err());
+        assert!(VersionRange::parse(">=").unwrap().is_err());
+    }
+
+    #[test]
+    fn test_caret() {
+        let versions = |s| {
+            let versions = [
+                "0.2.9",
+                "1.1.0",
+                "1.2.0",
+                "1.9.3",
+                "2.0.0",
+                "2.0.0-rc1",
+                "v1.5.0",
+            ];
+            range(s).filter(versions.iter().map(|v| v.to_string()).collect())
+        };
+        assert_eq!(versions("^1.2"), vec!["1.2.0", "1.9.3", "v1.5.0"]);
+        assert_eq!(versions("^0.2"), vec!["0.2.9"]);
+        assert!(range("^0.0.3").matches("0.0.3"));
+        assert!(!range("^0.0.3").matches("0.0.4"));
+    }
+
+    #[test]
+    fn test_tilde() {
+        assert!(range("~1.4.0").matches("1.4.9"));
+        assert!(!range("~1.4.0").matches("1.5.0"));
+        assert!(!range("~1.4.2").matches("1.4.1"));
+        assert!(range("~1").matches("1.9.0"));
+        assert!(!range("~1").matches("2.0.0"));
+    }
+
+    #[test]
+    fn test_comparators() {
+        let r = range(">=1.2,<2");
+        assert!(r.matches("1.2"));
+        assert!(r.matches("1.99.0"));
+        assert!(!r.matches("1.1.9"));
+        assert!(!r.matches("2.0.0"));
+        assert!(!r.matches("2.0.0-beta1"));
+        assert!(r.matches("1.3.0rc1"));
+        assert!(range("^2").matches("2.0.0-rc1"));
+        assert!(range(">1.2 <=1.3").matches("1.3.0"));
+        assert!(!range(">1.2 <=1.3").matches("1.2.0"));
+        assert!(range("=1.2").matches("1.2.0"));
+        assert!(!range("=1.2").matches("1.2.1"));
+    }
+}
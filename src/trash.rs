@@ -0,0 +1,106 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use eyre::{bail, Result};
+use itertools::Itertools;
+use walkdir::WalkDir;
+
+use crate::file::display_path;
+use crate::rand::random_string;
+use crate::{dirs, file};
+
+/// on windows a running exe can't be deleted but it can be renamed, files that are in use are
+/// moved here and deleted by `empty()` the next time mise upgrades or uninstalls something
+pub fn dir() -> PathBuf {
+    dirs::DATA.join(".trash")
+}
+
+/// removes a directory, files inside of it that are in use are moved to the trash so the
+/// directory can still be removed or replaced
+pub fn remove_all<P: AsRef<Path>>(path: P) -> Result<()> {
+    let path = path.as_ref();
+    let err = match file::remove_all(path) {
+        Ok(()) => return Ok(()),
+        Err(err) => err,
+    };
+    if !cfg!(windows) || !path.is_dir() {
+        return Err(err);
+    }
+    debug!("{err:#}, moving files in use to the trash");
+    let trash = dir().join(random_string(8));
+    let mut locked = vec![];
+    for f in WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.into_path())
+    {
+        if fs::remove_file(&f).is_ok() {
+            continue;
+        }
+        let dest = trash.join(f.strip_prefix(path)?);
+        file::create_dir_all(dest.parent().unwrap())?;
+        if fs::rename(&f, &dest).is_err() {
+            locked.push(f);
+        }
+    }
+    if !locked.is_empty() {
+        let processes = locking_processes(path);
+        bail!(
+            "failed to remove {}, files are in use:\n  {}\n{}",
+            display_path(path),
+            locked.iter().map(display_path).join("\n  "),
+            match processes.is_empty() {
+                true => "close any programs using them and try again".to_string(),
+                false => format!(
+                    "close these programs and try again:\n  {}",
+                    processes.join("\n  ")
+                ),
+            }
+        );
+    }
+    file::remove_all(path)
+}
+
+/// deletes files moved to the trash by `remove_all()` that are no longer in use
+pub fn empty() {
+    for f in file::ls(&dir()).unwrap_or_default() {
+        if let Err(err) = file::remove_all(&f) {
+            debug!("failed to empty trash: {err:#}");
+        }
+    }
+}
+
+/// processes running an exe from inside of a directory
+fn locking_processes(dir: &Path) -> Vec<String> {
+    let script = format!(
+        r#"Get-Process | Where-Object {{ $_.Path -like '{}\*' }} | ForEach-Object {{ "$($_.ProcessName) (pid $($_.Id)): $($_.Path)" }}"#,
+        dir.display()
+    );
+    match cmd!("powershell", "-NoProfile", "-Command", script).read() {
+        Ok(out) => out
+            .lines()
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty())
+            .collect(),
+        Err(err) => {
+            debug!("failed to list processes: {err:#}");
+            vec![]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remove_all() {
+        let dir = dirs::CACHE.join("trash-test");
+        file::create_dir_all(dir.join("bin")).unwrap();
+        file::write(dir.join("bin/tool"), "").unwrap();
+        remove_all(&dir).unwrap();
+        assert!(!dir.exists());
+        remove_all(&dir).unwrap();
+    }
+}
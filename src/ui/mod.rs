@@ -3,6 +3,7 @@ pub use prompt::confirm;
 #[cfg_attr(any(test, target_os = "windows"), path = "ctrlc_stub.rs")]
 pub mod ctrlc;
 pub mod multi_progress_report;
+pub mod progress_events;
 pub mod progress_report;
 pub mod prompt;
 pub mod style;
@@ -3,6 +3,8 @@ use std::sync::{Arc, Mutex, Weak};
 use indicatif::MultiProgress;
 
 use crate::config::Settings;
+use crate::ui::progress_events;
+use crate::ui::progress_events::EventReport;
 use crate::ui::progress_report::{ProgressReport, QuietReport, SingleReport, VerboseReport};
 
 #[derive(Debug)]
@@ -48,6 +50,24 @@ impl MultiProgressReport {
         }
     }
     pub fn add(&self, prefix: &str) -> Box<dyn SingleReport> {
+        let pr = self.add_report(prefix);
+        match progress_events::enabled() {
+            true => Box::new(EventReport::new(pr, prefix)),
+            false => pr,
+        }
+    }
+    /// a line above the other reports with the overall progress, only shown with a progress bar
+    pub fn add_summary(&self, prefix: &str) -> Box<dyn SingleReport> {
+        match &self.mp {
+            Some(mp) if !self.quiet => {
+                let mut pr = ProgressReport::new(prefix.into());
+                pr.pb = mp.insert(0, pr.pb);
+                Box::new(pr)
+            }
+            _ => Box::new(QuietReport::new()),
+        }
+    }
+    fn add_report(&self, prefix: &str) -> Box<dyn SingleReport> {
         match &self.mp {
             _ if self.quiet => Box::new(QuietReport::new()),
             Some(mp) => {
@@ -0,0 +1,176 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::sync::Mutex;
+
+use eyre::Result;
+use once_cell::sync::Lazy;
+use serde_derive::Serialize;
+
+use crate::config::Settings;
+use crate::ui::progress_report::SingleReport;
+
+type Sink = Mutex<Box<dyn Write + Send>>;
+
+/// where progress events are written, set with `--progress-events` or MISE_PROGRESS_EVENTS
+static SINK: Lazy<Option<Sink>> = Lazy::new(|| {
+    let target = Settings::get().progress_events.clone()?;
+    match open(&target) {
+        Ok(w) => Some(Mutex::new(w)),
+        Err(err) => {
+            warn!("failed to open progress events target {target}: {err:#}");
+            None
+        }
+    }
+});
+
+pub fn enabled() -> bool {
+    SINK.is_some()
+}
+
+/// `fd:<N>` writes to an inherited file descriptor, `unix:<path>` connects to a unix socket,
+/// anything else is a file that events are appended to
+fn open(target: &str) -> Result<Box<dyn Write + Send>> {
+    #[cfg(unix)]
+    {
+        if let Some(fd) = target.strip_prefix("fd:") {
+            use std::os::fd::FromRawFd;
+            let fd: i32 = fd.parse()?;
+            // SAFETY: the fd is passed in by the wrapper that started mise
+            return Ok(Box::new(unsafe { std::fs::File::from_raw_fd(fd) }));
+        }
+        if let Some(path) = target.strip_prefix("unix:") {
+            return Ok(Box::new(std::os::unix::net::UnixStream::connect(path)?));
+        }
+    }
+    let file = OpenOptions::new().create(true).append(true).open(target)?;
+    Ok(Box::new(file))
+}
+
+/// one line of newline-delimited JSON
+#[derive(Debug, Serialize)]
+struct ProgressEvent<'a> {
+    /// start, message, progress, or finish
+    event: &'a str,
+    tool: &'a str,
+    /// the current step, e.g.: "downloading node-v20.0.0.tar.gz"
+    phase: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    percent: Option<u64>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    phase: String,
+    bytes: u64,
+    total_bytes: Option<u64>,
+    last_percent: Option<u64>,
+}
+
+/// wraps the progress report shown in the terminal and also emits every update as an event
+pub struct EventReport {
+    inner: Box<dyn SingleReport>,
+    tool: String,
+    state: Mutex<State>,
+}
+
+impl EventReport {
+    pub fn new(inner: Box<dyn SingleReport>, prefix: &str) -> Self {
+        let pr = Self {
+            inner,
+            tool: console::strip_ansi_codes(prefix).to_string(),
+            state: Default::default(),
+        };
+        pr.emit("start", &pr.state.lock().unwrap());
+        pr
+    }
+
+    fn emit(&self, event: &str, state: &State) {
+        let Some(sink) = &*SINK else {
+            return;
+        };
+        let has_bytes = state.bytes > 0 || state.total_bytes.is_some();
+        let event = ProgressEvent {
+            event,
+            tool: &self.tool,
+            phase: &state.phase,
+            bytes: has_bytes.then_some(state.bytes),
+            total_bytes: state.total_bytes,
+            percent: percent(state),
+        };
+        let mut sink = sink.lock().unwrap();
+        let result = serde_json::to_string(&event)
+            .map_err(std::io::Error::from)
+            .and_then(|line| writeln!(sink, "{line}"))
+            .and_then(|_| sink.flush());
+        if let Err(err) = result {
+            debug!("failed to write progress event: {err}");
+        }
+    }
+}
+
+fn percent(state: &State) -> Option<u64> {
+    match state.total_bytes {
+        Some(total) if total > 0 => Some((state.bytes * 100 / total).min(100)),
+        _ => None,
+    }
+}
+
+impl SingleReport for EventReport {
+    fn println(&self, message: String) {
+        self.inner.println(message);
+    }
+    fn set_message(&self, message: String) {
+        let mut state = self.state.lock().unwrap();
+        state.phase = console::strip_ansi_codes(&message).to_string();
+        self.emit("message", &state);
+        self.inner.set_message(message);
+    }
+    fn inc(&self, delta: u64) {
+        let mut state = self.state.lock().unwrap();
+        state.bytes += delta;
+        // only emit when the percentage changes so downloads don't emit an event per chunk
+        let percent = percent(&state);
+        if percent.is_some() && percent != state.last_percent {
+            state.last_percent = percent;
+            self.emit("progress", &state);
+        }
+        self.inner.inc(delta);
+    }
+    fn set_length(&self, length: u64) {
+        let mut state = self.state.lock().unwrap();
+        state.bytes = 0;
+        state.total_bytes = Some(length);
+        state.last_percent = None;
+        self.inner.set_length(length);
+    }
+    fn finish(&self) {
+        self.emit("finish", &self.state.lock().unwrap());
+        self.inner.finish();
+    }
+    fn finish_with_message(&self, message: String) {
+        let mut state = self.state.lock().unwrap();
+        state.phase = console::strip_ansi_codes(&message).to_string();
+        self.emit("finish", &state);
+        self.inner.finish_with_message(message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percent() {
+        let mut state = State::default();
+        assert_eq!(percent(&state), None);
+        state.total_bytes = Some(200);
+        state.bytes = 50;
+        assert_eq!(percent(&state), Some(25));
+        state.bytes = 300;
+        assert_eq!(percent(&state), Some(100));
+    }
+}
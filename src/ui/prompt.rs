@@ -1,6 +1,6 @@
 use std::sync::Mutex;
 
-use demand::{Confirm, Dialog, DialogButton};
+use demand::{Confirm, Dialog, DialogButton, Input};
 
 use crate::env;
 use crate::ui::ctrlc;
@@ -20,6 +20,22 @@ pub fn confirm<S: Into<String>>(message: S) -> eyre::Result<bool> {
     Ok(result)
 }
 
+/// asks for a line of input, `None` if stderr isn't a terminal
+pub fn input<S: Into<String>>(message: S, default: Option<&str>) -> eyre::Result<Option<String>> {
+    let _lock = MUTEX.lock().unwrap(); // Prevent multiple prompts at once
+    let _ctrlc = ctrlc::handle_ctrlc()?;
+
+    if !console::user_attended_stderr() || env::__USAGE.is_some() {
+        return Ok(None);
+    }
+    let mut input = Input::new(message);
+    if let Some(default) = default {
+        input = input.placeholder(default);
+    }
+    let result = input.run()?;
+    Ok(Some(result))
+}
+
 pub fn confirm_with_all<S: Into<String>>(message: S) -> eyre::Result<bool> {
     let _lock = MUTEX.lock().unwrap(); // Prevent multiple prompts at once
     let _ctrlc = ctrlc::handle_ctrlc()?;